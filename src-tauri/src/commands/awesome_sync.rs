@@ -2,6 +2,14 @@
 use tauri::State;
 use serde::Serialize;
 use anyhow::Context;
+use std::collections::HashMap;
+use crate::core::discovery_metadata::{
+    arrange_discovered_skills, enrich_discovered_skills as enrich_discovered_skills_core,
+    metadata_by_skill, metadata_for, DiscoverySort, EnrichmentReport, ENRICH_BATCH_SIZE,
+};
+use crate::core::git_credentials::github_api_token;
+use crate::core::install_state::{InstalledSkills, InstalledState};
+use crate::core::skill_store::DiscoveredSkillMetadata;
 
 #[tauri::command]
 pub async fn sync_awesome_claude_skills(
@@ -23,84 +31,62 @@ pub struct SyncAwesomeSkillsResult {
 }
 
 fn sync_awesome_claude_skills_impl(store: &crate::core::skill_store::SkillStore) -> Result<SyncAwesomeSkillsResult, anyhow::Error> {
-    use crate::core::discovery_parser::{parse_awesome_skills_readme, skills_to_records};
+    use crate::core::registry_sources::{ensure_default_registry_sources, sync_due_registry_sources};
     
-    // Check if we already have data in the database
-    let existing_skills = store.list_discovered_skills()
-        .context("Failed to check existing skills")?;
+    // Refresh the registry sources that are due; the others keep their stored skills
+    ensure_default_registry_sources(store)?;
+    let results = sync_due_registry_sources(store)?;
+    let total = store.list_discovered_skills()
+        .context("Failed to check existing skills")?
+        .len();
     
-    // If we have data, return early without fetching from network
-    if !existing_skills.is_empty() {
+    if results.is_empty() {
         return Ok(SyncAwesomeSkillsResult {
-            total_synced: existing_skills.len(),
-            source: "awesome-claude-skills (cached)".to_string(),
+            total_synced: total,
+            source: "registry sources (cached)".to_string(),
         });
     }
     
-    // Only fetch from network if database is empty
-    let url = "https://raw.githubusercontent.com/BehiSecc/awesome-claude-skills/main/README.md";
-    
-    println!("Fetching README from {} (database is empty)...", url);
-    
-    let response = reqwest::blocking::get(url)
-        .with_context(|| format!("Failed to fetch README from {}", url))?;
-    
-    if !response.status().is_success() {
-        anyhow::bail!("Failed to fetch README: HTTP {}", response.status());
-    }
-    
-    let content = response.text()
-        .context("Failed to read response body")?;
-    
-    println!("README fetched, parsing...");
-    
-    // Parse README
-    let parsed_skills = parse_awesome_skills_readme(&content)
-        .context("Failed to parse README")?;
-    
-    let total = parsed_skills.len();
-    println!("Parsed {} skills from README", total);
-    
-    // Convert to database records
-    let records = skills_to_records(parsed_skills, "awesome-claude-skills");
-    
-    // Clear existing skills from this source
-    store.clear_discovered_skills()
-        .context("Failed to clear existing skills")?;
-    
-    // Insert new skills
-    for record in &records {
-        store.upsert_discovered_skill(record)
-            .with_context(|| format!("Failed to insert skill: {}", record.name))?;
+    // Surface the failure when nothing could be synced at all
+    if total == 0 {
+        if let Some(error) = results.iter().find_map(|r| r.error.clone()) {
+            anyhow::bail!("{}", error);
+        }
     }
     
     println!("Successfully synced {} skills to database", total);
     
     Ok(SyncAwesomeSkillsResult {
         total_synced: total,
-        source: "awesome-claude-skills".to_string(),
+        source: results
+            .iter()
+            .filter(|r| r.error.is_none())
+            .map(|r| r.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
     })
 }
 
+/// Discovered skills sorted by `sort` (`name`, `popularity` or `freshness`). Skills in
+/// archived or missing repositories are left out unless `includeUnavailable` is set.
 #[tauri::command]
+#[allow(non_snake_case)]
 pub async fn fetch_discovered_skills_from_db(
     store: State<'_, crate::core::skill_store::SkillStore>,
+    sort: Option<DiscoverySort>,
+    includeUnavailable: Option<bool>,
 ) -> Result<Vec<DiscoveredSkillDto>, String> {
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let skills = store.list_discovered_skills()?;
-        Ok::<_, anyhow::Error>(
-            skills
-                .into_iter()
-                .map(|s| DiscoveredSkillDto {
-                    name: s.name,
-                    description: s.description,
-                    github_url: s.github_url,
-                    category: s.category,
-                    tags: s.tags.split(',').map(|t: &str| t.trim().to_string()).collect(),
-                })
-                .collect(),
-        )
+        let metadata = metadata_by_skill(&store)?;
+        let skills = arrange_discovered_skills(
+            store.list_discovered_skills()?,
+            &metadata,
+            sort.unwrap_or_default(),
+            includeUnavailable.unwrap_or(false),
+        );
+        let installed = InstalledSkills::load(&store)?;
+        Ok::<_, anyhow::Error>(to_discovered_skill_dtos(skills, &metadata, &installed))
     })
     .await
     .map_err(|e| e.to_string())?
@@ -115,18 +101,10 @@ pub async fn fetch_discovered_skills_by_category_from_db(
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
         let skills = store.list_discovered_skills_by_category(&category)?;
-        Ok::<_, anyhow::Error>(
-            skills
-                .into_iter()
-                .map(|s| DiscoveredSkillDto {
-                    name: s.name,
-                    description: s.description,
-                    github_url: s.github_url,
-                    category: s.category,
-                    tags: s.tags.split(',').map(|t: &str| t.trim().to_string()).collect(),
-                })
-                .collect(),
-        )
+        let metadata = metadata_by_skill(&store)?;
+        let skills = arrange_discovered_skills(skills, &metadata, DiscoverySort::Name, false);
+        let installed = InstalledSkills::load(&store)?;
+        Ok::<_, anyhow::Error>(to_discovered_skill_dtos(skills, &metadata, &installed))
     })
     .await
     .map_err(|e| e.to_string())?
@@ -141,17 +119,49 @@ pub async fn search_discovered_skills_from_db(
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
         let skills = store.search_discovered_skills(&query)?;
-        Ok::<_, anyhow::Error>(
-            skills
-                .into_iter()
-                .map(|s| DiscoveredSkillDto {
-                    name: s.name,
-                    description: s.description,
-                    github_url: s.github_url,
-                    category: s.category,
-                    tags: s.tags.split(',').map(|t: &str| t.trim().to_string()).collect(),
-                })
-                .collect(),
+        let metadata = metadata_by_skill(&store)?;
+        let skills = arrange_discovered_skills(skills, &metadata, DiscoverySort::Name, false);
+        let installed = InstalledSkills::load(&store)?;
+        Ok::<_, anyhow::Error>(to_discovered_skill_dtos(skills, &metadata, &installed))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+/// Skills first listed by a registry source within the last `days` days (default 7).
+#[tauri::command]
+pub async fn fetch_new_discovered_skills_from_db(
+    store: State<'_, crate::core::skill_store::SkillStore>,
+    days: Option<u32>,
+) -> Result<Vec<DiscoveredSkillDto>, String> {
+    let store = store.inner().clone();
+    let days = i64::from(days.unwrap_or(7));
+    tauri::async_runtime::spawn_blocking(move || {
+        let now = chrono::Utc::now().timestamp_millis();
+        let skills = store.list_new_discovered_skills(now - days * 24 * 60 * 60 * 1000)?;
+        let metadata = metadata_by_skill(&store)?;
+        let skills = arrange_discovered_skills(skills, &metadata, DiscoverySort::Name, false);
+        let installed = InstalledSkills::load(&store)?;
+        Ok::<_, anyhow::Error>(to_discovered_skill_dtos(skills, &metadata, &installed))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+/// Enrich discovered skills with repository metadata now, up to `limit` of them (default 20).
+#[tauri::command]
+pub async fn enrich_discovered_skills(
+    store: State<'_, crate::core::skill_store::SkillStore>,
+    limit: Option<usize>,
+) -> Result<EnrichmentReport, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        enrich_discovered_skills_core(
+            &store,
+            github_api_token(&store).as_deref(),
+            limit.unwrap_or(ENRICH_BATCH_SIZE),
         )
     })
     .await
@@ -159,6 +169,53 @@ pub async fn search_discovered_skills_from_db(
     .map_err(format_anyhow_error)
 }
 
+fn to_discovered_skill_dtos(
+    skills: Vec<crate::core::skill_store::DiscoveredSkillRecord>,
+    metadata: &HashMap<String, DiscoveredSkillMetadata>,
+    installed: &InstalledSkills,
+) -> Vec<DiscoveredSkillDto> {
+    skills
+        .into_iter()
+        .map(|s| {
+            let meta = metadata_for(metadata, &s);
+            to_discovered_skill_dto(s, meta, installed)
+        })
+        .collect()
+}
+
+fn to_discovered_skill_dto(
+    s: crate::core::skill_store::DiscoveredSkillRecord,
+    meta: Option<&DiscoveredSkillMetadata>,
+    installed: &InstalledSkills,
+) -> DiscoveredSkillDto {
+    let installed = installed.lookup(&s.github_url, meta.and_then(|m| m.head_revision.as_deref()));
+    DiscoveredSkillDto {
+        name: s.name,
+        description: s.description,
+        github_url: s.github_url,
+        category: s.category,
+        tags: s
+            .tags
+            .split(',')
+            .map(|t: &str| t.trim().to_string())
+            .collect(),
+        source: s.source,
+        first_seen_at: s.first_seen_at,
+        last_seen_at: s.last_seen_at,
+        repo_status: meta.map(|m| m.status.clone()),
+        stars: meta.and_then(|m| m.stars),
+        last_commit_at: meta.and_then(|m| m.last_commit_at),
+        license: meta.and_then(|m| m.license.clone()),
+        archived: meta.is_some_and(|m| m.archived),
+        skill_md_name: meta.and_then(|m| m.skill_name.clone()),
+        skill_md_description: meta.and_then(|m| m.skill_description.clone()),
+        install_status: meta.and_then(|m| m.install_status.clone()),
+        skill_count: meta.and_then(|m| m.skill_count),
+        installed_state: installed.state,
+        installed_skill_id: installed.skill_id,
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct DiscoveredSkillDto {
     pub name: String,
@@ -166,6 +223,29 @@ pub struct DiscoveredSkillDto {
     pub github_url: String,
     pub category: String,
     pub tags: Vec<String>,
+    /// Id of the registry source the skill came from.
+    pub source: String,
+    /// When a sync first / last listed the skill (ms).
+    pub first_seen_at: i64,
+    pub last_seen_at: i64,
+    /// Repository metadata, once enriched: `ok`, `not_found`, `unsupported` or `error`.
+    pub repo_status: Option<String>,
+    pub stars: Option<i64>,
+    /// Last push to the repository (ms).
+    pub last_commit_at: Option<i64>,
+    pub license: Option<String>,
+    pub archived: bool,
+    /// Frontmatter of the `SKILL.md` at the skill's path.
+    pub skill_md_name: Option<String>,
+    pub skill_md_description: Option<String>,
+    /// `installable`, `multiple`, `no_skill` or `not_found`, once checked.
+    pub install_status: Option<String>,
+    /// Skills offered when installing from the URL.
+    pub skill_count: Option<i64>,
+    /// `installed`, `outdated` or `not_installed`.
+    pub installed_state: InstalledState,
+    /// Managed skill installed from the URL, e.g. to update it.
+    pub installed_skill_id: Option<String>,
 }
 
 fn format_anyhow_error(err: anyhow::Error) -> String {
@@ -0,0 +1,77 @@
+// Backup and restore commands
+use super::file_operations::pick_save_path;
+use super::format_anyhow_error;
+use super::skill_sync::sync_pending_targets;
+use crate::core::analytics_store::AnalyticsStore;
+use crate::core::backup::{
+    create_backup as create_backup_core, restore_backup as restore_backup_core, BackupSummary,
+    ConflictPolicy, RestoreReport, BACKUP_EXTENSION,
+};
+use crate::core::skill_store::SkillStore;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn create_backup(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    analytics: State<'_, Arc<AnalyticsStore>>,
+    destPath: Option<String>,
+) -> Result<BackupSummary, String> {
+    let dest = match destPath {
+        Some(path) => PathBuf::from(path),
+        None => {
+            pick_save_path(
+                &app,
+                "Skills Hub Backup",
+                &["gz"],
+                "Back Up Skills Hub",
+                &format!("skills-hub.{}", BACKUP_EXTENSION),
+            )
+            .await?
+        }
+    };
+
+    let store = store.inner().clone();
+    let analytics = analytics.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        create_backup_core(&store, Some(analytics.as_ref()), &dest)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn restore_backup(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    analytics: State<'_, Arc<AnalyticsStore>>,
+    archivePath: String,
+    conflictPolicy: Option<ConflictPolicy>,
+) -> Result<RestoreReport, String> {
+    let store = store.inner().clone();
+    let analytics = analytics.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut report = restore_backup_core(
+            &app,
+            &store,
+            Some(analytics.as_ref()),
+            &PathBuf::from(archivePath),
+            conflictPolicy.unwrap_or_default(),
+        )?;
+
+        // Tool directories are resolved against this machine's home; a failed target
+        // does not undo the restore.
+        let pending = std::mem::take(&mut report.pending_targets);
+        (report.synced_targets, report.target_errors) = sync_pending_targets(&store, pending);
+
+        Ok::<_, anyhow::Error>(report)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
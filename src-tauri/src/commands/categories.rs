@@ -1,6 +1,12 @@
+use crate::core::category_taxonomy::{
+    delete_category_rule as delete_category_rule_core, is_builtin_category,
+    list_categories as list_categories_core, list_category_rules as list_category_rules_core,
+    recategorize_discovered_skills, reset_category_rules as reset_category_rules_core,
+    save_category_rule as save_category_rule_core, CategoryRule, CategoryRuleInput,
+};
 use crate::core::skill_store::SkillStore;
 use tauri::State;
-use super::CategoryInfoDto;
+use super::{format_anyhow_error, CategoryInfoDto};
 
 // Categories commands
 #[tauri::command]
@@ -12,20 +18,39 @@ pub fn add_category(
     icon: String,
     color: String,
 ) -> Result<String, String> {
+    if is_builtin_category(&id) {
+        return Err(format!("category already exists: {}", id));
+    }
     let store = store.inner().clone();
     store.add_category(&id, &name, &description, &icon, &color).map_err(|err| err.to_string())
 }
 
+/// Remove a category; skills its rules matched fall through to the next rule.
 #[tauri::command]
-pub fn remove_category(store: State<'_, SkillStore>, id: String) -> Result<(), String> {
+pub async fn remove_category(store: State<'_, SkillStore>, id: String) -> Result<(), String> {
+    if is_builtin_category(&id) {
+        return Err(format!("built-in categories cannot be removed: {}", id));
+    }
     let store = store.inner().clone();
-    store.remove_category(&id).map_err(|err| err.to_string())
+    tauri::async_runtime::spawn_blocking(move || {
+        store.remove_category(&id)?;
+        recategorize_discovered_skills(&store)?;
+        Ok::<_, anyhow::Error>(())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
 }
 
+/// Built-in categories named in `locale` (default English), then the ones the user added.
 #[tauri::command]
-pub fn list_categories_db(store: State<'_, SkillStore>) -> Result<Vec<CategoryInfoDto>, String> {
+pub fn list_categories_db(
+    store: State<'_, SkillStore>,
+    locale: Option<String>,
+) -> Result<Vec<CategoryInfoDto>, String> {
     let store = store.inner().clone();
-    let categories = store.list_categories().map_err(|err| err.to_string())?;
+    let categories = list_categories_core(&store, locale.as_deref().unwrap_or("en"))
+        .map_err(|err| err.to_string())?;
     Ok(categories
         .into_iter()
         .map(|c| CategoryInfoDto {
@@ -36,4 +61,49 @@ pub fn list_categories_db(store: State<'_, SkillStore>) -> Result<Vec<CategoryIn
             color: c.color,
         })
         .collect())
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub async fn list_category_rules(
+    store: State<'_, SkillStore>,
+) -> Result<Vec<CategoryRule>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || list_category_rules_core(&store))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+/// Add or update a mapping rule; discovered skills are re-categorized right away.
+#[tauri::command]
+pub async fn save_category_rule(
+    store: State<'_, SkillStore>,
+    rule: CategoryRuleInput,
+) -> Result<CategoryRule, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || save_category_rule_core(&store, rule))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn delete_category_rule(store: State<'_, SkillStore>, id: String) -> Result<(), String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || delete_category_rule_core(&store, &id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+/// Restore the default mapping rules.
+#[tauri::command]
+pub async fn reset_category_rules(
+    store: State<'_, SkillStore>,
+) -> Result<Vec<CategoryRule>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || reset_category_rules_core(&store))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
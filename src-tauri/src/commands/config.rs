@@ -1,14 +1,27 @@
 use crate::core::cache_cleanup::{
-    cleanup_git_cache_dirs, get_git_cache_cleanup_days as get_git_cache_cleanup_days_core,
+    cleanup_git_cache_dirs, enforce_git_cache_budget,
+    get_git_cache_cleanup_days as get_git_cache_cleanup_days_core,
+    get_git_cache_max_size_mb as get_git_cache_max_size_mb_core,
     get_git_cache_ttl_secs as get_git_cache_ttl_secs_core,
+    get_git_cache_usage as get_git_cache_usage_core,
     set_git_cache_cleanup_days as set_git_cache_cleanup_days_core,
-    set_git_cache_ttl_secs as set_git_cache_ttl_secs_core,
+    set_git_cache_max_size_mb as set_git_cache_max_size_mb_core,
+    set_git_cache_ttl_secs as set_git_cache_ttl_secs_core, GitCacheUsage,
+};
+use crate::core::network::{
+    activate_network_config, check_connectivity, get_network_config as get_network_config_core,
+    network_status, set_network_config as set_network_config_core,
+    set_offline_mode as set_offline_mode_core, NetworkConfig, NetworkStatus, NETWORK_STATUS_EVENT,
 };
 use crate::core::onboarding::{build_onboarding_plan, OnboardingPlan};
 use crate::core::skill_store::SkillStore;
+use crate::core::tarball_fetcher::{
+    get_fetch_strategy as get_fetch_strategy_core, set_fetch_strategy as set_fetch_strategy_core,
+    FetchStrategy,
+};
 use crate::core::tool_adapters::{default_tool_adapters, is_tool_installed, resolve_default_path};
 use serde::Serialize;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, State};
 
 #[derive(Debug, Serialize)]
 pub struct ToolInfoDto {
@@ -128,13 +141,36 @@ pub async fn get_git_cache_ttl_secs(store: State<'_, SkillStore>) -> Result<i64,
 }
 
 #[tauri::command]
-pub async fn set_git_cache_ttl_secs(
+pub async fn set_git_cache_ttl_secs(store: State<'_, SkillStore>, secs: i64) -> Result<(), String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        set_git_cache_ttl_secs_core(&store, secs)?;
+        Ok::<_, anyhow::Error>(())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn get_git_fetch_strategy(store: State<'_, SkillStore>) -> Result<FetchStrategy, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok::<_, anyhow::Error>(get_fetch_strategy_core(&store))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn set_git_fetch_strategy(
     store: State<'_, SkillStore>,
-    secs: i64,
+    strategy: FetchStrategy,
 ) -> Result<(), String> {
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        set_git_cache_ttl_secs_core(&store, secs)?;
+        set_fetch_strategy_core(&store, strategy)?;
         Ok::<_, anyhow::Error>(())
     })
     .await
@@ -142,6 +178,108 @@ pub async fn set_git_cache_ttl_secs(
     .map_err(format_anyhow_error)
 }
 
+#[tauri::command]
+pub async fn get_git_cache_max_size_mb(store: State<'_, SkillStore>) -> Result<i64, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok::<_, anyhow::Error>(get_git_cache_max_size_mb_core(&store))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn set_git_cache_max_size_mb(
+    store: State<'_, SkillStore>,
+    mb: i64,
+) -> Result<(), String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        set_git_cache_max_size_mb_core(&store, mb)?;
+        enforce_git_cache_budget(&store, None)?;
+        Ok::<_, anyhow::Error>(())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn get_network_config(store: State<'_, SkillStore>) -> Result<NetworkConfig, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok::<_, anyhow::Error>(get_network_config_core(&store))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn set_network_config(
+    store: State<'_, SkillStore>,
+    config: NetworkConfig,
+) -> Result<NetworkConfig, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let config = set_network_config_core(&store, config)?;
+        activate_network_config(&store)?;
+        Ok::<_, anyhow::Error>(config)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+/// Current offline state; `refresh` re-probes connectivity first.
+#[tauri::command]
+pub async fn get_network_status(refresh: Option<bool>) -> Result<NetworkStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok::<_, anyhow::Error>(if refresh.unwrap_or(false) {
+            check_connectivity()
+        } else {
+            network_status()
+        })
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn set_offline_mode(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    enabled: bool,
+) -> Result<NetworkStatus, String> {
+    let store = store.inner().clone();
+    let status = tauri::async_runtime::spawn_blocking(move || {
+        set_offline_mode_core(&store, enabled)?;
+        activate_network_config(&store)?;
+        // Leaving offline mode re-probes so a stale failed probe does not keep us offline.
+        Ok::<_, anyhow::Error>(if enabled {
+            network_status()
+        } else {
+            check_connectivity()
+        })
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)?;
+    let _ = app.emit(NETWORK_STATUS_EVENT, &status);
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_git_cache_usage(store: State<'_, SkillStore>) -> Result<GitCacheUsage, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || get_git_cache_usage_core(&store))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
 #[tauri::command]
 pub async fn clear_git_cache_now(
     app: AppHandle,
@@ -150,13 +288,13 @@ pub async fn clear_git_cache_now(
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
         let cleanup_days = get_git_cache_cleanup_days_core(&store);
+        let mut removed = 0;
         if cleanup_days > 0 {
             let max_age = std::time::Duration::from_secs(cleanup_days as u64 * 24 * 60 * 60);
-            let removed = cleanup_git_cache_dirs(&app, max_age)?;
-            Ok::<_, anyhow::Error>(removed as i64)
-        } else {
-            Ok::<_, anyhow::Error>(0)
+            removed += cleanup_git_cache_dirs(&app, &store, max_age)?;
         }
+        removed += enforce_git_cache_budget(&store, None)?;
+        Ok::<_, anyhow::Error>(removed as i64)
     })
     .await
     .map_err(|err| err.to_string())?
@@ -196,7 +334,7 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
     {
         if lower.contains("securetransport") {
             return format!(
-        "无法从 GitHub 拉取仓库：TLS/证书校验失败（macOS SecureTransport）。\n\n建议：\n- 检查网络/代理是否拦截 HTTPS\n- 如在公司网络，可能需要安装公司根证书或使用可信代理\n- 也可在终端确认 `git clone {}` 是否可用\n\n详细：{}",
+        "无法从 GitHub 拉取仓库：TLS/证书校验失败（macOS SecureTransport）。\n\n建议：\n- 检查网络/代理是否拦截 HTTPS\n- 如在公司网络，可在设置的网络选项中配置代理或添加公司根证书（PEM 文件）\n- 也可在终端确认 `git clone {}` 是否可用\n\n详细：{}",
         "https://github.com/<owner>/<repo>",
         root
       );
@@ -52,4 +52,31 @@ pub async fn select_directory_dialog(app: tauri::AppHandle) -> Result<String, St
         Ok(None) => Err("User cancelled the directory selection".to_string()),
         Err(_) => Err("Failed to receive dialog result".to_string()),
     }
-}
\ No newline at end of file
+}
+/// Ask for a save location; used by commands that write archives.
+pub(super) async fn pick_save_path(
+    app: &tauri::AppHandle,
+    filter_name: &str,
+    extensions: &[&str],
+    title: &str,
+    default_filename: &str,
+) -> Result<std::path::PathBuf, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    app.dialog()
+        .file()
+        .add_filter(filter_name, extensions)
+        .set_title(title)
+        .set_file_name(default_filename)
+        .save_file(move |result| {
+            let _ = tx.send(result);
+        });
+
+    match rx.await {
+        Ok(Some(path)) => Ok(std::path::PathBuf::from(path.to_string())),
+        Ok(None) => Err("User cancelled the save dialog".to_string()),
+        Err(_) => Err("Failed to receive dialog result".to_string()),
+    }
+}
@@ -0,0 +1,58 @@
+// Git host credential commands
+use super::format_anyhow_error;
+use crate::core::git_credentials::{
+    delete_git_credential as delete_git_credential_core,
+    list_git_credentials as list_git_credentials_core,
+    save_git_credential as save_git_credential_core,
+    test_git_credential as test_git_credential_core, GitCredentialInfo, GitCredentialInput,
+    GitCredentialTestResult,
+};
+use crate::core::skill_store::SkillStore;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_git_credentials(
+    store: State<'_, SkillStore>,
+) -> Result<Vec<GitCredentialInfo>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || list_git_credentials_core(&store))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn save_git_credential(
+    store: State<'_, SkillStore>,
+    credential: GitCredentialInput,
+) -> Result<GitCredentialInfo, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || save_git_credential_core(&store, credential))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn delete_git_credential(
+    store: State<'_, SkillStore>,
+    host: String,
+) -> Result<(), String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || delete_git_credential_core(&store, &host))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn test_git_credential(
+    store: State<'_, SkillStore>,
+    url: String,
+) -> Result<GitCredentialTestResult, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || test_git_credential_core(&store, &url))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
@@ -1,11 +1,17 @@
-use crate::core::github_search::{search_github_repos, RepoSummary};
+use crate::core::git_credentials::github_api_token;
+use crate::core::github_search::{
+    search_github_repos, search_github_skills, RepoSummary, SkillRepoMatch,
+};
 use crate::core::installer::{
     install_git_skill, install_git_skill_from_selection, list_git_skills, GitSkillCandidate,
     InstallResult,
 };
+use crate::core::network::{is_offline_error, Offline};
 use serde::Serialize;
 use tauri::{AppHandle, State};
 
+use super::{is_git_auth_failure, GIT_AUTH_HINT};
+
 #[derive(Debug, Serialize)]
 pub struct InstallResultDto {
     pub skill_id: String,
@@ -66,15 +72,39 @@ pub async fn install_git_selection(
 }
 
 #[tauri::command]
-pub async fn search_github(query: String, limit: Option<u32>) -> Result<Vec<RepoSummary>, String> {
+pub async fn search_github(
+    store: State<'_, crate::core::skill_store::SkillStore>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<RepoSummary>, String> {
     let limit = limit.unwrap_or(10) as usize;
-    tauri::async_runtime::spawn_blocking(move || search_github_repos(&query, limit))
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        search_github_repos(&query, limit, github_api_token(&store).as_deref())
+    })
         .await
         .map_err(|err| err.to_string())?
         .map_err(format_anyhow_error)
 }
 
-fn to_install_dto(result: InstallResult) -> InstallResultDto {
+/// Code search for `SKILL.md` files; hits are grouped by repository.
+#[tauri::command]
+pub async fn search_github_skills_cmd(
+    store: State<'_, crate::core::skill_store::SkillStore>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<SkillRepoMatch>, String> {
+    let limit = limit.unwrap_or(30) as usize;
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        search_github_skills(&query, limit, github_api_token(&store).as_deref())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+pub(crate) fn to_install_dto(result: InstallResult) -> InstallResultDto {
     InstallResultDto {
         skill_id: result.skill_id,
         name: result.name,
@@ -91,6 +121,9 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
     {
         return first;
     }
+    if is_offline_error(&err) {
+        return Offline.to_string();
+    }
 
     let mut full = format!("{:#}", err);
 
@@ -116,7 +149,7 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
     {
         if lower.contains("securetransport") {
             return format!(
-        "无法从 GitHub 拉取仓库：TLS/证书校验失败（macOS SecureTransport）。\n\n建议：\n- 检查网络/代理是否拦截 HTTPS\n- 如在公司网络，可能需要安装公司根证书或使用可信代理\n- 也可在终端确认 `git clone {}` 是否可用\n\n详细：{}",
+        "无法从 GitHub 拉取仓库：TLS/证书校验失败（macOS SecureTransport）。\n\n建议：\n- 检查网络/代理是否拦截 HTTPS\n- 如在公司网络，可在设置的网络选项中配置代理或添加公司根证书（PEM 文件）\n- 也可在终端确认 `git clone {}` 是否可用\n\n详细：{}",
         "https://github.com/<owner>/<repo>",
         root
       );
@@ -125,7 +158,7 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
             || lower.contains("permission denied")
             || lower.contains("credentials")
         {
-            "无法访问该仓库：可能是私有仓库/权限不足/需要鉴权。"
+            GIT_AUTH_HINT
         } else if lower.contains("not found") {
             "仓库不存在或无权限访问（GitHub 返回 not found）。"
         } else if lower.contains("failed to resolve")
@@ -144,5 +177,10 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
         return format!("{}\n\n详细：{}", hint, root);
     }
 
+    // Other hosts: at least point private-repo failures at the credentials settings.
+    if is_git_auth_failure(&lower) {
+        return format!("{}\n\n详细：{}", GIT_AUTH_HINT, root);
+    }
+
     full
 }
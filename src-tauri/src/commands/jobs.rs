@@ -0,0 +1,120 @@
+// Long-running operations run as cancellable jobs that report progress events
+use super::format_anyhow_error;
+use super::git_install::to_install_dto;
+use crate::core::auto_update::update_git_skills;
+use crate::core::installer::{install_git_selections, install_git_skill};
+use crate::core::jobs::{JobHandle, JobInfo, JobRegistry, JOB_PROGRESS_EVENT};
+use crate::core::skill_store::SkillStore;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkInstallItemDto {
+    pub subpath: String,
+    pub skill_id: Option<String>,
+    pub name: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Run `work` on a blocking thread as a job of `kind`, returning its id right away.
+/// Progress and the final status are emitted as `job-progress` events.
+pub(crate) fn spawn_job<T, F>(app: &AppHandle, jobs: &JobRegistry, kind: &str, work: F) -> String
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce(&JobHandle) -> anyhow::Result<T> + Send + 'static,
+{
+    let emitter = app.clone();
+    let job = jobs.start(kind, move |info| {
+        let _ = emitter.emit(JOB_PROGRESS_EVENT, info);
+    });
+    let id = job.id().to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        let outcome = job.run(|| work(&job));
+        job.finish(outcome, format_anyhow_error);
+    });
+    id
+}
+
+#[tauri::command]
+pub async fn list_jobs(jobs: State<'_, JobRegistry>) -> Result<Vec<JobInfo>, String> {
+    Ok(jobs.list())
+}
+
+#[tauri::command]
+pub async fn get_job(jobs: State<'_, JobRegistry>, id: String) -> Result<Option<JobInfo>, String> {
+    Ok(jobs.get(&id))
+}
+
+#[tauri::command]
+pub async fn cancel_job(jobs: State<'_, JobRegistry>, id: String) -> Result<bool, String> {
+    jobs.cancel(&id).map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn start_install_git_job(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    jobs: State<'_, JobRegistry>,
+    repoUrl: String,
+    name: Option<String>,
+) -> Result<String, String> {
+    let store = store.inner().clone();
+    let handle = app.clone();
+    Ok(spawn_job(&app, &jobs, "install_git", move |_| {
+        let result = install_git_skill(&handle, &store, &repoUrl, name)?;
+        Ok(to_install_dto(result))
+    }))
+}
+
+/// Install several skills picked from one repository. A failed skill does not stop the
+/// others; cancelling does.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn start_install_git_selection_job(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    jobs: State<'_, JobRegistry>,
+    repoUrl: String,
+    subpaths: Vec<String>,
+) -> Result<String, String> {
+    let store = store.inner().clone();
+    let handle = app.clone();
+    Ok(spawn_job(&app, &jobs, "install_git_selection", move |_| {
+        let results = install_git_selections(&handle, &store, &repoUrl, &subpaths)?;
+        Ok(results
+            .into_iter()
+            .map(|(subpath, result)| match result {
+                Ok(result) => BulkInstallItemDto {
+                    subpath,
+                    skill_id: Some(result.skill_id),
+                    name: Some(result.name),
+                    error: None,
+                },
+                Err(err) => BulkInstallItemDto {
+                    subpath,
+                    skill_id: None,
+                    name: None,
+                    error: Some(format_anyhow_error(err)),
+                },
+            })
+            .collect::<Vec<_>>())
+    }))
+}
+
+/// Update git skills from their sources (all of them when `skillIds` is omitted).
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn start_update_skills_job(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    jobs: State<'_, JobRegistry>,
+    skillIds: Option<Vec<String>>,
+) -> Result<String, String> {
+    let store = store.inner().clone();
+    let handle = app.clone();
+    Ok(spawn_job(&app, &jobs, "update_skills", move |_| {
+        update_git_skills(&handle, &store, skillIds.as_deref())
+    }))
+}
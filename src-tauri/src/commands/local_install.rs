@@ -106,7 +106,7 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
     {
         if lower.contains("securetransport") {
             return format!(
-        "无法从 GitHub 拉取仓库：TLS/证书校验失败（macOS SecureTransport）。\n\n建议：\n- 检查网络/代理是否拦截 HTTPS\n- 如在公司网络，可能需要安装公司根证书或使用可信代理\n- 也可在终端确认 `git clone {}` 是否可用\n\n详细：{}",
+        "无法从 GitHub 拉取仓库：TLS/证书校验失败（macOS SecureTransport）。\n\n建议：\n- 检查网络/代理是否拦截 HTTPS\n- 如在公司网络，可在设置的网络选项中配置代理或添加公司根证书（PEM 文件）\n- 也可在终端确认 `git clone {}` 是否可用\n\n详细：{}",
         "https://github.com/<owner>/<repo>",
         root
       );
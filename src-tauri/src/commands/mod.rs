@@ -12,6 +12,15 @@ pub mod analytics;
 pub mod awesome_sync;
 pub mod file_operations;
 pub mod ai_agents;
+pub mod skill_templates;
+pub mod skill_publish;
+pub mod skill_bundle;
+pub mod backup;
+pub mod state_sync;
+pub mod git_credentials;
+pub mod jobs;
+pub mod registry_sources;
+pub mod skill_search;
 
 // Re-export all commands for use in lib.rs
 pub use config::*;
@@ -27,6 +36,15 @@ pub use analytics::*;
 pub use awesome_sync::*;
 pub use file_operations::*;
 pub use ai_agents::*;
+pub use skill_templates::*;
+pub use skill_publish::*;
+pub use skill_bundle::*;
+pub use backup::*;
+pub use state_sync::*;
+pub use git_credentials::*;
+pub use jobs::*;
+pub use registry_sources::*;
+pub use skill_search::*;
 
 // Re-export DTOs for use in other modules and lib.rs
 pub use skill_discovery::CategoryInfoDto;
@@ -35,8 +53,10 @@ use anyhow::Context;
 use tauri::State;
 
 use crate::core::central_repo::{ensure_central_repo, resolve_central_repo_path};
+use crate::core::network::{is_offline_error, Offline};
 use crate::core::skill_store::SkillStore;
 use crate::core::sync_engine::copy_dir_recursive;
+use crate::core::util::now_ms;
 
 fn format_anyhow_error(err: anyhow::Error) -> String {
     let first = err.to_string();
@@ -47,6 +67,9 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
     {
         return first;
     }
+    if is_offline_error(&err) {
+        return Offline.to_string();
+    }
 
     // Include the full error chain (causes), not just the top context.
     let mut full = format!("{:#}", err);
@@ -76,7 +99,7 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
     {
         if lower.contains("securetransport") {
             return format!(
-        "无法从 GitHub 拉取仓库：TLS/证书校验失败（macOS SecureTransport）。\n\n建议：\n- 检查网络/代理是否拦截 HTTPS\n- 如在公司网络，可能需要安装公司根证书或使用可信代理\n- 也可在终端确认 `git clone {}` 是否可用\n\n详细：{}",
+        "无法从 GitHub 拉取仓库：TLS/证书校验失败（macOS SecureTransport）。\n\n建议：\n- 检查网络/代理是否拦截 HTTPS\n- 如在公司网络，可在设置的网络选项中配置代理或添加公司根证书（PEM 文件）\n- 也可在终端确认 `git clone {}` 是否可用\n\n详细：{}",
         "https://github.com/<owner>/<repo>",
         root
       );
@@ -85,7 +108,7 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
             || lower.contains("permission denied")
             || lower.contains("credentials")
         {
-            "无法访问该仓库：可能是私有仓库/权限不足/需要鉴权。"
+            GIT_AUTH_HINT
         } else if lower.contains("not found") {
             "仓库不存在或无权限访问（GitHub 返回 not found）。"
         } else if lower.contains("failed to resolve")
@@ -104,9 +127,29 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
         return format!("{}\n\n详细：{}", hint, root);
     }
 
+    // Other hosts: at least point private-repo failures at the credentials settings.
+    if is_git_auth_failure(&lower) {
+        return format!("{}\n\n详细：{}", GIT_AUTH_HINT, root);
+    }
+
     full
 }
 
+const GIT_AUTH_HINT: &str =
+    "无法访问该仓库：可能是私有仓库/权限不足/需要鉴权。可在设置中为该主机配置访问凭据（Token、SSH 密钥或系统凭据助手）。";
+
+fn is_git_auth_failure(lower: &str) -> bool {
+    [
+        "authentication failed",
+        "could not read username",
+        "could not read password",
+        "terminal prompts disabled",
+        "permission denied (publickey",
+    ]
+    .iter()
+    .any(|marker| lower.contains(marker))
+}
+
 fn expand_home_path(input: &str) -> Result<std::path::PathBuf, anyhow::Error> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
@@ -229,13 +272,6 @@ fn remove_path_any(path: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn now_ms() -> i64 {
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::SystemTime::UNIX_EPOCH)
-        .unwrap_or_default();
-    now.as_millis() as i64
-}
-
 // Scan paths commands moved to scan_paths.rs module
 // Categories commands moved to categories.rs module
 // Analytics commands moved to analytics.rs module
@@ -0,0 +1,177 @@
+// Registry sources feeding the discovery catalogue
+use super::format_anyhow_error;
+use super::git_install::{to_install_dto, InstallResultDto};
+use crate::core::registry_sources::{
+    add_registry_source as add_registry_source_core, get_registry_refresh_interval_hours,
+    list_registry_sources as list_registry_sources_core,
+    remove_registry_source as remove_registry_source_core,
+    set_registry_refresh_interval_hours as set_registry_refresh_interval_hours_core,
+    set_registry_source_enabled as set_registry_source_enabled_core, sync_registry_source,
+    sync_registry_sources as sync_registry_sources_core, RegistrySourceInfo, RegistrySourceInput,
+    RegistrySyncResult,
+};
+use crate::core::skill_store::SkillStore;
+use crate::core::team_index::{
+    compute_skill_checksum as compute_skill_checksum_core, get_team_index as get_team_index_core,
+    install_team_index_skill as install_team_index_skill_core, TeamIndex,
+};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn list_registry_sources(
+    store: State<'_, SkillStore>,
+) -> Result<Vec<RegistrySourceInfo>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || list_registry_sources_core(&store))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+/// Add a source and sync it right away. A failed first sync is recorded on the source
+/// rather than rejecting it.
+#[tauri::command]
+pub async fn add_registry_source(
+    store: State<'_, SkillStore>,
+    source: RegistrySourceInput,
+) -> Result<RegistrySourceInfo, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let id = add_registry_source_core(&store, source)?;
+        let _ = sync_registry_source(&store, &id);
+        list_registry_sources_core(&store)?
+            .into_iter()
+            .find(|info| info.id == id)
+            .ok_or_else(|| anyhow::anyhow!("registry source not found: {}", id))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn set_registry_source_enabled(
+    store: State<'_, SkillStore>,
+    id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        set_registry_source_enabled_core(&store, &id, enabled)?;
+        if enabled {
+            let _ = sync_registry_source(&store, &id);
+        }
+        Ok::<_, anyhow::Error>(())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn remove_registry_source(
+    store: State<'_, SkillStore>,
+    id: String,
+) -> Result<(), String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || remove_registry_source_core(&store, &id))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+/// Re-fetch one source (`sourceId`) or every enabled source.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn sync_registry_sources(
+    store: State<'_, SkillStore>,
+    sourceId: Option<String>,
+) -> Result<Vec<RegistrySyncResult>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || match sourceId {
+        Some(id) => {
+            let stats = sync_registry_source(&store, &id)?;
+            let name = store
+                .get_registry_source(&id)?
+                .map(|record| record.name)
+                .unwrap_or_default();
+            Ok(vec![RegistrySyncResult {
+                source_id: id,
+                name,
+                stats,
+                error: None,
+            }])
+        }
+        None => sync_registry_sources_core(&store),
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn get_registry_refresh_interval(store: State<'_, SkillStore>) -> Result<u32, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || get_registry_refresh_interval_hours(&store))
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// Hours between scheduled refreshes of each registry source; `0` refreshes on demand only.
+#[tauri::command]
+pub async fn set_registry_refresh_interval(
+    store: State<'_, SkillStore>,
+    hours: u32,
+) -> Result<u32, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        set_registry_refresh_interval_hours_core(&store, hours)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+/// The full team index behind a `team_index` source (revisions, maintainers, checksums).
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn get_team_index(
+    store: State<'_, SkillStore>,
+    sourceId: String,
+) -> Result<TeamIndex, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || get_team_index_core(&store, &sourceId))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+/// Install a skill from a team index at its pinned revision, verifying its checksum.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn install_team_index_skill(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    sourceId: String,
+    name: String,
+) -> Result<InstallResultDto, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = install_team_index_skill_core(&app, &store, &sourceId, &name)?;
+        Ok::<_, anyhow::Error>(to_install_dto(result))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+/// The `checksum` value a team index should declare for the skill folder at `path`.
+#[tauri::command]
+pub async fn compute_skill_checksum(path: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        compute_skill_checksum_core(std::path::Path::new(&path))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
@@ -0,0 +1,73 @@
+// Skill bundle export/import commands
+use super::file_operations::pick_save_path;
+use super::format_anyhow_error;
+use crate::core::skill_bundle::{
+    export_skills_bundle as export_skills_bundle_core,
+    import_skills_bundle as import_skills_bundle_core, read_skill_bundle, BundleManifest,
+    ExportBundleResult, ImportBundleResult, BUNDLE_EXTENSION,
+};
+use crate::core::skill_store::SkillStore;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn export_skills_bundle(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    skillIds: Vec<String>,
+    destPath: Option<String>,
+) -> Result<ExportBundleResult, String> {
+    let dest = match destPath {
+        Some(path) => PathBuf::from(path),
+        None => {
+            pick_save_path(
+                &app,
+                "Skill Bundle",
+                &["gz"],
+                "Export Skills",
+                &format!("skills.{}", BUNDLE_EXTENSION),
+            )
+            .await?
+        }
+    };
+
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        export_skills_bundle_core(&store, &skillIds, &dest)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn preview_skills_bundle(bundlePath: String) -> Result<BundleManifest, String> {
+    tauri::async_runtime::spawn_blocking(move || read_skill_bundle(&PathBuf::from(bundlePath)))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn import_skills_bundle(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    bundlePath: String,
+    overwrite: Option<bool>,
+) -> Result<ImportBundleResult, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        import_skills_bundle_core(
+            &app,
+            &store,
+            &PathBuf::from(bundlePath),
+            overwrite.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
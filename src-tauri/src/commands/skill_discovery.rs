@@ -1,11 +1,18 @@
 use crate::core::skill_store::SkillStore;
-use crate::core::discovery::{get_recommended_skills, get_skills_by_category as get_skills_by_category_core, search_skills as search_skills_core};
-use crate::core::discovery_remote::{fetch_skills_by_category, DEFAULT_SKILLS_PER_CATEGORY};
+use crate::core::category_taxonomy::list_categories;
+use crate::core::discovery::{fetch_category_skills, get_recommended_skills, get_skills_by_category as get_skills_by_category_core, search_skills as search_skills_core, DiscoveredSkill};
+use crate::core::git_credentials::github_api_token;
+use crate::core::discovery_remote::DEFAULT_SKILLS_PER_CATEGORY;
+use crate::core::install_state::{InstalledSkills, InstalledState};
+use crate::core::recommendations::{
+    detect_project, get_project_recommendations as get_project_recommendations_core, relevance,
+    ProjectRecommendations,
+};
 use crate::core::tool_adapters::{DetectedSkill, ToolAdapter, ToolId, default_tool_adapters, resolve_default_path, scan_tool_dir};
 use serde::Serialize;
 use std::path::{Path, PathBuf};
 use tauri::State;
-use super::format_anyhow_error;
+use super::{expand_home_path, format_anyhow_error};
 use anyhow::Context;
 
 #[derive(Debug, Serialize)]
@@ -15,6 +22,10 @@ pub struct DiscoveredSkillDto {
     pub github_url: String,
     pub category: String,
     pub tags: Vec<String>,
+    /// `installed`, `outdated` or `not_installed`.
+    pub installed_state: InstalledState,
+    /// Managed skill installed from the URL, e.g. to update it.
+    pub installed_skill_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -40,20 +51,25 @@ pub struct PaginationInfo {
     pub total_pages: u32,
 }
 
+/// Recommended skills; with `projectPath`, the ones relevant to that project come first.
 #[tauri::command]
-pub async fn fetch_discovered_skills() -> Result<Vec<DiscoveredSkillDto>, String> {
+#[allow(non_snake_case)]
+pub async fn fetch_discovered_skills(
+    store: State<'_, SkillStore>,
+    projectPath: Option<String>,
+) -> Result<Vec<DiscoveredSkillDto>, String> {
+    let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let skills = get_recommended_skills();
+        let mut skills = get_recommended_skills(&store, github_api_token(&store).as_deref());
+        if let Some(path) = projectPath.as_deref() {
+            let profile = detect_project(&expand_home_path(path)?)?;
+            skills.sort_by_cached_key(|skill| std::cmp::Reverse(relevance(&profile, skill).0));
+        }
+        let installed = InstalledSkills::load(&store)?;
         Ok::<_, anyhow::Error>(
             skills
                 .into_iter()
-                .map(|s| DiscoveredSkillDto {
-                    name: s.name,
-                    description: s.description,
-                    github_url: s.github_url,
-                    category: s.category,
-                    tags: s.tags,
-                })
+                .map(|s| to_discovered_skill_dto(s, &installed))
                 .collect(),
         )
     })
@@ -62,11 +78,39 @@ pub async fn fetch_discovered_skills() -> Result<Vec<DiscoveredSkillDto>, String
     .map_err(format_anyhow_error)
 }
 
+/// Skills from the discovery catalogue and the built-in list ranked for the project at
+/// `projectPath`, each with the reasons it was recommended.
 #[tauri::command]
-pub async fn get_categories(store: State<'_, SkillStore>) -> Result<Vec<CategoryInfoDto>, String> {
+#[allow(non_snake_case)]
+pub async fn get_project_recommendations(
+    store: State<'_, SkillStore>,
+    projectPath: String,
+    limit: Option<usize>,
+) -> Result<ProjectRecommendations, String> {
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let categories = store.list_categories()?;
+        let project_dir = expand_home_path(&projectPath)?;
+        get_project_recommendations_core(
+            &store,
+            &project_dir,
+            github_api_token(&store).as_deref(),
+            limit,
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+/// Built-in categories named in `locale` (default English), then the ones the user added.
+#[tauri::command]
+pub async fn get_categories(
+    store: State<'_, SkillStore>,
+    locale: Option<String>,
+) -> Result<Vec<CategoryInfoDto>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let categories = list_categories(&store, locale.as_deref().unwrap_or("en"))?;
         Ok::<_, anyhow::Error>(
             categories
                 .into_iter()
@@ -86,19 +130,18 @@ pub async fn get_categories(store: State<'_, SkillStore>) -> Result<Vec<Category
 }
 
 #[tauri::command]
-pub async fn get_skills_by_category(category_id: String) -> Result<Vec<DiscoveredSkillDto>, String> {
+pub async fn get_skills_by_category(
+    store: State<'_, SkillStore>,
+    category_id: String,
+) -> Result<Vec<DiscoveredSkillDto>, String> {
+    let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let skills = get_skills_by_category_core(&category_id);
+        let skills = get_skills_by_category_core(&store, &category_id);
+        let installed = InstalledSkills::load(&store)?;
         Ok::<_, anyhow::Error>(
             skills
                 .into_iter()
-                .map(|s| DiscoveredSkillDto {
-                    name: s.name,
-                    description: s.description,
-                    github_url: s.github_url,
-                    category: s.category,
-                    tags: s.tags,
-                })
+                .map(|s| to_discovered_skill_dto(s, &installed))
                 .collect(),
         )
     })
@@ -108,19 +151,18 @@ pub async fn get_skills_by_category(category_id: String) -> Result<Vec<Discovere
 }
 
 #[tauri::command]
-pub async fn search_skills(query: String) -> Result<Vec<DiscoveredSkillDto>, String> {
+pub async fn search_skills(
+    store: State<'_, SkillStore>,
+    query: String,
+) -> Result<Vec<DiscoveredSkillDto>, String> {
+    let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let skills = search_skills_core(&query);
+        let skills = search_skills_core(&store, &query)?;
+        let installed = InstalledSkills::load(&store)?;
         Ok::<_, anyhow::Error>(
             skills
                 .into_iter()
-                .map(|s| DiscoveredSkillDto {
-                    name: s.name,
-                    description: s.description,
-                    github_url: s.github_url,
-                    category: s.category,
-                    tags: s.tags,
-                })
+                .map(|s| to_discovered_skill_dto(s, &installed))
                 .collect(),
         )
     })
@@ -132,6 +174,7 @@ pub async fn search_skills(query: String) -> Result<Vec<DiscoveredSkillDto>, Str
 #[tauri::command]
 #[allow(non_snake_case)]
 pub async fn fetch_skills_by_category_with_pagination(
+    store: State<'_, SkillStore>,
     categoryId: String,
     page: Option<u32>,
     pageSize: Option<u32>,
@@ -139,9 +182,15 @@ pub async fn fetch_skills_by_category_with_pagination(
     let page = page.unwrap_or(1);
     let page_size = pageSize.unwrap_or(10);
     
+    let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        // 获取该分类的所有技能（从远程）
-        let all_skills = fetch_skills_by_category(&categoryId, DEFAULT_SKILLS_PER_CATEGORY)?;
+        // 获取该分类的所有技能（从远程，离线时使用内置列表）
+        let all_skills = fetch_category_skills(
+            &store,
+            &categoryId,
+            DEFAULT_SKILLS_PER_CATEGORY,
+            github_api_token(&store).as_deref(),
+        )?;
         
         // 计算分页
         let total = all_skills.len() as u32;
@@ -151,17 +200,12 @@ pub async fn fetch_skills_by_category_with_pagination(
         let start_index = ((page - 1) * page_size) as usize;
         let _end_index = (start_index + page_size as usize).min(all_skills.len());
         
+        let installed = InstalledSkills::load(&store)?;
         let paginated_skills: Vec<DiscoveredSkillDto> = all_skills
             .into_iter()
             .skip(start_index)
             .take(page_size as usize)
-            .map(|s| DiscoveredSkillDto {
-                name: s.name,
-                description: s.description,
-                github_url: s.github_url,
-                category: s.category,
-                tags: s.tags,
-            })
+            .map(|s| to_discovered_skill_dto(s, &installed))
             .collect();
         
         Ok::<_, anyhow::Error>(PaginatedSkillsDto {
@@ -179,6 +223,19 @@ pub async fn fetch_skills_by_category_with_pagination(
     .map_err(format_anyhow_error)
 }
 
+fn to_discovered_skill_dto(s: DiscoveredSkill, installed: &InstalledSkills) -> DiscoveredSkillDto {
+    let installed = installed.lookup(&s.github_url, None);
+    DiscoveredSkillDto {
+        name: s.name,
+        description: s.description,
+        github_url: s.github_url,
+        category: s.category,
+        tags: s.tags,
+        installed_state: installed.state,
+        installed_skill_id: installed.skill_id,
+    }
+}
+
 // New command to scan custom paths for new skills
 #[derive(Debug, Serialize)]
 pub struct LocalDiscoveredSkillDto {
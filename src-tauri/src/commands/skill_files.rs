@@ -2,7 +2,7 @@ use crate::core::skill_store::SkillStore;
 use serde::Serialize;
 use tauri::State;
 use anyhow::Context;
-use super::now_ms;
+use crate::core::util::now_ms;
 use super::format_anyhow_error;
 
 #[tauri::command]
@@ -70,6 +70,15 @@ pub async fn write_skill_file(
         
         // Update the skill's updated_at timestamp
         store.update_skill_timestamp(&skillId, now)?;
+
+        // Published skills track edits as local commits; pushing is an explicit step.
+        if let Err(err) = crate::core::skill_publish::commit_skill_change(
+            &store,
+            &skillId,
+            &format!("Update {}", fileName),
+        ) {
+            log::warn!("[skill_files] failed to commit change for {}: {:#}", skillId, err);
+        }
         
         Ok::<_, anyhow::Error>(())
     })
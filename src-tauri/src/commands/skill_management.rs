@@ -1,8 +1,7 @@
 use crate::core::skill_store::SkillStore;
-use crate::core::installer::install_local_skill;
+use crate::core::installer::{install_local_skill, uninstall_skill};
 use serde::Serialize;
 use tauri::State;
-use super::remove_path_any;
 use super::format_anyhow_error;
 
 #[derive(Debug, Serialize)]
@@ -120,34 +119,7 @@ pub async fn delete_managed_skill(
         // 便于排查"按钮点了没反应"：确认前端确实触发了命令
         println!("[delete_managed_skill] skillId={}", skillId);
 
-        // 先删除已同步到各工具目录的副本/软链接
-        // 注意：如果先删 skills 行，会触发 skill_targets cascade，导致无法再拿到 target_path
-        let targets = store.list_skill_targets(&skillId)?;
-
-        let mut remove_failures: Vec<String> = Vec::new();
-        for target in targets {
-            if let Err(err) = remove_path_any(&target.target_path) {
-                remove_failures.push(format!("{}: {}", target.target_path, err));
-            }
-        }
-
-        let record = store.get_skill_by_id(&skillId)?;
-        if let Some(skill) = record {
-            let path = std::path::PathBuf::from(skill.central_path);
-            if path.exists() {
-                std::fs::remove_dir_all(&path)?;
-            }
-            store.delete_skill(&skillId)?;
-        }
-
-        if !remove_failures.is_empty() {
-            anyhow::bail!(
-                "已删除托管记录，但清理部分工具目录失败：\n- {}",
-                remove_failures.join("\n- ")
-            );
-        }
-
-        Ok::<_, anyhow::Error>(())
+        uninstall_skill(&store, &skillId)
     })
     .await
     .map_err(|err| err.to_string())?
@@ -0,0 +1,63 @@
+// Skill publishing commands
+use super::format_anyhow_error;
+use crate::core::skill_publish::{
+    get_publish_status, publish_skill as publish_skill_core,
+    push_skill_changes as push_skill_changes_core, PublishOptions, PublishStatus,
+};
+use crate::core::skill_store::SkillStore;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn publish_skill(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    skillId: String,
+    remoteUrl: String,
+    branch: Option<String>,
+    subpath: Option<String>,
+    message: Option<String>,
+) -> Result<PublishStatus, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let opts = PublishOptions {
+            remote_url: remoteUrl,
+            branch,
+            subpath,
+            message,
+        };
+        publish_skill_core(&app, &store, &skillId, &opts)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn push_skill_changes(
+    store: State<'_, SkillStore>,
+    skillId: String,
+    message: Option<String>,
+) -> Result<PublishStatus, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        push_skill_changes_core(&store, &skillId, message.as_deref())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn get_skill_publish_status(
+    store: State<'_, SkillStore>,
+    skillId: String,
+) -> Result<Option<PublishStatus>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || get_publish_status(&store, &skillId))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
@@ -0,0 +1,54 @@
+// Full-text search over discovered and installed skills
+use super::format_anyhow_error;
+use crate::core::skill_search::{search_skills, SkillSearchQuery};
+use crate::core::skill_store::{SkillSearchHit, SkillStore};
+use serde::Serialize;
+use tauri::State;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillSearchHitDto {
+    /// `discovered` or `installed`.
+    pub kind: String,
+    pub ref_id: String,
+    pub name: String,
+    pub description: String,
+    pub category: String,
+    pub source: String,
+    pub github_url: Option<String>,
+    /// Set when the hit is an installed skill, or a catalogue entry installed from the same URL.
+    pub installed_skill_id: Option<String>,
+    /// Best matching fragment with matches wrapped in `<mark>`.
+    pub snippet: String,
+    pub score: f64,
+}
+
+fn to_search_hit_dto(hit: SkillSearchHit) -> SkillSearchHitDto {
+    SkillSearchHitDto {
+        kind: hit.kind,
+        ref_id: hit.ref_id,
+        name: hit.name,
+        description: hit.description,
+        category: hit.category,
+        source: hit.source,
+        github_url: hit.github_url,
+        installed_skill_id: hit.installed_skill_id,
+        snippet: hit.snippet,
+        score: hit.score,
+    }
+}
+
+#[tauri::command]
+pub async fn search_skill_index(
+    store: State<'_, SkillStore>,
+    query: SkillSearchQuery,
+) -> Result<Vec<SkillSearchHitDto>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let hits = search_skills(&store, &query)?;
+        Ok::<_, anyhow::Error>(hits.into_iter().map(to_search_hit_dto).collect())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
@@ -1,12 +1,14 @@
+use crate::core::backup::PendingTarget;
 use crate::core::skill_store::SkillStore;
 use crate::core::sync_engine::{SyncMode, sync_dir_hybrid, sync_dir_for_tool_with_overwrite};
 use crate::core::installer::update_managed_skill_from_source;
 use crate::core::tool_adapters::{adapter_by_key, is_tool_installed, resolve_default_path};
 use crate::core::skill_store::SkillTargetRecord;
 use serde::Serialize;
+use std::path::Path;
 use tauri::State;
 use uuid::Uuid;
-use super::now_ms;
+use crate::core::util::now_ms;
 use super::remove_path_any;
 use super::format_anyhow_error;
 
@@ -51,63 +53,108 @@ pub async fn sync_skill_to_tool(
 ) -> Result<SyncResultDto, String> {
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let adapter = adapter_by_key(&tool).ok_or_else(|| anyhow::anyhow!("unknown tool"))?;
-        if !is_tool_installed(&adapter)? {
-            anyhow::bail!("TOOL_NOT_INSTALLED|{}", adapter.id.as_key());
-        }
-        let tool_root = resolve_default_path(&adapter)?;
-        let target = tool_root.join(&name);
-        let overwrite = overwrite.unwrap_or(false);
-        let result =
-            sync_dir_for_tool_with_overwrite(&tool, sourcePath.as_ref(), &target, overwrite)
-                .map_err(|err| {
-                    let msg = err.to_string();
-                    if msg.contains("target already exists") {
-                        anyhow::anyhow!("TARGET_EXISTS|{}", target.to_string_lossy())
-                    } else {
-                        anyhow::anyhow!(msg)
-                    }
-                })?;
+        sync_skill_to_tool_blocking(
+            &store,
+            sourcePath.as_ref(),
+            &skillId,
+            &tool,
+            &name,
+            overwrite.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
 
-        // Some tools share the same global skills directory; keep DB records consistent across them.
-        let group = crate::core::tool_adapters::adapters_sharing_skills_dir(&adapter);
-        for a in group {
-            if !is_tool_installed(&a)? {
-                continue;
+pub(super) fn sync_skill_to_tool_blocking(
+    store: &SkillStore,
+    source_path: &Path,
+    skill_id: &str,
+    tool: &str,
+    name: &str,
+    overwrite: bool,
+) -> anyhow::Result<SyncResultDto> {
+    let adapter = adapter_by_key(tool).ok_or_else(|| anyhow::anyhow!("unknown tool"))?;
+    if !is_tool_installed(&adapter)? {
+        anyhow::bail!("TOOL_NOT_INSTALLED|{}", adapter.id.as_key());
+    }
+    let tool_root = resolve_default_path(&adapter)?;
+    let target = tool_root.join(name);
+    let result = sync_dir_for_tool_with_overwrite(tool, source_path, &target, overwrite)
+        .map_err(|err| {
+            let msg = err.to_string();
+            if msg.contains("target already exists") {
+                anyhow::anyhow!("TARGET_EXISTS|{}", target.to_string_lossy())
+            } else {
+                anyhow::anyhow!(msg)
             }
-            let record = SkillTargetRecord {
-                id: Uuid::new_v4().to_string(),
-                skill_id: skillId.clone(),
-                tool: a.id.as_key().to_string(),
-                target_path: result.target_path.to_string_lossy().to_string(),
-                mode: match result.mode_used {
-                    SyncMode::Auto => "auto",
-                    SyncMode::Symlink => "symlink",
-                    SyncMode::Junction => "junction",
-                    SyncMode::Copy => "copy",
-                }
-                .to_string(),
-                status: "ok".to_string(),
-                last_error: None,
-                synced_at: Some(now_ms()),
-            };
-            store.upsert_skill_target(&record)?;
-        }
+        })?;
 
-        Ok::<_, anyhow::Error>(SyncResultDto {
-            mode_used: match result.mode_used {
+    // Some tools share the same global skills directory; keep DB records consistent across them.
+    let group = crate::core::tool_adapters::adapters_sharing_skills_dir(&adapter);
+    for a in group {
+        if !is_tool_installed(&a)? {
+            continue;
+        }
+        let record = SkillTargetRecord {
+            id: Uuid::new_v4().to_string(),
+            skill_id: skill_id.to_string(),
+            tool: a.id.as_key().to_string(),
+            target_path: result.target_path.to_string_lossy().to_string(),
+            mode: match result.mode_used {
                 SyncMode::Auto => "auto",
                 SyncMode::Symlink => "symlink",
                 SyncMode::Junction => "junction",
                 SyncMode::Copy => "copy",
             }
             .to_string(),
-            target_path: result.target_path.to_string_lossy().to_string(),
-        })
+            status: "ok".to_string(),
+            last_error: None,
+            synced_at: Some(now_ms()),
+        };
+        store.upsert_skill_target(&record)?;
+    }
+
+    Ok(SyncResultDto {
+        mode_used: match result.mode_used {
+            SyncMode::Auto => "auto",
+            SyncMode::Symlink => "symlink",
+            SyncMode::Junction => "junction",
+            SyncMode::Copy => "copy",
+        }
+        .to_string(),
+        target_path: result.target_path.to_string_lossy().to_string(),
     })
-    .await
-    .map_err(|err| err.to_string())?
-    .map_err(format_anyhow_error)
+}
+
+/// Re-create tool targets recorded elsewhere (backup, another machine). Failures are
+/// collected instead of aborting, since the skills themselves are already in place.
+pub(super) fn sync_pending_targets(
+    store: &SkillStore,
+    targets: Vec<PendingTarget>,
+) -> (Vec<String>, Vec<String>) {
+    let mut synced = Vec::new();
+    let mut errors = Vec::new();
+    for target in targets {
+        match sync_skill_to_tool_blocking(
+            store,
+            &target.central_path,
+            &target.skill_id,
+            &target.tool,
+            &target.name,
+            false,
+        ) {
+            Ok(_) => synced.push(format!("{}: {}", target.name, target.tool)),
+            Err(err) => errors.push(format!(
+                "{}: {}: {}",
+                target.name,
+                target.tool,
+                format_anyhow_error(err)
+            )),
+        }
+    }
+    (synced, errors)
 }
 
 #[tauri::command]
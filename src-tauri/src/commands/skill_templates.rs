@@ -0,0 +1,80 @@
+// Skill scaffolding commands
+use super::format_anyhow_error;
+use super::skill_sync::sync_skill_to_tool_blocking;
+use crate::core::skill_store::SkillStore;
+use crate::core::skill_templates::{
+    create_skill_from_template, list_skill_templates as list_skill_templates_core, NewSkillSpec,
+    SkillTemplate,
+};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Serialize)]
+pub struct CreatedSkillDto {
+    pub skill_id: String,
+    pub name: String,
+    pub central_path: String,
+    pub content_hash: Option<String>,
+    pub synced_tools: Vec<String>,
+    pub sync_errors: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn list_skill_templates(app: AppHandle) -> Result<Vec<SkillTemplate>, String> {
+    tauri::async_runtime::spawn_blocking(move || list_skill_templates_core(&app))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn create_skill(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    templateId: String,
+    name: String,
+    description: String,
+    category: Option<String>,
+    tools: Option<Vec<String>>,
+) -> Result<CreatedSkillDto, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let spec = NewSkillSpec {
+            template_id: templateId,
+            name,
+            description,
+            category,
+        };
+        let result = create_skill_from_template(&app, &store, &spec)?;
+
+        // The skill is already registered; a failed sync must not undo the creation.
+        let mut synced_tools = Vec::new();
+        let mut sync_errors = Vec::new();
+        for tool in tools.unwrap_or_default() {
+            match sync_skill_to_tool_blocking(
+                &store,
+                &result.central_path,
+                &result.skill_id,
+                &tool,
+                &result.name,
+                false,
+            ) {
+                Ok(_) => synced_tools.push(tool),
+                Err(err) => sync_errors.push(format!("{}: {}", tool, format_anyhow_error(err))),
+            }
+        }
+
+        Ok::<_, anyhow::Error>(CreatedSkillDto {
+            skill_id: result.skill_id,
+            name: result.name,
+            central_path: result.central_path.to_string_lossy().to_string(),
+            content_hash: result.content_hash,
+            synced_tools,
+            sync_errors,
+        })
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
@@ -0,0 +1,104 @@
+// Cross-machine state sync commands
+use super::format_anyhow_error;
+use super::skill_sync::sync_pending_targets;
+use crate::core::central_repo::{ensure_central_repo, resolve_central_repo_path};
+use crate::core::skill_store::SkillStore;
+use crate::core::state_sync::{
+    disable_state_sync as disable_state_sync_core, enable_state_sync as enable_state_sync_core,
+    get_state_sync_config as get_state_sync_config_core, has_local_changes, is_state_sync_enabled,
+    resolve_state_sync_conflicts as resolve_state_sync_conflicts_core, sync_state,
+    ConflictResolution, StateSyncConfig, StateSyncReport,
+};
+use tauri::{AppHandle, State};
+
+/// Sync once and re-create any tool targets other machines asked for.
+/// Called on startup, when local changes are detected, and from the UI.
+pub(crate) fn run_state_sync(
+    app: &AppHandle,
+    store: &SkillStore,
+    only_if_changed: bool,
+) -> anyhow::Result<Option<StateSyncReport>> {
+    if !is_state_sync_enabled(store) {
+        return Ok(None);
+    }
+    let central_dir = resolve_central_repo_path(app, store)?;
+    if only_if_changed && !has_local_changes(store, &central_dir)? {
+        return Ok(None);
+    }
+    let report = sync_state(store, &central_dir)?;
+    Ok(Some(with_targets(store, report)))
+}
+
+fn with_targets(store: &SkillStore, mut report: StateSyncReport) -> StateSyncReport {
+    let pending = std::mem::take(&mut report.pending_targets);
+    (report.synced_targets, report.target_errors) = sync_pending_targets(store, pending);
+    report
+}
+
+#[tauri::command]
+pub async fn get_state_sync_config(
+    store: State<'_, SkillStore>,
+) -> Result<StateSyncConfig, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || get_state_sync_config_core(&store))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn enable_state_sync(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    remoteUrl: String,
+) -> Result<StateSyncReport, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let central_dir = resolve_central_repo_path(&app, &store)?;
+        ensure_central_repo(&central_dir)?;
+        let report = enable_state_sync_core(&store, &central_dir, &remoteUrl)?;
+        Ok::<_, anyhow::Error>(with_targets(&store, report))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn disable_state_sync(store: State<'_, SkillStore>) -> Result<(), String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || disable_state_sync_core(&store))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn sync_state_now(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+) -> Result<Option<StateSyncReport>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || run_state_sync(&app, &store, false))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn resolve_state_sync_conflicts(
+    app: AppHandle,
+    store: State<'_, SkillStore>,
+    resolution: ConflictResolution,
+) -> Result<StateSyncReport, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let central_dir = resolve_central_repo_path(&app, &store)?;
+        let report = resolve_state_sync_conflicts_core(&store, &central_dir, resolution)?;
+        Ok::<_, anyhow::Error>(with_targets(&store, report))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
@@ -29,6 +29,15 @@ fn format_anyhow_error_github_hint_auth() {
     assert!(msg.contains("无法访问该仓库"));
 }
 
+#[test]
+fn format_anyhow_error_auth_hint_for_other_hosts() {
+    let err = anyhow::anyhow!(
+        "git clone failed: fatal: could not read Username for 'https://gitlab.example.com': terminal prompts disabled"
+    );
+    let msg = format_anyhow_error(err);
+    assert!(msg.contains("访问凭据"), "{msg}");
+}
+
 #[test]
 fn expand_home_path_basic() {
     let home = dirs::home_dir().expect("home");
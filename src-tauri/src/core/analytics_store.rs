@@ -1,9 +1,28 @@
 use anyhow::Result;
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+/// Tables copied by [`AnalyticsStore::merge_from`], with the columns both sides share.
+const MERGE_COLUMNS: [(&str, &str); 3] = [
+    (
+        "skill_events",
+        "id, event_type, skill_id, timestamp, user_id, session_id, input_hash, success, \
+         duration_ms, error, feedback_score, token_input, token_output, api_cost_usd, \
+         caller_agent, caller_workflow, caller_tool, metadata_json, created_at",
+    ),
+    (
+        "skill_daily_stats",
+        "skill_id, date, total_calls, success_count, fail_count, p50_ms, p95_ms, p99_ms, \
+         avg_ms, unique_users, total_cost_usd, thumbs_up, thumbs_down",
+    ),
+    (
+        "analytics_alerts",
+        "id, skill_id, alert_type, severity, message, detected_at, resolved_at, acknowledged",
+    ),
+];
+
 /// Analytics 存储层，管理 skill_events / skill_daily_stats / analytics_alerts 三张表
 pub struct AnalyticsStore {
     #[allow(dead_code)]
@@ -613,4 +632,39 @@ impl AnalyticsStore {
         ))?;
         Ok(())
     }
+
+    /// 导出一致性快照到 `dest`（用于备份）
+    pub fn snapshot_to(&self, dest: &Path) -> Result<()> {
+        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{}", e))?;
+        conn.execute("VACUUM INTO ?1", params![dest.to_string_lossy()])?;
+        Ok(())
+    }
+
+    /// 合并备份库中的数据，已存在的记录保持不变；返回新增事件数
+    pub fn merge_from(&self, src: &Path) -> Result<usize> {
+        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{}", e))?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS backup",
+            params![src.to_string_lossy()],
+        )?;
+        let merged = (|| -> Result<usize> {
+            // Columns are named so snapshots from other schema versions still line up.
+            let mut events = 0;
+            for (table, columns) in MERGE_COLUMNS {
+                let merged = conn.execute(
+                    &format!(
+                        "INSERT OR IGNORE INTO {0} ({1}) SELECT {1} FROM backup.{0}",
+                        table, columns
+                    ),
+                    [],
+                )?;
+                if table == "skill_events" {
+                    events = merged;
+                }
+            }
+            Ok(events)
+        })();
+        conn.execute("DETACH DATABASE backup", [])?;
+        merged
+    }
 }
@@ -1,6 +1,9 @@
 use anyhow::Result;
 
-use crate::core::installer::update_managed_skill_from_source;
+use crate::core::installer::{prefetch_git_sources, update_managed_skill_with_prefetched};
+use crate::core::jobs;
+use crate::core::network;
+use crate::core::skill_publish::has_unpushed_changes;
 use crate::core::skill_store::SkillStore;
 
 /// Check for updates to Git skills if auto-update is enabled
@@ -15,12 +18,47 @@ pub fn check_auto_updates<R: tauri::Runtime>(
         log::info!("[auto_update] Auto-update is disabled, skipping");
         return Ok(vec![]);
     }
+    if network::is_offline() {
+        log::info!("[auto_update] Offline, skipping");
+        return Ok(vec![]);
+    }
+
+    update_git_skills(app, store, None)
+}
 
-    // Get all Git skills
+/// Update Git skills from their sources: all of them, or only those in `skill_ids`.
+/// Returns the names of the updated skills; failures are logged and skipped. When run as
+/// a job, reports the skill being updated and stops once the job is cancelled.
+/// Fails right away when offline instead of reporting every skill as failed.
+pub fn update_git_skills<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    skill_ids: Option<&[String]>,
+) -> Result<Vec<String>> {
+    network::ensure_online()?;
     let all_skills = store.list_skills()?;
     let git_skills: Vec<_> = all_skills
         .into_iter()
         .filter(|skill| skill.source_type == "git")
+        .filter(|skill| skill_ids.map_or(true, |ids| ids.contains(&skill.id)))
+        // Pulling now would overwrite local commits that have not been pushed yet.
+        .filter(|skill| {
+            let unpushed = has_unpushed_changes(store, &skill.id).unwrap_or_else(|err| {
+                log::warn!(
+                    "[auto_update] Skipping {}: publication state unavailable: {:#}",
+                    skill.name,
+                    err
+                );
+                true
+            });
+            if unpushed {
+                log::info!(
+                    "[auto_update] Skipping {}: unpushed local changes",
+                    skill.name
+                );
+            }
+            !unpushed
+        })
         .collect();
 
     if git_skills.is_empty() {
@@ -33,16 +71,41 @@ pub fn check_auto_updates<R: tauri::Runtime>(
         git_skills.len()
     );
 
+    let job = jobs::current();
+    // Fetch every repository once, several at a time, then update its skills from the cache.
+    if let Some(job) = &job {
+        job.set_phase("Fetching");
+    }
+    let sources: Vec<(&str, Option<&str>)> = git_skills
+        .iter()
+        .filter_map(|skill| skill.source_ref.as_deref())
+        .map(|source_ref| (source_ref, None))
+        .collect();
+    let prefetched = prefetch_git_sources(app, store, &sources);
+    if let Some(job) = &job {
+        job.check_cancelled()?;
+        job.set_phase("Updating");
+    }
+
     let mut updated_skills = Vec::new();
     let mut failed_skills = Vec::new();
+    let total = git_skills.len() as u64;
 
-    for skill in git_skills {
+    for (index, skill) in git_skills.into_iter().enumerate() {
         let skill_name = skill.name.clone();
         let skill_id = skill.id.clone();
+        if let Some(job) = &job {
+            job.check_cancelled()?;
+            job.report(|p| {
+                p.current_skill = Some(skill_name.clone());
+                p.current = Some(index as u64);
+                p.total = Some(total);
+            });
+        }
 
         log::info!("[auto_update] Checking update for skill: {}", skill_name);
 
-        match update_managed_skill_from_source(app, store, &skill_id) {
+        match update_managed_skill_with_prefetched(app, store, &skill_id, Some(&prefetched)) {
             Ok(result) => {
                 log::info!(
                     "[auto_update] Successfully updated skill: {} (updated {} targets)",
@@ -51,6 +114,7 @@ pub fn check_auto_updates<R: tauri::Runtime>(
                 );
                 updated_skills.push(skill_name);
             }
+            Err(err) if jobs::is_cancelled(&err) => return Err(err),
             Err(err) => {
                 log::warn!(
                     "[auto_update] Failed to update skill {}: {}",
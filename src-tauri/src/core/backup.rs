@@ -0,0 +1,955 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::analytics_store::AnalyticsStore;
+use super::category_taxonomy::{ensure_default_category_rules, recategorize_discovered_skills};
+use super::central_repo::{ensure_central_repo, resolve_central_repo_path};
+use super::installer::compute_content_hash;
+use super::skill_bundle::{
+    append_bytes, archive_entry_name, collect_skill_files, BundleCategory, BundleFile,
+    SkippedBundleSkill,
+};
+use super::skill_store::{
+    CategoryRuleRecord, RegistrySourceRecord, SkillPublicationRecord, SkillRecord, SkillStore,
+};
+use super::skill_templates::validate_skill_name;
+use super::sync_engine::copy_dir_recursive;
+use super::util::now_ms;
+
+pub const BACKUP_FORMAT: &str = "skills-hub-backup";
+pub const BACKUP_VERSION: u32 = 1;
+pub const BACKUP_EXTENSION: &str = "skillshub-backup.tar.gz";
+const MANIFEST_NAME: &str = "backup.json";
+const CENTRAL_PREFIX: &str = "central/";
+const ANALYTICS_NAME: &str = "analytics.db";
+// Refuse to unpack archives beyond this; the analytics snapshot dominates the size.
+const MAX_BACKUP_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+// Paths, detection caches and one-time flags that only make sense on the machine that
+// wrote them.
+const MACHINE_SETTINGS: [&str; 6] = [
+    "central_repo_path",
+    "installed_tools_v1",
+    "offline_mode",
+    "github_rate_limited_until",
+    "registry_sources_seeded",
+    "category_rules_seeded",
+];
+// State sync points at this machine's clone; proxies and CA files depend on its network.
+const MACHINE_SETTING_PREFIXES: [&str; 2] = ["state_sync_", "network_"];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: i64,
+    pub app_version: String,
+    pub home_dir: Option<String>,
+    pub skills: Vec<BackupSkill>,
+    pub settings: BTreeMap<String, String>,
+    pub scan_paths: Vec<String>,
+    pub categories: Vec<BundleCategory>,
+    pub ai_agents: Vec<BackupAgent>,
+    #[serde(default)]
+    pub registry_sources: Vec<BackupRegistrySource>,
+    /// `None` in backups written before mapping rules were included; local rules stay.
+    #[serde(default)]
+    pub category_rules: Option<Vec<BackupCategoryRule>>,
+    #[serde(default)]
+    pub skill_publications: Vec<BackupPublication>,
+    pub has_analytics: bool,
+    /// Every archive entry other than the manifest, relative to the archive root.
+    pub files: Vec<BundleFile>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackupSkill {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub source_type: String,
+    pub source_ref: Option<String>,
+    pub source_revision: Option<String>,
+    /// Directory name under the central repo.
+    pub dir: String,
+    pub created_at: i64,
+    /// Tool keys the skill was synced to.
+    pub targets: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackupAgent {
+    pub name: String,
+    pub api_key: String,
+    pub base_url: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackupRegistrySource {
+    pub id: String,
+    pub kind: String,
+    pub name: String,
+    pub location: String,
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackupCategoryRule {
+    pub id: String,
+    pub kind: String,
+    pub pattern: String,
+    pub category_id: String,
+    pub position: i64,
+}
+
+/// Only what was pushed; the publish work tree is cloned again on the next push.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackupPublication {
+    pub skill_id: String,
+    pub remote_url: String,
+    pub branch: String,
+    pub subpath: Option<String>,
+    pub work_dir: String,
+    pub pushed_commit: Option<String>,
+    pub last_pushed_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BackupSummary {
+    pub path: String,
+    pub skill_count: usize,
+    pub file_count: usize,
+    pub has_analytics: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Keep the local skill and ignore the one in the backup.
+    #[default]
+    Skip,
+    /// Replace the local files and metadata, keeping the local skill id.
+    Replace,
+    /// Restore the backup copy next to the local one under a `-restored` name.
+    KeepBoth,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RestoredSkill {
+    pub skill_id: String,
+    pub name: String,
+    pub action: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct PendingTarget {
+    pub skill_id: String,
+    pub name: String,
+    pub central_path: PathBuf,
+    pub tool: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RestoreReport {
+    pub restored: Vec<RestoredSkill>,
+    pub skipped: Vec<SkippedBundleSkill>,
+    pub settings_restored: usize,
+    pub scan_paths_added: usize,
+    pub categories_added: usize,
+    pub ai_agents_added: usize,
+    pub registry_sources_added: usize,
+    pub category_rules_restored: usize,
+    pub publications_restored: usize,
+    pub analytics_events_merged: usize,
+    /// Tool targets to re-create on this machine; the caller performs the sync.
+    #[serde(skip)]
+    pub pending_targets: Vec<PendingTarget>,
+    pub synced_targets: Vec<String>,
+    pub target_errors: Vec<String>,
+}
+
+/// Write skills (files + records + targets + publications), settings, scan paths,
+/// categories and their mapping rules, registry sources, AI agents and an analytics
+/// snapshot into a single `.tar.gz`. The archive contains API keys.
+pub fn create_backup(
+    store: &SkillStore,
+    analytics: Option<&AnalyticsStore>,
+    dest: &Path,
+) -> Result<BackupSummary> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("create {:?}", parent))?;
+    }
+
+    let mut skills = Vec::new();
+    let mut skill_files = Vec::new();
+    let mut seen_dirs = HashSet::new();
+    for record in store.list_skills()? {
+        let central_path = PathBuf::from(&record.central_path);
+        let Some(dir) = central_path
+            .file_name()
+            .map(|v| v.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+        if !central_path.is_dir() {
+            log::warn!(
+                "[backup] skipping {}: central path missing {:?}",
+                record.name,
+                central_path
+            );
+            continue;
+        }
+        if !seen_dirs.insert(dir.clone()) {
+            log::warn!(
+                "[backup] skipping {}: duplicate directory {}",
+                record.name,
+                dir
+            );
+            continue;
+        }
+        let files = collect_skill_files(&central_path)?;
+        let targets = store
+            .list_skill_targets(&record.id)?
+            .into_iter()
+            .map(|t| t.tool)
+            .collect();
+        for (mut meta, source) in files {
+            meta.path = format!("{}{}/{}", CENTRAL_PREFIX, dir, meta.path);
+            skill_files.push((meta, source));
+        }
+        skills.push(BackupSkill {
+            id: record.id,
+            name: record.name,
+            description: record.description,
+            category: record.category,
+            source_type: record.source_type,
+            source_ref: record.source_ref,
+            source_revision: record.source_revision,
+            dir,
+            created_at: record.created_at,
+            targets,
+        });
+    }
+
+    let snapshot = analytics
+        .map(|analytics| -> Result<PathBuf> {
+            let path = dest.with_file_name(format!(".{}.analytics.tmp", Uuid::new_v4()));
+            analytics
+                .snapshot_to(&path)
+                .context("snapshot analytics database")?;
+            Ok(path)
+        })
+        .transpose()?;
+
+    let result = (|| -> Result<BackupSummary> {
+        let mut files: Vec<BundleFile> = skill_files.iter().map(|(m, _)| m.clone()).collect();
+        if let Some(path) = &snapshot {
+            let (size, sha256) = hash_file(path)?;
+            files.push(BundleFile {
+                path: ANALYTICS_NAME.to_string(),
+                size,
+                sha256,
+                executable: false,
+            });
+        }
+
+        let skill_publications = store
+            .list_skill_publications()?
+            .into_iter()
+            .filter(|p| skills.iter().any(|s| s.id == p.skill_id))
+            .map(|p| BackupPublication {
+                skill_id: p.skill_id,
+                remote_url: p.remote_url,
+                branch: p.branch,
+                subpath: p.subpath,
+                work_dir: p.work_dir,
+                pushed_commit: p.pushed_commit,
+                last_pushed_at: p.last_pushed_at,
+            })
+            .collect();
+        let manifest = BackupManifest {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created_at: now_ms(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            home_dir: dirs::home_dir().map(|h| h.to_string_lossy().to_string()),
+            skills,
+            settings: store
+                .list_settings()?
+                .into_iter()
+                .filter(|(key, _)| !is_machine_setting(key))
+                .collect(),
+            scan_paths: store.list_scan_paths()?,
+            categories: store
+                .list_categories()?
+                .into_iter()
+                .map(|c| BundleCategory {
+                    id: c.id,
+                    name: c.name,
+                    description: c.description,
+                    icon: c.icon,
+                    color: c.color,
+                })
+                .collect(),
+            ai_agents: store
+                .list_ai_agents()?
+                .into_iter()
+                .map(|a| BackupAgent {
+                    name: a.name,
+                    api_key: a.api_key,
+                    base_url: a.base_url,
+                })
+                .collect(),
+            registry_sources: store
+                .list_registry_sources()?
+                .into_iter()
+                .map(|r| BackupRegistrySource {
+                    id: r.id,
+                    kind: r.kind,
+                    name: r.name,
+                    location: r.location,
+                    enabled: r.enabled,
+                })
+                .collect(),
+            category_rules: Some(
+                store
+                    .list_category_rules()?
+                    .into_iter()
+                    .map(|r| BackupCategoryRule {
+                        id: r.id,
+                        kind: r.kind,
+                        pattern: r.pattern,
+                        category_id: r.category_id,
+                        position: r.position,
+                    })
+                    .collect(),
+            ),
+            skill_publications,
+            has_analytics: snapshot.is_some(),
+            files,
+        };
+
+        let file = std::fs::File::create(dest).with_context(|| format!("create {:?}", dest))?;
+        let mut builder = tar::Builder::new(GzEncoder::new(file, flate2::Compression::default()));
+        append_bytes(
+            &mut builder,
+            MANIFEST_NAME,
+            &serde_json::to_vec_pretty(&manifest)?,
+            false,
+        )?;
+        for (meta, source) in &skill_files {
+            let bytes = std::fs::read(source).with_context(|| format!("read {:?}", source))?;
+            append_bytes(&mut builder, &meta.path, &bytes, meta.executable)?;
+        }
+        if let Some(path) = &snapshot {
+            let mut file = std::fs::File::open(path).with_context(|| format!("open {:?}", path))?;
+            builder
+                .append_file(ANALYTICS_NAME, &mut file)
+                .context("append analytics snapshot")?;
+        }
+        builder
+            .into_inner()
+            .context("finish backup archive")?
+            .finish()
+            .context("finish backup compression")?;
+
+        Ok(BackupSummary {
+            path: dest.to_string_lossy().to_string(),
+            skill_count: manifest.skills.len(),
+            file_count: manifest.files.len(),
+            has_analytics: manifest.has_analytics,
+        })
+    })();
+
+    if let Some(path) = &snapshot {
+        let _ = std::fs::remove_file(path);
+    }
+    if result.is_err() {
+        let _ = std::fs::remove_file(dest);
+    }
+    result
+}
+
+pub fn restore_backup<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    analytics: Option<&AnalyticsStore>,
+    archive: &Path,
+    policy: ConflictPolicy,
+) -> Result<RestoreReport> {
+    let central_dir = resolve_central_repo_path(app, store)?;
+    ensure_central_repo(&central_dir)?;
+    restore_backup_in(store, analytics, &central_dir, archive, policy)
+}
+
+/// Unpack and verify a backup, then merge it into this machine's state. Paths under the
+/// old home directory are rebased onto the current one; tool targets are returned in
+/// `pending_targets` so they can be re-synced against this machine's tool directories.
+/// A failed restore leaves the skill folders and the database as they were.
+pub fn restore_backup_in(
+    store: &SkillStore,
+    analytics: Option<&AnalyticsStore>,
+    central_dir: &Path,
+    archive: &Path,
+    policy: ConflictPolicy,
+) -> Result<RestoreReport> {
+    let staging = central_dir.join(format!(".restore-{}", Uuid::new_v4()));
+    let result = unpack_verified(archive, &staging).and_then(|manifest| {
+        apply_backup(store, analytics, central_dir, &staging, &manifest, policy)
+    });
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+fn apply_backup(
+    store: &SkillStore,
+    analytics: Option<&AnalyticsStore>,
+    central_dir: &Path,
+    staging: &Path,
+    manifest: &BackupManifest,
+    policy: ConflictPolicy,
+) -> Result<RestoreReport> {
+    let mut report = RestoreReport::default();
+    let plans = plan_skill_restores(store, central_dir, manifest, policy, &mut report)?;
+
+    // Skill folders go in first, keeping what they replace aside; the records are then
+    // written in one transaction, and the folders are put back if anything fails.
+    let mut placed = Vec::new();
+    let result = (|| -> Result<()> {
+        for plan in &plans {
+            let staged = staging.join(CENTRAL_PREFIX).join(&plan.skill.dir);
+            std::fs::create_dir_all(&staged).with_context(|| format!("create {:?}", staged))?;
+            placed.push(place_dir(&staged, &plan.central_path)?);
+        }
+        store
+            .transaction(|store| write_backup_records(store, manifest, &plans, policy, &mut report))
+    })();
+    match result {
+        Ok(()) => placed.into_iter().for_each(PlacedDir::finish),
+        Err(err) => {
+            placed.into_iter().rev().for_each(PlacedDir::undo);
+            return Err(err);
+        }
+    }
+
+    // Merged with `INSERT OR IGNORE` into its own database, so restoring again completes it.
+    if manifest.has_analytics {
+        if let Some(analytics) = analytics {
+            report.analytics_events_merged = analytics
+                .merge_from(&staging.join(ANALYTICS_NAME))
+                .context("merge analytics database")?;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Settings, scan paths, categories, agents, rules, registry sources and the records
+/// of the restored skills and their publications.
+fn write_backup_records(
+    store: &SkillStore,
+    manifest: &BackupManifest,
+    plans: &[SkillRestore],
+    policy: ConflictPolicy,
+    report: &mut RestoreReport,
+) -> Result<()> {
+    let old_home = manifest.home_dir.as_deref();
+    let new_home = dirs::home_dir();
+
+    for (key, value) in &manifest.settings {
+        if is_machine_setting(key) {
+            continue;
+        }
+        store.set_setting(key, value)?;
+        report.settings_restored += 1;
+    }
+
+    let scan_paths = store.list_scan_paths()?;
+    for path in &manifest.scan_paths {
+        let path = rebase_home(path, old_home, new_home.as_deref());
+        if !scan_paths.contains(&path) {
+            store.add_scan_path(&path)?;
+            report.scan_paths_added += 1;
+        }
+    }
+
+    let categories = store.list_categories()?;
+    for category in &manifest.categories {
+        if !categories.iter().any(|c| c.id == category.id) {
+            store.add_category(
+                &category.id,
+                &category.name,
+                &category.description,
+                &category.icon,
+                &category.color,
+            )?;
+            report.categories_added += 1;
+        }
+    }
+
+    let agents = store.list_ai_agents()?;
+    for agent in &manifest.ai_agents {
+        if !agents.iter().any(|a| a.name == agent.name) {
+            store.add_ai_agent(&agent.name, &agent.api_key, &agent.base_url)?;
+            report.ai_agents_added += 1;
+        }
+    }
+
+    if let Some(rules) = &manifest.category_rules {
+        report.category_rules_restored = restore_category_rules(store, rules, policy)?;
+    }
+
+    let sources = store.list_registry_sources()?;
+    for source in &manifest.registry_sources {
+        let location = rebase_home(&source.location, old_home, new_home.as_deref());
+        if sources
+            .iter()
+            .any(|s| s.id == source.id || (s.kind == source.kind && s.location == location))
+        {
+            continue;
+        }
+        let now = now_ms();
+        store.upsert_registry_source(&RegistrySourceRecord {
+            id: source.id.clone(),
+            kind: source.kind.clone(),
+            name: source.name.clone(),
+            location,
+            enabled: source.enabled,
+            last_synced_at: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+            etag: None,
+            last_modified: None,
+        })?;
+        report.registry_sources_added += 1;
+    }
+
+    let mut restored_ids = BTreeMap::new();
+    for plan in plans {
+        write_skill_record(store, plan, old_home, new_home.as_deref(), report)?;
+        restored_ids.insert(plan.skill.id.as_str(), plan.id.as_str());
+    }
+
+    for publication in &manifest.skill_publications {
+        let Some(&skill_id) = restored_ids.get(publication.skill_id.as_str()) else {
+            continue;
+        };
+        if store.get_skill_publication(skill_id)?.is_some() {
+            continue;
+        }
+        let now = now_ms();
+        store.upsert_skill_publication(&SkillPublicationRecord {
+            skill_id: skill_id.to_string(),
+            remote_url: publication.remote_url.clone(),
+            branch: publication.branch.clone(),
+            subpath: publication.subpath.clone(),
+            work_dir: rebase_home(&publication.work_dir, old_home, new_home.as_deref()),
+            last_commit: publication.pushed_commit.clone(),
+            pushed_commit: publication.pushed_commit.clone(),
+            last_pushed_at: publication.last_pushed_at,
+            created_at: now,
+            updated_at: now,
+        })?;
+        report.publications_restored += 1;
+    }
+
+    Ok(())
+}
+
+/// `Replace` swaps the local mapping rules for the backup's; the other policies keep
+/// them and add the backup rules they lack at their backup position. Returns how many
+/// rules were written.
+fn restore_category_rules(
+    store: &SkillStore,
+    rules: &[BackupCategoryRule],
+    policy: ConflictPolicy,
+) -> Result<usize> {
+    // Before the defaults are seeded there are no local rules to keep.
+    let keep_local = policy != ConflictPolicy::Replace && !store.list_category_rules()?.is_empty();
+    // Seed first so the defaults are not added on top of the restored rules later.
+    ensure_default_category_rules(store)?;
+    let now = now_ms();
+    let records = rules.iter().map(|r| CategoryRuleRecord {
+        id: r.id.clone(),
+        kind: r.kind.clone(),
+        pattern: r.pattern.clone(),
+        category_id: r.category_id.clone(),
+        position: r.position,
+        created_at: now,
+        updated_at: now,
+    });
+    if !keep_local {
+        let records: Vec<CategoryRuleRecord> = records.collect();
+        store.replace_category_rules(&records)?;
+        recategorize_discovered_skills(store)?;
+        return Ok(records.len());
+    }
+    let local = store.list_category_rules()?;
+    let mut written = 0;
+    for record in records {
+        if local
+            .iter()
+            .any(|r| r.id == record.id || (r.kind == record.kind && r.pattern == record.pattern))
+        {
+            continue;
+        }
+        store.upsert_category_rule(&record)?;
+        written += 1;
+    }
+    if written > 0 {
+        recategorize_discovered_skills(store)?;
+    }
+    Ok(written)
+}
+
+/// Where a backup skill is restored, decided before anything is written.
+struct SkillRestore<'a> {
+    skill: &'a BackupSkill,
+    id: String,
+    name: String,
+    central_path: PathBuf,
+    action: &'static str,
+}
+
+/// Apply `policy` to every backup skill that clashes with a local one; skipped skills
+/// are recorded in `report`.
+fn plan_skill_restores<'a>(
+    store: &SkillStore,
+    central_dir: &Path,
+    manifest: &'a BackupManifest,
+    policy: ConflictPolicy,
+    report: &mut RestoreReport,
+) -> Result<Vec<SkillRestore<'a>>> {
+    let local_skills = store.list_skills()?;
+    let mut plans: Vec<SkillRestore> = Vec::new();
+    for skill in &manifest.skills {
+        let default_path = central_dir.join(&skill.dir);
+        if plans
+            .iter()
+            .any(|p| p.name == skill.name || p.central_path == default_path)
+        {
+            report.skipped.push(SkippedBundleSkill {
+                name: skill.name.clone(),
+                reason: "duplicate in backup".to_string(),
+            });
+            continue;
+        }
+        let existing = local_skills
+            .iter()
+            .find(|r| r.name == skill.name || Path::new(&r.central_path) == default_path);
+        let conflict = existing.is_some() || default_path.exists();
+        let id_taken =
+            |id: &str| local_skills.iter().any(|r| r.id == id) || plans.iter().any(|p| p.id == id);
+
+        let (id, name, central_path, action) = if !conflict {
+            let id = if id_taken(&skill.id) {
+                Uuid::new_v4().to_string()
+            } else {
+                skill.id.clone()
+            };
+            (id, skill.name.clone(), default_path, "created")
+        } else {
+            match policy {
+                ConflictPolicy::Skip => {
+                    report.skipped.push(SkippedBundleSkill {
+                        name: skill.name.clone(),
+                        reason: "already exists".to_string(),
+                    });
+                    continue;
+                }
+                ConflictPolicy::Replace => {
+                    let id = match existing {
+                        Some(record) => record.id.clone(),
+                        None if id_taken(&skill.id) => Uuid::new_v4().to_string(),
+                        None => skill.id.clone(),
+                    };
+                    let path = existing
+                        .map(|r| PathBuf::from(&r.central_path))
+                        .unwrap_or(default_path);
+                    (id, skill.name.clone(), path, "replaced")
+                }
+                ConflictPolicy::KeepBoth => {
+                    let name =
+                        unique_restored_name(&skill.name, central_dir, &local_skills, &plans);
+                    let path = central_dir.join(&name);
+                    (Uuid::new_v4().to_string(), name, path, "renamed")
+                }
+            }
+        };
+        plans.push(SkillRestore {
+            skill,
+            id,
+            name,
+            central_path,
+            action,
+        });
+    }
+    Ok(plans)
+}
+
+/// Record a skill whose folder is already in place and queue its tool targets.
+fn write_skill_record(
+    store: &SkillStore,
+    plan: &SkillRestore,
+    old_home: Option<&str>,
+    new_home: Option<&Path>,
+    report: &mut RestoreReport,
+) -> Result<()> {
+    let skill = plan.skill;
+    let source_ref = match skill.source_type.as_str() {
+        "local" => skill
+            .source_ref
+            .as_deref()
+            .map(|r| rebase_home(r, old_home, new_home)),
+        _ => skill.source_ref.clone(),
+    };
+    let now = now_ms();
+    let record = SkillRecord {
+        id: plan.id.clone(),
+        name: plan.name.clone(),
+        description: skill.description.clone(),
+        category: skill.category.clone(),
+        source_type: skill.source_type.clone(),
+        source_ref,
+        source_revision: skill.source_revision.clone(),
+        central_path: plan.central_path.to_string_lossy().to_string(),
+        content_hash: compute_content_hash(&plan.central_path),
+        created_at: skill.created_at,
+        updated_at: now,
+        last_sync_at: None,
+        last_seen_at: now,
+        status: "ok".to_string(),
+    };
+    store.upsert_skill(&record)?;
+
+    let target_name = plan
+        .central_path
+        .file_name()
+        .map(|v| v.to_string_lossy().to_string())
+        .unwrap_or_else(|| plan.name.clone());
+    for tool in &skill.targets {
+        report.pending_targets.push(PendingTarget {
+            skill_id: plan.id.clone(),
+            name: target_name.clone(),
+            central_path: plan.central_path.clone(),
+            tool: tool.clone(),
+        });
+    }
+    report.restored.push(RestoredSkill {
+        skill_id: plan.id.clone(),
+        name: plan.name.clone(),
+        action: plan.action.to_string(),
+    });
+    Ok(())
+}
+
+/// A restored folder moved into place; what it replaced is kept aside until the
+/// restore either commits or is rolled back.
+struct PlacedDir {
+    dest: PathBuf,
+    previous: Option<PathBuf>,
+}
+
+impl PlacedDir {
+    fn finish(self) {
+        if let Some(previous) = self.previous {
+            let _ = std::fs::remove_dir_all(previous);
+        }
+    }
+
+    fn undo(self) {
+        let _ = std::fs::remove_dir_all(&self.dest);
+        if let Some(previous) = self.previous {
+            let _ = std::fs::rename(previous, &self.dest);
+        }
+    }
+}
+
+/// Move `staged` to `dest` without leaving `dest` half-written: the copy lands in a
+/// sibling temp directory first, and the old contents are only moved aside once the new
+/// ones are complete.
+fn place_dir(staged: &Path, dest: &Path) -> Result<PlacedDir> {
+    let parent = dest
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("invalid restore path {:?}", dest))?;
+    std::fs::create_dir_all(parent).with_context(|| format!("create {:?}", parent))?;
+    let dir_name = dest
+        .file_name()
+        .map(|v| v.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let incoming = parent.join(format!(".{}.restore-{}", dir_name, Uuid::new_v4()));
+    if std::fs::rename(staged, &incoming).is_err() {
+        // A replaced skill may live outside the central repo (different filesystem).
+        if let Err(err) = copy_dir_recursive(staged, &incoming) {
+            let _ = std::fs::remove_dir_all(&incoming);
+            return Err(err).with_context(|| format!("copy {:?} -> {:?}", staged, incoming));
+        }
+    }
+
+    let previous = if dest.exists() {
+        let previous = parent.join(format!(".{}.previous-{}", dir_name, Uuid::new_v4()));
+        if let Err(err) = std::fs::rename(dest, &previous) {
+            let _ = std::fs::remove_dir_all(&incoming);
+            return Err(err).with_context(|| format!("move aside {:?}", dest));
+        }
+        Some(previous)
+    } else {
+        None
+    };
+    if let Err(err) = std::fs::rename(&incoming, dest) {
+        if let Some(previous) = &previous {
+            let _ = std::fs::rename(previous, dest);
+        }
+        let _ = std::fs::remove_dir_all(&incoming);
+        return Err(err).with_context(|| format!("move {:?} -> {:?}", incoming, dest));
+    }
+    Ok(PlacedDir {
+        dest: dest.to_path_buf(),
+        previous,
+    })
+}
+
+fn unique_restored_name(
+    name: &str,
+    central_dir: &Path,
+    local_skills: &[SkillRecord],
+    plans: &[SkillRestore],
+) -> String {
+    let taken = |candidate: &str| {
+        central_dir.join(candidate).exists()
+            || local_skills.iter().any(|r| r.name == candidate)
+            || plans.iter().any(|p| p.name == candidate)
+    };
+    let mut candidate = format!("{}-restored", name);
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{}-restored-{}", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// Extract the archive into `staging` and check every entry against the manifest.
+fn unpack_verified(archive: &Path, staging: &Path) -> Result<BackupManifest> {
+    std::fs::create_dir_all(staging).with_context(|| format!("create {:?}", staging))?;
+    let file = std::fs::File::open(archive).with_context(|| format!("open {:?}", archive))?;
+    let mut tar = tar::Archive::new(GzDecoder::new(file));
+
+    let mut manifest: Option<BackupManifest> = None;
+    let mut unpacked = HashSet::new();
+    let mut total: u64 = 0;
+    for entry in tar.entries().context("read backup archive")? {
+        let mut entry = entry.context("read backup entry")?;
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            continue;
+        }
+        let name = archive_entry_name(&entry.path()?)?;
+        if !entry_type.is_file() {
+            anyhow::bail!("unsupported entry in backup: {}", name);
+        }
+        total += entry.size();
+        if total > MAX_BACKUP_BYTES {
+            anyhow::bail!("backup is larger than {} bytes", MAX_BACKUP_BYTES);
+        }
+        if name == MANIFEST_NAME {
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            manifest = Some(serde_json::from_slice(&bytes).context("parse backup.json")?);
+            continue;
+        }
+        if !unpacked.insert(name.clone()) {
+            anyhow::bail!("duplicate entry in backup: {}", name);
+        }
+        let target = staging.join(&name);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).with_context(|| format!("create {:?}", parent))?;
+        }
+        let mut out =
+            std::fs::File::create(&target).with_context(|| format!("create {:?}", target))?;
+        std::io::copy(&mut entry, &mut out).with_context(|| format!("extract {}", name))?;
+    }
+
+    let manifest = manifest.ok_or_else(|| anyhow::anyhow!("backup has no backup.json"))?;
+    if manifest.format != BACKUP_FORMAT {
+        anyhow::bail!("not a Skills Hub backup (format: {})", manifest.format);
+    }
+    if manifest.version > BACKUP_VERSION {
+        anyhow::bail!(
+            "backup format version {} is newer than supported ({})",
+            manifest.version,
+            BACKUP_VERSION
+        );
+    }
+    for skill in &manifest.skills {
+        validate_skill_name(&skill.dir)?;
+    }
+
+    let mut expected = HashSet::new();
+    for file in &manifest.files {
+        let name = archive_entry_name(Path::new(&file.path))?;
+        if !unpacked.contains(&name) {
+            anyhow::bail!("integrity check failed: missing {}", name);
+        }
+        let (size, sha256) = hash_file(&staging.join(&name))?;
+        if size != file.size || sha256 != file.sha256 {
+            anyhow::bail!("integrity check failed: {} does not match manifest", name);
+        }
+        #[cfg(unix)]
+        if file.executable {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(staging.join(&name), std::fs::Permissions::from_mode(0o755))?;
+        }
+        expected.insert(name);
+    }
+    if let Some(extra) = unpacked.iter().find(|name| !expected.contains(*name)) {
+        anyhow::bail!("integrity check failed: unexpected entry {}", extra);
+    }
+    if manifest.has_analytics && !expected.contains(ANALYTICS_NAME) {
+        anyhow::bail!("integrity check failed: missing {}", ANALYTICS_NAME);
+    }
+
+    Ok(manifest)
+}
+
+fn is_machine_setting(key: &str) -> bool {
+    MACHINE_SETTINGS.contains(&key)
+        || MACHINE_SETTING_PREFIXES
+            .iter()
+            .any(|prefix| key.starts_with(prefix))
+}
+
+fn hash_file(path: &Path) -> Result<(u64, String)> {
+    let mut file = std::fs::File::open(path).with_context(|| format!("open {:?}", path))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher).with_context(|| format!("read {:?}", path))?;
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+/// Map `<old_home>/rest` to `<new_home>/rest`; anything else is returned unchanged.
+pub(crate) fn rebase_home(path: &str, old_home: Option<&str>, new_home: Option<&Path>) -> String {
+    let (Some(old_home), Some(new_home)) = (old_home, new_home) else {
+        return path.to_string();
+    };
+    if old_home.is_empty() {
+        return path.to_string();
+    }
+    match Path::new(path).strip_prefix(old_home) {
+        Ok(rest) if rest.as_os_str().is_empty() => new_home.to_string_lossy().to_string(),
+        Ok(rest) => new_home.join(rest).to_string_lossy().to_string(),
+        Err(_) => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/backup.rs"]
+mod tests;
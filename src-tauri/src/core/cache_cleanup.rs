@@ -1,11 +1,15 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tauri::Manager;
 
-use super::skill_store::SkillStore;
+use super::git_fetcher::remove_worktree;
+use super::installer::{git_cache_key_for_source, git_cache_lock};
+use super::skill_store::{GitCacheEntryRecord, SkillStore};
+use super::util::now_ms;
 
 const CACHE_DIR_NAME: &str = "skills-hub-git-cache";
 const CACHE_META_FILE: &str = ".skills-hub-cache.json";
@@ -15,12 +19,39 @@ const MAX_GIT_CACHE_CLEANUP_DAYS: i64 = 3650;
 pub const GIT_CACHE_TTL_SECS_KEY: &str = "git_cache_ttl_secs";
 pub const DEFAULT_GIT_CACHE_TTL_SECS: i64 = 60;
 const MAX_GIT_CACHE_TTL_SECS: i64 = 3600;
+pub const GIT_CACHE_MAX_SIZE_MB_KEY: &str = "git_cache_max_size_mb";
+pub const DEFAULT_GIT_CACHE_MAX_SIZE_MB: i64 = 2048;
+const MAX_GIT_CACHE_MAX_SIZE_MB: i64 = 1024 * 1024;
 
 #[derive(Debug, Deserialize)]
 struct RepoCacheMeta {
     last_fetched_ms: i64,
 }
 
+/// Disk usage of one cached repository (its mirror plus every worktree/tarball).
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCacheRepoUsage {
+    pub clone_url: String,
+    pub size_bytes: i64,
+    pub last_used_at: i64,
+    pub last_fetched_at: i64,
+    /// Cached refs; `None` stands for the remote default branch.
+    pub refs: Vec<Option<String>>,
+    /// Managed skills installed from this repository. Their entries are never evicted.
+    pub skill_ids: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCacheUsage {
+    pub total_bytes: i64,
+    /// Configured budget in bytes; `0` means unlimited.
+    pub max_bytes: i64,
+    /// Least recently used first.
+    pub repos: Vec<GitCacheRepoUsage>,
+}
+
 pub fn get_git_cache_cleanup_days(store: &SkillStore) -> i64 {
     let raw = store.get_setting(GIT_CACHE_CLEANUP_DAYS_KEY).ok().flatten();
     parse_cleanup_days(raw).unwrap_or(DEFAULT_GIT_CACHE_CLEANUP_DAYS)
@@ -53,15 +84,229 @@ pub fn set_git_cache_ttl_secs(store: &SkillStore, secs: i64) -> Result<i64> {
     Ok(secs)
 }
 
+pub fn get_git_cache_max_size_mb(store: &SkillStore) -> i64 {
+    let raw = store.get_setting(GIT_CACHE_MAX_SIZE_MB_KEY).ok().flatten();
+    parse_max_size_mb(raw).unwrap_or(DEFAULT_GIT_CACHE_MAX_SIZE_MB)
+}
+
+pub fn set_git_cache_max_size_mb(store: &SkillStore, mb: i64) -> Result<i64> {
+    if !(0..=MAX_GIT_CACHE_MAX_SIZE_MB).contains(&mb) {
+        anyhow::bail!(
+            "cache size limit must be between 0 and {} MB",
+            MAX_GIT_CACHE_MAX_SIZE_MB
+        );
+    }
+    store.set_setting(GIT_CACHE_MAX_SIZE_MB_KEY, &mb.to_string())?;
+    Ok(mb)
+}
+
+/// Cache keys referenced by managed git skills, with the referencing skill ids. Combines
+/// the recorded install refs with keys derived from each skill's current `source_ref`.
+fn referenced_cache_keys(store: &SkillStore) -> Result<HashMap<String, Vec<String>>> {
+    let mut refs: HashMap<String, Vec<String>> = HashMap::new();
+    for (key, skill_id) in store.list_git_cache_skill_refs()? {
+        refs.entry(key).or_default().push(skill_id);
+    }
+    for skill in store.list_skills()? {
+        let Some(source_ref) = skill
+            .source_ref
+            .as_deref()
+            .filter(|_| skill.source_type == "git")
+        else {
+            continue;
+        };
+        let ids = refs
+            .entry(git_cache_key_for_source(source_ref))
+            .or_default();
+        if !ids.contains(&skill.id) {
+            ids.push(skill.id);
+        }
+    }
+    Ok(refs)
+}
+
+/// Per-repository disk usage from the cache index, least recently used first.
+pub fn get_git_cache_usage(store: &SkillStore) -> Result<GitCacheUsage> {
+    let referenced = referenced_cache_keys(store)?;
+    let mut repos: BTreeMap<String, GitCacheRepoUsage> = BTreeMap::new();
+    for entry in store.list_git_cache_entries()? {
+        let repo = repos
+            .entry(entry.clone_url.clone())
+            .or_insert_with(|| GitCacheRepoUsage {
+                clone_url: entry.clone_url.clone(),
+                size_bytes: 0,
+                last_used_at: 0,
+                last_fetched_at: 0,
+                refs: Vec::new(),
+                skill_ids: Vec::new(),
+            });
+        repo.size_bytes += entry.size_bytes;
+        repo.last_used_at = repo.last_used_at.max(entry.last_used_at);
+        repo.last_fetched_at = repo.last_fetched_at.max(entry.last_fetched_at);
+        if entry.kind == "mirror" {
+            continue;
+        }
+        if !repo.refs.contains(&entry.ref_name) {
+            repo.refs.push(entry.ref_name.clone());
+        }
+        for id in referenced.get(&entry.key).into_iter().flatten() {
+            if !repo.skill_ids.contains(id) {
+                repo.skill_ids.push(id.clone());
+            }
+        }
+    }
+
+    let mut repos: Vec<GitCacheRepoUsage> = repos.into_values().collect();
+    repos.sort_by_key(|r| r.last_used_at);
+    Ok(GitCacheUsage {
+        total_bytes: repos.iter().map(|r| r.size_bytes).sum(),
+        max_bytes: get_git_cache_max_size_mb(store).saturating_mul(1024 * 1024),
+        repos,
+    })
+}
+
+/// Evict least recently used cache entries until the cache fits the configured size
+/// budget. Entries referenced by managed skills, and `keep` (the entry in use), stay.
+pub fn enforce_git_cache_budget(store: &SkillStore, keep: Option<&str>) -> Result<usize> {
+    let max_bytes = get_git_cache_max_size_mb(store).saturating_mul(1024 * 1024);
+    if max_bytes <= 0 {
+        return Ok(0);
+    }
+    let entries = store.list_git_cache_entries()?;
+    let mut total: i64 = entries.iter().map(|e| e.size_bytes).sum();
+    if total <= max_bytes {
+        return Ok(0);
+    }
+
+    let referenced = referenced_cache_keys(store)?;
+    let mirror_sizes: HashMap<String, i64> = entries
+        .iter()
+        .filter(|e| e.kind == "mirror")
+        .map(|e| (e.key.clone(), e.size_bytes))
+        .collect();
+    let mut mirror_users: HashMap<String, usize> = HashMap::new();
+    for key in entries.iter().filter_map(|e| e.mirror_key.clone()) {
+        *mirror_users.entry(key).or_default() += 1;
+    }
+
+    let removed = evict_git_cache_entries(store, |entry| {
+        if total <= max_bytes
+            || Some(entry.key.as_str()) == keep
+            || referenced.contains_key(&entry.key)
+        {
+            return false;
+        }
+        total -= entry.size_bytes;
+        if let Some(mirror) = entry.mirror_key.as_deref() {
+            let users = mirror_users.entry(mirror.to_string()).or_default();
+            *users = users.saturating_sub(1);
+            if *users == 0 {
+                total -= mirror_sizes.get(mirror).copied().unwrap_or(0);
+            }
+        }
+        true
+    })?;
+    if total > max_bytes {
+        log::info!(
+            "[cache_cleanup] git cache still {} bytes over budget; remaining entries are in use",
+            total - max_bytes
+        );
+    }
+    Ok(removed)
+}
+
+/// Remove cache entries not used within `max_age`: indexed mirrors/worktrees/tarballs by
+/// their last use (unless a managed skill references them), and directories from the
+/// older one-clone-per-ref layout by age.
 pub fn cleanup_git_cache_dirs<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
+    store: &SkillStore,
     max_age: Duration,
 ) -> Result<usize> {
     let cache_dir = app
         .path()
         .app_cache_dir()
         .context("failed to resolve app cache dir")?;
-    cleanup_git_cache_dirs_in(&cache_dir, max_age)
+    let cutoff_ms = now_ms().saturating_sub(max_age.as_millis().try_into().unwrap_or(i64::MAX));
+    let referenced = referenced_cache_keys(store)?;
+    let removed = evict_git_cache_entries(store, |entry| {
+        entry.last_used_at <= cutoff_ms && !referenced.contains_key(&entry.key)
+    })?;
+    Ok(removed + cleanup_git_cache_dirs_in(&cache_dir, max_age)?)
+}
+
+/// Walk the cache index least recently used first and remove every worktree, clone or
+/// tarball entry `should_evict` accepts (entries whose directory vanished are dropped
+/// too). A mirror is removed once no remaining worktree uses it. Returns how many
+/// directories were deleted.
+pub fn evict_git_cache_entries(
+    store: &SkillStore,
+    mut should_evict: impl FnMut(&GitCacheEntryRecord) -> bool,
+) -> Result<usize> {
+    let entries = store.list_git_cache_entries()?;
+    let mirror_path = |key: &str| {
+        entries
+            .iter()
+            .find(|e| e.kind == "mirror" && e.key == key)
+            .map(|e| PathBuf::from(&e.path))
+    };
+
+    let mut removed = 0usize;
+    let mut used_mirrors = HashSet::new();
+    for entry in entries.iter().filter(|e| e.kind != "mirror") {
+        // Leave a repository alone while it is being fetched.
+        let lock = git_cache_lock(&entry.clone_url);
+        let Ok(_guard) = lock.try_lock() else {
+            used_mirrors.extend(entry.mirror_key.clone());
+            continue;
+        };
+        let path = Path::new(&entry.path);
+        let exists = path.exists();
+        if exists && !should_evict(entry) {
+            used_mirrors.extend(entry.mirror_key.clone());
+            continue;
+        }
+        match entry.mirror_key.as_deref().and_then(mirror_path) {
+            Some(mirror) => remove_worktree(&mirror, path)?,
+            None if exists => {
+                std::fs::remove_dir_all(path).with_context(|| format!("remove {:?}", path))?
+            }
+            None => {}
+        }
+        store.delete_git_cache_entry(&entry.key)?;
+        removed += usize::from(exists);
+    }
+
+    for mirror in entries.iter().filter(|e| e.kind == "mirror") {
+        if used_mirrors.contains(&mirror.key) {
+            continue;
+        }
+        let lock = git_cache_lock(&mirror.clone_url);
+        let Ok(_guard) = lock.try_lock() else {
+            continue;
+        };
+        let path = Path::new(&mirror.path);
+        let exists = path.exists();
+        if exists {
+            std::fs::remove_dir_all(path).with_context(|| format!("remove {:?}", path))?;
+        }
+        store.delete_git_cache_entry(&mirror.key)?;
+        removed += usize::from(exists);
+    }
+    Ok(removed)
+}
+
+/// Total size of the files under `path`, without following symlinks.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|rd| rd.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or(0)
 }
 
 fn cleanup_git_cache_dirs_in(cache_dir: &Path, max_age: Duration) -> Result<usize> {
@@ -93,7 +338,8 @@ fn cleanup_git_cache_dirs_in(cache_dir: &Path, max_age: Duration) -> Result<usiz
             continue;
         }
 
-        if !path.join(".git").exists() {
+        // Indexed layout dirs (`mirrors/`, `worktrees/`, `tarballs/`) have neither marker.
+        if !path.join(".git").exists() && !path.join(CACHE_META_FILE).exists() {
             continue;
         }
 
@@ -136,6 +382,15 @@ fn parse_cleanup_days(raw: Option<String>) -> Option<i64> {
     }
 }
 
+fn parse_max_size_mb(raw: Option<String>) -> Option<i64> {
+    let value = raw?.trim().parse::<i64>().ok()?;
+    if !(0..=MAX_GIT_CACHE_MAX_SIZE_MB).contains(&value) {
+        None
+    } else {
+        Some(value)
+    }
+}
+
 fn parse_cache_ttl_secs(raw: Option<String>) -> Option<i64> {
     let value = raw?.trim().parse::<i64>().ok()?;
     if !(0..=MAX_GIT_CACHE_TTL_SECS).contains(&value) {
@@ -145,9 +400,6 @@ fn parse_cache_ttl_secs(raw: Option<String>) -> Option<i64> {
     }
 }
 
-fn now_ms() -> i64 {
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap_or_default();
-    now.as_millis() as i64
-}
+#[cfg(test)]
+#[path = "tests/cache_cleanup.rs"]
+mod tests;
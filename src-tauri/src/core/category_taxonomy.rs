@@ -0,0 +1,470 @@
+//! Category taxonomy of discovered skills.
+//!
+//! Built-in categories have stable ids and a name per locale; categories the user adds
+//! are stored as rows with the name they typed. Registry sources list skills under their
+//! own headings or topics, which mapping rules (keyword or regex, tried in order) turn
+//! into a category id. Changing the rules re-categorizes the skills already synced.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::discovery::CategoryInfo;
+use super::skill_store::{CategoryRuleRecord, SkillStore};
+use super::util::now_ms;
+
+/// Set once the default rules have been added, so rules the user removed stay removed.
+const DEFAULT_RULES_SEEDED_KEY: &str = "category_rules_seeded";
+
+/// Category of skills no rule matches.
+pub const FALLBACK_CATEGORY: &str = "other";
+
+struct BuiltinCategory {
+    id: &'static str,
+    icon: &'static str,
+    color: &'static str,
+    /// `(name, description)` in English and Chinese.
+    en: (&'static str, &'static str),
+    zh: (&'static str, &'static str),
+}
+
+const BUILTIN_CATEGORIES: &[BuiltinCategory] = &[
+    BuiltinCategory {
+        id: "development",
+        icon: "💻",
+        color: "#3b82f6",
+        en: (
+            "Development",
+            "Tools and skills for writing and shipping code",
+        ),
+        zh: ("开发工具", "提升开发效率的工具和技能"),
+    },
+    BuiltinCategory {
+        id: "productivity",
+        icon: "⚡",
+        color: "#f59e0b",
+        en: ("Productivity", "Skills that speed up everyday work"),
+        zh: ("生产力", "提高工作效率的技能"),
+    },
+    BuiltinCategory {
+        id: "ai",
+        icon: "🤖",
+        color: "#8b5cf6",
+        en: ("AI Assistance", "Skills and tools around AI models"),
+        zh: ("AI 辅助", "AI 相关的技能和工具"),
+    },
+    BuiltinCategory {
+        id: "documentation",
+        icon: "📚",
+        color: "#10b981",
+        en: ("Documentation", "Writing and maintaining documentation"),
+        zh: ("文档", "文档编写和管理技能"),
+    },
+    BuiltinCategory {
+        id: "document",
+        icon: "📄",
+        color: "#0ea5e9",
+        en: ("Documents", "Creating and editing office files and PDFs"),
+        zh: ("文档处理", "创建和编辑办公文档与 PDF"),
+    },
+    BuiltinCategory {
+        id: "data-analysis",
+        icon: "📊",
+        color: "#14b8a6",
+        en: (
+            "Data & Analysis",
+            "Working with data sets, queries and charts",
+        ),
+        zh: ("数据分析", "处理数据、查询与图表"),
+    },
+    BuiltinCategory {
+        id: "research",
+        icon: "🔬",
+        color: "#6366f1",
+        en: ("Research", "Scientific and academic research"),
+        zh: ("科研", "科学与学术研究"),
+    },
+    BuiltinCategory {
+        id: "writing",
+        icon: "✍️",
+        color: "#ec4899",
+        en: ("Writing", "Drafting, editing and copywriting"),
+        zh: ("写作", "起草、编辑与文案"),
+    },
+    BuiltinCategory {
+        id: "learning",
+        icon: "🎓",
+        color: "#84cc16",
+        en: ("Learning", "Studying and managing knowledge"),
+        zh: ("学习", "学习与知识管理"),
+    },
+    BuiltinCategory {
+        id: "media",
+        icon: "🎬",
+        color: "#f43f5e",
+        en: (
+            "Media & Content",
+            "Images, video, audio and content creation",
+        ),
+        zh: ("媒体与内容", "图片、视频、音频与内容创作"),
+    },
+    BuiltinCategory {
+        id: "health",
+        icon: "🩺",
+        color: "#22c55e",
+        en: ("Health", "Health and wellbeing"),
+        zh: ("健康", "健康与生活"),
+    },
+    BuiltinCategory {
+        id: "collaboration",
+        icon: "🤝",
+        color: "#a855f7",
+        en: ("Collaboration", "Teamwork and project management"),
+        zh: ("协作", "团队协作与项目管理"),
+    },
+    BuiltinCategory {
+        id: "security",
+        icon: "🛡️",
+        color: "#ef4444",
+        en: ("Security & Testing", "Security reviews and testing"),
+        zh: ("安全与测试", "安全审查与测试"),
+    },
+    BuiltinCategory {
+        id: "utility",
+        icon: "🧰",
+        color: "#64748b",
+        en: ("Utilities & Automation", "Everyday helpers and automation"),
+        zh: ("工具与自动化", "日常辅助与自动化"),
+    },
+    BuiltinCategory {
+        id: "collections",
+        icon: "📦",
+        color: "#78716c",
+        en: ("Collections", "Bundles of several skills"),
+        zh: ("合集", "多个技能的合集"),
+    },
+    BuiltinCategory {
+        id: FALLBACK_CATEGORY,
+        icon: "🏷️",
+        color: "#9ca3af",
+        en: ("Other", "Skills without a matching category"),
+        zh: ("其他", "未匹配到分类的技能"),
+    },
+];
+
+/// Rules added on first use: the category headings of the awesome lists the app ships
+/// with, in the order they used to be checked.
+const DEFAULT_RULES: &[(&str, &str)] = &[
+    ("document", "document"),
+    ("development|code", "development"),
+    ("data|analysis", "data-analysis"),
+    ("scientific|research", "research"),
+    ("writing", "writing"),
+    ("learning|knowledge", "learning"),
+    ("media|content", "media"),
+    ("health", "health"),
+    ("collaboration|project", "collaboration"),
+    ("security|testing", "security"),
+    ("utility|automation", "utility"),
+    ("collection", "collections"),
+];
+
+/// Whether `id` is a built-in category, which cannot be added or removed.
+pub fn is_builtin_category(id: &str) -> bool {
+    BUILTIN_CATEGORIES.iter().any(|category| category.id == id)
+}
+
+/// Built-in categories named in `locale` (`zh`, `zh-CN`, ...; anything else is English).
+pub fn builtin_categories(locale: &str) -> Vec<CategoryInfo> {
+    let chinese = locale.to_ascii_lowercase().starts_with("zh");
+    BUILTIN_CATEGORIES
+        .iter()
+        .map(|category| {
+            let (name, description) = if chinese { category.zh } else { category.en };
+            CategoryInfo {
+                id: category.id.to_string(),
+                name: name.to_string(),
+                description: description.to_string(),
+                icon: category.icon.to_string(),
+                color: category.color.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Built-in categories in `locale`, followed by the ones the user added.
+pub fn list_categories(store: &SkillStore, locale: &str) -> Result<Vec<CategoryInfo>> {
+    let mut categories = builtin_categories(locale);
+    categories.extend(
+        store
+            .list_categories()?
+            .into_iter()
+            .filter(|category| !is_builtin_category(&category.id)),
+    );
+    Ok(categories)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CategoryRuleKind {
+    /// Case-insensitive substring of the listed category.
+    Keyword,
+    /// Case-insensitive regular expression searched in the listed category.
+    Regex,
+}
+
+impl CategoryRuleKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Regex => "regex",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "keyword" => Some(Self::Keyword),
+            "regex" => Some(Self::Regex),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryRule {
+    pub id: String,
+    pub kind: CategoryRuleKind,
+    pub pattern: String,
+    pub category_id: String,
+    pub position: i64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryRuleInput {
+    /// Rule to update; a new rule is added without one.
+    pub id: Option<String>,
+    pub kind: CategoryRuleKind,
+    pub pattern: String,
+    pub category_id: String,
+    /// Defaults to after the last rule for new rules, and to the current one otherwise.
+    pub position: Option<i64>,
+}
+
+enum Matcher {
+    Keyword(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(kind: CategoryRuleKind, pattern: &str) -> Result<Self> {
+        Ok(match kind {
+            CategoryRuleKind::Keyword => Self::Keyword(pattern.to_lowercase()),
+            CategoryRuleKind::Regex => Self::Regex(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .with_context(|| format!("invalid category rule pattern: {}", pattern))?,
+            ),
+        })
+    }
+
+    fn matches(&self, raw_category: &str) -> bool {
+        match self {
+            Self::Keyword(keyword) => raw_category.to_lowercase().contains(keyword),
+            Self::Regex(regex) => regex.is_match(raw_category),
+        }
+    }
+}
+
+/// The mapping rules, ready to categorize skills.
+pub struct CategoryRules {
+    rules: Vec<(Matcher, String)>,
+}
+
+impl CategoryRules {
+    /// The stored rules, seeding the defaults first if needed. Rules pointing at a
+    /// category that has since been removed are skipped.
+    pub fn load(store: &SkillStore) -> Result<Self> {
+        ensure_default_category_rules(store)?;
+        let known: HashSet<String> = list_categories(store, "en")?
+            .into_iter()
+            .map(|category| category.id)
+            .collect();
+        let records: Vec<_> = store
+            .list_category_rules()?
+            .into_iter()
+            .filter(|record| known.contains(&record.category_id))
+            .collect();
+        Ok(Self::from_records(&records))
+    }
+
+    fn from_records(records: &[CategoryRuleRecord]) -> Self {
+        let rules = records
+            .iter()
+            .filter_map(|record| {
+                let kind = CategoryRuleKind::parse(&record.kind)?;
+                match Matcher::new(kind, &record.pattern) {
+                    Ok(matcher) => Some((matcher, record.category_id.clone())),
+                    Err(err) => {
+                        log::warn!("[categories] skipping rule {}: {:#}", record.id, err);
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Category id of a skill a source listed under `raw_category`.
+    pub fn categorize(&self, raw_category: &str) -> String {
+        self.rules
+            .iter()
+            .find(|(matcher, _)| matcher.matches(raw_category))
+            .map(|(_, category_id)| category_id.clone())
+            .unwrap_or_else(|| FALLBACK_CATEGORY.to_string())
+    }
+}
+
+/// Add the default rules the first time the app runs with configurable rules.
+pub fn ensure_default_category_rules(store: &SkillStore) -> Result<()> {
+    if store.get_setting(DEFAULT_RULES_SEEDED_KEY)?.is_some() {
+        return Ok(());
+    }
+    if store.list_category_rules()?.is_empty() {
+        store.replace_category_rules(&default_rule_records(now_ms()))?;
+    }
+    store.set_setting(DEFAULT_RULES_SEEDED_KEY, "true")?;
+    Ok(())
+}
+
+fn default_rule_records(now: i64) -> Vec<CategoryRuleRecord> {
+    DEFAULT_RULES
+        .iter()
+        .enumerate()
+        .map(|(index, (pattern, category_id))| CategoryRuleRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind: CategoryRuleKind::Regex.as_str().to_string(),
+            pattern: pattern.to_string(),
+            category_id: category_id.to_string(),
+            position: index as i64,
+            created_at: now,
+            updated_at: now,
+        })
+        .collect()
+}
+
+pub fn list_category_rules(store: &SkillStore) -> Result<Vec<CategoryRule>> {
+    ensure_default_category_rules(store)?;
+    Ok(store
+        .list_category_rules()?
+        .into_iter()
+        .filter_map(|record| {
+            Some(CategoryRule {
+                kind: CategoryRuleKind::parse(&record.kind)?,
+                id: record.id,
+                pattern: record.pattern,
+                category_id: record.category_id,
+                position: record.position,
+            })
+        })
+        .collect())
+}
+
+/// Add or update a rule and re-categorize the discovered skills.
+pub fn save_category_rule(store: &SkillStore, input: CategoryRuleInput) -> Result<CategoryRule> {
+    let pattern = input.pattern.trim().to_string();
+    if pattern.is_empty() {
+        anyhow::bail!("category rule pattern is empty");
+    }
+    Matcher::new(input.kind, &pattern)?;
+    let category_id = input.category_id.trim().to_string();
+    if !list_categories(store, "en")?
+        .iter()
+        .any(|category| category.id == category_id)
+    {
+        anyhow::bail!("unknown category: {}", category_id);
+    }
+
+    ensure_default_category_rules(store)?;
+    let records = store.list_category_rules()?;
+    let now = now_ms();
+    let (id, created_at, current_position) = match input.id {
+        Some(id) => {
+            let existing = records
+                .iter()
+                .find(|record| record.id == id)
+                .with_context(|| format!("category rule not found: {}", id))?;
+            (id, existing.created_at, existing.position)
+        }
+        None => {
+            let next = records
+                .iter()
+                .map(|record| record.position + 1)
+                .max()
+                .unwrap_or(0);
+            (uuid::Uuid::new_v4().to_string(), now, next)
+        }
+    };
+    let record = CategoryRuleRecord {
+        id,
+        kind: input.kind.as_str().to_string(),
+        pattern,
+        category_id,
+        position: input.position.unwrap_or(current_position),
+        created_at,
+        updated_at: now,
+    };
+    store.upsert_category_rule(&record)?;
+    recategorize_discovered_skills(store)?;
+    Ok(CategoryRule {
+        id: record.id,
+        kind: input.kind,
+        pattern: record.pattern,
+        category_id: record.category_id,
+        position: record.position,
+    })
+}
+
+/// Remove a rule and re-categorize the discovered skills.
+pub fn delete_category_rule(store: &SkillStore, id: &str) -> Result<()> {
+    if !store.delete_category_rule(id)? {
+        anyhow::bail!("category rule not found: {}", id);
+    }
+    recategorize_discovered_skills(store)?;
+    Ok(())
+}
+
+/// Replace the rules with the defaults and re-categorize the discovered skills.
+pub fn reset_category_rules(store: &SkillStore) -> Result<Vec<CategoryRule>> {
+    store.replace_category_rules(&default_rule_records(now_ms()))?;
+    store.set_setting(DEFAULT_RULES_SEEDED_KEY, "true")?;
+    recategorize_discovered_skills(store)?;
+    list_category_rules(store)
+}
+
+/// Apply the current rules to every discovered skill whose listed category is known.
+/// Returns how many skills moved to another category.
+pub fn recategorize_discovered_skills(store: &SkillStore) -> Result<usize> {
+    let rules = CategoryRules::load(store)?;
+    let updates: Vec<(String, String)> = store
+        .list_discovered_skill_categories()?
+        .into_iter()
+        .filter_map(|(id, raw_category, category)| {
+            let next = rules.categorize(raw_category.as_deref()?);
+            (next != category).then_some((id, next))
+        })
+        .collect();
+    let changed = store.set_discovered_skill_categories(&updates)?;
+    if changed > 0 {
+        log::info!("[categories] re-categorized {} discovered skills", changed);
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+#[path = "tests/category_taxonomy.rs"]
+mod tests;
@@ -427,6 +427,7 @@ fn stream_anthropic_response(
         "{}/v1/messages",
         base_url.unwrap_or("https://api.anthropic.com")
     );
+    crate::core::network::ensure_online_for(&url)?;
 
     // Build the messages array (filter out system messages for the body)
     let system_prompt: Option<String> = messages
@@ -464,7 +465,7 @@ fn stream_anthropic_response(
         body["system"] = serde_json::Value::String(system);
     }
 
-    let client = reqwest::blocking::Client::new();
+    let client = crate::core::network::http_client()?;
     let response = client
         .post(&url)
         .header("Content-Type", "application/json")
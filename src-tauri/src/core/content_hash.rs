@@ -4,23 +4,26 @@ use anyhow::{Context, Result};
 use sha2::{Digest, Sha256};
 use walkdir::{DirEntry, WalkDir};
 
-const IGNORE_NAMES: [&str; 4] = [".git", ".DS_Store", "Thumbs.db", ".gitignore"];
+pub(crate) const IGNORE_NAMES: [&str; 4] = [".git", ".DS_Store", "Thumbs.db", ".gitignore"];
 
 fn is_ignored(entry: &DirEntry) -> bool {
     let file_name = entry.file_name().to_string_lossy();
     IGNORE_NAMES.iter().any(|name| name == &file_name.as_ref())
 }
 
+/// Hash of the regular files under `path` (names and contents) in a stable order, so the
+/// same skill hashes the same on every machine.
 pub fn hash_dir(path: &Path) -> Result<String> {
     let mut hasher = Sha256::new();
 
     for entry in WalkDir::new(path)
         .follow_links(false)
+        .sort_by_file_name()
         .into_iter()
         .filter_entry(|entry| !is_ignored(entry))
     {
         let entry = entry?;
-        if is_ignored(&entry) {
+        if is_ignored(&entry) || !entry.file_type().is_file() {
             continue;
         }
 
@@ -28,13 +31,17 @@ pub fn hash_dir(path: &Path) -> Result<String> {
             .path()
             .strip_prefix(path)
             .with_context(|| format!("strip prefix {:?}", entry.path()))?;
-        hasher.update(relative.to_string_lossy().as_bytes());
-
-        if entry.file_type().is_file() {
-            let bytes = std::fs::read(entry.path())
-                .with_context(|| format!("read file {:?}", entry.path()))?;
-            hasher.update(bytes);
-        }
+        let relative = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+
+        let bytes =
+            std::fs::read(entry.path()).with_context(|| format!("read file {:?}", entry.path()))?;
+        hasher.update(bytes);
     }
 
     let digest = hasher.finalize();
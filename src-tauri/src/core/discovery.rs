@@ -1,5 +1,11 @@
-use super::discovery_config::{DiscoveryConfig, RecommendedSkill, SkillCategory};
-use super::discovery_remote::{fetch_all_category_skills, DEFAULT_SKILLS_PER_CATEGORY};
+use super::category_taxonomy::builtin_categories;
+use super::discovery_config::{DiscoveryConfig, RecommendedSkill};
+use super::discovery_remote::{
+    fetch_all_category_skills, fetch_skills_by_category, DEFAULT_SKILLS_PER_CATEGORY,
+};
+use super::network;
+use super::registry_sources::{is_registry_source_enabled, RECOMMENDED_ID, SKILLS_SH_POPULAR_ID};
+use super::skill_store::{DiscoveredSkillRecord, SkillStore};
 
 /// 发现的 Skill 信息（简化版）
 #[derive(Debug, Clone, serde::Serialize)]
@@ -21,18 +27,6 @@ pub struct CategoryInfo {
     pub color: String,
 }
 
-impl From<SkillCategory> for CategoryInfo {
-    fn from(cat: SkillCategory) -> Self {
-        CategoryInfo {
-            id: cat.id,
-            name: cat.name,
-            description: cat.description,
-            icon: cat.icon,
-            color: cat.color,
-        }
-    }
-}
-
 impl From<RecommendedSkill> for DiscoveredSkill {
     fn from(skill: RecommendedSkill) -> Self {
         DiscoveredSkill {
@@ -45,17 +39,54 @@ impl From<RecommendedSkill> for DiscoveredSkill {
     }
 }
 
+/// 内置推荐列表；对应的技能源停用时为空
+fn builtin_recommendations(store: &SkillStore) -> DiscoveryConfig {
+    if is_registry_source_enabled(store, RECOMMENDED_ID) {
+        DiscoveryConfig::get_default()
+    } else {
+        DiscoveryConfig { skills: Vec::new() }
+    }
+}
+
+impl From<DiscoveredSkillRecord> for DiscoveredSkill {
+    fn from(record: DiscoveredSkillRecord) -> Self {
+        DiscoveredSkill {
+            name: record.name,
+            description: record.description,
+            github_url: record.github_url,
+            category: record.category,
+            tags: record
+                .tags
+                .split(',')
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+        }
+    }
+}
+
 /// 获取所有推荐的技能（混合本地和远程数据）
-pub fn get_recommended_skills() -> Vec<DiscoveredSkill> {
-    let config = DiscoveryConfig::get_default();
+pub fn get_recommended_skills(
+    store: &SkillStore,
+    github_token: Option<&str>,
+) -> Vec<DiscoveredSkill> {
+    let config = builtin_recommendations(store);
     let mut skills: Vec<DiscoveredSkill> = config
         .skills
         .into_iter()
         .map(DiscoveredSkill::from)
         .collect();
     
+    // 离线时只返回内置推荐，避免等待网络超时
+    if network::is_offline() {
+        return skills;
+    }
+
     // 尝试从远程获取更多技能
-    if let Ok(remote_skills_map) = fetch_all_category_skills(DEFAULT_SKILLS_PER_CATEGORY) {
+    let popular = is_registry_source_enabled(store, SKILLS_SH_POPULAR_ID);
+    if let Ok(remote_skills_map) =
+        fetch_all_category_skills(DEFAULT_SKILLS_PER_CATEGORY, github_token, popular)
+    {
         for (category_id, remote_skills) in remote_skills_map {
             // 过滤掉已存在的技能（通过 github_url 去重）
             let existing_urls: std::collections::HashSet<&str> = skills
@@ -83,21 +114,39 @@ pub fn get_recommended_skills() -> Vec<DiscoveredSkill> {
     skills
 }
 
-/// 获取所有分类
+/// 按分类从远程获取技能；离线时回退到内置推荐列表
+pub fn fetch_category_skills(
+    store: &SkillStore,
+    category_id: &str,
+    limit: usize,
+    github_token: Option<&str>,
+) -> anyhow::Result<Vec<DiscoveredSkill>> {
+    if network::is_offline() {
+        return Ok(get_skills_by_category(store, category_id));
+    }
+    let popular = is_registry_source_enabled(store, SKILLS_SH_POPULAR_ID);
+    let skills = fetch_skills_by_category(category_id, limit, github_token, popular)?;
+    Ok(skills
+        .into_iter()
+        .map(|remote| DiscoveredSkill {
+            name: remote.name,
+            description: remote.description,
+            github_url: remote.github_url,
+            category: remote.category,
+            tags: remote.tags,
+        })
+        .collect())
+}
+
+/// 获取所有内置分类（按语言显示名称）
 #[allow(dead_code)]
-pub fn get_categories() -> Vec<CategoryInfo> {
-    let config = DiscoveryConfig::get_default();
-    config
-        .categories
-        .values()
-        .cloned()
-        .map(CategoryInfo::from)
-        .collect()
+pub fn get_categories(locale: &str) -> Vec<CategoryInfo> {
+    builtin_categories(locale)
 }
 
 /// 按分类获取技能
-pub fn get_skills_by_category(category_id: &str) -> Vec<DiscoveredSkill> {
-    let config = DiscoveryConfig::get_default();
+pub fn get_skills_by_category(store: &SkillStore, category_id: &str) -> Vec<DiscoveredSkill> {
+    let config = builtin_recommendations(store);
     config
         .get_skills_by_category(category_id)
         .into_iter()
@@ -105,12 +154,11 @@ pub fn get_skills_by_category(category_id: &str) -> Vec<DiscoveredSkill> {
         .collect()
 }
 
-/// 搜索技能
-pub fn search_skills(query: &str) -> Vec<DiscoveredSkill> {
-    let config = DiscoveryConfig::get_default();
-    config
-        .search_skills(query)
+/// 搜索技能：在发现目录的全文索引中检索（内置列表也是技能源），最相关的排在前面
+pub fn search_skills(store: &SkillStore, query: &str) -> anyhow::Result<Vec<DiscoveredSkill>> {
+    Ok(store
+        .search_discovered_skills(query)?
         .into_iter()
-        .map(|skill| DiscoveredSkill::from(skill.clone()))
-        .collect()
-}
\ No newline at end of file
+        .map(DiscoveredSkill::from)
+        .collect())
+}
@@ -1,5 +1,4 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 /// 推荐的技能配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,75 +6,19 @@ pub struct RecommendedSkill {
     pub name: String,
     pub description: String,
     pub github_url: String,
+    /// 分类 id，见 `category_taxonomy`
     pub category: String,
     pub tags: Vec<String>,
 }
 
-/// 技能分类配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SkillCategory {
-    pub id: String,
-    pub name: String,
-    pub description: String,
-    pub icon: String,
-    pub color: String,
-}
-
 /// 发现配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscoveryConfig {
-    pub categories: HashMap<String, SkillCategory>,
     pub skills: Vec<RecommendedSkill>,
 }
 
 impl Default for DiscoveryConfig {
     fn default() -> Self {
-        let mut categories = HashMap::new();
-        
-        categories.insert(
-            "development".to_string(),
-            SkillCategory {
-                id: "development".to_string(),
-                name: "开发工具".to_string(),
-                description: "提升开发效率的工具和技能".to_string(),
-                icon: "💻".to_string(),
-                color: "#3b82f6".to_string(),
-            },
-        );
-        
-        categories.insert(
-            "productivity".to_string(),
-            SkillCategory {
-                id: "productivity".to_string(),
-                name: "生产力".to_string(),
-                description: "提高工作效率的技能".to_string(),
-                icon: "⚡".to_string(),
-                color: "#f59e0b".to_string(),
-            },
-        );
-        
-        categories.insert(
-            "ai".to_string(),
-            SkillCategory {
-                id: "ai".to_string(),
-                name: "AI 辅助".to_string(),
-                description: "AI 相关的技能和工具".to_string(),
-                icon: "🤖".to_string(),
-                color: "#8b5cf6".to_string(),
-            },
-        );
-        
-        categories.insert(
-            "documentation".to_string(),
-            SkillCategory {
-                id: "documentation".to_string(),
-                name: "文档".to_string(),
-                description: "文档编写和管理技能".to_string(),
-                icon: "📚".to_string(),
-                color: "#10b981".to_string(),
-            },
-        );
-
         let skills = vec![
             // 开发工具
             RecommendedSkill {
@@ -128,10 +71,7 @@ impl Default for DiscoveryConfig {
             },
         ];
 
-        DiscoveryConfig {
-            categories,
-            skills,
-        }
+        DiscoveryConfig { skills }
     }
 }
 
@@ -148,17 +88,4 @@ impl DiscoveryConfig {
             .filter(|skill| skill.category == category_id)
             .collect()
     }
-    
-    /// 搜索技能
-    pub fn search_skills(&self, query: &str) -> Vec<&RecommendedSkill> {
-        let query_lower = query.to_lowercase();
-        self.skills
-            .iter()
-            .filter(|skill| {
-                skill.name.to_lowercase().contains(&query_lower)
-                    || skill.description.to_lowercase().contains(&query_lower)
-                    || skill.tags.iter().any(|tag| tag.to_lowercase().contains(&query_lower))
-            })
-            .collect()
-    }
 }
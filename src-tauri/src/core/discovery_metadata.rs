@@ -0,0 +1,830 @@
+//! Repository metadata for discovered skills.
+//!
+//! The enricher resolves each listed skill's GitHub repository (stars, last push,
+//! license, archived flag) and reads the `SKILL.md` frontmatter at the skill's
+//! path. Results are cached in `discovered_skill_metadata` and refreshed once they
+//! are older than a day; failed lookups are retried after an hour. Repository
+//! lookups are conditional (`If-None-Match`), are shared by the skills of one
+//! repository within a batch, and stop as soon as GitHub reports the API rate limit
+//! as exhausted; the next run waits for the reset. `SKILL.md` is read from
+//! raw.githubusercontent.com, which does not count against the API limit.
+//!
+//! Each entry is also checked for installability: the commit a repository's ref
+//! points at, and one recursive tree listing of it, give the paths of its `SKILL.md`
+//! files, from which the candidates `list_git_skills` would offer for the URL are
+//! counted. The commit is kept to tell installed copies that are behind.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::blocking::{Client, Response};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::git_url::{github_owner_repo, parse_git_source};
+use super::installer::{parse_skill_md_text, SKILL_DISCOVERY_DIRS};
+use super::network;
+use super::skill_store::{DiscoveredSkillMetadata, DiscoveredSkillRecord, SkillStore};
+
+pub const METADATA_EVENT: &str = "discovered-skills-enriched";
+/// Skills enriched per scheduled run; at up to three API requests per repository, stays
+/// under the unauthenticated limit of 60 requests an hour.
+pub const ENRICH_BATCH_SIZE: usize = 20;
+const METADATA_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// Age after which failed lookups (network errors, 5xx) and unchecked install states are
+/// retried, instead of waiting for the full `METADATA_MAX_AGE`.
+const METADATA_RETRY_AGE: Duration = Duration::from_secs(60 * 60);
+/// Until when (ms) the GitHub API reported its rate limit as exhausted.
+const RATE_LIMITED_UNTIL_KEY: &str = "github_rate_limited_until";
+/// Wait used when a rate-limited response carries no reset time.
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60 * 60);
+
+const STATUS_OK: &str = "ok";
+const STATUS_NOT_FOUND: &str = "not_found";
+const STATUS_UNSUPPORTED: &str = "unsupported";
+const STATUS_ERROR: &str = "error";
+
+const GITHUB_JSON: &str = "application/vnd.github+json";
+
+const INSTALL_INSTALLABLE: &str = "installable";
+const INSTALL_MULTIPLE: &str = "multiple";
+const INSTALL_NO_SKILL: &str = "no_skill";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoverySort {
+    #[default]
+    Name,
+    /// Most stars first.
+    Popularity,
+    /// Most recently pushed first.
+    Freshness,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichmentReport {
+    /// Skills whose metadata was written.
+    pub enriched: usize,
+    /// Set when the run stopped (or did not start) because of the GitHub rate limit.
+    pub rate_limited_until: Option<i64>,
+}
+
+struct Endpoints {
+    api: String,
+    raw: String,
+}
+
+impl Endpoints {
+    fn github() -> Self {
+        Self {
+            api: "https://api.github.com".to_string(),
+            raw: "https://raw.githubusercontent.com".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoResponse {
+    stargazers_count: i64,
+    pushed_at: Option<String>,
+    archived: bool,
+    license: Option<LicenseResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LicenseResponse {
+    spdx_id: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TreeResponse {
+    tree: Vec<TreeEntry>,
+    /// Set when GitHub cut the listing short (very large trees).
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct TreeEntry {
+    path: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// Repository fields shared by every skill of the repository.
+#[derive(Clone, Debug)]
+struct RepoState {
+    status: &'static str,
+    /// Carried over from the previous record after a `304 Not Modified`.
+    not_modified: bool,
+    stars: Option<i64>,
+    last_commit_at: Option<i64>,
+    license: Option<String>,
+    archived: bool,
+    etag: Option<String>,
+    error: Option<String>,
+}
+
+enum RepoFetch {
+    Found(RepoState),
+    NotModified,
+    NotFound,
+    RateLimited,
+}
+
+/// A ref's commit and the paths of the `SKILL.md` files in its tree.
+#[derive(Clone, Debug, Default)]
+struct RefListing {
+    /// Unset for an empty repository.
+    revision: Option<String>,
+    files: Vec<String>,
+    /// The tree listing is incomplete, so a missing `SKILL.md` proves nothing.
+    truncated: bool,
+}
+
+enum RefFetch {
+    Found(RefListing),
+    /// The ref does not exist.
+    NotFound,
+    RateLimited,
+}
+
+enum ApiReply {
+    /// Any other status; not checked for errors yet.
+    Ok(Response),
+    NotModified,
+    NotFound,
+    RateLimited,
+}
+
+/// What installing from a skill's URL would offer.
+#[derive(Debug, PartialEq)]
+struct InstallCheck {
+    status: String,
+    skill_count: Option<i64>,
+    /// Folder of the only skill (`""` for the repository root).
+    skill_dir: Option<String>,
+    /// Commit the URL's ref points at.
+    revision: Option<String>,
+}
+
+impl InstallCheck {
+    /// `listing` is `None` when the ref does not exist.
+    fn new(listing: Option<&RefListing>, subpath: Option<&str>) -> Self {
+        let Some(listing) = listing else {
+            return Self::missing();
+        };
+        let dirs = candidate_dirs(&listing.files, subpath);
+        let status = match dirs.len() {
+            0 => INSTALL_NO_SKILL,
+            1 => INSTALL_INSTALLABLE,
+            _ => INSTALL_MULTIPLE,
+        };
+        Self {
+            status: status.to_string(),
+            skill_count: Some(dirs.len() as i64),
+            skill_dir: (dirs.len() == 1).then(|| dirs[0].clone()),
+            revision: listing.revision.clone(),
+        }
+    }
+
+    fn missing() -> Self {
+        Self {
+            status: STATUS_NOT_FOUND.to_string(),
+            skill_count: None,
+            skill_dir: None,
+            revision: None,
+        }
+    }
+}
+
+/// Enrich up to `limit` skills whose metadata is missing or stale.
+pub fn enrich_discovered_skills(
+    store: &SkillStore,
+    github_token: Option<&str>,
+    limit: usize,
+) -> Result<EnrichmentReport> {
+    enrich_with(
+        store,
+        &Endpoints::github(),
+        github_token,
+        limit,
+        chrono::Utc::now().timestamp_millis(),
+    )
+}
+
+fn enrich_with(
+    store: &SkillStore,
+    endpoints: &Endpoints,
+    github_token: Option<&str>,
+    limit: usize,
+    now: i64,
+) -> Result<EnrichmentReport> {
+    let mut report = EnrichmentReport::default();
+    if let Some(until) = rate_limited_until(store)?.filter(|until| *until > now) {
+        report.rate_limited_until = Some(until);
+        return Ok(report);
+    }
+
+    let stale_before = now - METADATA_MAX_AGE.as_millis() as i64;
+    let retry_before = now - METADATA_RETRY_AGE.as_millis() as i64;
+    let pending =
+        store.list_discovered_skills_needing_metadata(stale_before, retry_before, limit)?;
+    if pending.is_empty() {
+        return Ok(report);
+    }
+    network::ensure_online_for(&endpoints.api)?;
+
+    let previous = metadata_by_skill(store)?;
+    let client = network::http_client()?;
+    let mut repos: HashMap<String, RepoState> = HashMap::new();
+    // Listings by `owner/repo@ref`; `None` when the ref does not exist.
+    let mut listings: HashMap<String, Option<RefListing>> = HashMap::new();
+
+    for skill in pending {
+        let parsed = parse_git_source(&skill.github_url);
+        let Some((owner, name)) = github_owner_repo(&parsed.clone_url) else {
+            store.upsert_discovered_skill_metadata(&metadata_record(
+                &skill,
+                None,
+                &unsupported_repo(),
+                None,
+                None,
+                now,
+            ))?;
+            report.enriched += 1;
+            continue;
+        };
+        let repo = format!("{}/{}", owner, name);
+        let previous = previous
+            .get(&skill.id)
+            .filter(|meta| meta.repo.as_deref() == Some(repo.as_str()));
+
+        let state = match repos.get(&repo) {
+            Some(state) => state.clone(),
+            None => {
+                let etag = previous.and_then(|meta| meta.etag.as_deref());
+                let (fetch, exhausted_until) =
+                    match fetch_repo(&client, endpoints, &repo, github_token, etag, now) {
+                        Ok(result) => result,
+                        Err(err) if network::is_offline_error(&err) => return Err(err),
+                        Err(err) => (RepoFetch::Found(failed_repo(&err)), None),
+                    };
+                let state = match fetch {
+                    RepoFetch::Found(state) => Some(state),
+                    // Only sent with a previous ETag, so there is a previous record.
+                    RepoFetch::NotModified => previous.map(repo_state_from).or_else(|| {
+                        Some(failed_repo(&anyhow::anyhow!("unexpected 304 for {}", repo)))
+                    }),
+                    RepoFetch::NotFound => Some(missing_repo()),
+                    RepoFetch::RateLimited => None,
+                };
+                if let Some(until) = exhausted_until {
+                    record_rate_limit(store, &mut report, until)?;
+                }
+                let Some(state) = state else {
+                    break;
+                };
+                repos.insert(repo.clone(), state.clone());
+                state
+            }
+        };
+
+        let unchanged = previous.filter(|meta| {
+            state.not_modified
+                && meta.github_url == skill.github_url
+                && meta.install_status.is_some()
+        });
+        let (check, frontmatter) = if state.status == STATUS_NOT_FOUND {
+            (Some(InstallCheck::missing()), None)
+        } else if state.status != STATUS_OK {
+            (None, None)
+        } else if let Some(meta) = unchanged {
+            let check = InstallCheck {
+                status: meta.install_status.clone().unwrap_or_default(),
+                skill_count: meta.skill_count,
+                skill_dir: None,
+                revision: meta.head_revision.clone(),
+            };
+            let frontmatter = meta
+                .skill_name
+                .clone()
+                .map(|name| (name, meta.skill_description.clone()));
+            (Some(check), frontmatter)
+        } else if report.rate_limited_until.is_some() {
+            // No quota left for the ref listing; checked on a later run.
+            (None, None)
+        } else {
+            let git_ref = parsed.branch.as_deref().unwrap_or("HEAD");
+            let key = format!("{}@{}", repo, git_ref);
+            let listing = match listings.get(&key) {
+                Some(listing) => Some(listing.clone()),
+                None => {
+                    let (fetch, exhausted_until) = match fetch_ref_listing(
+                        &client,
+                        endpoints,
+                        &repo,
+                        git_ref,
+                        github_token,
+                        now,
+                    ) {
+                        Ok((fetch, until)) => (Some(fetch), until),
+                        Err(err) if network::is_offline_error(&err) => return Err(err),
+                        // Left unchecked; the repository fields are still recorded.
+                        Err(_) => (None, None),
+                    };
+                    if let Some(until) = exhausted_until {
+                        record_rate_limit(store, &mut report, until)?;
+                    }
+                    let listing = match fetch {
+                        Some(RefFetch::Found(listing)) => Some(Some(listing)),
+                        Some(RefFetch::NotFound) => Some(None),
+                        Some(RefFetch::RateLimited) => break,
+                        None => None,
+                    };
+                    if let Some(listing) = &listing {
+                        listings.insert(key, listing.clone());
+                    }
+                    listing
+                }
+            };
+            let subpath = parsed.subpath.as_deref();
+            match listing {
+                Some(Some(listing)) if listing.truncated => check_truncated(
+                    &client,
+                    endpoints,
+                    &repo,
+                    parsed.branch.as_deref(),
+                    &listing,
+                    subpath,
+                ),
+                listing => {
+                    let check = listing.map(|listing| InstallCheck::new(listing.as_ref(), subpath));
+                    let frontmatter = check
+                        .as_ref()
+                        .and_then(|check| check.skill_dir.as_deref())
+                        .and_then(|dir| {
+                            fetch_frontmatter(
+                                &client,
+                                endpoints,
+                                &repo,
+                                parsed.branch.as_deref(),
+                                Some(dir).filter(|dir| !dir.is_empty()),
+                            )
+                        });
+                    (check, frontmatter)
+                }
+            }
+        };
+        store.upsert_discovered_skill_metadata(&metadata_record(
+            &skill,
+            Some(repo),
+            &state,
+            check.as_ref(),
+            frontmatter,
+            now,
+        ))?;
+        report.enriched += 1;
+
+        if report.rate_limited_until.is_some() {
+            break;
+        }
+    }
+    Ok(report)
+}
+
+/// Cached metadata by discovered skill id.
+pub fn metadata_by_skill(store: &SkillStore) -> Result<HashMap<String, DiscoveredSkillMetadata>> {
+    Ok(store
+        .list_discovered_skill_metadata()?
+        .into_iter()
+        .map(|meta| (meta.skill_id.clone(), meta))
+        .collect())
+}
+
+/// The metadata of `skill`, unless it was fetched for a URL the skill no longer has.
+pub fn metadata_for<'a>(
+    metadata: &'a HashMap<String, DiscoveredSkillMetadata>,
+    skill: &DiscoveredSkillRecord,
+) -> Option<&'a DiscoveredSkillMetadata> {
+    metadata
+        .get(&skill.id)
+        .filter(|meta| meta.github_url == skill.github_url)
+}
+
+/// Archived repositories and links to repositories that no longer exist.
+pub fn is_unavailable(meta: &DiscoveredSkillMetadata) -> bool {
+    meta.archived || meta.status == STATUS_NOT_FOUND
+}
+
+/// Drop unavailable skills (unless `include_unavailable`) and order the rest.
+/// Skills without metadata sort after those with it; ties keep the incoming order.
+pub fn arrange_discovered_skills(
+    mut skills: Vec<DiscoveredSkillRecord>,
+    metadata: &HashMap<String, DiscoveredSkillMetadata>,
+    sort: DiscoverySort,
+    include_unavailable: bool,
+) -> Vec<DiscoveredSkillRecord> {
+    if !include_unavailable {
+        skills.retain(|skill| !metadata_for(metadata, skill).is_some_and(is_unavailable));
+    }
+    let key = |skill: &DiscoveredSkillRecord| {
+        let meta = metadata_for(metadata, skill);
+        match sort {
+            DiscoverySort::Name => None,
+            DiscoverySort::Popularity => meta.and_then(|meta| meta.stars),
+            DiscoverySort::Freshness => meta.and_then(|meta| meta.last_commit_at),
+        }
+    };
+    if sort != DiscoverySort::Name {
+        skills.sort_by_key(|skill| std::cmp::Reverse(key(skill)));
+    }
+    skills
+}
+
+fn rate_limited_until(store: &SkillStore) -> Result<Option<i64>> {
+    Ok(store
+        .get_setting(RATE_LIMITED_UNTIL_KEY)?
+        .and_then(|value| value.parse().ok()))
+}
+
+fn record_rate_limit(store: &SkillStore, report: &mut EnrichmentReport, until: i64) -> Result<()> {
+    store.set_setting(RATE_LIMITED_UNTIL_KEY, &until.to_string())?;
+    report.rate_limited_until = Some(until);
+    Ok(())
+}
+
+/// GET `url` from the GitHub API. The second value is set when the response shows
+/// the rate limit exhausted, to when (ms) it resets.
+fn api_get(
+    client: &Client,
+    url: &str,
+    accept: &str,
+    token: Option<&str>,
+    etag: Option<&str>,
+    now: i64,
+) -> Result<(ApiReply, Option<i64>)> {
+    let mut request = client
+        .get(url)
+        .header("User-Agent", "skills-hub")
+        .header("Accept", accept);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    if let Some(etag) = etag {
+        request = request.header("If-None-Match", etag);
+    }
+    let response = request
+        .send()
+        .with_context(|| format!("GitHub request failed: {}", url))?;
+
+    let exhausted_until = exhausted_until(&response, now);
+    let status = response.status();
+    if (status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS)
+        && (exhausted_until.is_some() || response.headers().contains_key("retry-after"))
+    {
+        let until = exhausted_until
+            .or_else(|| retry_after(&response).map(|wait| now + wait.as_millis() as i64))
+            .unwrap_or(now + DEFAULT_RATE_LIMIT_WAIT.as_millis() as i64);
+        return Ok((ApiReply::RateLimited, Some(until)));
+    }
+    if status == StatusCode::NOT_MODIFIED {
+        return Ok((ApiReply::NotModified, exhausted_until));
+    }
+    if status == StatusCode::NOT_FOUND || status == StatusCode::GONE {
+        return Ok((ApiReply::NotFound, exhausted_until));
+    }
+    Ok((ApiReply::Ok(response), exhausted_until))
+}
+
+/// Look up `repo` (`owner/name`); see [`api_get`] for the second value.
+fn fetch_repo(
+    client: &Client,
+    endpoints: &Endpoints,
+    repo: &str,
+    token: Option<&str>,
+    etag: Option<&str>,
+    now: i64,
+) -> Result<(RepoFetch, Option<i64>)> {
+    let url = format!("{}/repos/{}", endpoints.api.trim_end_matches('/'), repo);
+    let (reply, exhausted_until) = api_get(client, &url, GITHUB_JSON, token, etag, now)?;
+    let response = match reply {
+        ApiReply::Ok(response) => response,
+        ApiReply::NotModified => return Ok((RepoFetch::NotModified, exhausted_until)),
+        ApiReply::NotFound => return Ok((RepoFetch::NotFound, exhausted_until)),
+        ApiReply::RateLimited => return Ok((RepoFetch::RateLimited, exhausted_until)),
+    };
+
+    let etag = response
+        .headers()
+        .get("etag")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body: RepoResponse = response
+        .error_for_status()
+        .with_context(|| format!("GitHub returned an error for {}", repo))?
+        .json()
+        .context("parse GitHub repository")?;
+    let state = RepoState {
+        status: STATUS_OK,
+        not_modified: false,
+        stars: Some(body.stargazers_count),
+        last_commit_at: body
+            .pushed_at
+            .as_deref()
+            .and_then(|value| chrono::DateTime::parse_from_rfc3339(value).ok())
+            .map(|time| time.timestamp_millis()),
+        license: body.license.and_then(|license| {
+            license
+                .spdx_id
+                .filter(|id| id != "NOASSERTION")
+                .or(license.name)
+        }),
+        archived: body.archived,
+        etag,
+        error: None,
+    };
+    Ok((RepoFetch::Found(state), exhausted_until))
+}
+
+/// The commit `git_ref` of `repo` points at and the `SKILL.md` files in its tree, from
+/// two requests; see [`api_get`] for the second value.
+fn fetch_ref_listing(
+    client: &Client,
+    endpoints: &Endpoints,
+    repo: &str,
+    git_ref: &str,
+    token: Option<&str>,
+    now: i64,
+) -> Result<(RefFetch, Option<i64>)> {
+    let api = endpoints.api.trim_end_matches('/');
+    let url = format!("{}/repos/{}/commits/{}", api, repo, git_ref);
+    let (reply, exhausted_until) =
+        api_get(client, &url, "application/vnd.github.sha", token, None, now)?;
+    let response = match reply {
+        ApiReply::Ok(response) => response,
+        ApiReply::NotFound => return Ok((RefFetch::NotFound, exhausted_until)),
+        ApiReply::RateLimited => return Ok((RefFetch::RateLimited, exhausted_until)),
+        ApiReply::NotModified => anyhow::bail!("unexpected 304 for {}", url),
+    };
+    match response.status() {
+        // Empty repositories have no commits.
+        StatusCode::CONFLICT => {
+            return Ok((RefFetch::Found(RefListing::default()), exhausted_until))
+        }
+        // Not a branch, tag or commit of the repository.
+        StatusCode::UNPROCESSABLE_ENTITY => return Ok((RefFetch::NotFound, exhausted_until)),
+        _ => {}
+    }
+    let revision = response
+        .error_for_status()
+        .with_context(|| format!("GitHub returned an error for {}", url))?
+        .text()
+        .context("read GitHub commit")?
+        .trim()
+        .to_string();
+    if let Some(until) = exhausted_until {
+        // No quota left for the tree.
+        return Ok((RefFetch::RateLimited, Some(until)));
+    }
+
+    let url = format!("{}/repos/{}/git/trees/{}?recursive=1", api, repo, revision);
+    let (reply, exhausted_until) = api_get(client, &url, GITHUB_JSON, token, None, now)?;
+    let response = match reply {
+        ApiReply::Ok(response) => response,
+        ApiReply::RateLimited => return Ok((RefFetch::RateLimited, exhausted_until)),
+        ApiReply::NotFound | ApiReply::NotModified => {
+            anyhow::bail!("no tree for {} at {}", repo, revision)
+        }
+    };
+    let body: TreeResponse = response
+        .error_for_status()
+        .with_context(|| format!("GitHub returned an error for {}", url))?
+        .json()
+        .context("parse GitHub tree")?;
+    let files = body
+        .tree
+        .into_iter()
+        .filter(|entry| {
+            entry.kind == "blob" && (entry.path == "SKILL.md" || entry.path.ends_with("/SKILL.md"))
+        })
+        .map(|entry| entry.path)
+        .collect();
+    let listing = RefListing {
+        revision: Some(revision),
+        files,
+        truncated: body.truncated,
+    };
+    Ok((RefFetch::Found(listing), exhausted_until))
+}
+
+/// The skill folders `list_git_skills` would offer for `subpath`, given the paths of
+/// a repository's `SKILL.md` files: the folder itself for a folder URL, otherwise the
+/// root and the subfolders of the standard discovery locations.
+fn candidate_dirs(files: &[String], subpath: Option<&str>) -> Vec<String> {
+    let subpath = subpath
+        .map(|subpath| subpath.trim_matches('/'))
+        .filter(|subpath| !subpath.is_empty() && *subpath != ".");
+    let mut dirs: Vec<String> = files
+        .iter()
+        .filter_map(|path| {
+            let dir = if path == "SKILL.md" {
+                ""
+            } else {
+                path.strip_suffix("/SKILL.md")?
+            };
+            let offered = match subpath {
+                Some(subpath) => dir == subpath,
+                None => {
+                    dir.is_empty()
+                        || SKILL_DISCOVERY_DIRS.iter().any(|base| {
+                            dir.strip_prefix(base)
+                                .and_then(|rest| rest.strip_prefix('/'))
+                                .is_some_and(|name| !name.contains('/'))
+                        })
+                }
+            };
+            offered.then(|| dir.to_string())
+        })
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+fn exhausted_until(response: &Response, now: i64) -> Option<i64> {
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<i64>().ok())
+    };
+    if header("x-ratelimit-remaining")? > 0 {
+        return None;
+    }
+    Some(
+        header("x-ratelimit-reset")
+            .map(|reset| reset * 1000)
+            .unwrap_or(now + DEFAULT_RATE_LIMIT_WAIT.as_millis() as i64),
+    )
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get("retry-after")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+/// Install check against a tree listing GitHub cut short.
+///
+/// A folder URL is settled by its own `SKILL.md`, probed directly when the
+/// listing does not reach it. A repository URL may offer skills past the cut,
+/// so it is left unchecked rather than counted.
+fn check_truncated(
+    client: &Client,
+    endpoints: &Endpoints,
+    repo: &str,
+    git_ref: Option<&str>,
+    listing: &RefListing,
+    subpath: Option<&str>,
+) -> (Option<InstallCheck>, Option<(String, Option<String>)>) {
+    let Some(dir) = subpath
+        .map(|subpath| subpath.trim_matches('/'))
+        .filter(|subpath| !subpath.is_empty() && *subpath != ".")
+    else {
+        return (None, None);
+    };
+    let listed = !candidate_dirs(&listing.files, Some(dir)).is_empty();
+    let frontmatter = fetch_frontmatter(client, endpoints, repo, git_ref, Some(dir));
+    if !listed && frontmatter.is_none() {
+        return (None, None);
+    }
+    let check = InstallCheck {
+        status: INSTALL_INSTALLABLE.to_string(),
+        skill_count: Some(1),
+        skill_dir: Some(dir.to_string()),
+        revision: listing.revision.clone(),
+    };
+    (Some(check), frontmatter)
+}
+
+/// `(name, description)` from the `SKILL.md` at `subpath`, if there is a parsable one.
+fn fetch_frontmatter(
+    client: &Client,
+    endpoints: &Endpoints,
+    repo: &str,
+    git_ref: Option<&str>,
+    subpath: Option<&str>,
+) -> Option<(String, Option<String>)> {
+    let mut url = format!(
+        "{}/{}/{}/",
+        endpoints.raw.trim_end_matches('/'),
+        repo,
+        git_ref.unwrap_or("HEAD")
+    );
+    if let Some(subpath) = subpath {
+        url.push_str(subpath.trim_matches('/'));
+        url.push('/');
+    }
+    url.push_str("SKILL.md");
+
+    let response = client
+        .get(&url)
+        .header("User-Agent", "skills-hub")
+        .send()
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let text = response.text().ok()?;
+    let (name, description, _) = parse_skill_md_text(&text).ok()?;
+    Some((name, description))
+}
+
+fn repo_state_from(meta: &DiscoveredSkillMetadata) -> RepoState {
+    RepoState {
+        status: match meta.status.as_str() {
+            STATUS_NOT_FOUND => STATUS_NOT_FOUND,
+            STATUS_ERROR => STATUS_ERROR,
+            _ => STATUS_OK,
+        },
+        not_modified: true,
+        stars: meta.stars,
+        last_commit_at: meta.last_commit_at,
+        license: meta.license.clone(),
+        archived: meta.archived,
+        etag: meta.etag.clone(),
+        error: meta.error.clone(),
+    }
+}
+
+fn missing_repo() -> RepoState {
+    RepoState {
+        status: STATUS_NOT_FOUND,
+        not_modified: false,
+        stars: None,
+        last_commit_at: None,
+        license: None,
+        archived: false,
+        etag: None,
+        error: None,
+    }
+}
+
+fn unsupported_repo() -> RepoState {
+    RepoState {
+        status: STATUS_UNSUPPORTED,
+        ..missing_repo()
+    }
+}
+
+fn failed_repo(err: &anyhow::Error) -> RepoState {
+    RepoState {
+        status: STATUS_ERROR,
+        error: Some(format!("{:#}", err)),
+        ..missing_repo()
+    }
+}
+
+fn metadata_record(
+    skill: &DiscoveredSkillRecord,
+    repo: Option<String>,
+    state: &RepoState,
+    check: Option<&InstallCheck>,
+    frontmatter: Option<(String, Option<String>)>,
+    now: i64,
+) -> DiscoveredSkillMetadata {
+    let (skill_name, skill_description) = match frontmatter {
+        Some((name, description)) => (Some(name), description),
+        None => (None, None),
+    };
+    DiscoveredSkillMetadata {
+        skill_id: skill.id.clone(),
+        github_url: skill.github_url.clone(),
+        repo,
+        status: state.status.to_string(),
+        stars: state.stars,
+        last_commit_at: state.last_commit_at,
+        license: state.license.clone(),
+        archived: state.archived,
+        skill_name,
+        skill_description,
+        install_status: check.map(|check| check.status.clone()),
+        skill_count: check.and_then(|check| check.skill_count),
+        head_revision: check.and_then(|check| check.revision.clone()),
+        error: state.error.clone(),
+        etag: state.etag.clone(),
+        fetched_at: now,
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/discovery_metadata.rs"]
+mod tests;
@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 use regex::Regex;
 use uuid::Uuid;
 
+use super::category_taxonomy::CategoryRules;
+use super::git_url::recognize_git_host_url;
 use super::skill_store::DiscoveredSkillRecord;
 
 #[derive(Debug, Clone)]
@@ -10,6 +12,8 @@ pub struct ParsedSkill {
     pub description: String,
     pub github_url: String,
     pub category: String,
+    /// Explicit tags; when empty the raw category is used as the tag.
+    pub tags: Vec<String>,
 }
 
 /// Parse awesome-claude-skills README.md content
@@ -42,12 +46,12 @@ pub fn parse_awesome_skills_readme(content: &str) -> Result<Vec<ParsedSkill>> {
             let url = caps.get(2).map(|m| m.as_str().trim().to_string()).unwrap_or_default();
             let description = caps.get(3).map(|m| m.as_str().trim().to_string()).unwrap_or_default();
             
-            // Skip if any field is empty or if it's not a GitHub URL
+            // Skip if any field is empty or if it's not a git host URL
             if name.is_empty() || url.is_empty() || description.is_empty() {
                 continue;
             }
             
-            if !url.starts_with("https://github.com/") {
+            if recognize_git_host_url(&url).is_none() {
                 continue;
             }
             
@@ -56,6 +60,7 @@ pub fn parse_awesome_skills_readme(content: &str) -> Result<Vec<ParsedSkill>> {
                 description,
                 github_url: url,
                 category: current_category.clone(),
+                tags: Vec::new(),
             });
         }
     }
@@ -63,8 +68,12 @@ pub fn parse_awesome_skills_readme(content: &str) -> Result<Vec<ParsedSkill>> {
     Ok(skills)
 }
 
-/// Convert parsed skills to database records
-pub fn skills_to_records(skills: Vec<ParsedSkill>, source: &str) -> Vec<DiscoveredSkillRecord> {
+/// Convert parsed skills to database records, categorized by `rules`
+pub fn skills_to_records(
+    skills: Vec<ParsedSkill>,
+    source: &str,
+    rules: &CategoryRules,
+) -> Vec<DiscoveredSkillRecord> {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
@@ -77,57 +86,30 @@ pub fn skills_to_records(skills: Vec<ParsedSkill>, source: &str) -> Vec<Discover
             let id = Uuid::new_v5(&Uuid::NAMESPACE_URL, skill.github_url.as_bytes()).to_string();
             
             // Extract tags from category
-            let tags = skill.category.clone();
+            let tags = if skill.tags.is_empty() {
+                skill.category.clone()
+            } else {
+                skill.tags.join(",")
+            };
             
             DiscoveredSkillRecord {
                 id,
                 name: skill.name,
                 description: skill.description,
                 github_url: skill.github_url,
-                category: normalize_category(&skill.category),
+                category: rules.categorize(&skill.category),
                 source: source.to_string(),
                 tags,
                 created_at: now,
                 updated_at: now,
+                first_seen_at: now,
+                last_seen_at: now,
+                raw_category: Some(skill.category),
             }
         })
         .collect()
 }
 
-/// Normalize category names to match existing categories
-fn normalize_category(category: &str) -> String {
-    let lower = category.to_lowercase();
-    
-    // Map to existing category IDs
-    if lower.contains("document") {
-        "document".to_string()
-    } else if lower.contains("development") || lower.contains("code") {
-        "development".to_string()
-    } else if lower.contains("data") || lower.contains("analysis") {
-        "data-analysis".to_string()
-    } else if lower.contains("scientific") || lower.contains("research") {
-        "research".to_string()
-    } else if lower.contains("writing") {
-        "writing".to_string()
-    } else if lower.contains("learning") || lower.contains("knowledge") {
-        "learning".to_string()
-    } else if lower.contains("media") || lower.contains("content") {
-        "media".to_string()
-    } else if lower.contains("health") {
-        "health".to_string()
-    } else if lower.contains("collaboration") || lower.contains("project") {
-        "collaboration".to_string()
-    } else if lower.contains("security") || lower.contains("testing") {
-        "security".to_string()
-    } else if lower.contains("utility") || lower.contains("automation") {
-        "utility".to_string()
-    } else if lower.contains("collection") {
-        "collections".to_string()
-    } else {
-        "other".to_string()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,10 +139,16 @@ mod tests {
     }
 
     #[test]
-    fn test_normalize_category() {
-        assert_eq!(normalize_category("Document Skills"), "document");
-        assert_eq!(normalize_category("Development & Code Tools"), "development");
-        assert_eq!(normalize_category("Data & Analysis"), "data-analysis");
-        assert_eq!(normalize_category("Unknown Category"), "other");
+    fn test_parse_readme_non_github_hosts() {
+        let content = r#"
+## 🔧 Development & Code Tools
+- [lint](https://gitlab.com/acme/skills/-/tree/main/lint) - Lint helper.
+- [fmt](https://codeberg.org/acme/skills/src/branch/main/fmt) - Formatter.
+- [docs](https://example.com/docs) - Not a repository.
+"#;
+
+        let skills = parse_awesome_skills_readme(content).unwrap();
+        let names: Vec<&str> = skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["lint", "fmt"]);
     }
 }
@@ -1,8 +1,9 @@
 use anyhow::{Context, Result};
-use reqwest::blocking::Client;
 use serde::Deserialize;
 use std::collections::HashMap;
 
+use super::network;
+
 /// skills.sh 热门技能列表（基于真实排行榜数据）
 /// 格式：(仓库, 技能名, 分类, 描述)
 const SKILLS_SH_POPULAR: &[(&str, &str, &str, &str)] = &[
@@ -62,13 +63,19 @@ pub struct RemoteDiscoveredSkill {
     pub tags: Vec<String>,
 }
 
-/// 从 skills.sh 热门列表按分类获取技能
+/// 从 skills.sh 热门列表按分类获取技能；`popular` 为 false（该源已停用）时只用 GitHub 搜索
 pub fn fetch_skills_by_category(
     category_id: &str,
     limit: usize,
+    github_token: Option<&str>,
+    popular: bool,
 ) -> Result<Vec<RemoteDiscoveredSkill>> {
     // 优先使用 skills.sh 的热门技能列表
-    let skills_from_list = fetch_skills_from_popular_list(category_id, limit)?;
+    let skills_from_list = if popular {
+        fetch_skills_from_popular_list(category_id, limit)?
+    } else {
+        Vec::new()
+    };
     
     // 如果热门列表中的技能数量不足，补充 GitHub 搜索结果
     if skills_from_list.len() < limit {
@@ -79,7 +86,7 @@ pub fn fetch_skills_by_category(
             .map(|(_, query)| *query)
             .unwrap_or("topic:claude-skill");
         
-        let mut github_skills = fetch_github_skills(topic_query, category_id, remaining)?;
+        let mut github_skills = fetch_github_skills(topic_query, category_id, remaining, github_token)?;
         
         // 合并结果，去重（避免重复）
         let existing_repos: std::collections::HashSet<String> = skills_from_list
@@ -97,25 +104,29 @@ pub fn fetch_skills_by_category(
     }
 }
 
+/// skills.sh 热门列表中的全部技能（内置技能源 `skills-sh-popular` 的内容）
+pub fn popular_skills() -> Vec<RemoteDiscoveredSkill> {
+    SKILLS_SH_POPULAR
+        .iter()
+        .map(|(repo, name, cat, desc)| RemoteDiscoveredSkill {
+            name: name.to_string(),
+            description: desc.to_string(),
+            github_url: format!("https://github.com/{}", repo),
+            category: cat.to_string(),
+            tags: vec!["skills.sh".to_string(), "popular".to_string()],
+        })
+        .collect()
+}
+
 /// 从 skills.sh 热门列表获取技能
 fn fetch_skills_from_popular_list(
     category_id: &str,
     limit: usize,
 ) -> Result<Vec<RemoteDiscoveredSkill>> {
-    let skills: Vec<RemoteDiscoveredSkill> = SKILLS_SH_POPULAR
-        .iter()
-        .filter(|(_, _, cat, _)| *cat == category_id)
+    let skills: Vec<RemoteDiscoveredSkill> = popular_skills()
+        .into_iter()
+        .filter(|skill| skill.category == category_id)
         .take(limit)
-        .map(|(repo, name, _, desc)| {
-            let github_url = format!("https://github.com/{}", repo);
-            RemoteDiscoveredSkill {
-                name: name.to_string(),
-                description: desc.to_string(),
-                github_url,
-                category: category_id.to_string(),
-                tags: vec!["skills.sh".to_string(), "popular".to_string()],
-            }
-        })
         .collect();
     
     Ok(skills)
@@ -124,11 +135,14 @@ fn fetch_skills_from_popular_list(
 /// 获取所有分类的技能
 pub fn fetch_all_category_skills(
     limit_per_category: usize,
+    github_token: Option<&str>,
+    popular: bool,
 ) -> Result<HashMap<String, Vec<RemoteDiscoveredSkill>>> {
     let mut result = HashMap::new();
     
     for (category_id, _) in CATEGORY_TOPICS {
-        let skills = fetch_skills_by_category(category_id, limit_per_category)?;
+        let skills =
+            fetch_skills_by_category(category_id, limit_per_category, github_token, popular)?;
         result.insert(category_id.to_string(), skills);
     }
     
@@ -136,21 +150,25 @@ pub fn fetch_all_category_skills(
 }
 
 /// 从 GitHub 搜索技能仓库
-fn fetch_github_skills(
+pub(crate) fn fetch_github_skills(
     query: &str,
     category: &str,
     limit: usize,
+    github_token: Option<&str>,
 ) -> Result<Vec<RemoteDiscoveredSkill>> {
-    let client = Client::new();
+    let client = network::http_client()?;
     let url = format!(
         "https://api.github.com/search/repositories?q={}&per_page={}&sort=stars&order=desc",
         urlencoding::encode(query),
         limit.clamp(1, 100)
     );
+    network::ensure_online_for(&url)?;
 
-    let response = client
-        .get(&url)
-        .header("User-Agent", "skills-hub")
+    let mut request = client.get(&url).header("User-Agent", "skills-hub");
+    if let Some(token) = github_token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
         .send()
         .context("GitHub search request failed")?
         .error_for_status()
@@ -188,7 +206,7 @@ mod tests {
 
     #[test]
     fn test_fetch_skills_by_category() {
-        let result = fetch_skills_by_category("development", 5);
+        let result = fetch_skills_by_category("development", 5, None, true);
         assert!(result.is_ok());
         let skills = result.unwrap();
         assert!(skills.len() <= 5);
@@ -0,0 +1,395 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use git2::{Cred, RemoteCallbacks};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::git_fetcher::{git_credential_fill, run_git_in_with_auth};
+use super::skill_store::{GitCredentialRecord, SkillStore};
+use super::util::now_ms;
+
+/// Username sent with a token when none is configured. GitHub ignores it, and
+/// GitLab/Gitea accept any non-empty value for personal access tokens.
+const DEFAULT_TOKEN_USERNAME: &str = "x-access-token";
+/// OS keychain service holding tokens; `git_credentials.secret_ref` names the account.
+const KEYCHAIN_SERVICE: &str = "skills-hub-git-credentials";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitCredentialKind {
+    /// Personal access token / app password sent over HTTPS.
+    Token,
+    /// Private key file used for SSH remotes.
+    SshKey,
+    /// Defer to the user's configured git credential helper (keychain, GCM, ...).
+    System,
+}
+
+impl GitCredentialKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Token => "token",
+            Self::SshKey => "ssh_key",
+            Self::System => "system",
+        }
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "token" => Some(Self::Token),
+            "ssh_key" => Some(Self::SshKey),
+            "system" => Some(Self::System),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCredentialInput {
+    pub host: String,
+    pub kind: GitCredentialKind,
+    pub username: Option<String>,
+    /// Token to store. `None` keeps the stored token when updating a token credential.
+    pub token: Option<String>,
+    pub ssh_key_path: Option<String>,
+}
+
+/// What the frontend sees: never includes the token itself.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCredentialInfo {
+    pub host: String,
+    pub kind: GitCredentialKind,
+    pub username: Option<String>,
+    pub has_token: bool,
+    pub ssh_key_path: Option<String>,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCredentialTestResult {
+    pub host: String,
+    pub ok: bool,
+    pub message: String,
+}
+
+/// Credentials resolved for one remote, ready to inject into git-cli or libgit2.
+#[derive(Clone, Debug)]
+pub struct GitAuth {
+    pub kind: GitCredentialKind,
+    pub username: Option<String>,
+    pub token: Option<String>,
+    pub ssh_key_path: Option<String>,
+}
+
+impl GitAuth {
+    fn from_record(record: GitCredentialRecord) -> Option<Self> {
+        Some(Self {
+            kind: GitCredentialKind::parse(&record.kind)?,
+            token: read_secret(&record),
+            username: record.username,
+            ssh_key_path: record.ssh_key_path,
+        })
+    }
+
+    fn token_username(&self) -> &str {
+        self.username
+            .as_deref()
+            .filter(|u| !u.is_empty())
+            .unwrap_or(DEFAULT_TOKEN_USERNAME)
+    }
+
+    /// Configure a `git` command to authenticate with these credentials.
+    ///
+    /// Secrets travel through environment variables (not argv) so they don't show
+    /// up in process listings; the inline credential helper echoes them back to git.
+    pub fn apply_to_command(&self, cmd: &mut Command) {
+        match self.kind {
+            GitCredentialKind::Token => {
+                let Some(token) = self.token.as_deref() else {
+                    return;
+                };
+                // An empty helper entry resets the list so only ours is consulted.
+                cmd.env("GIT_CONFIG_COUNT", "2")
+                    .env("GIT_CONFIG_KEY_0", "credential.helper")
+                    .env("GIT_CONFIG_VALUE_0", "")
+                    .env("GIT_CONFIG_KEY_1", "credential.helper")
+                    .env(
+                        "GIT_CONFIG_VALUE_1",
+                        "!f() { echo \"username=$SKILLS_HUB_GIT_USERNAME\"; echo \"password=$SKILLS_HUB_GIT_TOKEN\"; }; f",
+                    )
+                    .env("SKILLS_HUB_GIT_USERNAME", self.token_username())
+                    .env("SKILLS_HUB_GIT_TOKEN", token);
+            }
+            GitCredentialKind::SshKey => {
+                let Some(key) = self.ssh_key_path.as_deref() else {
+                    return;
+                };
+                cmd.env(
+                    "GIT_SSH_COMMAND",
+                    format!(
+                        "ssh -i \"{}\" -o IdentitiesOnly=yes -o BatchMode=yes",
+                        key.replace('"', "\\\"")
+                    ),
+                );
+            }
+            // git-cli already consults the configured helpers.
+            GitCredentialKind::System => {}
+        }
+    }
+
+    /// libgit2 callbacks answering credential requests with these credentials.
+    pub fn remote_callbacks(&self) -> RemoteCallbacks<'_> {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(move |url, username_from_url, _allowed| match self.kind {
+            GitCredentialKind::Token => Cred::userpass_plaintext(
+                self.token_username(),
+                self.token.as_deref().unwrap_or_default(),
+            ),
+            GitCredentialKind::SshKey => Cred::ssh_key(
+                username_from_url.unwrap_or("git"),
+                None,
+                Path::new(self.ssh_key_path.as_deref().unwrap_or_default()),
+                None,
+            ),
+            GitCredentialKind::System => {
+                let config = git2::Config::open_default()?;
+                Cred::credential_helper(&config, url, username_from_url)
+            }
+        });
+        callbacks
+    }
+}
+
+/// Extract the lowercase host from `https://`, `ssh://` and scp-style (`git@host:org/repo`) URLs.
+pub fn url_host(url: &str) -> Option<String> {
+    let url = url.trim();
+    if url.contains("://") {
+        let parsed = url::Url::parse(url).ok()?;
+        return parsed.host_str().map(|h| h.to_ascii_lowercase());
+    }
+    // scp-like syntax: [user@]host:path (but not a Windows drive like C:\).
+    let (head, _) = url.split_once(':')?;
+    let host = head.rsplit('@').next()?;
+    if host.len() < 2 || host.contains('/') || host.contains('\\') {
+        return None;
+    }
+    Some(host.to_ascii_lowercase())
+}
+
+fn normalize_host(raw: &str) -> Result<String> {
+    let trimmed = raw.trim();
+    let host = if trimmed.contains("://") || trimmed.contains(':') {
+        url_host(trimmed).unwrap_or_default()
+    } else {
+        trimmed.trim_end_matches('/').to_ascii_lowercase()
+    };
+    if host.is_empty() || host.contains('/') || host.contains(char::is_whitespace) {
+        anyhow::bail!("invalid host: {}", raw);
+    }
+    Ok(host)
+}
+
+/// Look up stored credentials for the host of `url`.
+pub fn resolve_git_auth(store: &SkillStore, url: &str) -> Result<Option<GitAuth>> {
+    let Some(host) = url_host(url) else {
+        return Ok(None);
+    };
+    Ok(store
+        .get_git_credential(&host)?
+        .and_then(GitAuth::from_record))
+}
+
+/// Token for GitHub REST API calls, from a stored token or the system credential helper.
+pub fn github_api_token(store: &SkillStore) -> Option<String> {
+    let record = ["github.com", "api.github.com"]
+        .iter()
+        .find_map(|host| store.get_git_credential(host).ok().flatten())?;
+    let auth = GitAuth::from_record(record)?;
+    match auth.kind {
+        GitCredentialKind::Token => auth.token.filter(|t| !t.is_empty()),
+        GitCredentialKind::System => git_credential_fill("https", "github.com")
+            .ok()
+            .flatten()
+            .map(|(_, password)| password),
+        GitCredentialKind::SshKey => None,
+    }
+}
+
+pub fn list_git_credentials(store: &SkillStore) -> Result<Vec<GitCredentialInfo>> {
+    Ok(store
+        .list_git_credentials()?
+        .into_iter()
+        .filter_map(to_info)
+        .collect())
+}
+
+pub fn save_git_credential(
+    store: &SkillStore,
+    input: GitCredentialInput,
+) -> Result<GitCredentialInfo> {
+    let host = normalize_host(&input.host)?;
+    let existing = store.get_git_credential(&host)?;
+    let username = input
+        .username
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty());
+
+    let existing_token = existing
+        .as_ref()
+        .filter(|r| r.kind == GitCredentialKind::Token.as_str());
+    let (secret_ref, ssh_key_path) = match input.kind {
+        GitCredentialKind::Token => {
+            let token = input
+                .token
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .or_else(|| existing_token.and_then(|r| r.secret.clone()));
+            let secret_ref = existing_token
+                .and_then(|r| r.secret_ref.clone())
+                .unwrap_or_else(|| Uuid::new_v4().to_string());
+            match token {
+                Some(token) => write_secret(&secret_ref, &token)?,
+                None if existing_token.is_some_and(|r| r.secret_ref.is_some()) => {}
+                None => anyhow::bail!("token is required"),
+            }
+            (Some(secret_ref), None)
+        }
+        GitCredentialKind::SshKey => {
+            let path = input
+                .ssh_key_path
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .context("ssh key path is required")?;
+            if !Path::new(&path).is_file() {
+                anyhow::bail!("ssh key not found: {}", path);
+            }
+            (None, Some(path))
+        }
+        GitCredentialKind::System => (None, None),
+    };
+
+    let now = now_ms();
+    let record = GitCredentialRecord {
+        host,
+        kind: input.kind.as_str().to_string(),
+        username,
+        secret: None,
+        secret_ref,
+        ssh_key_path,
+        created_at: existing.as_ref().map(|r| r.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    store.upsert_git_credential(&record)?;
+    // Switching away from a token leaves nothing that refers to the keychain item.
+    if let Some(old_ref) = existing.and_then(|r| r.secret_ref) {
+        if record.secret_ref.as_deref() != Some(old_ref.as_str()) {
+            delete_secret(&old_ref);
+        }
+    }
+    to_info(record).context("invalid credential kind")
+}
+
+pub fn delete_git_credential(store: &SkillStore, host: &str) -> Result<()> {
+    let host = normalize_host(host)?;
+    if let Some(secret_ref) = store.get_git_credential(&host)?.and_then(|r| r.secret_ref) {
+        delete_secret(&secret_ref);
+    }
+    store.delete_git_credential(&host)
+}
+
+/// Move tokens that older versions stored in plaintext into the OS keychain. Rows whose
+/// token cannot be moved keep it, so they still work, and are retried on the next start.
+pub fn move_git_secrets_to_keychain(store: &SkillStore) -> Result<usize> {
+    let mut moved = 0;
+    for record in store.list_git_credentials()? {
+        let Some(secret) = record.secret.clone() else {
+            continue;
+        };
+        let secret_ref = record
+            .secret_ref
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        if let Err(err) = write_secret(&secret_ref, &secret) {
+            log::warn!(
+                "[git_credentials] keeping token for {}: {:#}",
+                record.host,
+                err
+            );
+            continue;
+        }
+        store.upsert_git_credential(&GitCredentialRecord {
+            secret: None,
+            secret_ref: Some(secret_ref),
+            ..record
+        })?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+fn keychain_entry(secret_ref: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, secret_ref).context("open OS keychain")
+}
+
+fn write_secret(secret_ref: &str, secret: &str) -> Result<()> {
+    keychain_entry(secret_ref)?
+        .set_password(secret)
+        .context("store token in OS keychain")
+}
+
+fn read_secret(record: &GitCredentialRecord) -> Option<String> {
+    let Some(secret_ref) = record.secret_ref.as_deref() else {
+        return record.secret.clone();
+    };
+    match keychain_entry(secret_ref)
+        .and_then(|entry| entry.get_password().context("read token from OS keychain"))
+    {
+        Ok(secret) => Some(secret),
+        Err(err) => {
+            log::warn!("[git_credentials] no token for {}: {:#}", record.host, err);
+            record.secret.clone()
+        }
+    }
+}
+
+fn delete_secret(secret_ref: &str) {
+    match keychain_entry(secret_ref).map(|entry| entry.delete_credential()) {
+        Ok(Ok(())) | Ok(Err(keyring::Error::NoEntry)) => {}
+        Ok(Err(err)) => log::warn!("[git_credentials] failed to delete keychain item: {}", err),
+        Err(err) => log::warn!("[git_credentials] {:#}", err),
+    }
+}
+
+/// Check that the stored credentials for the URL's host can list its refs.
+pub fn test_git_credential(store: &SkillStore, url: &str) -> Result<GitCredentialTestResult> {
+    let host = url_host(url).with_context(|| format!("cannot determine host of {}", url))?;
+    let auth = resolve_git_auth(store, url)?;
+    let work_dir = std::env::temp_dir();
+    let (ok, message) =
+        match run_git_in_with_auth(&work_dir, &["ls-remote", "--heads", url], auth.as_ref()) {
+            Ok(out) => (true, format!("{} branch(es) visible", out.lines().count())),
+            Err(err) => (false, format!("{:#}", err)),
+        };
+    Ok(GitCredentialTestResult { host, ok, message })
+}
+
+fn to_info(record: GitCredentialRecord) -> Option<GitCredentialInfo> {
+    Some(GitCredentialInfo {
+        kind: GitCredentialKind::parse(&record.kind)?,
+        has_token: record.secret_ref.is_some()
+            || record.secret.as_deref().is_some_and(|s| !s.is_empty()),
+        host: record.host,
+        username: record.username,
+        ssh_key_path: record.ssh_key_path,
+        updated_at: record.updated_at,
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/git_credentials.rs"]
+mod tests;
@@ -1,18 +1,33 @@
 use std::path::Path;
 use std::process::Command;
 use std::process::Stdio;
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use git2::{FetchOptions, Repository};
+use git2::{FetchOptions, RemoteCallbacks, Repository};
 
-pub fn clone_or_pull(repo_url: &str, dest: &Path, branch: Option<&str>) -> Result<String> {
+use super::git_credentials::GitAuth;
+use super::jobs;
+use super::network;
+
+/// Clone `repo_url` into `dest` (or fetch if it exists) and return the checked-out HEAD.
+///
+/// With `sparse_paths`, the git-cli path only checks out those directories (cone mode,
+/// plus root-level files); later calls widen the sparse set as needed. `None` means a
+/// full checkout. The libgit2 fallback always checks out the full tree.
+pub fn clone_or_pull(
+    repo_url: &str,
+    dest: &Path,
+    branch: Option<&str>,
+    auth: Option<&GitAuth>,
+    sparse_paths: Option<&[&str]>,
+) -> Result<String> {
     // Prefer the system `git` binary if available. It tends to work better on macOS
     // networks because it respects user git config (proxy/certs) and OS trust store.
     if let Some(git_bin) = resolve_git_bin() {
         let started = Instant::now();
-        match clone_or_pull_via_git_cli(repo_url, dest, branch) {
+        match clone_or_pull_via_git_cli(repo_url, dest, branch, auth, sparse_paths) {
             Ok(head) => {
                 log::info!(
                     "[git_fetcher] git-cli ok (bin={}) {}s url={}",
@@ -34,6 +49,9 @@ pub fn clone_or_pull(repo_url: &str, dest: &Path, branch: Option<&str>) -> Resul
                     repo_url,
                     err
                 );
+                if jobs::is_cancelled(&err) {
+                    return Err(err);
+                }
                 if !allow_fallback {
                     anyhow::bail!(
                         "git 命令执行失败（为避免卡死，已停止并不再回退到内置 git）。请检查系统 git/网络/代理；或设置环境变量 SKILLS_HUB_ALLOW_LIBGIT2_FALLBACK=1 允许回退。\n{:#}",
@@ -51,11 +69,15 @@ pub fn clone_or_pull(repo_url: &str, dest: &Path, branch: Option<&str>) -> Resul
 
     let repo = if dest.exists() {
         let repo = Repository::open(dest).with_context(|| format!("open repo at {:?}", dest))?;
-        fetch_origin(&repo)?;
+        fetch_origin(&repo, auth).map_err(jobs::cancellation_or)?;
         repo
     } else {
-        Repository::clone(repo_url, dest)
-            .with_context(|| format!("clone {} into {:?}", repo_url, dest))?
+        let mut builder = git2::build::RepoBuilder::new();
+        builder.fetch_options(fetch_options(auth));
+        builder
+            .clone(repo_url, dest)
+            .with_context(|| format!("clone {} into {:?}", repo_url, dest))
+            .map_err(jobs::cancellation_or)?
     };
 
     // Best-effort: move working tree HEAD to the fetched remote head (so "pull" actually updates).
@@ -159,43 +181,192 @@ fn git_cmd() -> Command {
     // Abort stalled HTTPS transfers (helps avoid "spinner forever" on bad networks).
     cmd.env("GIT_HTTP_LOW_SPEED_LIMIT", "1024")
         .env("GIT_HTTP_LOW_SPEED_TIME", "120");
+    network::apply_to_git_command(&mut cmd);
     cmd
 }
 
+fn git_cmd_with_auth(auth: Option<&GitAuth>) -> Command {
+    let mut cmd = git_cmd();
+    if let Some(auth) = auth {
+        auth.apply_to_command(&mut cmd);
+    }
+    cmd
+}
+
+/// How many repositories bulk operations fetch at once
+/// (`SKILLS_HUB_GIT_FETCH_WORKERS`, 1-16, default 4).
+pub fn git_fetch_workers() -> usize {
+    std::env::var("SKILLS_HUB_GIT_FETCH_WORKERS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(4)
+        .clamp(1, 16)
+}
+
+/// Run `f` over `items` on at most `workers` threads and return the results in input
+/// order. The caller's job stays current on every worker, so fetches keep reporting
+/// progress and stop when it is cancelled.
+pub fn run_bounded<T, R, F>(items: Vec<T>, workers: usize, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let job = jobs::current();
+    let count = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new((0..count).map(|_| None).collect::<Vec<Option<R>>>());
+    std::thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, count.max(1)) {
+            scope.spawn(|| {
+                let work = || loop {
+                    let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                    let Some((index, item)) = next else {
+                        break;
+                    };
+                    let result = f(item);
+                    results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+                };
+                match &job {
+                    Some(job) => job.run(work),
+                    None => work(),
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|result| result.expect("every item is processed"))
+        .collect()
+}
+
 fn run_cmd_with_timeout(
+    cmd: Command,
+    timeout: Duration,
+    context: String,
+) -> Result<std::process::Output> {
+    run_cmd_with_timeout_and_input(cmd, timeout, context, None)
+}
+
+fn run_cmd_with_timeout_and_input(
     mut cmd: Command,
     timeout: Duration,
     context: String,
+    input: Option<&str>,
 ) -> Result<std::process::Output> {
-    cmd.stdin(Stdio::null())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
+    cmd.stdin(if input.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
 
     let mut child = cmd.spawn().with_context(|| context.clone())?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        use std::io::Write;
+        stdin
+            .write_all(input.as_bytes())
+            .with_context(|| context.clone())?;
+    }
+    // Drain stderr on the side: git's `--progress` output feeds the current job and is
+    // kept out of error messages.
+    let job = jobs::current();
+    let stderr_reader = child.stderr.take().map(|stderr| {
+        let job = job.clone();
+        std::thread::spawn(move || read_git_stderr(stderr, job.as_ref()))
+    });
+    let collect_stderr = |reader: Option<std::thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+
     let start = Instant::now();
     loop {
+        if job.as_ref().is_some_and(|job| job.is_cancelled()) {
+            let _ = child.kill();
+            let _ = child.wait();
+            collect_stderr(stderr_reader);
+            return Err(anyhow::Error::new(jobs::JobCancelled).context(context));
+        }
         if start.elapsed() > timeout {
             let _ = child.kill();
-            let stderr = child
-                .wait_with_output()
-                .map(|out| String::from_utf8_lossy(&out.stderr).to_string())
-                .unwrap_or_default();
+            let _ = child.wait();
+            let stderr = collect_stderr(stderr_reader);
             anyhow::bail!(
                 "git 操作超时（{}s）。请检查网络/代理是否可访问 GitHub；也可设置环境变量 SKILLS_HUB_GIT_TIMEOUT_SECS 增大超时。\n{}",
                 timeout.as_secs(),
-                stderr.trim()
+                String::from_utf8_lossy(&stderr).trim()
             );
         }
 
         match child.try_wait() {
-            Ok(Some(_)) => return child.wait_with_output().with_context(|| context.clone()),
+            Ok(Some(_)) => {
+                let mut out = child.wait_with_output().with_context(|| context.clone())?;
+                out.stderr = collect_stderr(stderr_reader);
+                return Ok(out);
+            }
             Ok(None) => std::thread::sleep(Duration::from_millis(200)),
             Err(err) => return Err(err).with_context(|| context.clone()),
         }
     }
 }
 
-fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>) -> Result<String> {
+/// Read a git child's stderr, reporting `--progress` lines (separated by `\r`) to `job`
+/// and returning everything else.
+fn read_git_stderr(mut stderr: impl std::io::Read, job: Option<&jobs::JobHandle>) -> Vec<u8> {
+    let mut kept = Vec::new();
+    let mut line = Vec::new();
+    let mut buf = [0u8; 4096];
+    let handle_line = |line: &[u8], kept: &mut Vec<u8>| {
+        let text = String::from_utf8_lossy(line);
+        if let Some(progress) = jobs::parse_git_progress(&text) {
+            if let Some(job) = job {
+                job.report(|p| {
+                    if p.phase != progress.phase {
+                        p.bytes = None;
+                    }
+                    p.phase = progress.phase;
+                    p.current = progress.current;
+                    p.total = progress.total;
+                    p.bytes = progress.bytes.or(p.bytes);
+                });
+            }
+        } else if !text.trim().is_empty() {
+            kept.extend_from_slice(line);
+            kept.push(b'\n');
+        }
+    };
+    loop {
+        let n = match stderr.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        for &byte in &buf[..n] {
+            if byte == b'\n' || byte == b'\r' {
+                handle_line(&line, &mut kept);
+                line.clear();
+            } else {
+                line.push(byte);
+            }
+        }
+    }
+    if !line.is_empty() {
+        handle_line(&line, &mut kept);
+    }
+    kept
+}
+
+fn clone_or_pull_via_git_cli(
+    repo_url: &str,
+    dest: &Path,
+    branch: Option<&str>,
+    auth: Option<&GitAuth>,
+    sparse_paths: Option<&[&str]>,
+) -> Result<String> {
     // Ensure parent exists so `git clone` can create dest.
     if let Some(parent) = dest.parent() {
         std::fs::create_dir_all(parent)
@@ -206,8 +377,10 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
         // Fetch updates.
         let out = run_cmd_with_timeout(
             {
-                let mut cmd = git_cmd();
-                cmd.arg("-C").arg(dest).args(["fetch", "--prune", "origin"]);
+                let mut cmd = git_cmd_with_auth(auth);
+                cmd.arg("-C")
+                    .arg(dest)
+                    .args(["fetch", "--progress", "--prune", "origin"]);
                 cmd
             },
             git_fetch_timeout(),
@@ -221,7 +394,7 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
         if let Some(branch) = branch {
             let out = run_cmd_with_timeout(
                 {
-                    let mut cmd = git_cmd();
+                    let mut cmd = git_cmd_with_auth(auth);
                     cmd.arg("-C").arg(dest).args([
                         "checkout",
                         "-B",
@@ -242,7 +415,7 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
         } else {
             let out = run_cmd_with_timeout(
                 {
-                    let mut cmd = git_cmd();
+                    let mut cmd = git_cmd_with_auth(auth);
                     cmd.arg("-C")
                         .arg(dest)
                         .args(["reset", "--hard", "FETCH_HEAD"]);
@@ -260,9 +433,13 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
         }
     } else {
         // Clone.
-        let mut cmd = git_cmd();
-        cmd.arg("clone")
+        let mut cmd = git_cmd_with_auth(auth);
+        cmd.args(["clone", "--progress"])
             .args(["--depth", "1", "--filter=blob:none", "--no-tags"]);
+        if sparse_paths.is_some() {
+            // Start with only root-level files; the requested paths are added below.
+            cmd.arg("--sparse");
+        }
         if let Some(branch) = branch {
             cmd.arg("--branch").arg(branch).arg("--single-branch");
         }
@@ -276,12 +453,13 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
             anyhow::bail!("git clone failed: {}", String::from_utf8_lossy(&out.stderr));
         }
     }
+    ensure_sparse_checkout(dest, sparse_paths, auth)?;
 
     // Checkout desired branch if specified (best-effort; shallow clones may already be on it).
     if let Some(branch) = branch {
         let out = run_cmd_with_timeout(
             {
-                let mut cmd = git_cmd();
+                let mut cmd = git_cmd_with_auth(auth);
                 cmd.arg("-C").arg(dest).args(["checkout", branch]);
                 cmd
             },
@@ -301,7 +479,7 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
     // Read HEAD revision.
     let out = run_cmd_with_timeout(
         {
-            let mut cmd = git_cmd();
+            let mut cmd = git_cmd_with_auth(auth);
             cmd.arg("-C").arg(dest).args(["rev-parse", "HEAD"]);
             cmd
         },
@@ -317,9 +495,246 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
     Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
 }
 
-fn fetch_origin(repo: &Repository) -> Result<()> {
-    let mut remote = repo.find_remote("origin")?;
+/// Make sure the checkout at `dest` contains `sparse_paths`, widening its sparse set
+/// (`None` disables sparse checkout). Full checkouts are left alone.
+pub fn ensure_sparse_checkout(
+    dest: &Path,
+    sparse_paths: Option<&[&str]>,
+    auth: Option<&GitAuth>,
+) -> Result<()> {
+    if resolve_git_bin().is_none() {
+        return Ok(());
+    }
+    let sparse_enabled = run_git_in(dest, &["config", "--get", "core.sparseCheckout"])
+        .map(|v| v == "true")
+        .unwrap_or(false);
+    if !sparse_enabled {
+        return Ok(());
+    }
+    match sparse_paths {
+        None => {
+            run_git_in_with_auth(dest, &["sparse-checkout", "disable"], auth)?;
+        }
+        Some(paths) => {
+            let current = run_git_in(dest, &["sparse-checkout", "list"]).unwrap_or_default();
+            let covered: Vec<&str> = current.lines().map(str::trim).collect();
+            let missing: Vec<&str> = paths
+                .iter()
+                .copied()
+                .filter(|p| {
+                    let p = p.trim_matches('/');
+                    !covered.iter().any(|c| {
+                        let c = c.trim_matches('/');
+                        p == c || p.starts_with(&format!("{}/", c))
+                    })
+                })
+                .collect();
+            if !missing.is_empty() {
+                let mut args = vec!["sparse-checkout", "add"];
+                args.extend(missing);
+                run_git_in_with_auth(dest, &args, auth)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fetch `branch` (the remote HEAD when `None`) into the bare `mirror` of `repo_url` and
+/// check it out into `worktree`, creating either as needed. Every ref of a repository gets
+/// its own worktree while objects are shared through one partial mirror. `sparse_paths`
+/// behaves as in [`clone_or_pull`]. Requires the system git binary.
+pub fn fetch_into_worktree(
+    repo_url: &str,
+    mirror: &Path,
+    worktree: &Path,
+    branch: Option<&str>,
+    auth: Option<&GitAuth>,
+    sparse_paths: Option<&[&str]>,
+) -> Result<String> {
+    if resolve_git_bin().is_none() {
+        anyhow::bail!("system git is required for the shared repository cache");
+    }
+    for dir in [mirror, worktree] {
+        if let Some(parent) = dir.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create parent dir {:?}", parent))?;
+        }
+    }
+
+    if !mirror.join("HEAD").exists() {
+        if mirror.exists() {
+            std::fs::remove_dir_all(mirror)
+                .with_context(|| format!("remove incomplete mirror {:?}", mirror))?;
+        }
+        let mut cmd = git_cmd_with_auth(auth);
+        cmd.arg("clone")
+            .args(["--bare", "--progress"])
+            .args(["--depth", "1", "--filter=blob:none", "--no-tags"])
+            .arg(repo_url)
+            .arg(mirror);
+        let out = run_cmd_with_timeout(
+            cmd,
+            git_timeout(),
+            format!("git clone {} into {:?}", repo_url, mirror),
+        )?;
+        if !out.status.success() {
+            anyhow::bail!("git clone failed: {}", String::from_utf8_lossy(&out.stderr));
+        }
+    }
+
+    let git_ref = branch.unwrap_or("HEAD");
+    run_git_in_with_auth(
+        mirror,
+        &[
+            "fetch",
+            "--progress",
+            "--depth",
+            "1",
+            "--no-tags",
+            "origin",
+            git_ref,
+        ],
+        auth,
+    )?;
+    let head = run_git_in(mirror, &["rev-parse", "FETCH_HEAD"])?;
+
+    if is_linked_worktree(worktree) {
+        run_git_in_with_auth(worktree, &["reset", "--hard", &head], auth)?;
+        ensure_sparse_checkout(worktree, sparse_paths, auth)?;
+    } else {
+        // Leftovers from a standalone (libgit2) clone or a removed mirror.
+        if worktree.exists() {
+            std::fs::remove_dir_all(worktree)
+                .with_context(|| format!("remove stale worktree {:?}", worktree))?;
+        }
+        let _ = run_git_in(mirror, &["worktree", "prune"]);
+        let worktree_arg = worktree.to_string_lossy();
+        run_git_in(
+            mirror,
+            &[
+                "worktree",
+                "add",
+                "--no-checkout",
+                "--detach",
+                &worktree_arg,
+                &head,
+            ],
+        )?;
+        if let Some(paths) = sparse_paths {
+            let mut args = vec!["sparse-checkout", "set", "--cone"];
+            args.extend(paths);
+            run_git_in_with_auth(worktree, &args, auth)?;
+        }
+        // Populates the (sparse) working tree, fetching the needed blobs lazily.
+        run_git_in_with_auth(worktree, &["reset", "--hard", &head], auth)?;
+    }
+    Ok(head)
+}
+
+/// Whether `dir` is a linked worktree whose repository still exists.
+pub fn is_linked_worktree(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join(".git"))
+        .ok()
+        .and_then(|raw| raw.strip_prefix("gitdir:").map(|p| p.trim().to_string()))
+        .is_some_and(|gitdir| Path::new(&gitdir).exists())
+}
+
+/// Delete a linked worktree and drop its bookkeeping from `mirror` (best effort).
+pub fn remove_worktree(mirror: &Path, worktree: &Path) -> Result<()> {
+    if worktree.exists() {
+        std::fs::remove_dir_all(worktree).with_context(|| format!("remove {:?}", worktree))?;
+    }
+    if mirror.exists() && resolve_git_bin().is_some() {
+        let _ = run_git_in(mirror, &["worktree", "prune"]);
+    }
+    Ok(())
+}
+
+/// Whether a usable system `git` binary was found.
+pub fn git_cli_available() -> bool {
+    resolve_git_bin().is_some()
+}
+
+/// Run `git -C <dir> <args...>` with the same non-interactive environment and timeout as
+/// clone/fetch, returning trimmed stdout. Fails with stderr when git exits non-zero.
+pub fn run_git_in(dir: &Path, args: &[&str]) -> Result<String> {
+    run_git_in_with_auth(dir, args, None)
+}
+
+/// [`run_git_in`] for commands that talk to a remote, authenticating with `auth`.
+pub fn run_git_in_with_auth(dir: &Path, args: &[&str], auth: Option<&GitAuth>) -> Result<String> {
+    let out = run_cmd_with_timeout(
+        {
+            let mut cmd = git_cmd_with_auth(auth);
+            cmd.arg("-C").arg(dir).args(args);
+            cmd
+        },
+        git_timeout(),
+        format!("git {} in {:?}", args.join(" "), dir),
+    )?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Ask the configured git credential helpers for `protocol://host` credentials without
+/// prompting. Returns `(username, password)` when a helper has them.
+pub fn git_credential_fill(protocol: &str, host: &str) -> Result<Option<(String, String)>> {
+    let mut cmd = git_cmd();
+    // `echo` as askpass would hand the prompt text back as the credential.
+    cmd.env_remove("GIT_ASKPASS").args(["credential", "fill"]);
+    let out = run_cmd_with_timeout_and_input(
+        cmd,
+        git_fetch_timeout(),
+        format!("git credential fill for {}", host),
+        Some(&format!("protocol={}\nhost={}\n\n", protocol, host)),
+    )?;
+    if !out.status.success() {
+        return Ok(None);
+    }
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let field = |key: &str| {
+        stdout
+            .lines()
+            .find_map(|l| l.strip_prefix(key))
+            .map(|v| v.to_string())
+    };
+    Ok(field("password=").map(|password| (field("username=").unwrap_or_default(), password)))
+}
+
+fn fetch_options(auth: Option<&GitAuth>) -> FetchOptions<'_> {
     let mut opts = FetchOptions::new();
+    let mut callbacks = match auth {
+        Some(auth) => auth.remote_callbacks(),
+        None => RemoteCallbacks::new(),
+    };
+    if let Some(job) = jobs::current() {
+        job.set_phase("Receiving objects");
+        // Returning false aborts the transfer once the job is cancelled.
+        callbacks.transfer_progress(move |stats| {
+            job.report(|p| {
+                p.current = Some(stats.received_objects() as u64);
+                p.total = Some(stats.total_objects() as u64);
+                p.bytes = Some(stats.received_bytes() as u64);
+            });
+            !job.is_cancelled()
+        });
+    }
+    opts.remote_callbacks(callbacks);
+    if let Some(proxy) = network::git2_proxy_options() {
+        opts.proxy_options(proxy);
+    }
+    opts
+}
+
+fn fetch_origin(repo: &Repository, auth: Option<&GitAuth>) -> Result<()> {
+    let mut remote = repo.find_remote("origin")?;
+    let mut opts = fetch_options(auth);
     remote.fetch(
         &["refs/heads/*:refs/remotes/origin/*"],
         Some(&mut opts),
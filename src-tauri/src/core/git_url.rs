@@ -0,0 +1,304 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsedGitSource {
+    pub clone_url: String,
+    pub branch: Option<String>,
+    pub subpath: Option<String>,
+}
+
+impl ParsedGitSource {
+    fn plain(clone_url: &str) -> Self {
+        Self {
+            clone_url: clone_url.to_string(),
+            branch: None,
+            subpath: None,
+        }
+    }
+}
+
+/// A parser for one git host's web URL conventions.
+pub trait GitHostParser: Sync {
+    /// Returns `None` when the URL doesn't belong to this host.
+    fn parse(&self, url: &str) -> Option<ParsedGitSource>;
+}
+
+/// `https://github.com/<owner>/<repo>[/tree|blob/<ref>/<path>]`
+pub struct GitHubParser;
+
+/// `https://<host>/<group>[/<subgroup>...]/<repo>/-/tree|blob/<ref>/<path>`,
+/// plus bare repo URLs on `gitlab.com`.
+pub struct GitLabParser;
+
+/// `https://<host>/<owner>/<repo>/src/branch|tag|commit/<ref>/<path>`
+/// (Gitea, Forgejo, Codeberg).
+pub struct GiteaParser;
+
+/// `https://bitbucket.org/<workspace>/<repo>[/src/<ref>/<path>]`
+pub struct BitbucketParser;
+
+static HOST_PARSERS: &[&dyn GitHostParser] =
+    &[&GitHubParser, &GiteaParser, &GitLabParser, &BitbucketParser];
+
+/// Parse a repository/folder URL into clone URL, ref and subpath.
+///
+/// Host parsers are tried in order; inputs none of them claim (local paths,
+/// `ssh://`, `git@host:org/repo.git`) are cloned as-is. A trailing
+/// `#<ref>:<subpath>` fragment (or `#<ref>` / `#:<subpath>`) overrides what the
+/// host parser derived, which also covers unknown hosts and refs containing `/`.
+pub fn parse_git_source(input: &str) -> ParsedGitSource {
+    let trimmed = input.trim();
+    let (base, fragment) = split_fragment(trimmed);
+    let base = base.trim_end_matches('/');
+
+    let mut parsed = recognize_host(base).unwrap_or_else(|| ParsedGitSource::plain(base));
+
+    if let Some(fragment) = fragment {
+        let (git_ref, subpath) = match fragment.split_once(':') {
+            Some((r, p)) => (r, Some(p)),
+            None => (fragment, None),
+        };
+        if !git_ref.trim().is_empty() {
+            parsed.branch = Some(git_ref.trim().to_string());
+        }
+        if let Some(subpath) = subpath.map(|p| p.trim().trim_matches('/')) {
+            parsed.subpath = if subpath.is_empty() || subpath == "." {
+                None
+            } else {
+                Some(subpath.to_string())
+            };
+        }
+    }
+
+    parsed
+}
+
+/// Like [`parse_git_source`], but only succeeds for URLs a known host parser
+/// claims. Used when scraping links from lists, where arbitrary web pages must
+/// not be mistaken for repositories.
+pub fn recognize_git_host_url(input: &str) -> Option<ParsedGitSource> {
+    let (base, _) = split_fragment(input.trim());
+    recognize_host(base.trim_end_matches('/'))?;
+    Some(parse_git_source(input))
+}
+
+/// `(owner, repo)` for GitHub remotes in https, ssh or scp-like form.
+pub fn github_owner_repo(remote_url: &str) -> Option<(String, String)> {
+    let rest = [
+        "https://github.com/",
+        "http://github.com/",
+        "ssh://git@github.com/",
+        "git@github.com:",
+    ]
+    .iter()
+    .find_map(|prefix| remote_url.trim().strip_prefix(prefix))?;
+    let rest = rest.trim_end_matches('/');
+    let rest = rest.strip_suffix(".git").unwrap_or(rest);
+    match rest.split('/').collect::<Vec<_>>()[..] {
+        [owner, repo] if !owner.is_empty() && !repo.is_empty() => {
+            Some((owner.to_string(), repo.to_string()))
+        }
+        _ => None,
+    }
+}
+
+fn recognize_host(url: &str) -> Option<ParsedGitSource> {
+    let normalized = normalize_github_input(url);
+    HOST_PARSERS.iter().find_map(|p| p.parse(&normalized))
+}
+
+fn split_fragment(input: &str) -> (&str, Option<&str>) {
+    match input.split_once('#') {
+        Some((base, fragment)) if !fragment.is_empty() => (base, Some(fragment)),
+        Some((base, _)) => (base, None),
+        None => (input, None),
+    }
+}
+
+// Convenience: allow GitHub shorthand inputs like `owner/repo` (and
+// `owner/repo/tree/<branch>/...`). This keeps the UI friendly while still
+// allowing local paths or other git remotes.
+fn normalize_github_input(trimmed: &str) -> String {
+    if trimmed.starts_with("http://github.com/") {
+        trimmed.replacen("http://github.com/", "https://github.com/", 1)
+    } else if trimmed.starts_with("github.com/") {
+        format!("https://{}", trimmed)
+    } else if looks_like_github_shorthand(trimmed) {
+        format!("https://github.com/{}", trimmed)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn looks_like_github_shorthand(input: &str) -> bool {
+    if input.is_empty() {
+        return false;
+    }
+    if input.starts_with('/') || input.starts_with('~') || input.starts_with('.') {
+        return false;
+    }
+    // Avoid scp-like ssh URLs (git@github.com:owner/repo) and any explicit schemes.
+    if input.contains("://") || input.contains('@') || input.contains(':') {
+        return false;
+    }
+
+    let parts: Vec<&str> = input.split('/').collect();
+    if parts.len() < 2 {
+        return false;
+    }
+
+    let owner = parts[0];
+    let repo = parts[1];
+    if owner.is_empty()
+        || repo.is_empty()
+        || owner == "."
+        || owner == ".."
+        || repo == "."
+        || repo == ".."
+    {
+        return false;
+    }
+
+    let is_safe_segment = |s: &str| {
+        s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    };
+    if !is_safe_segment(owner) || !is_safe_segment(repo.trim_end_matches(".git")) {
+        return false;
+    }
+
+    // If there are more path parts, only accept the GitHub UI patterns we can parse.
+    if parts.len() > 2 {
+        matches!(parts[2], "tree" | "blob")
+    } else {
+        true
+    }
+}
+
+/// Splits `https://host/a/b/c` into (`https://host`, `host`, [`a`, `b`, `c`]).
+fn split_http_url(url: &str) -> Option<(&str, &str, Vec<&str>)> {
+    let scheme_end = if url.starts_with("https://") {
+        "https://".len()
+    } else if url.starts_with("http://") {
+        "http://".len()
+    } else {
+        return None;
+    };
+    let after_scheme = &url[scheme_end..];
+    let host_end = after_scheme.find('/').unwrap_or(after_scheme.len());
+    let host = &after_scheme[..host_end];
+    if host.is_empty() {
+        return None;
+    }
+    let origin = &url[..scheme_end + host_end];
+    let parts = after_scheme[host_end..]
+        .split('/')
+        .filter(|p| !p.is_empty())
+        .collect();
+    Some((origin, host, parts))
+}
+
+fn repo_clone_url(origin: &str, repo_parts: &[&str]) -> String {
+    let joined = repo_parts.join("/");
+    let joined = joined.strip_suffix(".git").unwrap_or(&joined);
+    format!("{}/{}.git", origin, joined)
+}
+
+fn ref_and_subpath(parts: &[&str]) -> (Option<String>, Option<String>) {
+    let branch = parts.first().map(|s| s.to_string());
+    let subpath = if parts.len() > 1 {
+        Some(parts[1..].join("/"))
+    } else {
+        None
+    };
+    (branch, subpath)
+}
+
+impl GitHostParser for GitHubParser {
+    fn parse(&self, url: &str) -> Option<ParsedGitSource> {
+        let (origin, host, parts) = split_http_url(url)?;
+        if !host.eq_ignore_ascii_case("github.com") {
+            return None;
+        }
+        if parts.len() < 2 {
+            return Some(ParsedGitSource::plain(url));
+        }
+        let origin = origin.replacen("http://", "https://", 1);
+        let clone_url = repo_clone_url(&origin, &parts[..2]);
+        let (branch, subpath) = if parts.len() >= 4 && matches!(parts[2], "tree" | "blob") {
+            ref_and_subpath(&parts[3..])
+        } else {
+            (None, None)
+        };
+        Some(ParsedGitSource {
+            clone_url,
+            branch,
+            subpath,
+        })
+    }
+}
+
+impl GitHostParser for GitLabParser {
+    fn parse(&self, url: &str) -> Option<ParsedGitSource> {
+        let (origin, host, parts) = split_http_url(url)?;
+        // GitLab separates the (arbitrarily nested) project path from UI routes with `/-/`.
+        if let Some(marker) = parts.iter().position(|p| *p == "-") {
+            if marker < 2 {
+                return None;
+            }
+            let clone_url = repo_clone_url(origin, &parts[..marker]);
+            let route = &parts[marker + 1..];
+            let (branch, subpath) = if route.len() >= 2 && matches!(route[0], "tree" | "blob") {
+                ref_and_subpath(&route[1..])
+            } else {
+                (None, None)
+            };
+            return Some(ParsedGitSource {
+                clone_url,
+                branch,
+                subpath,
+            });
+        }
+        if host.eq_ignore_ascii_case("gitlab.com") && parts.len() >= 2 {
+            return Some(ParsedGitSource::plain(&repo_clone_url(origin, &parts)));
+        }
+        None
+    }
+}
+
+impl GitHostParser for GiteaParser {
+    fn parse(&self, url: &str) -> Option<ParsedGitSource> {
+        let (origin, _host, parts) = split_http_url(url)?;
+        if parts.len() < 5 || parts[2] != "src" || !matches!(parts[3], "branch" | "tag" | "commit")
+        {
+            return None;
+        }
+        let (branch, subpath) = ref_and_subpath(&parts[4..]);
+        Some(ParsedGitSource {
+            clone_url: repo_clone_url(origin, &parts[..2]),
+            branch,
+            subpath,
+        })
+    }
+}
+
+impl GitHostParser for BitbucketParser {
+    fn parse(&self, url: &str) -> Option<ParsedGitSource> {
+        let (origin, host, parts) = split_http_url(url)?;
+        if !host.eq_ignore_ascii_case("bitbucket.org") || parts.len() < 2 {
+            return None;
+        }
+        let (branch, subpath) = if parts.len() >= 4 && parts[2] == "src" {
+            ref_and_subpath(&parts[3..])
+        } else {
+            (None, None)
+        };
+        Some(ParsedGitSource {
+            clone_url: repo_clone_url(origin, &parts[..2]),
+            branch,
+            subpath,
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/git_url.rs"]
+mod tests;
@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
-use reqwest::blocking::Client;
 use serde::Deserialize;
 
+use super::installer::{parse_skill_md_text, GitSkillCandidate};
+use super::network;
+
 #[derive(Debug, Deserialize)]
 struct SearchResponse {
     items: Vec<RepoItem>,
@@ -27,16 +29,50 @@ pub struct RepoSummary {
     pub clone_url: String,
 }
 
-pub fn search_github_repos(query: &str, limit: usize) -> Result<Vec<RepoSummary>> {
-    search_github_repos_inner("https://api.github.com", query, limit)
+#[derive(Debug, Deserialize)]
+struct CodeSearchResponse {
+    items: Vec<CodeItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeItem {
+    name: String,
+    path: String,
+    repository: CodeRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeRepository {
+    full_name: String,
+    html_url: String,
+    description: Option<String>,
+}
+
+/// A repository with the skills that matched a code search. Each skill installs with
+/// `install_git_skill_from_selection(html_url, subpath)`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SkillRepoMatch {
+    pub full_name: String,
+    pub html_url: String,
+    pub description: Option<String>,
+    pub skills: Vec<GitSkillCandidate>,
+}
+
+pub fn search_github_repos(
+    query: &str,
+    limit: usize,
+    token: Option<&str>,
+) -> Result<Vec<RepoSummary>> {
+    search_github_repos_inner("https://api.github.com", query, limit, token)
 }
 
 fn search_github_repos_inner(
     base_url: &str,
     query: &str,
     limit: usize,
+    token: Option<&str>,
 ) -> Result<Vec<RepoSummary>> {
-    let client = Client::new();
+    let client = network::http_client()?;
     let base_url = base_url.trim_end_matches('/');
     let url = format!(
         "{}/search/repositories?q={}&per_page={}",
@@ -44,10 +80,13 @@ fn search_github_repos_inner(
         urlencoding::encode(query),
         limit.clamp(1, 50)
     );
+    network::ensure_online_for(&url)?;
 
-    let response = client
-        .get(url)
-        .header("User-Agent", "skills-hub")
+    let mut request = client.get(url).header("User-Agent", "skills-hub");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
         .send()
         .context("GitHub search request failed")?
         .error_for_status()
@@ -69,6 +108,139 @@ fn search_github_repos_inner(
         .collect())
 }
 
+/// Search GitHub code for `SKILL.md` files matching `query`, so skills in subfolders of
+/// large repositories are found too. Code search only covers default branches and
+/// requires a token. Names and descriptions come from each file's frontmatter.
+pub fn search_github_skills(
+    query: &str,
+    limit: usize,
+    token: Option<&str>,
+) -> Result<Vec<SkillRepoMatch>> {
+    search_github_skills_inner(
+        "https://api.github.com",
+        "https://raw.githubusercontent.com",
+        query,
+        limit,
+        token,
+    )
+}
+
+fn search_github_skills_inner(
+    base_url: &str,
+    raw_base_url: &str,
+    query: &str,
+    limit: usize,
+    token: Option<&str>,
+) -> Result<Vec<SkillRepoMatch>> {
+    let query = query.trim();
+    if query.is_empty() {
+        anyhow::bail!("search query is empty");
+    }
+    let Some(token) = token else {
+        anyhow::bail!(
+            "GitHub code search requires a GitHub token; add one in the git credentials settings"
+        );
+    };
+    let client = network::http_client()?;
+    let base_url = base_url.trim_end_matches('/');
+    let url = format!(
+        "{}/search/code?q={}&per_page={}",
+        base_url,
+        urlencoding::encode(&format!("{} filename:SKILL.md", query)),
+        limit.clamp(1, 50)
+    );
+    network::ensure_online_for(&url)?;
+
+    let response = client
+        .get(url)
+        .header("User-Agent", "skills-hub")
+        .bearer_auth(token)
+        .send()
+        .context("GitHub code search request failed")?
+        .error_for_status()
+        .context("GitHub code search returned error")?;
+    let result: CodeSearchResponse = response.json().context("parse GitHub response")?;
+
+    // Repositories keep the order of their best hit.
+    let mut out: Vec<SkillRepoMatch> = Vec::new();
+    for item in result.items {
+        // `filename:` also matches other letter cases, which the installer does not accept.
+        if item.name != "SKILL.md" {
+            continue;
+        }
+        let subpath = match item.path.rsplit_once('/') {
+            Some((dir, _)) => dir.to_string(),
+            None => ".".to_string(),
+        };
+        let index = match out
+            .iter()
+            .position(|repo| repo.full_name == item.repository.full_name)
+        {
+            Some(index) => index,
+            None => {
+                out.push(SkillRepoMatch {
+                    full_name: item.repository.full_name.clone(),
+                    html_url: item.repository.html_url,
+                    description: item.repository.description,
+                    skills: Vec::new(),
+                });
+                out.len() - 1
+            }
+        };
+        let repo = &mut out[index];
+        if repo.skills.iter().any(|skill| skill.subpath == subpath) {
+            continue;
+        }
+        let frontmatter =
+            fetch_skill_frontmatter(&client, raw_base_url, &repo.full_name, &item.path);
+        let (name, description) = match frontmatter {
+            Some((name, description)) => (name, description),
+            None => {
+                let fallback = match subpath.as_str() {
+                    "." => repo.full_name.rsplit('/').next().unwrap_or_default(),
+                    dir => dir.rsplit('/').next().unwrap_or(dir),
+                };
+                (fallback.to_string(), None)
+            }
+        };
+        repo.skills.push(GitSkillCandidate {
+            name,
+            description,
+            subpath,
+        });
+    }
+    for repo in &mut out {
+        repo.skills.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    Ok(out)
+}
+
+/// `(name, description)` from the frontmatter of `path` on the default branch.
+fn fetch_skill_frontmatter(
+    client: &reqwest::blocking::Client,
+    raw_base_url: &str,
+    full_name: &str,
+    path: &str,
+) -> Option<(String, Option<String>)> {
+    let url = format!(
+        "{}/{}/HEAD/{}",
+        raw_base_url.trim_end_matches('/'),
+        full_name,
+        path
+    );
+    let response = client
+        .get(url)
+        .header("User-Agent", "skills-hub")
+        .send()
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let text = response.text().ok()?;
+    let (name, description, _) = parse_skill_md_text(&text).ok()?;
+    Some((name, description))
+}
+
 #[cfg(test)]
 #[path = "tests/github_search.rs"]
 mod tests;
@@ -0,0 +1,123 @@
+//! Whether discovery results are installed as managed skills.
+//!
+//! Results and managed git skills are matched on repository and skill folder, so a
+//! result and an install that spell the URL differently (`.git` suffix, `tree/<ref>`,
+//! `#<ref>:<subpath>`) still match. An install is outdated when its revision differs
+//! from the commit a team index pins, or, for a result on the same branch as the
+//! install, from the commit the metadata enricher last saw that branch at.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::git_url::{github_owner_repo, parse_git_source};
+use super::skill_store::{SkillRecord, SkillStore};
+use super::team_index::looks_like_commit;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstalledState {
+    #[default]
+    NotInstalled,
+    Installed,
+    /// Installed at a revision other than the one the result points at.
+    Outdated,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstalledMatch {
+    pub state: InstalledState,
+    /// The managed skill installed from the result.
+    pub skill_id: Option<String>,
+}
+
+/// Managed git skills by repository and skill folder.
+pub struct InstalledSkills {
+    by_source: HashMap<(String, String), Vec<SkillRecord>>,
+}
+
+impl InstalledSkills {
+    pub fn load(store: &SkillStore) -> Result<Self> {
+        Ok(Self::new(store.list_skills()?))
+    }
+
+    fn new(skills: Vec<SkillRecord>) -> Self {
+        let mut by_source: HashMap<(String, String), Vec<SkillRecord>> = HashMap::new();
+        for skill in skills {
+            if skill.source_type != "git" {
+                continue;
+            }
+            if let Some(key) = skill.source_ref.as_deref().map(source_key) {
+                by_source.entry(key).or_default().push(skill);
+            }
+        }
+        Self { by_source }
+    }
+
+    /// Install state of the result at `url`. `head_revision` is the commit its branch
+    /// pointed at when last enriched. With several installs, an up-to-date one wins.
+    pub fn lookup(&self, url: &str, head_revision: Option<&str>) -> InstalledMatch {
+        let Some(skills) = self.by_source.get(&source_key(url)) else {
+            return InstalledMatch::default();
+        };
+        let result_ref = parse_git_source(url).branch;
+        skills
+            .iter()
+            .map(|skill| {
+                let outdated = is_outdated(skill, result_ref.as_deref(), head_revision);
+                (outdated, skill)
+            })
+            .min_by_key(|(outdated, _)| *outdated)
+            .map(|(outdated, skill)| InstalledMatch {
+                state: if outdated {
+                    InstalledState::Outdated
+                } else {
+                    InstalledState::Installed
+                },
+                skill_id: Some(skill.id.clone()),
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Repository and skill folder of a git source, normalized for comparison.
+fn source_key(url: &str) -> (String, String) {
+    let parsed = parse_git_source(url);
+    let repo = match github_owner_repo(&parsed.clone_url) {
+        Some((owner, name)) => format!("github.com/{}/{}", owner, name),
+        None => {
+            let url = parsed.clone_url.trim_end_matches('/');
+            url.strip_suffix(".git").unwrap_or(url).to_string()
+        }
+    };
+    let subpath = parsed.subpath.unwrap_or_default();
+    (repo.to_lowercase(), subpath.trim_matches('/').to_string())
+}
+
+fn is_outdated(skill: &SkillRecord, result_ref: Option<&str>, head_revision: Option<&str>) -> bool {
+    let Some(installed) = skill.source_revision.as_deref() else {
+        return false;
+    };
+    let installed_ref = skill
+        .source_ref
+        .as_deref()
+        .and_then(|source_ref| parse_git_source(source_ref).branch);
+    let latest = match result_ref {
+        Some(pinned) if looks_like_commit(pinned) => Some(pinned),
+        _ if installed_ref.as_deref() == result_ref => head_revision,
+        // Installed from another branch; not comparable.
+        _ => None,
+    };
+    latest.is_some_and(|latest| !same_revision(installed, latest))
+}
+
+/// Commit ids compared case-insensitively, allowing either to be abbreviated.
+fn same_revision(a: &str, b: &str) -> bool {
+    let (a, b) = (a.to_ascii_lowercase(), b.to_ascii_lowercase());
+    a.starts_with(&b) || b.starts_with(&a)
+}
+
+#[cfg(test)]
+#[path = "tests/install_state.rs"]
+mod tests;
@@ -1,20 +1,32 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use tauri::Manager;
 use uuid::Uuid;
 
-use super::cache_cleanup::get_git_cache_ttl_secs;
+use super::cache_cleanup::{dir_size, enforce_git_cache_budget, get_git_cache_ttl_secs};
 use super::central_repo::{ensure_central_repo, resolve_central_repo_path};
 use super::content_hash::hash_dir;
-use super::git_fetcher::clone_or_pull;
-use super::skill_store::{SkillRecord, SkillStore};
+use super::git_credentials::{github_api_token, resolve_git_auth};
+use super::git_fetcher::{
+    clone_or_pull, ensure_sparse_checkout, fetch_into_worktree, git_cli_available,
+    git_fetch_workers, run_bounded,
+};
+use super::git_url::{github_owner_repo, parse_git_source};
+use super::jobs;
+use super::network;
+use super::skill_publish::has_unpushed_changes;
+use super::skill_store::{GitCacheEntryRecord, SkillRecord, SkillStore};
 use super::sync_engine::copy_dir_recursive;
+use super::sync_engine::remove_path_any;
 use super::sync_engine::sync_dir_copy_with_overwrite;
+use super::tarball_fetcher::{fetch_github_tarball, get_fetch_strategy};
 use super::tool_adapters::adapter_by_key;
 use super::tool_adapters::is_tool_installed;
+use super::util::now_ms;
 
 pub struct InstallResult {
     pub skill_id: String,
@@ -96,7 +108,7 @@ pub fn install_git_skill<R: tauri::Runtime>(
     repo_url: &str,
     name: Option<String>,
 ) -> Result<InstallResult> {
-    let parsed = parse_github_url(repo_url);
+    let parsed = parse_git_source(repo_url);
     let name = name.unwrap_or_else(|| {
         if let Some(subpath) = &parsed.subpath {
             subpath
@@ -120,7 +132,14 @@ pub fn install_git_skill<R: tauri::Runtime>(
     // Always clone into a temp dir first, then copy the skill directory into central repo.
     // This avoids storing a full git repo (with .git) inside central repo and allows
     // handling GitHub folder URLs (/tree/<branch>/<path>).
-    let (repo_dir, rev) = clone_to_cache(app, store, &parsed.clone_url, parsed.branch.as_deref())?;
+    let sparse = parsed.subpath.as_deref().map(|p| [p]);
+    let (repo_dir, rev) = clone_to_cache(
+        app,
+        store,
+        &parsed.clone_url,
+        parsed.branch.as_deref(),
+        sparse.as_ref().map(|p| &p[..]),
+    )?;
 
     let copy_src = if let Some(subpath) = &parsed.subpath {
         let sub_src = repo_dir.join(subpath);
@@ -184,6 +203,10 @@ pub fn install_git_skill<R: tauri::Runtime>(
     };
 
     store.upsert_skill(&record)?;
+    let _ = store.set_git_cache_skill_ref(
+        &record.id,
+        &repo_cache_key(&parsed.clone_url, parsed.branch.as_deref()),
+    );
 
     Ok(InstallResult {
         skill_id: record.id,
@@ -193,138 +216,9 @@ pub fn install_git_skill<R: tauri::Runtime>(
     })
 }
 
-#[derive(Clone, Debug)]
-struct ParsedGitSource {
-    clone_url: String,
-    branch: Option<String>,
-    subpath: Option<String>,
-}
-
-fn parse_github_url(input: &str) -> ParsedGitSource {
-    // Supports:
-    // - https://github.com/owner/repo
-    // - https://github.com/owner/repo.git
-    // - https://github.com/owner/repo/tree/<branch>/<path>
-    // - https://github.com/owner/repo/blob/<branch>/<path>
-    let trimmed = input.trim().trim_end_matches('/');
-
-    // Convenience: allow GitHub shorthand inputs like `owner/repo` (and `owner/repo/tree/<branch>/...`).
-    // This keeps the UI friendly while still allowing local paths or other git remotes.
-    let normalized = if trimmed.starts_with("https://github.com/") {
-        trimmed.to_string()
-    } else if trimmed.starts_with("http://github.com/") {
-        trimmed.replacen("http://github.com/", "https://github.com/", 1)
-    } else if trimmed.starts_with("github.com/") {
-        format!("https://{}", trimmed)
-    } else if looks_like_github_shorthand(trimmed) {
-        format!("https://github.com/{}", trimmed)
-    } else {
-        trimmed.to_string()
-    };
-
-    let trimmed = normalized.trim_end_matches('/');
-    let gh_prefix = "https://github.com/";
-    if !trimmed.starts_with(gh_prefix) {
-        return ParsedGitSource {
-            clone_url: trimmed.to_string(),
-            branch: None,
-            subpath: None,
-        };
-    }
-
-    let rest = &trimmed[gh_prefix.len()..];
-    let parts: Vec<&str> = rest.split('/').collect();
-    if parts.len() < 2 {
-        return ParsedGitSource {
-            clone_url: trimmed.to_string(),
-            branch: None,
-            subpath: None,
-        };
-    }
-
-    let owner = parts[0];
-    let mut repo = parts[1].to_string();
-    if let Some(stripped) = repo.strip_suffix(".git") {
-        repo = stripped.to_string();
-    }
-    let clone_url = format!("https://github.com/{}/{}.git", owner, repo);
-
-    if parts.len() >= 4 && (parts[2] == "tree" || parts[2] == "blob") {
-        let branch = Some(parts[3].to_string());
-        let subpath = if parts.len() > 4 {
-            Some(parts[4..].join("/"))
-        } else {
-            None
-        };
-        return ParsedGitSource {
-            clone_url,
-            branch,
-            subpath,
-        };
-    }
-
-    ParsedGitSource {
-        clone_url,
-        branch: None,
-        subpath: None,
-    }
-}
-
-fn looks_like_github_shorthand(input: &str) -> bool {
-    if input.is_empty() {
-        return false;
-    }
-    if input.starts_with('/') || input.starts_with('~') || input.starts_with('.') {
-        return false;
-    }
-    // Avoid scp-like ssh URLs (git@github.com:owner/repo) and any explicit schemes.
-    if input.contains("://") || input.contains('@') || input.contains(':') {
-        return false;
-    }
-
-    let parts: Vec<&str> = input.split('/').collect();
-    if parts.len() < 2 {
-        return false;
-    }
-
-    let owner = parts[0];
-    let repo = parts[1];
-    if owner.is_empty()
-        || repo.is_empty()
-        || owner == "."
-        || owner == ".."
-        || repo == "."
-        || repo == ".."
-    {
-        return false;
-    }
-
-    let is_safe_segment = |s: &str| {
-        s.chars()
-            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
-    };
-    if !is_safe_segment(owner) || !is_safe_segment(repo.trim_end_matches(".git")) {
-        return false;
-    }
-
-    // If there are more path parts, only accept the GitHub UI patterns we can parse.
-    if parts.len() > 2 {
-        matches!(parts[2], "tree" | "blob")
-    } else {
-        true
-    }
-}
-
-fn now_ms() -> i64 {
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::SystemTime::UNIX_EPOCH)
-        .unwrap_or_default();
-    now.as_millis() as i64
-}
-
 fn derive_name_from_repo_url(repo_url: &str) -> String {
     let mut name = repo_url
-        .split('/')
+        .split(['/', ':'])
         .next_back()
         .unwrap_or("skill")
         .to_string();
@@ -338,7 +232,36 @@ fn derive_name_from_repo_url(repo_url: &str) -> String {
     }
 }
 
-fn compute_content_hash(path: &Path) -> Option<String> {
+/// Remove a managed skill: its copies/links in tool directories, its central copy and its
+/// record. Failing target removals are reported after the skill itself is gone.
+pub fn uninstall_skill(store: &SkillStore, skill_id: &str) -> Result<()> {
+    // 先删除已同步到各工具目录的副本/软链接
+    // 注意：如果先删 skills 行，会触发 skill_targets cascade，导致无法再拿到 target_path
+    let mut remove_failures: Vec<String> = Vec::new();
+    for target in store.list_skill_targets(skill_id)? {
+        if let Err(err) = remove_path_any(Path::new(&target.target_path)) {
+            remove_failures.push(format!("{}: {:#}", target.target_path, err));
+        }
+    }
+
+    if let Some(skill) = store.get_skill_by_id(skill_id)? {
+        let path = PathBuf::from(skill.central_path);
+        if path.exists() {
+            std::fs::remove_dir_all(&path).with_context(|| format!("remove {:?}", path))?;
+        }
+        store.delete_skill(skill_id)?;
+    }
+
+    if !remove_failures.is_empty() {
+        anyhow::bail!(
+            "已删除托管记录，但清理部分工具目录失败：\n- {}",
+            remove_failures.join("\n- ")
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn compute_content_hash(path: &Path) -> Option<String> {
     if should_compute_content_hash() {
         hash_dir(path).ok()
     } else {
@@ -370,10 +293,28 @@ pub fn update_managed_skill_from_source<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     store: &SkillStore,
     skill_id: &str,
+) -> Result<UpdateResult> {
+    update_managed_skill_with_prefetched(app, store, skill_id, None)
+}
+
+/// [`update_managed_skill_from_source`] using a checkout from [`prefetch_git_sources`]
+/// when the skill's repository is in `prefetched`.
+pub fn update_managed_skill_with_prefetched<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    skill_id: &str,
+    prefetched: Option<&PrefetchedRepos>,
 ) -> Result<UpdateResult> {
     let record = store
         .get_skill_by_id(skill_id)?
         .ok_or_else(|| anyhow::anyhow!("skill not found"))?;
+    // Pulling now would overwrite local commits that have not been pushed yet.
+    if has_unpushed_changes(store, skill_id)? {
+        anyhow::bail!(
+            "skill {} has unpushed local changes; push or discard them before updating",
+            record.name
+        );
+    }
 
     let central_path = PathBuf::from(record.central_path.clone());
     if !central_path.exists() {
@@ -399,11 +340,22 @@ pub fn update_managed_skill_from_source<R: tauri::Runtime>(
             .source_ref
             .as_deref()
             .ok_or_else(|| anyhow::anyhow!("missing source_ref for git skill"))?;
-        let parsed = parse_github_url(repo_url);
-
-        let (repo_dir, rev) =
-            clone_to_cache(app, store, &parsed.clone_url, parsed.branch.as_deref())?;
+        let parsed = parse_git_source(repo_url);
+
+        let sparse = parsed.subpath.as_deref().map(|p| [p]);
+        let (repo_dir, rev) = checkout_with_prefetched(
+            app,
+            store,
+            &parsed.clone_url,
+            parsed.branch.as_deref(),
+            sparse.as_ref().map(|p| &p[..]),
+            prefetched,
+        )?;
         new_revision = Some(rev);
+        let _ = store.set_git_cache_skill_ref(
+            skill_id,
+            &repo_cache_key(&parsed.clone_url, parsed.branch.as_deref()),
+        );
 
         let copy_src = if let Some(subpath) = &parsed.subpath {
             repo_dir.join(subpath)
@@ -543,13 +495,36 @@ pub struct LocalSkillCandidate {
     pub reason: Option<String>,
 }
 
+/// Sparse set for enumerating candidates: root files (root `SKILL.md`) are always part
+/// of a cone-mode checkout, and every standard discovery location lives under `skills/`.
+const LIST_SPARSE_PATHS: &[&str] = &["skills"];
+
+/// Folders whose subfolders are offered as skills, besides the repository root.
+pub(crate) const SKILL_DISCOVERY_DIRS: &[&str] = &[
+    "skills",
+    "skills/.curated",
+    "skills/.experimental",
+    "skills/.system",
+];
+
 pub fn list_git_skills<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     store: &SkillStore,
     repo_url: &str,
 ) -> Result<Vec<GitSkillCandidate>> {
-    let parsed = parse_github_url(repo_url);
-    let (repo_dir, _rev) = clone_to_cache(app, store, &parsed.clone_url, parsed.branch.as_deref())?;
+    let parsed = parse_git_source(repo_url);
+    // A folder URL only needs that folder; otherwise check out the discovery roots below.
+    let sparse: Vec<&str> = match parsed.subpath.as_deref() {
+        Some(subpath) => vec![subpath],
+        None => LIST_SPARSE_PATHS.to_vec(),
+    };
+    let (repo_dir, _rev) = clone_to_cache(
+        app,
+        store,
+        &parsed.clone_url,
+        parsed.branch.as_deref(),
+        Some(&sparse),
+    )?;
 
     let mut out: Vec<GitSkillCandidate> = Vec::new();
 
@@ -587,12 +562,7 @@ pub fn list_git_skills<R: tauri::Runtime>(
 
     // Standard discovery locations (subset aligned with add-skill):
     // skills/*, skills/.curated/*, skills/.experimental/*, skills/.system/*
-    for base in [
-        "skills",
-        "skills/.curated",
-        "skills/.experimental",
-        "skills/.system",
-    ] {
+    for base in SKILL_DISCOVERY_DIRS {
         let base_dir = repo_dir.join(base);
         if !base_dir.exists() {
             continue;
@@ -675,12 +645,7 @@ pub fn list_local_skills(base_path: &Path) -> Result<Vec<LocalSkillCandidate>> {
         }
     }
 
-    for base in [
-        "skills",
-        "skills/.curated",
-        "skills/.experimental",
-        "skills/.system",
-    ] {
+    for base in SKILL_DISCOVERY_DIRS {
         let base_dir = base_path.join(base);
         if !base_dir.exists() {
             continue;
@@ -752,7 +717,66 @@ pub fn install_git_skill_from_selection<R: tauri::Runtime>(
     subpath: &str,
     name: Option<String>,
 ) -> Result<InstallResult> {
-    let parsed = parse_github_url(repo_url);
+    install_git_skill_from_selection_with_prefetched(app, store, repo_url, subpath, name, None)
+}
+
+/// Install several skills selected from one repository, fetching it only once. A failed
+/// skill does not stop the others; cancelling the current job does.
+pub fn install_git_selections<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    repo_url: &str,
+    subpaths: &[String],
+) -> Result<Vec<(String, Result<InstallResult>)>> {
+    let job = jobs::current();
+    if let Some(job) = &job {
+        job.set_phase("Fetching");
+    }
+    let sources: Vec<(&str, Option<&str>)> = subpaths
+        .iter()
+        .map(|subpath| (repo_url, Some(subpath.as_str())))
+        .collect();
+    let prefetched = prefetch_git_sources(app, store, &sources);
+    if let Some(job) = &job {
+        job.set_phase("Installing");
+    }
+
+    let mut results = Vec::new();
+    for (index, subpath) in subpaths.iter().enumerate() {
+        if let Some(job) = &job {
+            job.check_cancelled()?;
+            job.report(|p| {
+                p.current_skill = Some(subpath.clone());
+                p.current = Some(index as u64);
+                p.total = Some(subpaths.len() as u64);
+            });
+        }
+        match install_git_skill_from_selection_with_prefetched(
+            app,
+            store,
+            repo_url,
+            subpath,
+            None,
+            Some(&prefetched),
+        ) {
+            Err(err) if jobs::is_cancelled(&err) => return Err(err),
+            result => results.push((subpath.clone(), result)),
+        }
+    }
+    Ok(results)
+}
+
+/// [`install_git_skill_from_selection`] using a checkout from [`prefetch_git_sources`]
+/// when the repository is in `prefetched`.
+fn install_git_skill_from_selection_with_prefetched<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    repo_url: &str,
+    subpath: &str,
+    name: Option<String>,
+    prefetched: Option<&PrefetchedRepos>,
+) -> Result<InstallResult> {
+    let parsed = parse_git_source(repo_url);
     let display_name = name.unwrap_or_else(|| {
         subpath
             .rsplit('/')
@@ -768,8 +792,15 @@ pub fn install_git_skill_from_selection<R: tauri::Runtime>(
         anyhow::bail!("skill already exists in central repo: {:?}", central_path);
     }
 
-    let (repo_dir, revision) =
-        clone_to_cache(app, store, &parsed.clone_url, parsed.branch.as_deref())?;
+    let sparse = [subpath];
+    let (repo_dir, revision) = checkout_with_prefetched(
+        app,
+        store,
+        &parsed.clone_url,
+        parsed.branch.as_deref(),
+        (subpath != ".").then_some(&sparse[..]),
+        prefetched,
+    )?;
 
     let copy_src = if subpath == "." {
         repo_dir.clone()
@@ -812,6 +843,10 @@ pub fn install_git_skill_from_selection<R: tauri::Runtime>(
         status: "ok".to_string(),
     };
     store.upsert_skill(&record)?;
+    let _ = store.set_git_cache_skill_ref(
+        &record.id,
+        &repo_cache_key(&parsed.clone_url, parsed.branch.as_deref()),
+    );
 
     Ok(InstallResult {
         skill_id: record.id,
@@ -853,19 +888,113 @@ pub fn install_local_skill_from_selection<R: tauri::Runtime>(
     install_local_skill(app, store, &selected_dir, Some(display_name))
 }
 
+/// Checkouts fetched once for a batch of skills, keyed by repository and ref. A failed
+/// fetch is kept as its error message and reported by every skill that needs it.
+#[derive(Default)]
+pub struct PrefetchedRepos {
+    repos: HashMap<String, std::result::Result<(PathBuf, String), String>>,
+}
+
+/// Fetch the repositories behind `sources` into the git cache, each repository/ref once
+/// and several at a time on the git fetch worker pool. A source is a skill's
+/// `source_ref` plus an optional subpath overriding the one in the URL (`.` for the whole
+/// repository); every checkout covers the subpaths of all sources sharing it.
+pub fn prefetch_git_sources<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    sources: &[(&str, Option<&str>)],
+) -> PrefetchedRepos {
+    // (clone_url, branch, subpaths); `None` subpaths once any source needs the whole tree.
+    let mut groups: Vec<(String, Option<String>, Option<Vec<String>>)> = Vec::new();
+    for (source_ref, subpath) in sources {
+        let parsed = parse_git_source(source_ref);
+        let subpath = match subpath {
+            Some(".") => None,
+            Some(subpath) => Some(subpath.to_string()),
+            None => parsed.subpath,
+        };
+        let paths = subpath.map(|p| vec![p]);
+        match groups
+            .iter_mut()
+            .find(|(url, branch, _)| *url == parsed.clone_url && *branch == parsed.branch)
+        {
+            Some((_, _, existing)) => match (existing.as_mut(), paths) {
+                (Some(existing), Some(paths)) => {
+                    for path in paths {
+                        if !existing.contains(&path) {
+                            existing.push(path);
+                        }
+                    }
+                }
+                _ => *existing = None,
+            },
+            None => groups.push((parsed.clone_url, parsed.branch, paths)),
+        }
+    }
+
+    let fetched = run_bounded(groups, git_fetch_workers(), |(clone_url, branch, paths)| {
+        let sparse: Option<Vec<&str>> = paths
+            .as_ref()
+            .map(|paths| paths.iter().map(String::as_str).collect());
+        let result = clone_to_cache(app, store, &clone_url, branch.as_deref(), sparse.as_deref())
+            .map_err(|err| format!("{:#}", err));
+        (repo_cache_key(&clone_url, branch.as_deref()), result)
+    });
+    PrefetchedRepos {
+        repos: fetched.into_iter().collect(),
+    }
+}
+
+fn checkout_with_prefetched<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    clone_url: &str,
+    branch: Option<&str>,
+    sparse_paths: Option<&[&str]>,
+    prefetched: Option<&PrefetchedRepos>,
+) -> Result<(PathBuf, String)> {
+    let key = repo_cache_key(clone_url, branch);
+    match prefetched.and_then(|p| p.repos.get(&key)) {
+        Some(Ok(checkout)) => Ok(checkout.clone()),
+        Some(Err(err)) => Err(anyhow::anyhow!("{}", err)),
+        None => clone_to_cache(app, store, clone_url, branch, sparse_paths),
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct RepoCacheMeta {
     last_fetched_ms: i64,
     head: Option<String>,
+    /// Directories extracted into a tarball cache entry; `None` means the whole tree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tarball_paths: Option<Vec<String>>,
 }
 
-static GIT_CACHE_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+static GIT_CACHE_LOCKS: OnceLock<Mutex<HashMap<String, Arc<Mutex<()>>>>> = OnceLock::new();
+
+/// Lock serializing work on one repository's cache entries (its mirror and worktrees), so
+/// different repositories can be fetched in parallel.
+pub(crate) fn git_cache_lock(clone_url: &str) -> Arc<Mutex<()>> {
+    let locks = GIT_CACHE_LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut locks = locks.lock().unwrap_or_else(|err| err.into_inner());
+    locks
+        .entry(mirror_cache_key(clone_url))
+        .or_default()
+        .clone()
+}
 
+/// Fetch `clone_url` into the shared repo cache. `sparse_paths` limits the checkout to
+/// the directories the caller needs (`None` = whole tree); cached checkouts are widened.
+///
+/// Each repository is fetched into one bare mirror under `mirrors/`, and every ref gets
+/// a worktree of it under `worktrees/`, so branches and skills of the same repo share
+/// objects. Entries are tracked in the cache index for LRU cleanup.
 fn clone_to_cache<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     store: &SkillStore,
     clone_url: &str,
     branch: Option<&str>,
+    sparse_paths: Option<&[&str]>,
 ) -> Result<(PathBuf, String)> {
     let started = std::time::Instant::now();
     let cache_dir = app
@@ -876,18 +1005,70 @@ fn clone_to_cache<R: tauri::Runtime>(
     std::fs::create_dir_all(&cache_root)
         .with_context(|| format!("failed to create cache dir {:?}", cache_root))?;
 
-    let repo_dir = cache_root.join(repo_cache_key(clone_url, branch));
-    let meta_path = repo_dir.join(".skills-hub-cache.json");
+    let key = repo_cache_key(clone_url, branch);
+    let entry =
+        |kind: &str, path: &Path, head: &str, mirror_key: Option<String>| GitCacheEntryRecord {
+            key: key.clone(),
+            kind: kind.to_string(),
+            clone_url: clone_url.to_string(),
+            ref_name: branch.map(|b| b.to_string()),
+            mirror_key,
+            path: path.to_string_lossy().to_string(),
+            head: Some(head.to_string()),
+            size_bytes: 0,
+            last_fetched_at: now_ms(),
+            last_used_at: now_ms(),
+        };
 
-    let lock = GIT_CACHE_LOCK.get_or_init(|| Mutex::new(()));
+    let lock = git_cache_lock(clone_url);
     let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+    // Offline, any cached checkout is used regardless of its age.
+    let offline = network::is_offline() && network::needs_network(clone_url);
+
+    if let Some(github) = github_owner_repo(clone_url) {
+        if get_fetch_strategy(store).prefers_tarball(git_cli_available()) {
+            let tarball_dir = cache_root.join("tarballs").join(&key);
+            let (rev, fetched) =
+                tarball_to_cache(store, &tarball_dir, github, branch, sparse_paths, offline)?;
+            index_cache_entry(store, entry("tarball", &tarball_dir, &rev, None), fetched);
+            if fetched {
+                if let Err(err) = enforce_git_cache_budget(store, Some(&key)) {
+                    log::warn!("[installer] git cache budget enforcement failed: {:#}", err);
+                }
+            }
+            return Ok((tarball_dir, rev));
+        }
+    }
+
+    let auth = resolve_git_auth(store, clone_url)?;
+    // Without git-cli, libgit2 clones each ref on its own instead of sharing a mirror.
+    let use_mirror = git_cli_available();
+    let mirror_key = mirror_cache_key(clone_url);
+    let mirror_dir = cache_root
+        .join("mirrors")
+        .join(format!("{}.git", mirror_key));
+    let repo_dir = cache_root.join("worktrees").join(&key);
+    let meta_path = repo_dir.join(".skills-hub-cache.json");
+    let kind = if use_mirror { "worktree" } else { "clone" };
+    let worktree_entry = |head: &str| {
+        entry(
+            kind,
+            &repo_dir,
+            head,
+            use_mirror.then(|| mirror_key.clone()),
+        )
+    };
 
     if repo_dir.join(".git").exists() {
         if let Ok(meta) = std::fs::read_to_string(&meta_path) {
             if let Ok(meta) = serde_json::from_str::<RepoCacheMeta>(&meta) {
                 if let Some(head) = meta.head {
                     let ttl_ms = get_git_cache_ttl_secs(store).saturating_mul(1000);
-                    if ttl_ms > 0 && now_ms().saturating_sub(meta.last_fetched_ms) < ttl_ms {
+                    let fresh = offline
+                        || (ttl_ms > 0 && now_ms().saturating_sub(meta.last_fetched_ms) < ttl_ms);
+                    if fresh
+                        && ensure_sparse_checkout(&repo_dir, sparse_paths, auth.as_ref()).is_ok()
+                    {
                         log::info!(
                             "[installer] git cache hit (fresh) {}s url={} branch={:?} repo_dir={:?}",
                             started.elapsed().as_secs_f32(),
@@ -895,6 +1076,9 @@ fn clone_to_cache<R: tauri::Runtime>(
                             branch,
                             repo_dir
                         );
+                        let mut hit = worktree_entry(&head);
+                        hit.last_fetched_at = meta.last_fetched_ms;
+                        index_cache_entry(store, hit, false);
                         return Ok((repo_dir, head));
                     }
                 }
@@ -909,15 +1093,34 @@ fn clone_to_cache<R: tauri::Runtime>(
         branch,
         repo_dir
     );
-
-    let rev = match clone_or_pull(clone_url, &repo_dir, branch) {
+    // Fail before the retry below wipes a cache that was only unusable because offline.
+    network::ensure_online_for(clone_url)?;
+
+    let fetch = || {
+        if use_mirror {
+            fetch_into_worktree(
+                clone_url,
+                &mirror_dir,
+                &repo_dir,
+                branch,
+                auth.as_ref(),
+                sparse_paths,
+            )
+        } else {
+            clone_or_pull(clone_url, &repo_dir, branch, auth.as_ref(), sparse_paths)
+        }
+    };
+    let rev = match fetch() {
         Ok(rev) => rev,
         Err(err) => {
             // If cache got corrupted, retry once from a clean state.
             if repo_dir.exists() {
                 let _ = std::fs::remove_dir_all(&repo_dir);
             }
-            clone_or_pull(clone_url, &repo_dir, branch).with_context(|| format!("{:#}", err))?
+            if use_mirror {
+                drop_mirror(store, &mirror_key, &mirror_dir);
+            }
+            fetch().with_context(|| format!("{:#}", err))?
         }
     };
 
@@ -926,9 +1129,30 @@ fn clone_to_cache<R: tauri::Runtime>(
         serde_json::to_string(&RepoCacheMeta {
             last_fetched_ms: now_ms(),
             head: Some(rev.clone()),
+            tarball_paths: None,
         })
         .unwrap_or_else(|_| "{}".to_string()),
     );
+    index_cache_entry(store, worktree_entry(&rev), true);
+    if use_mirror {
+        index_cache_entry(
+            store,
+            GitCacheEntryRecord {
+                key: mirror_key.clone(),
+                kind: "mirror".to_string(),
+                ref_name: None,
+                mirror_key: None,
+                path: mirror_dir.to_string_lossy().to_string(),
+                head: None,
+                ..worktree_entry(&rev)
+            },
+            true,
+        );
+    }
+
+    if let Err(err) = enforce_git_cache_budget(store, Some(&key)) {
+        log::warn!("[installer] git cache budget enforcement failed: {:#}", err);
+    }
 
     log::info!(
         "[installer] git cache ready {}s url={} branch={:?} head={}",
@@ -940,6 +1164,138 @@ fn clone_to_cache<R: tauri::Runtime>(
     Ok((repo_dir, rev))
 }
 
+/// Remove a mirror together with every cached worktree checked out from it, so none of
+/// them is served from the cache once the mirror is gone.
+fn drop_mirror(store: &SkillStore, mirror_key: &str, mirror_dir: &Path) {
+    let entries = store.list_git_cache_entries().unwrap_or_default();
+    for entry in entries
+        .iter()
+        .filter(|e| e.key == mirror_key || e.mirror_key.as_deref() == Some(mirror_key))
+    {
+        let path = Path::new(&entry.path);
+        if path.exists() {
+            let _ = std::fs::remove_dir_all(path);
+        }
+        if let Err(err) = store.delete_git_cache_entry(&entry.key) {
+            log::warn!(
+                "[installer] failed to drop git cache entry {}: {:#}",
+                entry.key,
+                err
+            );
+        }
+    }
+    if mirror_dir.exists() {
+        let _ = std::fs::remove_dir_all(mirror_dir);
+    }
+}
+
+/// Record a cache entry in the index; sizes are only re-measured after a fetch. An entry
+/// that moved (e.g. after switching fetch strategy) has its old directory removed.
+fn index_cache_entry(store: &SkillStore, mut entry: GitCacheEntryRecord, fetched: bool) {
+    let existing = store.get_git_cache_entry(&entry.key).ok().flatten();
+    if let Some(old) = existing.as_ref().filter(|old| old.path != entry.path) {
+        let _ = std::fs::remove_dir_all(&old.path);
+    }
+    match existing.filter(|old| !fetched && old.path == entry.path) {
+        Some(old) => {
+            entry.size_bytes = old.size_bytes;
+            entry.last_fetched_at = old.last_fetched_at;
+        }
+        None => entry.size_bytes = dir_size(Path::new(&entry.path)) as i64,
+    }
+    if let Err(err) = store.upsert_git_cache_entry(&entry) {
+        log::warn!(
+            "[installer] failed to index git cache entry {}: {:#}",
+            entry.key,
+            err
+        );
+    }
+}
+
+/// Tarball flavour of [`clone_to_cache`] for GitHub repositories. The cached tree only
+/// holds the extracted paths, so a request outside them re-downloads the union.
+fn tarball_to_cache(
+    store: &SkillStore,
+    repo_dir: &Path,
+    (owner, repo): (String, String),
+    branch: Option<&str>,
+    sparse_paths: Option<&[&str]>,
+    offline: bool,
+) -> Result<(String, bool)> {
+    let started = std::time::Instant::now();
+    let meta_path = repo_dir.join(".skills-hub-cache.json");
+    let meta = std::fs::read_to_string(&meta_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<RepoCacheMeta>(&raw).ok())
+        .filter(|_| repo_dir.is_dir());
+
+    let covers = |have: &Option<Vec<String>>| match (have, sparse_paths) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(have), Some(want)) => want
+            .iter()
+            .all(|w| have.iter().any(|h| Path::new(w).starts_with(h))),
+    };
+    if let Some(meta) = &meta {
+        let ttl_ms = get_git_cache_ttl_secs(store).saturating_mul(1000);
+        let fresh =
+            offline || (ttl_ms > 0 && now_ms().saturating_sub(meta.last_fetched_ms) < ttl_ms);
+        if let (true, true, Some(head)) = (fresh, covers(&meta.tarball_paths), &meta.head) {
+            log::info!(
+                "[installer] tarball cache hit (fresh) {}s repo={}/{} branch={:?}",
+                started.elapsed().as_secs_f32(),
+                owner,
+                repo,
+                branch
+            );
+            return Ok((head.clone(), false));
+        }
+    }
+
+    // Keep what earlier callers extracted so their paths stay valid.
+    let paths: Option<Vec<String>> = match (meta.map(|m| m.tarball_paths), sparse_paths) {
+        (Some(None), _) | (_, None) => None,
+        (Some(Some(mut have)), Some(want)) => {
+            for w in want {
+                if !have.iter().any(|h| h == w) {
+                    have.push(w.to_string());
+                }
+            }
+            Some(have)
+        }
+        (None, Some(want)) => Some(want.iter().map(|w| w.to_string()).collect()),
+    };
+
+    let token = github_api_token(store);
+    let rev = fetch_github_tarball(
+        &owner,
+        &repo,
+        branch,
+        repo_dir,
+        paths.as_deref(),
+        token.as_deref(),
+    )?;
+    let _ = std::fs::write(
+        &meta_path,
+        serde_json::to_string(&RepoCacheMeta {
+            last_fetched_ms: now_ms(),
+            head: Some(rev.clone()),
+            tarball_paths: paths,
+        })
+        .unwrap_or_else(|_| "{}".to_string()),
+    );
+
+    log::info!(
+        "[installer] tarball cache ready {}s repo={}/{} branch={:?} head={}",
+        started.elapsed().as_secs_f32(),
+        owner,
+        repo,
+        branch,
+        rev
+    );
+    Ok((rev, true))
+}
+
 fn repo_cache_key(clone_url: &str, branch: Option<&str>) -> String {
     use sha2::Digest;
     let mut hasher = sha2::Sha256::new();
@@ -951,12 +1307,32 @@ fn repo_cache_key(clone_url: &str, branch: Option<&str>) -> String {
     hex::encode(hasher.finalize())
 }
 
+/// Cache index key of the worktree/tarball a git `source_ref` installs from.
+pub(crate) fn git_cache_key_for_source(source_ref: &str) -> String {
+    let parsed = parse_git_source(source_ref);
+    repo_cache_key(&parsed.clone_url, parsed.branch.as_deref())
+}
+
+fn mirror_cache_key(clone_url: &str) -> String {
+    use sha2::Digest;
+    hex::encode(sha2::Sha256::digest(clone_url.as_bytes()))
+}
+
 pub fn parse_skill_md(path: &Path) -> Option<(String, Option<String>, Option<String>)> {
     parse_skill_md_with_reason(path).ok()
 }
 
-fn parse_skill_md_with_reason(path: &Path) -> Result<(String, Option<String>, Option<String>), &'static str> {
+fn parse_skill_md_with_reason(
+    path: &Path,
+) -> Result<(String, Option<String>, Option<String>), &'static str> {
     let text = std::fs::read_to_string(path).map_err(|_| "read_failed")?;
+    parse_skill_md_text(&text)
+}
+
+/// `(name, description, category)` from the frontmatter of `SKILL.md` contents.
+pub(crate) fn parse_skill_md_text(
+    text: &str,
+) -> Result<(String, Option<String>, Option<String>), &'static str> {
     let mut lines = text.lines();
     if lines.next().map(|v| v.trim()) != Some("---") {
         return Err("invalid_frontmatter");
@@ -972,11 +1348,11 @@ fn parse_skill_md_with_reason(path: &Path) -> Result<(String, Option<String>, Op
             break;
         }
         if let Some(v) = l.strip_prefix("name:") {
-            name = Some(v.trim().trim_matches('"').to_string());
+            name = Some(frontmatter_value(v));
         } else if let Some(v) = l.strip_prefix("description:") {
-            desc = Some(v.trim().trim_matches('"').to_string());
+            desc = Some(frontmatter_value(v));
         } else if let Some(v) = l.strip_prefix("category:") {
-            category = Some(v.trim().trim_matches('"').to_string());
+            category = Some(frontmatter_value(v));
         }
     }
     if !found_end {
@@ -986,6 +1362,32 @@ fn parse_skill_md_with_reason(path: &Path) -> Result<(String, Option<String>, Op
     Ok((name, desc, category))
 }
 
+/// A frontmatter value with YAML quoting removed.
+fn frontmatter_value(raw: &str) -> String {
+    let value = raw.trim();
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        let mut out = String::new();
+        let mut chars = value[1..value.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(escaped) => out.push(escaped),
+                None => {}
+            }
+        }
+        return out;
+    }
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].replace("''", "'");
+    }
+    value.trim_matches('"').to_string()
+}
+
 #[cfg(test)]
 #[path = "tests/installer.rs"]
 mod tests;
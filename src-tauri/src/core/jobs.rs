@@ -0,0 +1,393 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::util::now_ms;
+
+/// Tauri event carrying a [`JobInfo`] whenever a job's progress or status changes.
+pub const JOB_PROGRESS_EVENT: &str = "job-progress";
+/// Finished jobs kept for `list_jobs`; older ones are dropped first.
+const MAX_FINISHED_JOBS: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgress {
+    /// What the job is doing, e.g. `Receiving objects` from git or `Installing`.
+    pub phase: Option<String>,
+    /// Objects, files or skills done so far, out of `total` when known.
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+    /// Bytes transferred in the current phase.
+    pub bytes: Option<u64>,
+    pub current_skill: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfo {
+    pub id: String,
+    pub kind: String,
+    pub status: JobStatus,
+    pub progress: JobProgress,
+    /// Serialized return value of a succeeded job.
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+/// Error returned by an operation whose job was cancelled.
+#[derive(Debug)]
+pub struct JobCancelled;
+
+impl std::fmt::Display for JobCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "操作已取消")
+    }
+}
+
+impl std::error::Error for JobCancelled {}
+
+/// Whether `err` (or one of its causes) comes from a cancelled job.
+pub fn is_cancelled(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<JobCancelled>())
+}
+
+type Notifier = Arc<dyn Fn(&JobInfo) + Send + Sync>;
+
+struct JobEntry {
+    info: JobInfo,
+    cancel: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+struct RegistryState {
+    next_id: u64,
+    jobs: VecDeque<JobEntry>,
+}
+
+/// Running and recently finished long operations. Cheap to clone; managed as Tauri state.
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    state: Arc<Mutex<RegistryState>>,
+}
+
+impl JobRegistry {
+    /// Register a running job of `kind`; `notify` sees every change to it.
+    pub fn start(
+        &self,
+        kind: &str,
+        notify: impl Fn(&JobInfo) + Send + Sync + 'static,
+    ) -> JobHandle {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let info = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.next_id += 1;
+            let info = JobInfo {
+                id: format!("job-{}", state.next_id),
+                kind: kind.to_string(),
+                status: JobStatus::Running,
+                progress: JobProgress::default(),
+                result: None,
+                error: None,
+                started_at: now_ms(),
+                finished_at: None,
+            };
+            state.jobs.push_back(JobEntry {
+                info: info.clone(),
+                cancel: cancel.clone(),
+            });
+            info
+        };
+        let notify: Notifier = Arc::new(notify);
+        notify(&info);
+        JobHandle {
+            id: info.id,
+            registry: self.clone(),
+            cancel,
+            notify,
+        }
+    }
+
+    /// All known jobs, oldest first.
+    pub fn list(&self) -> Vec<JobInfo> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.jobs.iter().map(|entry| entry.info.clone()).collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobInfo> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .jobs
+            .iter()
+            .find(|entry| entry.info.id == id)
+            .map(|entry| entry.info.clone())
+    }
+
+    /// Ask a running job to stop. Returns `false` if it has already finished.
+    pub fn cancel(&self, id: &str) -> Result<bool> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = state
+            .jobs
+            .iter()
+            .find(|entry| entry.info.id == id)
+            .ok_or_else(|| anyhow::anyhow!("job not found: {}", id))?;
+        if entry.info.status != JobStatus::Running {
+            return Ok(false);
+        }
+        entry.cancel.store(true, Ordering::SeqCst);
+        Ok(true)
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut JobInfo) -> bool) -> Option<JobInfo> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = state.jobs.iter_mut().find(|entry| entry.info.id == id)?;
+        if !f(&mut entry.info) {
+            return None;
+        }
+        let info = entry.info.clone();
+        if info.status != JobStatus::Running {
+            let finished = state
+                .jobs
+                .iter()
+                .filter(|entry| entry.info.status != JobStatus::Running)
+                .count();
+            let mut excess = finished.saturating_sub(MAX_FINISHED_JOBS);
+            state.jobs.retain(|entry| {
+                if excess > 0 && entry.info.status != JobStatus::Running {
+                    excess -= 1;
+                    return false;
+                }
+                true
+            });
+        }
+        Some(info)
+    }
+}
+
+/// The reporting side of a job, handed to the code doing the work.
+#[derive(Clone)]
+pub struct JobHandle {
+    id: String,
+    registry: JobRegistry,
+    cancel: Arc<AtomicBool>,
+    notify: Notifier,
+}
+
+impl JobHandle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+
+    /// Fail with [`JobCancelled`] once cancellation was requested.
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(JobCancelled.into());
+        }
+        Ok(())
+    }
+
+    /// Change the progress; listeners only hear about actual changes.
+    pub fn report(&self, f: impl FnOnce(&mut JobProgress)) {
+        let updated = self.registry.update(&self.id, |info| {
+            let before = info.progress.clone();
+            f(&mut info.progress);
+            info.progress != before
+        });
+        if let Some(info) = updated {
+            (self.notify)(&info);
+        }
+    }
+
+    /// Start a new phase, clearing the counters of the previous one.
+    pub fn set_phase(&self, phase: &str) {
+        self.report(|p| {
+            p.phase = Some(phase.to_string());
+            p.current = None;
+            p.total = None;
+            p.bytes = None;
+        });
+    }
+
+    /// Record the outcome of the job; `describe` turns a failure into the message shown to
+    /// the user. Errors caused by cancellation mark it cancelled.
+    pub fn finish<T: Serialize>(
+        &self,
+        outcome: Result<T>,
+        describe: impl FnOnce(anyhow::Error) -> String,
+    ) {
+        let (status, result, error) = match outcome {
+            Ok(value) => (JobStatus::Succeeded, serde_json::to_value(value).ok(), None),
+            Err(err) if is_cancelled(&err) => {
+                (JobStatus::Cancelled, None, Some(JobCancelled.to_string()))
+            }
+            Err(err) => (JobStatus::Failed, None, Some(describe(err))),
+        };
+        let updated = self.registry.update(&self.id, |info| {
+            info.status = status;
+            info.result = result;
+            info.error = error;
+            info.finished_at = Some(now_ms());
+            true
+        });
+        if let Some(info) = updated {
+            (self.notify)(&info);
+        }
+    }
+
+    /// Run `f` with this job as [`current`] on this thread, so nested code (git commands,
+    /// downloads) can report progress and observe cancellation without extra parameters.
+    pub fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        struct Restore(Option<JobHandle>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT_JOB.with(|current| *current.borrow_mut() = self.0.take());
+            }
+        }
+        let previous = CURRENT_JOB.with(|current| current.borrow_mut().replace(self.clone()));
+        let _restore = Restore(previous);
+        f()
+    }
+}
+
+thread_local! {
+    static CURRENT_JOB: RefCell<Option<JobHandle>> = const { RefCell::new(None) };
+}
+
+/// The job the current thread is working for, if any.
+pub fn current() -> Option<JobHandle> {
+    CURRENT_JOB.with(|current| current.borrow().clone())
+}
+
+/// Fail with [`JobCancelled`] if the current thread's job was cancelled.
+pub fn check_current_cancelled() -> Result<()> {
+    match current() {
+        Some(job) => job.check_cancelled(),
+        None => Ok(()),
+    }
+}
+
+/// Replace `err` with [`JobCancelled`] if the current job was cancelled. For failures
+/// whose cause chain loses the original error, like libgit2's aborted transfers or
+/// I/O errors raised by [`JobReader`].
+pub fn cancellation_or(err: anyhow::Error) -> anyhow::Error {
+    match check_current_cancelled() {
+        Err(cancelled) => cancelled,
+        Ok(()) => err,
+    }
+}
+
+/// Wraps a download so the current job sees the bytes read and can abort it.
+pub struct JobReader<R> {
+    inner: R,
+    job: Option<JobHandle>,
+    read: u64,
+}
+
+impl<R> JobReader<R> {
+    /// `total` is the expected size, if the server sent one.
+    pub fn new(inner: R, phase: &str, total: Option<u64>) -> Self {
+        let job = current();
+        if let Some(job) = &job {
+            job.set_phase(phase);
+            job.report(|p| p.total = total);
+        }
+        Self {
+            inner,
+            job,
+            read: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for JobReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if let Some(job) = &self.job {
+            if job.is_cancelled() {
+                return Err(std::io::Error::other(JobCancelled));
+            }
+        }
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        if let Some(job) = &self.job {
+            // Reporting every chunk would flood the UI; a step per 64 KiB is plenty.
+            if n == 0 || self.read / (64 * 1024) != (self.read - n as u64) / (64 * 1024) {
+                let read = self.read;
+                job.report(|p| {
+                    p.bytes = Some(read);
+                    p.current = Some(read);
+                });
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// Parse one line of git's `--progress` output, e.g.
+/// `Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s`.
+/// Returns `None` for anything that is not a progress line.
+pub fn parse_git_progress(line: &str) -> Option<JobProgress> {
+    let line = line.trim();
+    let line = line.strip_prefix("remote:").unwrap_or(line).trim();
+    let (phase, rest) = line.split_once(": ")?;
+    let rest = rest.trim();
+    if !rest.starts_with(|c: char| c.is_ascii_digit())
+        || matches!(phase, "error" | "fatal" | "warning" | "hint")
+    {
+        return None;
+    }
+
+    let mut progress = JobProgress {
+        phase: Some(phase.to_string()),
+        ..JobProgress::default()
+    };
+    if let Some(start) = rest.find('(') {
+        let end = rest[start..].find(')')? + start;
+        let (current, total) = rest[start + 1..end].split_once('/')?;
+        progress.current = current.trim().parse().ok();
+        progress.total = total.trim().parse().ok();
+        if let Some(size) = rest[end + 1..].trim_start_matches(',').split('|').next() {
+            progress.bytes = parse_size(size.trim());
+        }
+    } else {
+        // Counters without a known total: `Enumerating objects: 42, done.`
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        progress.current = digits.parse().ok();
+    }
+    Some(progress)
+}
+
+fn parse_size(raw: &str) -> Option<u64> {
+    let (value, unit) = raw.split_once(' ')?;
+    let value: f64 = value.parse().ok()?;
+    let factor = match unit.trim() {
+        "bytes" | "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value * factor) as u64)
+}
+
+#[cfg(test)]
+#[path = "tests/jobs.rs"]
+mod tests;
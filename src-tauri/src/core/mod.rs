@@ -2,20 +2,38 @@ pub mod analytics_alert;
 pub mod analytics_ingest;
 pub mod analytics_store;
 pub mod auto_update;
+pub mod backup;
 pub mod cache_cleanup;
+pub mod category_taxonomy;
 pub mod central_repo;
 pub mod chat_server;
 pub mod claude_cli;
 pub mod content_hash;
 pub mod discovery;
 pub mod discovery_config;
+pub mod discovery_metadata;
 pub mod discovery_parser;
 pub mod discovery_remote;
+pub mod git_credentials;
 pub mod git_fetcher;
+pub mod git_url;
 pub mod github_search;
+pub mod install_state;
 pub mod installer;
+pub mod jobs;
+pub mod network;
 pub mod onboarding;
+pub mod recommendations;
+pub mod registry_sources;
+pub mod skill_bundle;
+pub mod skill_publish;
+pub mod skill_search;
 pub mod skill_store;
+pub mod skill_templates;
+pub mod state_sync;
 pub mod sync_engine;
+pub mod tarball_fetcher;
+pub mod team_index;
 pub mod temp_cleanup;
-pub mod tool_adapters;
\ No newline at end of file
+pub mod tool_adapters;
+pub(crate) mod util;
\ No newline at end of file
@@ -0,0 +1,406 @@
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use reqwest::blocking::{Client, ClientBuilder};
+use reqwest::{Certificate, NoProxy, Proxy, Url};
+use serde::{Deserialize, Serialize};
+
+use super::skill_store::SkillStore;
+use super::util::now_ms;
+
+pub const NETWORK_PROXY_URL_KEY: &str = "network_proxy_url";
+pub const NETWORK_NO_PROXY_KEY: &str = "network_no_proxy";
+pub const NETWORK_CA_CERT_PATH_KEY: &str = "network_ca_cert_path";
+pub const OFFLINE_MODE_KEY: &str = "offline_mode";
+
+/// Emitted with a [`NetworkStatus`] whenever the offline state may have changed.
+pub const NETWORK_STATUS_EVENT: &str = "network-status";
+
+/// Probed to detect connectivity when no proxy is configured.
+const PROBE_HOST: &str = "github.com";
+const PROBE_PORT: u16 = 443;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long a failed probe is trusted before the next network call checks again.
+const OFFLINE_RECHECK: Duration = Duration::from_secs(60);
+
+/// System root bundle plus the configured certificates, handed to git and libgit2,
+/// which only accept a single CA file.
+const GIT_CA_BUNDLE_FILE: &str = "git-ca-bundle.pem";
+
+/// Where common distributions keep the system root bundle.
+const SYSTEM_CA_BUNDLES: &[&str] = &[
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/ssl/cert.pem",
+];
+
+/// Proxy and extra trust settings applied to every HTTP client and git invocation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConfig {
+    /// `http://` or `https://` proxy used for all requests; `None` uses the environment.
+    pub proxy_url: Option<String>,
+    /// Comma-separated hosts, domains or CIDRs that bypass the proxy.
+    pub no_proxy: Option<String>,
+    /// PEM file with extra root certificates (e.g. a corporate TLS-inspection CA).
+    pub ca_cert_path: Option<String>,
+}
+
+/// A validated [`NetworkConfig`], ready to apply to clients and commands.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkSettings {
+    proxy_url: Option<String>,
+    no_proxy: Option<String>,
+    certs: Vec<Certificate>,
+    git_ca_bundle: Option<PathBuf>,
+}
+
+static ACTIVE: RwLock<Option<Arc<NetworkSettings>>> = RwLock::new(None);
+static OFFLINE_MODE: AtomicBool = AtomicBool::new(false);
+static REACHABILITY: Mutex<Option<Reachability>> = Mutex::new(None);
+
+#[derive(Clone, Copy, Debug)]
+struct Reachability {
+    reachable: bool,
+    checked: Instant,
+    checked_at: i64,
+}
+
+/// What the UI shows: whether network operations are skipped, and why.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+    /// Network-dependent operations short-circuit to cached data.
+    pub offline: bool,
+    /// Offline mode was switched on by the user.
+    pub offline_mode: bool,
+    /// Result of the last connectivity probe, if one ran.
+    pub reachable: Option<bool>,
+    pub checked_at: Option<i64>,
+}
+
+/// Returned instead of attempting a network call while offline.
+#[derive(Debug)]
+pub struct Offline;
+
+impl std::fmt::Display for Offline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("当前处于离线状态，该操作需要网络连接")
+    }
+}
+
+impl std::error::Error for Offline {}
+
+pub fn is_offline_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<Offline>())
+}
+
+pub fn get_network_config(store: &SkillStore) -> NetworkConfig {
+    let read = |key| store.get_setting(key).ok().flatten();
+    normalize(NetworkConfig {
+        proxy_url: read(NETWORK_PROXY_URL_KEY),
+        no_proxy: read(NETWORK_NO_PROXY_KEY),
+        ca_cert_path: read(NETWORK_CA_CERT_PATH_KEY),
+    })
+}
+
+/// Validate and persist `config`. Takes effect once [`activate_network_config`] runs.
+pub fn set_network_config(store: &SkillStore, config: NetworkConfig) -> Result<NetworkConfig> {
+    let config = normalize(config);
+    NetworkSettings::from_config(&config, None)?;
+    let value = |v: &Option<String>| v.clone().unwrap_or_default();
+    store.set_setting(NETWORK_PROXY_URL_KEY, &value(&config.proxy_url))?;
+    store.set_setting(NETWORK_NO_PROXY_KEY, &value(&config.no_proxy))?;
+    store.set_setting(NETWORK_CA_CERT_PATH_KEY, &value(&config.ca_cert_path))?;
+    Ok(config)
+}
+
+pub fn get_offline_mode(store: &SkillStore) -> bool {
+    matches!(
+        store
+            .get_setting(OFFLINE_MODE_KEY)
+            .ok()
+            .flatten()
+            .as_deref(),
+        Some("true")
+    )
+}
+
+/// Persist offline mode. Takes effect once [`activate_network_config`] runs.
+pub fn set_offline_mode(store: &SkillStore, enabled: bool) -> Result<bool> {
+    store.set_setting(OFFLINE_MODE_KEY, if enabled { "true" } else { "false" })?;
+    Ok(enabled)
+}
+
+/// Apply the stored settings (offline mode, proxy and CA) to clients and git commands
+/// created from now on. The git CA bundle is written next to the database.
+pub fn activate_network_config(store: &SkillStore) -> Result<()> {
+    OFFLINE_MODE.store(get_offline_mode(store), Ordering::Relaxed);
+    let config = get_network_config(store);
+    let settings = NetworkSettings::from_config(&config, store.db_path().parent())?;
+    let had_bundle = active().is_some_and(|s| s.git_ca_bundle.is_some());
+    // libgit2 keeps one process-wide CA file; fall back to the system one when cleared.
+    let libgit2_ca = settings
+        .git_ca_bundle
+        .clone()
+        .or_else(|| had_bundle.then(system_ca_bundle).flatten());
+    if let Some(path) = libgit2_ca {
+        // SAFETY: libgit2 reads this option when it opens a TLS stream. It only changes at
+        // startup and when the user saves the network settings.
+        unsafe { git2::opts::set_ssl_cert_file(&path) }
+            .with_context(|| format!("failed to load CA bundle {:?} into libgit2", path))?;
+    }
+    *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(settings));
+    Ok(())
+}
+
+fn active() -> Option<Arc<NetworkSettings>> {
+    ACTIVE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// True when network-dependent operations should fall back to cached data: offline mode
+/// is on, or the last connectivity probe failed. A failed probe is repeated at most once
+/// a minute, so calls pick the network back up once it returns.
+pub fn is_offline() -> bool {
+    if OFFLINE_MODE.load(Ordering::Relaxed) {
+        return true;
+    }
+    let last = *REACHABILITY.lock().unwrap_or_else(|e| e.into_inner());
+    match last {
+        Some(last) if !last.reachable => {
+            last.checked.elapsed() < OFFLINE_RECHECK
+                || !check_connectivity().reachable.unwrap_or(true)
+        }
+        _ => false,
+    }
+}
+
+pub fn ensure_online() -> Result<()> {
+    if is_offline() {
+        return Err(Offline.into());
+    }
+    Ok(())
+}
+
+/// Fail with [`Offline`] when `url` needs the network and we are offline.
+pub fn ensure_online_for(url: &str) -> Result<()> {
+    if needs_network(url) {
+        ensure_online().with_context(|| format!("cannot reach {}", url))?;
+    }
+    Ok(())
+}
+
+/// Whether fetching `url` leaves this machine: local paths, `file://` URLs and loopback
+/// hosts work offline.
+pub fn needs_network(url: &str) -> bool {
+    let lower = url.trim().to_ascii_lowercase();
+    if lower.starts_with("file://") {
+        return false;
+    }
+    let host = match Url::parse(&lower) {
+        Ok(parsed) if lower.contains("://") => parsed.host_str().unwrap_or_default().to_string(),
+        // scp-like `user@host:path`; anything else (including `C:\...`) is a local path.
+        _ => match lower
+            .split_once('@')
+            .and_then(|(_, rest)| rest.split_once(':'))
+        {
+            Some((host, _)) if !host.contains('/') => host.to_string(),
+            _ => return false,
+        },
+    };
+    !matches!(
+        host.as_str(),
+        "" | "localhost" | "127.0.0.1" | "[::1]" | "::1"
+    )
+}
+
+/// Probe connectivity to GitHub (or the configured proxy) and record the result.
+pub fn check_connectivity() -> NetworkStatus {
+    let target = active()
+        .and_then(|s| s.proxy_url.as_deref().and_then(|u| Url::parse(u).ok()))
+        .and_then(|u| Some((u.host_str()?.to_string(), u.port_or_known_default()?)))
+        .unwrap_or_else(|| (PROBE_HOST.to_string(), PROBE_PORT));
+    let reachable = probe(&target.0, target.1, PROBE_TIMEOUT);
+    if !reachable {
+        log::warn!(
+            "[network] {}:{} is unreachable; working offline",
+            target.0,
+            target.1
+        );
+    }
+    *REACHABILITY.lock().unwrap_or_else(|e| e.into_inner()) = Some(Reachability {
+        reachable,
+        checked: Instant::now(),
+        checked_at: now_ms(),
+    });
+    network_status()
+}
+
+pub fn network_status() -> NetworkStatus {
+    let offline_mode = OFFLINE_MODE.load(Ordering::Relaxed);
+    let last = *REACHABILITY.lock().unwrap_or_else(|e| e.into_inner());
+    NetworkStatus {
+        offline: offline_mode || last.is_some_and(|l| !l.reachable),
+        offline_mode,
+        reachable: last.map(|l| l.reachable),
+        checked_at: last.map(|l| l.checked_at),
+    }
+}
+
+fn probe(host: &str, port: u16, timeout: Duration) -> bool {
+    let Ok(addrs) = (host, port).to_socket_addrs() else {
+        return false;
+    };
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, timeout).is_ok())
+}
+
+/// Blocking HTTP client with the active proxy and CA settings.
+pub fn http_client() -> Result<Client> {
+    http_client_builder()?
+        .build()
+        .context("failed to build HTTP client")
+}
+
+pub fn http_client_builder() -> Result<ClientBuilder> {
+    match active() {
+        Some(settings) => settings.client_builder(),
+        None => Ok(Client::builder()),
+    }
+}
+
+/// Pass the active proxy and CA settings to a spawned git command.
+pub fn apply_to_git_command(cmd: &mut Command) {
+    if let Some(settings) = active() {
+        settings.apply_to_command(cmd);
+    }
+}
+
+/// libgit2 proxy options: the configured proxy, or none (libgit2's default).
+pub fn git2_proxy_options() -> Option<git2::ProxyOptions<'static>> {
+    let settings = active()?;
+    let url = settings.proxy_url.as_deref()?;
+    let mut opts = git2::ProxyOptions::new();
+    opts.url(url);
+    Some(opts)
+}
+
+impl NetworkSettings {
+    /// Validate `config`. With `bundle_dir`, also write the combined CA bundle for git there.
+    pub fn from_config(config: &NetworkConfig, bundle_dir: Option<&Path>) -> Result<Self> {
+        if let Some(proxy) = config.proxy_url.as_deref() {
+            validate_proxy_url(proxy)?;
+        }
+        let mut settings = NetworkSettings {
+            proxy_url: config.proxy_url.clone(),
+            no_proxy: config.no_proxy.clone(),
+            ..Default::default()
+        };
+        let Some(ca_path) = config.ca_cert_path.as_deref() else {
+            return Ok(settings);
+        };
+        let pem = std::fs::read(ca_path)
+            .with_context(|| format!("failed to read CA certificate file {}", ca_path))?;
+        settings.certs = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("invalid PEM in CA certificate file {}", ca_path))?;
+        if settings.certs.is_empty() {
+            anyhow::bail!("no PEM certificates found in {}", ca_path);
+        }
+        if let Some(dir) = bundle_dir {
+            settings.git_ca_bundle = Some(write_git_ca_bundle(dir, &pem)?);
+        }
+        Ok(settings)
+    }
+
+    pub fn client_builder(&self) -> Result<ClientBuilder> {
+        let mut builder = Client::builder();
+        if let Some(url) = self.proxy_url.as_deref() {
+            let proxy = Proxy::all(url)
+                .with_context(|| format!("invalid proxy URL {}", url))?
+                .no_proxy(self.no_proxy.as_deref().and_then(NoProxy::from_string));
+            builder = builder.proxy(proxy);
+        }
+        for cert in &self.certs {
+            builder = builder.add_root_certificate(cert.clone());
+        }
+        Ok(builder)
+    }
+
+    /// Git reads the proxy from curl's environment variables and the CA bundle from
+    /// `GIT_SSL_CAINFO`. SSH remotes are not affected.
+    pub fn apply_to_command(&self, cmd: &mut Command) {
+        if let Some(url) = self.proxy_url.as_deref() {
+            for key in ["http_proxy", "https_proxy", "HTTP_PROXY", "HTTPS_PROXY"] {
+                cmd.env(key, url);
+            }
+            if let Some(no_proxy) = self.no_proxy.as_deref() {
+                cmd.env("no_proxy", no_proxy).env("NO_PROXY", no_proxy);
+            }
+        }
+        if let Some(bundle) = &self.git_ca_bundle {
+            cmd.env("GIT_SSL_CAINFO", bundle);
+        }
+    }
+}
+
+fn normalize(config: NetworkConfig) -> NetworkConfig {
+    let clean = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let no_proxy = config.no_proxy.map(|list| {
+        list.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    NetworkConfig {
+        proxy_url: clean(config.proxy_url),
+        no_proxy: clean(no_proxy),
+        ca_cert_path: clean(config.ca_cert_path),
+    }
+}
+
+fn validate_proxy_url(raw: &str) -> Result<()> {
+    let url = Url::parse(raw).with_context(|| format!("invalid proxy URL {}", raw))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("proxy URL must start with http:// or https://");
+    }
+    if url.host_str().map_or(true, str::is_empty) {
+        anyhow::bail!("proxy URL {} has no host", raw);
+    }
+    Ok(())
+}
+
+fn system_ca_bundle() -> Option<PathBuf> {
+    std::env::var_os("SSL_CERT_FILE")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(SYSTEM_CA_BUNDLES.iter().map(PathBuf::from))
+        .find(|path| path.is_file())
+}
+
+/// Git replaces (rather than extends) its trust store with `GIT_SSL_CAINFO`, so the
+/// extra certificates are appended to the system bundle when one can be found.
+fn write_git_ca_bundle(dir: &Path, extra_pem: &[u8]) -> Result<PathBuf> {
+    let mut bundle = system_ca_bundle()
+        .and_then(|path| std::fs::read(path).ok())
+        .unwrap_or_default();
+    if !bundle.is_empty() && !bundle.ends_with(b"\n") {
+        bundle.push(b'\n');
+    }
+    bundle.extend_from_slice(extra_pem);
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {:?}", dir))?;
+    let path = dir.join(GIT_CA_BUNDLE_FILE);
+    std::fs::write(&path, bundle).with_context(|| format!("failed to write {:?}", path))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+#[path = "tests/network.rs"]
+mod tests;
@@ -0,0 +1,658 @@
+//! Project-aware skill recommendations.
+//!
+//! A project directory is profiled from its marker files (`Cargo.toml`,
+//! `Dockerfile`, `.github/workflows`, ...), the dependencies declared in its
+//! manifests and the extensions of its source files. Each detected signal
+//! carries keywords and categories that are matched against catalogue entries,
+//! so every recommendation can say why it was made.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use super::discovery::{get_recommended_skills, DiscoveredSkill};
+use super::install_state::InstalledSkills;
+use super::skill_store::SkillStore;
+
+/// How far below the project root markers and manifests are looked for (monorepos
+/// keep packages one or two levels down).
+const MAX_SCAN_DEPTH: usize = 3;
+const MAX_SCAN_ENTRIES: usize = 5000;
+const DEFAULT_RECOMMENDATION_LIMIT: usize = 20;
+/// Built-in recommendations that come with the app rather than a registry source.
+const BUILTIN_SOURCE: &str = "recommended";
+
+const SKIPPED_DIRS: [&str; 12] = [
+    ".git",
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    ".next",
+    ".venv",
+    "venv",
+    "__pycache__",
+    "vendor",
+    ".idea",
+    ".vscode",
+];
+
+const MANIFESTS: [&str; 6] = [
+    "package.json",
+    "Cargo.toml",
+    "requirements.txt",
+    "pyproject.toml",
+    "Gemfile",
+    "go.mod",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    Language,
+    Framework,
+    Tool,
+}
+
+enum Detect {
+    /// A file or directory with this name (or relative path).
+    Path(&'static str),
+    /// A file with this extension.
+    Extension(&'static str),
+    /// A dependency declared in one of the [`MANIFESTS`].
+    Dependency(&'static str),
+}
+
+struct Rule {
+    id: &'static str,
+    label: &'static str,
+    kind: SignalKind,
+    detect: &'static [Detect],
+    /// Matched against skill tags and as whole words in names and descriptions.
+    /// Keywords shorter than three characters only match tags and names.
+    keywords: &'static [&'static str],
+    categories: &'static [&'static str],
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        id: "rust",
+        label: "Rust",
+        kind: SignalKind::Language,
+        detect: &[Detect::Path("Cargo.toml"), Detect::Extension("rs")],
+        keywords: &["rust", "cargo", "clippy"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "typescript",
+        label: "TypeScript",
+        kind: SignalKind::Language,
+        detect: &[
+            Detect::Path("tsconfig.json"),
+            Detect::Extension("ts"),
+            Detect::Extension("tsx"),
+        ],
+        keywords: &["typescript", "ts"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "javascript",
+        label: "JavaScript",
+        kind: SignalKind::Language,
+        detect: &[
+            Detect::Path("package.json"),
+            Detect::Extension("js"),
+            Detect::Extension("jsx"),
+            Detect::Extension("mjs"),
+        ],
+        keywords: &["javascript", "node", "nodejs", "npm", "js"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "python",
+        label: "Python",
+        kind: SignalKind::Language,
+        detect: &[
+            Detect::Path("pyproject.toml"),
+            Detect::Path("requirements.txt"),
+            Detect::Path("setup.py"),
+            Detect::Path("Pipfile"),
+            Detect::Extension("py"),
+        ],
+        keywords: &["python", "pip", "py"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "go",
+        label: "Go",
+        kind: SignalKind::Language,
+        detect: &[Detect::Path("go.mod"), Detect::Extension("go")],
+        keywords: &["golang", "go"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "java",
+        label: "Java/Kotlin",
+        kind: SignalKind::Language,
+        detect: &[
+            Detect::Path("pom.xml"),
+            Detect::Path("build.gradle"),
+            Detect::Path("build.gradle.kts"),
+            Detect::Extension("java"),
+            Detect::Extension("kt"),
+        ],
+        keywords: &["java", "kotlin", "gradle", "maven", "jvm"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "ruby",
+        label: "Ruby",
+        kind: SignalKind::Language,
+        detect: &[Detect::Path("Gemfile"), Detect::Extension("rb")],
+        keywords: &["ruby", "gem"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "swift",
+        label: "Swift",
+        kind: SignalKind::Language,
+        detect: &[Detect::Path("Package.swift"), Detect::Extension("swift")],
+        keywords: &["swift", "ios", "xcode"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "csharp",
+        label: "C#/.NET",
+        kind: SignalKind::Language,
+        detect: &[Detect::Extension("csproj"), Detect::Extension("cs")],
+        keywords: &["csharp", "dotnet", ".net"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "nextjs",
+        label: "Next.js",
+        kind: SignalKind::Framework,
+        detect: &[
+            Detect::Dependency("next"),
+            Detect::Path("next.config.js"),
+            Detect::Path("next.config.mjs"),
+            Detect::Path("next.config.ts"),
+        ],
+        keywords: &["next.js", "nextjs", "react", "vercel"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "react",
+        label: "React",
+        kind: SignalKind::Framework,
+        detect: &[Detect::Dependency("react")],
+        keywords: &["react", "jsx", "frontend"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "vue",
+        label: "Vue",
+        kind: SignalKind::Framework,
+        detect: &[Detect::Dependency("vue"), Detect::Extension("vue")],
+        keywords: &["vue", "vuejs", "nuxt", "frontend"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "svelte",
+        label: "Svelte",
+        kind: SignalKind::Framework,
+        detect: &[
+            Detect::Dependency("svelte"),
+            Detect::Dependency("@sveltejs/kit"),
+        ],
+        keywords: &["svelte", "sveltekit", "frontend"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "angular",
+        label: "Angular",
+        kind: SignalKind::Framework,
+        detect: &[Detect::Dependency("@angular/core")],
+        keywords: &["angular", "frontend"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "tailwind",
+        label: "Tailwind CSS",
+        kind: SignalKind::Framework,
+        detect: &[
+            Detect::Dependency("tailwindcss"),
+            Detect::Path("tailwind.config.js"),
+            Detect::Path("tailwind.config.ts"),
+        ],
+        keywords: &["tailwind", "tailwindcss", "css", "design"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "tauri",
+        label: "Tauri",
+        kind: SignalKind::Framework,
+        detect: &[
+            Detect::Path("tauri.conf.json"),
+            Detect::Dependency("tauri"),
+            Detect::Dependency("@tauri-apps/api"),
+        ],
+        keywords: &["tauri", "desktop"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "django",
+        label: "Django",
+        kind: SignalKind::Framework,
+        detect: &[Detect::Dependency("django"), Detect::Path("manage.py")],
+        keywords: &["django"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "fastapi",
+        label: "FastAPI/Flask",
+        kind: SignalKind::Framework,
+        detect: &[Detect::Dependency("fastapi"), Detect::Dependency("flask")],
+        keywords: &["fastapi", "flask", "rest api"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "rails",
+        label: "Ruby on Rails",
+        kind: SignalKind::Framework,
+        detect: &[Detect::Dependency("rails")],
+        keywords: &["rails", "ruby on rails"],
+        categories: &["development"],
+    },
+    Rule {
+        id: "machine-learning",
+        label: "machine learning",
+        kind: SignalKind::Framework,
+        detect: &[
+            Detect::Dependency("torch"),
+            Detect::Dependency("tensorflow"),
+            Detect::Dependency("transformers"),
+            Detect::Dependency("scikit-learn"),
+        ],
+        keywords: &["machine learning", "pytorch", "tensorflow", "ml", "model"],
+        categories: &["ai", "data-analysis", "research"],
+    },
+    Rule {
+        id: "data",
+        label: "data analysis",
+        kind: SignalKind::Framework,
+        detect: &[
+            Detect::Dependency("pandas"),
+            Detect::Dependency("numpy"),
+            Detect::Extension("ipynb"),
+        ],
+        keywords: &[
+            "pandas",
+            "jupyter",
+            "notebook",
+            "data analysis",
+            "csv",
+            "data",
+        ],
+        categories: &["data-analysis", "research"],
+    },
+    Rule {
+        id: "testing",
+        label: "automated tests",
+        kind: SignalKind::Tool,
+        detect: &[
+            Detect::Dependency("jest"),
+            Detect::Dependency("vitest"),
+            Detect::Dependency("pytest"),
+            Detect::Dependency("@playwright/test"),
+            Detect::Dependency("cypress"),
+        ],
+        keywords: &[
+            "testing",
+            "tests",
+            "jest",
+            "vitest",
+            "pytest",
+            "playwright",
+            "e2e",
+        ],
+        categories: &["security"],
+    },
+    Rule {
+        id: "docker",
+        label: "Docker",
+        kind: SignalKind::Tool,
+        detect: &[
+            Detect::Path("Dockerfile"),
+            Detect::Path("docker-compose.yml"),
+            Detect::Path("docker-compose.yaml"),
+            Detect::Path("compose.yaml"),
+        ],
+        keywords: &["docker", "container", "containers", "dockerfile"],
+        categories: &["development", "utility"],
+    },
+    Rule {
+        id: "kubernetes",
+        label: "Kubernetes",
+        kind: SignalKind::Tool,
+        detect: &[Detect::Path("Chart.yaml"), Detect::Path("k8s")],
+        keywords: &["kubernetes", "k8s", "helm"],
+        categories: &["development", "utility"],
+    },
+    Rule {
+        id: "terraform",
+        label: "Terraform",
+        kind: SignalKind::Tool,
+        detect: &[Detect::Extension("tf")],
+        keywords: &["terraform", "infrastructure", "iac"],
+        categories: &["development", "utility"],
+    },
+    Rule {
+        id: "github-actions",
+        label: "GitHub Actions",
+        kind: SignalKind::Tool,
+        detect: &[Detect::Path(".github/workflows")],
+        keywords: &["github actions", "ci/cd", "ci", "workflow"],
+        categories: &["development", "utility"],
+    },
+    Rule {
+        id: "docs",
+        label: "a documentation site",
+        kind: SignalKind::Tool,
+        detect: &[
+            Detect::Path("mkdocs.yml"),
+            Detect::Path("docusaurus.config.js"),
+            Detect::Path("docusaurus.config.ts"),
+        ],
+        keywords: &["documentation", "docs", "markdown"],
+        categories: &["documentation", "document", "writing"],
+    },
+];
+
+/// Something the project uses, with the file that gave it away.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSignal {
+    pub id: String,
+    pub label: String,
+    pub kind: SignalKind,
+    /// Project-relative path of the marker, manifest or first matching file.
+    pub evidence: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectProfile {
+    pub root: String,
+    pub signals: Vec<ProjectSignal>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillRecommendation {
+    pub name: String,
+    pub description: String,
+    pub github_url: String,
+    pub category: String,
+    pub tags: Vec<String>,
+    /// Registry source id, or `recommended` for the built-in list.
+    pub source: String,
+    pub score: u32,
+    /// One line per matching signal, e.g. "the project uses Next.js (package.json)".
+    pub reasons: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectRecommendations {
+    pub profile: ProjectProfile,
+    pub skills: Vec<SkillRecommendation>,
+}
+
+/// Profile `project_dir` and rank the discovery catalogue and the built-in
+/// recommendations against it. Skills that are already installed are left out.
+pub fn get_project_recommendations(
+    store: &SkillStore,
+    project_dir: &Path,
+    github_token: Option<&str>,
+    limit: Option<usize>,
+) -> Result<ProjectRecommendations> {
+    let profile = detect_project(project_dir)?;
+    let installed = InstalledSkills::load(store)?;
+
+    let mut candidates: Vec<(DiscoveredSkill, String)> = store
+        .list_discovered_skills()?
+        .into_iter()
+        .map(|record| {
+            let tags = record
+                .tags
+                .split(',')
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect();
+            (
+                DiscoveredSkill {
+                    name: record.name,
+                    description: record.description,
+                    github_url: record.github_url,
+                    category: record.category,
+                    tags,
+                },
+                record.source,
+            )
+        })
+        .collect();
+    candidates.extend(
+        get_recommended_skills(store, github_token)
+            .into_iter()
+            .map(|skill| (skill, BUILTIN_SOURCE.to_string())),
+    );
+
+    let mut seen = HashSet::new();
+    candidates.retain(|(skill, _)| {
+        installed.lookup(&skill.github_url, None).skill_id.is_none()
+            && seen.insert(skill.github_url.clone())
+    });
+
+    let mut skills = rank_skills(&profile, candidates);
+    skills.truncate(limit.unwrap_or(DEFAULT_RECOMMENDATION_LIMIT));
+    Ok(ProjectRecommendations { profile, skills })
+}
+
+pub fn detect_project(root: &Path) -> Result<ProjectProfile> {
+    if !root.is_dir() {
+        anyhow::bail!("project directory not found: {:?}", root);
+    }
+
+    let mut paths: HashMap<String, String> = HashMap::new();
+    let mut extensions: HashMap<String, String> = HashMap::new();
+    let mut dependencies: HashMap<String, String> = HashMap::new();
+
+    let walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(MAX_SCAN_DEPTH)
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            !(entry.file_type().is_dir()
+                && SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
+        });
+    for entry in walker.take(MAX_SCAN_ENTRIES) {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        paths
+            .entry(name.clone())
+            .or_insert_with(|| relative.clone());
+        paths
+            .entry(relative.clone())
+            .or_insert_with(|| relative.clone());
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Some(ext) = entry.path().extension() {
+            extensions
+                .entry(ext.to_string_lossy().to_ascii_lowercase())
+                .or_insert_with(|| relative.clone());
+        }
+        if MANIFESTS.contains(&name.as_str()) {
+            // One unreadable manifest only costs its dependencies.
+            let content = match std::fs::read_to_string(entry.path()) {
+                Ok(content) => content,
+                Err(err) => {
+                    log::warn!("[recommendations] skipping {:?}: {}", entry.path(), err);
+                    continue;
+                }
+            };
+            for dependency in manifest_dependencies(&name, &content) {
+                dependencies
+                    .entry(dependency)
+                    .or_insert_with(|| relative.clone());
+            }
+        }
+    }
+
+    let signals = RULES
+        .iter()
+        .filter_map(|rule| {
+            let evidence = rule.detect.iter().find_map(|detect| match detect {
+                Detect::Path(path) => paths.get(*path),
+                Detect::Extension(ext) => extensions.get(*ext),
+                Detect::Dependency(name) => dependencies.get(*name),
+            })?;
+            Some(ProjectSignal {
+                id: rule.id.to_string(),
+                label: rule.label.to_string(),
+                kind: rule.kind,
+                evidence: evidence.clone(),
+            })
+        })
+        .collect();
+
+    Ok(ProjectProfile {
+        root: root.to_string_lossy().to_string(),
+        signals,
+    })
+}
+
+/// Rank `candidates` (skill, source) by how well they match the project. Only
+/// skills matching at least one signal keyword are returned, best first.
+fn rank_skills(
+    profile: &ProjectProfile,
+    candidates: Vec<(DiscoveredSkill, String)>,
+) -> Vec<SkillRecommendation> {
+    let mut ranked: Vec<SkillRecommendation> = candidates
+        .into_iter()
+        .filter_map(|(skill, source)| {
+            let (score, reasons) = relevance(profile, &skill);
+            if score == 0 {
+                return None;
+            }
+            Some(SkillRecommendation {
+                name: skill.name,
+                description: skill.description,
+                github_url: skill.github_url,
+                category: skill.category,
+                tags: skill.tags,
+                source,
+                score,
+                reasons,
+            })
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    ranked
+}
+
+/// Score of `skill` for the project and the reasons behind it. A tag or name
+/// match is worth 3 per signal, a description match 1, and a shared category
+/// adds 1 to a signal that already matched.
+pub fn relevance(profile: &ProjectProfile, skill: &DiscoveredSkill) -> (u32, Vec<String>) {
+    let name = skill.name.to_lowercase();
+    let description = skill.description.to_lowercase();
+    let tags: Vec<String> = skill.tags.iter().map(|tag| tag.to_lowercase()).collect();
+
+    let mut score = 0;
+    let mut reasons = Vec::new();
+    for signal in &profile.signals {
+        let Some(rule) = RULES.iter().find(|rule| rule.id == signal.id) else {
+            continue;
+        };
+        let mut points = 0;
+        for keyword in rule.keywords {
+            if tags.iter().any(|tag| tag == keyword) || contains_word(&name, keyword) {
+                points = 3;
+                break;
+            }
+            if keyword.len() >= 3 && contains_word(&description, keyword) {
+                points = 1;
+            }
+        }
+        if points == 0 {
+            continue;
+        }
+        if rule.categories.contains(&skill.category.as_str()) {
+            points += 1;
+        }
+        score += points;
+        reasons.push(format!(
+            "the project uses {} ({})",
+            signal.label, signal.evidence
+        ));
+    }
+    (score, reasons)
+}
+
+fn manifest_dependencies(file_name: &str, content: &str) -> Vec<String> {
+    if file_name == "package.json" {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+            return Vec::new();
+        };
+        return ["dependencies", "devDependencies", "peerDependencies"]
+            .iter()
+            .filter_map(|key| json.get(key).and_then(|deps| deps.as_object()))
+            .flat_map(|deps| deps.keys().cloned())
+            .collect();
+    }
+
+    // The text manifests declare one dependency per line, as `name = ...`
+    // (Cargo, Poetry), `name>=1.0` (pip), `"name>=1.0",` (PEP 621),
+    // `gem "name"` (Bundler) or `host/path/name v1.0` (Go).
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix("gem ").unwrap_or(line);
+            let line = line.trim_start_matches(['"', '\'', '[']);
+            let token: String = line
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | '@'))
+                .collect();
+            let name = token.rsplit('/').next().unwrap_or("").to_ascii_lowercase();
+            (!name.is_empty()).then_some(name)
+        })
+        .collect()
+}
+
+/// Whether `keyword` occurs in `text` without letters or digits directly around it.
+fn contains_word(text: &str, keyword: &str) -> bool {
+    text.match_indices(keyword).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + keyword.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/recommendations.rs"]
+mod tests;
@@ -0,0 +1,741 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::category_taxonomy::CategoryRules;
+use super::discovery_config::DiscoveryConfig;
+use super::discovery_parser::{parse_awesome_skills_readme, skills_to_records, ParsedSkill};
+use super::discovery_remote::{fetch_github_skills, popular_skills};
+use super::git_credentials::github_api_token;
+use super::installer::parse_skill_md;
+use super::network;
+use super::skill_store::{RegistrySourceRecord, SkillStore};
+use super::team_index::{team_index_cache_file, TeamIndexSource};
+use super::util::now_ms;
+
+/// The generation of built-in sources added so far, so sources the user removed stay
+/// removed and only ones added in a later release are seeded (`true` means 1).
+const DEFAULT_SOURCES_SEEDED_KEY: &str = "registry_sources_seeded";
+const DEFAULT_SOURCES_GENERATION: u32 = 2;
+
+/// The README the app synced before sources were configurable; its id matches the
+/// `source` value of rows written back then.
+const AWESOME_CLAUDE_SKILLS_ID: &str = "awesome-claude-skills";
+const AWESOME_CLAUDE_SKILLS_URL: &str =
+    "https://raw.githubusercontent.com/BehiSecc/awesome-claude-skills/main/README.md";
+const GITHUB_TOPIC_ID: &str = "github-topic-claude-skill";
+const GITHUB_TOPIC_QUERY: &str = "topic:claude-skill";
+/// Built-in sources for the lists shipped with the app, which the non-database discovery
+/// commands also only show while their source is enabled.
+pub const SKILLS_SH_POPULAR_ID: &str = "skills-sh-popular";
+pub const RECOMMENDED_ID: &str = "builtin-recommended";
+const SKILLS_SH_POPULAR_LIST: &str = "skills_sh_popular";
+const RECOMMENDED_LIST: &str = "recommended";
+
+/// Repositories kept per GitHub topic source (one page of search results).
+const GITHUB_TOPIC_LIMIT: usize = 100;
+/// How deep a local directory source looks for `SKILL.md` files.
+const LOCAL_DIR_MAX_DEPTH: usize = 3;
+
+/// Hours between scheduled refreshes of each source; `0` refreshes only on demand.
+const REFRESH_INTERVAL_HOURS_KEY: &str = "registry_refresh_interval_hours";
+const DEFAULT_REFRESH_INTERVAL_HOURS: u32 = 24;
+const MAX_REFRESH_INTERVAL_HOURS: u32 = 24 * 30;
+/// How often the app checks whether a scheduled refresh is due.
+pub const REFRESH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+/// Emitted with the [`RegistrySyncResult`]s of a scheduled refresh.
+pub const REGISTRY_SYNC_EVENT: &str = "registry-sources-synced";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrySourceKind {
+    /// Markdown list of `- [name](url) - description` entries under `## ` category headers.
+    AwesomeList,
+    /// GitHub repository search query, e.g. `topic:claude-skill`.
+    GithubTopic,
+    /// JSON array of skills (or `{ "skills": [...] }`) at a URL or file path.
+    JsonIndex,
+    /// Directory containing skill folders with a `SKILL.md`.
+    LocalDir,
+    /// Versioned team registry index (JSON or YAML) at a URL or file path; see
+    /// [`super::team_index`].
+    TeamIndex,
+    /// A list shipped with the app (`skills_sh_popular` or `recommended`); only seeded,
+    /// never added by the user.
+    Builtin,
+}
+
+impl RegistrySourceKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::AwesomeList => "awesome_list",
+            Self::GithubTopic => "github_topic",
+            Self::JsonIndex => "json_index",
+            Self::LocalDir => "local_dir",
+            Self::TeamIndex => "team_index",
+            Self::Builtin => "builtin",
+        }
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "awesome_list" => Some(Self::AwesomeList),
+            "github_topic" => Some(Self::GithubTopic),
+            "json_index" => Some(Self::JsonIndex),
+            "local_dir" => Some(Self::LocalDir),
+            "team_index" => Some(Self::TeamIndex),
+            "builtin" => Some(Self::Builtin),
+            _ => None,
+        }
+    }
+}
+
+/// `ETag` / `Last-Modified` values from the last full fetch of a URL source.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HttpValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+pub enum Fetched {
+    /// The current listing, with validators for the next conditional request.
+    Skills(Vec<ParsedSkill>, HttpValidators),
+    /// The source answered `304 Not Modified`.
+    NotModified,
+}
+
+/// Lists skills for discovery. Each configured source feeds the `discovered_skills`
+/// rows tagged with its id.
+pub trait RegistrySource {
+    fn fetch(&self) -> Result<Vec<ParsedSkill>>;
+
+    /// Fetch unless the source is unchanged since `validators`. Sources without
+    /// conditional requests always fetch.
+    fn fetch_if_changed(&self, validators: &HttpValidators) -> Result<Fetched> {
+        let _ = validators;
+        Ok(Fetched::Skills(self.fetch()?, HttpValidators::default()))
+    }
+}
+
+pub struct AwesomeListSource {
+    pub location: String,
+}
+
+pub struct GithubTopicSource {
+    pub query: String,
+    pub token: Option<String>,
+}
+
+pub struct JsonIndexSource {
+    pub location: String,
+}
+
+pub struct LocalDirSource {
+    pub path: PathBuf,
+}
+
+pub struct BuiltinListSource {
+    pub list: String,
+}
+
+impl AwesomeListSource {
+    fn parse(&self, content: &str) -> Result<Vec<ParsedSkill>> {
+        parse_awesome_skills_readme(content).context("Failed to parse README")
+    }
+}
+
+impl RegistrySource for AwesomeListSource {
+    fn fetch(&self) -> Result<Vec<ParsedSkill>> {
+        self.parse(&read_location(&self.location)?)
+    }
+
+    fn fetch_if_changed(&self, validators: &HttpValidators) -> Result<Fetched> {
+        Ok(
+            match read_location_if_changed(&self.location, validators)? {
+                Some((content, next)) => Fetched::Skills(self.parse(&content)?, next),
+                None => Fetched::NotModified,
+            },
+        )
+    }
+}
+
+impl RegistrySource for GithubTopicSource {
+    fn fetch(&self) -> Result<Vec<ParsedSkill>> {
+        let repos =
+            fetch_github_skills(&self.query, "", GITHUB_TOPIC_LIMIT, self.token.as_deref())?;
+        Ok(repos
+            .into_iter()
+            .map(|repo| ParsedSkill {
+                // Every result carries the searched topic; categorize by the other ones.
+                category: repo
+                    .tags
+                    .iter()
+                    .filter(|t| !t.starts_with("claude"))
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                name: repo.name,
+                description: repo.description,
+                github_url: repo.github_url,
+                tags: repo.tags,
+            })
+            .collect())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonIndex {
+    List(Vec<JsonIndexEntry>),
+    Object { skills: Vec<JsonIndexEntry> },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonIndexEntry {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(alias = "githubUrl", alias = "gitUrl", alias = "repo")]
+    url: String,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl RegistrySource for JsonIndexSource {
+    fn fetch(&self) -> Result<Vec<ParsedSkill>> {
+        self.parse(&read_location(&self.location)?)
+    }
+
+    fn fetch_if_changed(&self, validators: &HttpValidators) -> Result<Fetched> {
+        Ok(
+            match read_location_if_changed(&self.location, validators)? {
+                Some((content, next)) => Fetched::Skills(self.parse(&content)?, next),
+                None => Fetched::NotModified,
+            },
+        )
+    }
+}
+
+impl JsonIndexSource {
+    fn parse(&self, content: &str) -> Result<Vec<ParsedSkill>> {
+        let index: JsonIndex = serde_json::from_str(content)
+            .with_context(|| format!("invalid skill index {}", self.location))?;
+        let entries = match index {
+            JsonIndex::List(entries) | JsonIndex::Object { skills: entries } => entries,
+        };
+        Ok(entries
+            .into_iter()
+            .filter(|e| !e.name.trim().is_empty() && !e.url.trim().is_empty())
+            .map(|e| ParsedSkill {
+                name: e.name.trim().to_string(),
+                description: e.description.unwrap_or_default(),
+                github_url: e.url.trim().to_string(),
+                category: e.category.unwrap_or_default(),
+                tags: e.tags,
+            })
+            .collect())
+    }
+}
+
+impl RegistrySource for LocalDirSource {
+    fn fetch(&self) -> Result<Vec<ParsedSkill>> {
+        if !self.path.is_dir() {
+            anyhow::bail!("skill directory not found: {:?}", self.path);
+        }
+        let mut skills = Vec::new();
+        collect_local_skills(&self.path, 0, &mut skills)?;
+        skills.sort_by(|a, b| a.github_url.cmp(&b.github_url));
+        Ok(skills)
+    }
+}
+
+impl RegistrySource for BuiltinListSource {
+    fn fetch(&self) -> Result<Vec<ParsedSkill>> {
+        Ok(match self.list.as_str() {
+            SKILLS_SH_POPULAR_LIST => popular_skills()
+                .into_iter()
+                .map(|skill| ParsedSkill {
+                    name: skill.name,
+                    description: skill.description,
+                    github_url: skill.github_url,
+                    category: skill.category,
+                    tags: skill.tags,
+                })
+                .collect(),
+            RECOMMENDED_LIST => DiscoveryConfig::get_default()
+                .skills
+                .into_iter()
+                .map(|skill| ParsedSkill {
+                    name: skill.name,
+                    description: skill.description,
+                    github_url: skill.github_url,
+                    category: skill.category,
+                    tags: skill.tags,
+                })
+                .collect(),
+            other => anyhow::bail!("unknown built-in skill list: {}", other),
+        })
+    }
+}
+
+fn collect_local_skills(dir: &Path, depth: usize, out: &mut Vec<ParsedSkill>) -> Result<()> {
+    let skill_md = dir.join("SKILL.md");
+    if skill_md.is_file() {
+        if let Some((name, description, category)) = parse_skill_md(&skill_md) {
+            out.push(ParsedSkill {
+                name,
+                description: description.unwrap_or_default(),
+                github_url: dir.to_string_lossy().to_string(),
+                category: category.unwrap_or_default(),
+                tags: vec!["local".to_string()],
+            });
+        }
+        return Ok(());
+    }
+    if depth >= LOCAL_DIR_MAX_DEPTH {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir).with_context(|| format!("read dir {:?}", dir))? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .map_or(true, |n| n.to_string_lossy().starts_with('.'));
+        if path.is_dir() && !hidden {
+            collect_local_skills(&path, depth + 1, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Contents of an `http(s)://` URL, or of a local file (`file://` or a plain path).
+fn read_location(location: &str) -> Result<String> {
+    read_location_if_changed(location, &HttpValidators::default())
+        .map(|read| read.map(|(content, _)| content).unwrap_or_default())
+}
+
+/// Like [`read_location`], but a URL is requested conditionally on `validators`;
+/// `None` means it is unchanged. Files are always read.
+fn read_location_if_changed(
+    location: &str,
+    validators: &HttpValidators,
+) -> Result<Option<(String, HttpValidators)>> {
+    let lower = location.to_ascii_lowercase();
+    if !(lower.starts_with("http://") || lower.starts_with("https://")) {
+        let path = location.strip_prefix("file://").unwrap_or(location);
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?;
+        return Ok(Some((content, HttpValidators::default())));
+    }
+
+    network::ensure_online_for(location)?;
+    let mut request = network::http_client()?
+        .get(location)
+        .header("User-Agent", "skills-hub");
+    if let Some(etag) = &validators.etag {
+        request = request.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.header(IF_MODIFIED_SINCE, last_modified);
+    }
+    let response = request
+        .send()
+        .with_context(|| format!("Failed to fetch {}", location))?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to fetch {}: HTTP {}", location, response.status());
+    }
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let next = HttpValidators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    let content = response.text().context("Failed to read response body")?;
+    Ok(Some((content, next)))
+}
+
+/// A configured source as the settings UI shows it.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrySourceInfo {
+    pub id: String,
+    pub kind: RegistrySourceKind,
+    pub name: String,
+    pub location: String,
+    pub enabled: bool,
+    pub last_synced_at: Option<i64>,
+    pub last_error: Option<String>,
+    /// Discovered skills currently attributed to this source.
+    pub skill_count: usize,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrySourceInput {
+    pub kind: RegistrySourceKind,
+    /// Display name; defaults to the location.
+    pub name: Option<String>,
+    /// URL or file path, GitHub search query, or directory, depending on `kind`.
+    pub location: String,
+}
+
+/// What one sync changed for a source.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceSyncStats {
+    /// Skills the source currently lists.
+    pub synced: usize,
+    /// Skills listed for the first time.
+    pub added: usize,
+    /// Skills the source stopped listing in this sync.
+    pub delisted: usize,
+    /// The source answered `304 Not Modified`; nothing was downloaded.
+    pub not_modified: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrySyncResult {
+    pub source_id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub stats: SourceSyncStats,
+    pub error: Option<String>,
+}
+
+/// Build the source a stored record describes.
+pub fn source_for(
+    store: &SkillStore,
+    record: &RegistrySourceRecord,
+) -> Result<Box<dyn RegistrySource>> {
+    let kind = RegistrySourceKind::parse(&record.kind)
+        .with_context(|| format!("unknown registry source kind: {}", record.kind))?;
+    let location = record.location.clone();
+    Ok(match kind {
+        RegistrySourceKind::AwesomeList => Box::new(AwesomeListSource { location }),
+        RegistrySourceKind::GithubTopic => Box::new(GithubTopicSource {
+            query: location,
+            token: github_api_token(store),
+        }),
+        RegistrySourceKind::JsonIndex => Box::new(JsonIndexSource { location }),
+        RegistrySourceKind::LocalDir => Box::new(LocalDirSource {
+            path: PathBuf::from(location),
+        }),
+        RegistrySourceKind::TeamIndex => Box::new(TeamIndexSource {
+            location,
+            cache_file: team_index_cache_file(store, &record.id),
+        }),
+        RegistrySourceKind::Builtin => Box::new(BuiltinListSource { list: location }),
+    })
+}
+
+/// Add the built-in sources the app did not seed yet: all of them on first run, and
+/// afterwards only the ones a later release introduced.
+pub fn ensure_default_registry_sources(store: &SkillStore) -> Result<()> {
+    let seeded = match store.get_setting(DEFAULT_SOURCES_SEEDED_KEY)?.as_deref() {
+        None => 0,
+        Some("true") => 1,
+        Some(value) => value.parse().unwrap_or(DEFAULT_SOURCES_GENERATION),
+    };
+    if seeded >= DEFAULT_SOURCES_GENERATION {
+        return Ok(());
+    }
+    let now = now_ms();
+    // (generation that introduced it, id, kind, name, location)
+    let defaults = [
+        (
+            1,
+            AWESOME_CLAUDE_SKILLS_ID,
+            RegistrySourceKind::AwesomeList,
+            "awesome-claude-skills",
+            AWESOME_CLAUDE_SKILLS_URL,
+        ),
+        (
+            1,
+            GITHUB_TOPIC_ID,
+            RegistrySourceKind::GithubTopic,
+            "GitHub topic:claude-skill",
+            GITHUB_TOPIC_QUERY,
+        ),
+        (
+            2,
+            SKILLS_SH_POPULAR_ID,
+            RegistrySourceKind::Builtin,
+            "skills.sh popular",
+            SKILLS_SH_POPULAR_LIST,
+        ),
+        (
+            2,
+            RECOMMENDED_ID,
+            RegistrySourceKind::Builtin,
+            "Built-in recommendations",
+            RECOMMENDED_LIST,
+        ),
+    ];
+    for (index, (generation, id, kind, name, location)) in defaults.into_iter().enumerate() {
+        if generation <= seeded || store.get_registry_source(id)?.is_some() {
+            continue;
+        }
+        store.upsert_registry_source(&RegistrySourceRecord {
+            id: id.to_string(),
+            kind: kind.as_str().to_string(),
+            name: name.to_string(),
+            location: location.to_string(),
+            enabled: true,
+            last_synced_at: None,
+            last_error: None,
+            // Keep the defaults in this order ahead of anything added later.
+            created_at: now + index as i64,
+            updated_at: now,
+            etag: None,
+            last_modified: None,
+        })?;
+    }
+    store.set_setting(
+        DEFAULT_SOURCES_SEEDED_KEY,
+        &DEFAULT_SOURCES_GENERATION.to_string(),
+    )?;
+    Ok(())
+}
+
+/// Whether the source `id` exists and is enabled.
+pub fn is_registry_source_enabled(store: &SkillStore, id: &str) -> bool {
+    store
+        .get_registry_source(id)
+        .ok()
+        .flatten()
+        .is_some_and(|record| record.enabled)
+}
+
+pub fn list_registry_sources(store: &SkillStore) -> Result<Vec<RegistrySourceInfo>> {
+    let counts = store.count_discovered_skills_by_source()?;
+    Ok(store
+        .list_registry_sources()?
+        .into_iter()
+        .filter_map(|record| {
+            let kind = RegistrySourceKind::parse(&record.kind)?;
+            Some(RegistrySourceInfo {
+                skill_count: counts.get(&record.id).copied().unwrap_or(0),
+                id: record.id,
+                kind,
+                name: record.name,
+                location: record.location,
+                enabled: record.enabled,
+                last_synced_at: record.last_synced_at,
+                last_error: record.last_error,
+            })
+        })
+        .collect())
+}
+
+pub fn add_registry_source(store: &SkillStore, input: RegistrySourceInput) -> Result<String> {
+    let location = input.location.trim().to_string();
+    if location.is_empty() {
+        anyhow::bail!("registry source location is required");
+    }
+    match input.kind {
+        RegistrySourceKind::LocalDir => {
+            if !Path::new(&location).is_dir() {
+                anyhow::bail!("skill directory not found: {}", location);
+            }
+        }
+        RegistrySourceKind::AwesomeList
+        | RegistrySourceKind::JsonIndex
+        | RegistrySourceKind::TeamIndex => {
+            let lower = location.to_ascii_lowercase();
+            let is_url = lower.starts_with("http://") || lower.starts_with("https://");
+            let path = location.strip_prefix("file://").unwrap_or(&location);
+            if !is_url && !Path::new(path).is_file() {
+                anyhow::bail!("expected an http(s) URL or an existing file: {}", location);
+            }
+        }
+        RegistrySourceKind::GithubTopic => {}
+        RegistrySourceKind::Builtin => {
+            anyhow::bail!("built-in registry sources cannot be added");
+        }
+    }
+    let name = input
+        .name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| location.clone());
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = now_ms();
+    store.upsert_registry_source(&RegistrySourceRecord {
+        id: id.clone(),
+        kind: input.kind.as_str().to_string(),
+        name,
+        location,
+        enabled: true,
+        last_synced_at: None,
+        last_error: None,
+        created_at: now,
+        updated_at: now,
+        etag: None,
+        last_modified: None,
+    })?;
+    Ok(id)
+}
+
+/// Disabling a source also removes the skills only it lists; the next sync after
+/// enabling it fetches the full listing again.
+pub fn set_registry_source_enabled(store: &SkillStore, id: &str, enabled: bool) -> Result<()> {
+    let mut record = store
+        .get_registry_source(id)?
+        .with_context(|| format!("registry source not found: {}", id))?;
+    record.enabled = enabled;
+    record.updated_at = now_ms();
+    if !enabled {
+        record.etag = None;
+        record.last_modified = None;
+    }
+    store.upsert_registry_source(&record)?;
+    if !enabled {
+        store.delete_discovered_skills_for_source(id)?;
+    }
+    Ok(())
+}
+
+pub fn remove_registry_source(store: &SkillStore, id: &str) -> Result<()> {
+    store.delete_registry_source(id)?;
+    if let Some(cache) = team_index_cache_file(store, id).filter(|p| p.exists()) {
+        let _ = std::fs::remove_file(cache);
+    }
+    Ok(())
+}
+
+/// Fetch one source and merge its listing into the discovered skills: new entries are
+/// added, changed ones updated, and ones it no longer lists marked delisted. URL sources
+/// are requested conditionally, so an unchanged source only refreshes when its skills
+/// were last seen. The outcome is recorded on the source either way.
+pub fn sync_registry_source(store: &SkillStore, id: &str) -> Result<SourceSyncStats> {
+    let mut record = store
+        .get_registry_source(id)?
+        .with_context(|| format!("registry source not found: {}", id))?;
+    let validators = HttpValidators {
+        etag: record.etag.clone(),
+        last_modified: record.last_modified.clone(),
+    };
+    let fetched =
+        source_for(store, &record).and_then(|source| source.fetch_if_changed(&validators));
+    let now = now_ms();
+    record.updated_at = now;
+    let stats = match fetched {
+        Ok(Fetched::NotModified) => SourceSyncStats {
+            synced: store.touch_discovered_skills_for_source(&record.id, now)?,
+            not_modified: true,
+            ..Default::default()
+        },
+        Ok(Fetched::Skills(skills, next)) => {
+            let rules = CategoryRules::load(store)?;
+            let records = skills_to_records(skills, &record.id, &rules);
+            let merge = store.merge_discovered_skills_for_source(&record.id, &records, now)?;
+            record.etag = next.etag;
+            record.last_modified = next.last_modified;
+            SourceSyncStats {
+                synced: merge.listed,
+                added: merge.added,
+                delisted: merge.delisted,
+                not_modified: false,
+            }
+        }
+        Err(err) => {
+            record.last_error = Some(format!("{:#}", err));
+            store.upsert_registry_source(&record)?;
+            return Err(err);
+        }
+    };
+    record.last_synced_at = Some(now);
+    record.last_error = None;
+    store.upsert_registry_source(&record)?;
+    Ok(stats)
+}
+
+/// Sync every enabled source; one failing source does not stop the others.
+pub fn sync_registry_sources(store: &SkillStore) -> Result<Vec<RegistrySyncResult>> {
+    sync_matching_sources(store, |_| true)
+}
+
+/// Sync the enabled sources whose last successful sync is older than the refresh
+/// interval. Sources that never synced are always due.
+pub fn sync_due_registry_sources(store: &SkillStore) -> Result<Vec<RegistrySyncResult>> {
+    let interval_ms = i64::from(get_registry_refresh_interval_hours(store)) * 60 * 60 * 1000;
+    let now = now_ms();
+    sync_matching_sources(store, |record| match record.last_synced_at {
+        None => true,
+        Some(_) if interval_ms == 0 => false,
+        Some(last) => now.saturating_sub(last) >= interval_ms,
+    })
+}
+
+fn sync_matching_sources(
+    store: &SkillStore,
+    due: impl Fn(&RegistrySourceRecord) -> bool,
+) -> Result<Vec<RegistrySyncResult>> {
+    let mut results = Vec::new();
+    for record in store.list_registry_sources()? {
+        if !record.enabled || !due(&record) {
+            continue;
+        }
+        let outcome = sync_registry_source(store, &record.id);
+        match &outcome {
+            Ok(stats) if stats.added > 0 || stats.delisted > 0 => log::info!(
+                "[registry] {}: {} new, {} delisted, {} listed",
+                record.name,
+                stats.added,
+                stats.delisted,
+                stats.synced
+            ),
+            Ok(_) => {}
+            Err(err) => log::warn!("[registry] sync of {} failed: {:#}", record.name, err),
+        }
+        let (stats, error) = match outcome {
+            Ok(stats) => (stats, None),
+            Err(err) => (SourceSyncStats::default(), Some(format!("{:#}", err))),
+        };
+        results.push(RegistrySyncResult {
+            source_id: record.id,
+            name: record.name,
+            stats,
+            error,
+        });
+    }
+    Ok(results)
+}
+
+pub fn get_registry_refresh_interval_hours(store: &SkillStore) -> u32 {
+    store
+        .get_setting(REFRESH_INTERVAL_HOURS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .unwrap_or(DEFAULT_REFRESH_INTERVAL_HOURS)
+}
+
+pub fn set_registry_refresh_interval_hours(store: &SkillStore, hours: u32) -> Result<u32> {
+    if hours > MAX_REFRESH_INTERVAL_HOURS {
+        anyhow::bail!(
+            "refresh interval hours must be between 0 and {}",
+            MAX_REFRESH_INTERVAL_HOURS
+        );
+    }
+    store.set_setting(REFRESH_INTERVAL_HOURS_KEY, &hours.to_string())?;
+    Ok(hours)
+}
+
+#[cfg(test)]
+#[path = "tests/registry_sources.rs"]
+mod tests;
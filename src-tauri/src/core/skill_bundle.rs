@@ -0,0 +1,509 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use std::path::{Component, Path};
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::central_repo::{ensure_central_repo, resolve_central_repo_path};
+use super::content_hash::{hash_dir, IGNORE_NAMES};
+use super::installer::compute_content_hash;
+use super::skill_store::{SkillRecord, SkillStore};
+use super::skill_templates::validate_skill_name;
+use super::util::{is_executable, now_ms};
+
+pub const BUNDLE_FORMAT: &str = "skills-hub-bundle";
+pub const BUNDLE_VERSION: u32 = 1;
+pub const BUNDLE_EXTENSION: &str = "skillbundle.tar.gz";
+const MANIFEST_NAME: &str = "manifest.json";
+const SKILLS_PREFIX: &str = "skills/";
+// Refuse to inflate archives beyond this; skills are text and small scripts.
+const MAX_BUNDLE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Bundle metadata for each file of a skill, next to the file it was read from.
+pub(crate) type SkillFiles = Vec<(BundleFile, std::path::PathBuf)>;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: i64,
+    pub app_version: String,
+    pub skills: Vec<BundleSkill>,
+    #[serde(default)]
+    pub categories: Vec<BundleCategory>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleSkill {
+    pub name: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub source_type: String,
+    pub source_ref: Option<String>,
+    pub source_revision: Option<String>,
+    pub content_hash: Option<String>,
+    pub files: Vec<BundleFile>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    #[serde(default)]
+    pub executable: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleCategory {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub icon: String,
+    pub color: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportBundleResult {
+    pub path: String,
+    pub skill_count: usize,
+    pub file_count: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportedBundleSkill {
+    pub skill_id: String,
+    pub name: String,
+    pub replaced: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SkippedBundleSkill {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ImportBundleResult {
+    pub imported: Vec<ImportedBundleSkill>,
+    pub skipped: Vec<SkippedBundleSkill>,
+}
+
+/// Write the given managed skills, their metadata and per-file checksums into one
+/// `.tar.gz` at `dest`.
+pub fn export_skills_bundle(
+    store: &SkillStore,
+    skill_ids: &[String],
+    dest: &Path,
+) -> Result<ExportBundleResult> {
+    if skill_ids.is_empty() {
+        anyhow::bail!("no skills selected for export");
+    }
+
+    let mut skills = Vec::new();
+    let mut seen_names = HashSet::new();
+    for skill_id in skill_ids {
+        let record = store
+            .get_skill_by_id(skill_id)?
+            .ok_or_else(|| anyhow::anyhow!("skill not found: {}", skill_id))?;
+        if !seen_names.insert(record.name.clone()) {
+            anyhow::bail!("duplicate skill name in export: {}", record.name);
+        }
+        let files = collect_skill_files(Path::new(&record.central_path))?;
+        let content_hash = hash_dir(Path::new(&record.central_path))?;
+        skills.push((record, files, content_hash));
+    }
+
+    let known_categories = store.list_categories()?;
+    let mut categories: Vec<BundleCategory> = Vec::new();
+    for (record, _, _) in &skills {
+        let Some(category) = record.category.as_deref() else {
+            continue;
+        };
+        if categories.iter().any(|c| c.id == category) {
+            continue;
+        }
+        if let Some(info) = known_categories.iter().find(|c| c.id == category) {
+            categories.push(BundleCategory {
+                id: info.id.clone(),
+                name: info.name.clone(),
+                description: info.description.clone(),
+                icon: info.icon.clone(),
+                color: info.color.clone(),
+            });
+        }
+    }
+
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        created_at: now_ms(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        skills: skills
+            .iter()
+            .map(|(record, files, content_hash)| BundleSkill {
+                name: record.name.clone(),
+                description: record.description.clone(),
+                category: record.category.clone(),
+                source_type: record.source_type.clone(),
+                source_ref: record.source_ref.clone(),
+                source_revision: record.source_revision.clone(),
+                content_hash: Some(content_hash.clone()),
+                files: files.iter().map(|(meta, _)| meta.clone()).collect(),
+            })
+            .collect(),
+        categories,
+    };
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("create {:?}", parent))?;
+    }
+    if let Err(err) = write_archive(dest, &manifest, &skills) {
+        let _ = std::fs::remove_file(dest);
+        return Err(err);
+    }
+
+    Ok(ExportBundleResult {
+        path: dest.to_string_lossy().to_string(),
+        skill_count: manifest.skills.len(),
+        file_count: manifest.skills.iter().map(|s| s.files.len()).sum(),
+    })
+}
+
+/// Read and verify a bundle without installing anything.
+pub fn read_skill_bundle(bundle_path: &Path) -> Result<BundleManifest> {
+    Ok(load_verified_bundle(bundle_path)?.0)
+}
+
+pub fn import_skills_bundle<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    bundle_path: &Path,
+    overwrite: bool,
+) -> Result<ImportBundleResult> {
+    let central_dir = resolve_central_repo_path(app, store)?;
+    ensure_central_repo(&central_dir)?;
+    import_skills_bundle_in(store, &central_dir, bundle_path, overwrite)
+}
+
+/// Verify every file against the manifest before touching the central repo, then install
+/// each skill whose staged copy matches its manifest `content_hash`. Existing skills are
+/// skipped unless `overwrite` is set, in which case their id (and therefore tool sync
+/// targets) is kept and a copy at a different central path is removed.
+pub fn import_skills_bundle_in(
+    store: &SkillStore,
+    central_dir: &Path,
+    bundle_path: &Path,
+    overwrite: bool,
+) -> Result<ImportBundleResult> {
+    let (manifest, mut contents) = load_verified_bundle(bundle_path)?;
+
+    let known_categories = store.list_categories()?;
+    for category in &manifest.categories {
+        if !known_categories.iter().any(|c| c.id == category.id) {
+            store.add_category(
+                &category.id,
+                &category.name,
+                &category.description,
+                &category.icon,
+                &category.color,
+            )?;
+        }
+    }
+
+    let existing_skills = store.list_skills()?;
+    let mut result = ImportBundleResult::default();
+    for skill in &manifest.skills {
+        let central_path = central_dir.join(&skill.name);
+        let existing = existing_skills
+            .iter()
+            .find(|r| r.name == skill.name || Path::new(&r.central_path) == central_path);
+        if (existing.is_some() || central_path.exists()) && !overwrite {
+            result.skipped.push(SkippedBundleSkill {
+                name: skill.name.clone(),
+                reason: "already exists".to_string(),
+            });
+            continue;
+        }
+
+        let staging = central_dir.join(format!(".{}.import-{}", skill.name, Uuid::new_v4()));
+        let staged = write_skill_files(&staging, skill, &mut contents).and_then(|_| {
+            if let Some(expected) = skill.content_hash.as_deref() {
+                if hash_dir(&staging)? != expected {
+                    anyhow::bail!("content hash does not match manifest");
+                }
+            }
+            if central_path.exists() {
+                std::fs::remove_dir_all(&central_path)
+                    .with_context(|| format!("remove {:?}", central_path))?;
+            }
+            std::fs::rename(&staging, &central_path)
+                .with_context(|| format!("move {:?} -> {:?}", staging, central_path))
+        });
+        if let Err(err) = staged {
+            let _ = std::fs::remove_dir_all(&staging);
+            result.skipped.push(SkippedBundleSkill {
+                name: skill.name.clone(),
+                reason: format!("{:#}", err),
+            });
+            continue;
+        }
+        if let Some(old_path) = existing
+            .map(|r| Path::new(&r.central_path))
+            .filter(|old_path| *old_path != central_path && old_path.exists())
+        {
+            if let Err(err) = std::fs::remove_dir_all(old_path) {
+                log::warn!(
+                    "[bundle] failed to remove old copy {:?}: {:#}",
+                    old_path,
+                    err
+                );
+            }
+        }
+
+        let now = now_ms();
+        let record = SkillRecord {
+            id: existing
+                .map(|r| r.id.clone())
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            name: skill.name.clone(),
+            description: skill.description.clone(),
+            category: skill.category.clone(),
+            source_type: skill.source_type.clone(),
+            source_ref: skill.source_ref.clone(),
+            source_revision: skill.source_revision.clone(),
+            central_path: central_path.to_string_lossy().to_string(),
+            content_hash: compute_content_hash(&central_path),
+            created_at: existing.map(|r| r.created_at).unwrap_or(now),
+            updated_at: now,
+            last_sync_at: existing.and_then(|r| r.last_sync_at),
+            last_seen_at: now,
+            status: "ok".to_string(),
+        };
+        store.upsert_skill(&record)?;
+        result.imported.push(ImportedBundleSkill {
+            skill_id: record.id,
+            name: record.name,
+            replaced: existing.is_some(),
+        });
+    }
+
+    Ok(result)
+}
+
+/// Files of a skill directory in a stable order, with their bundle metadata.
+pub(crate) fn collect_skill_files(root: &Path) -> Result<SkillFiles> {
+    if !root.is_dir() {
+        anyhow::bail!("skill directory not found: {:?}", root);
+    }
+    let mut files = Vec::new();
+    for entry in WalkDir::new(root)
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !IGNORE_NAMES.iter().any(|ignored| *ignored == name.as_ref())
+        })
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(root)
+            .with_context(|| format!("strip prefix {:?}", entry.path()))?;
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        let bytes =
+            std::fs::read(entry.path()).with_context(|| format!("read {:?}", entry.path()))?;
+        files.push((
+            BundleFile {
+                path,
+                size: bytes.len() as u64,
+                sha256: sha256_hex(&bytes),
+                executable: is_executable(entry.path()),
+            },
+            entry.path().to_path_buf(),
+        ));
+    }
+    Ok(files)
+}
+
+fn write_archive(
+    dest: &Path,
+    manifest: &BundleManifest,
+    skills: &[(SkillRecord, SkillFiles, String)],
+) -> Result<()> {
+    let file = std::fs::File::create(dest).with_context(|| format!("create {:?}", dest))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, flate2::Compression::default()));
+
+    let manifest_json = serde_json::to_vec_pretty(manifest)?;
+    append_bytes(&mut builder, MANIFEST_NAME, &manifest_json, false)?;
+    for (record, files, _) in skills {
+        for (meta, source) in files {
+            let bytes = std::fs::read(source).with_context(|| format!("read {:?}", source))?;
+            if sha256_hex(&bytes) != meta.sha256 {
+                anyhow::bail!("file changed during export: {:?}", source);
+            }
+            let name = format!("{}{}/{}", SKILLS_PREFIX, record.name, meta.path);
+            append_bytes(&mut builder, &name, &bytes, meta.executable)?;
+        }
+    }
+
+    builder
+        .into_inner()
+        .context("finish bundle archive")?
+        .finish()
+        .context("finish bundle compression")?;
+    Ok(())
+}
+
+pub(crate) fn append_bytes<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    bytes: &[u8],
+    executable: bool,
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(if executable { 0o755 } else { 0o644 });
+    header.set_mtime(0);
+    header.set_entry_type(tar::EntryType::Regular);
+    builder
+        .append_data(&mut header, name, bytes)
+        .with_context(|| format!("append {}", name))
+}
+
+/// Load the whole archive into memory and check it against its manifest: every listed
+/// file must be present with a matching size and sha256, and nothing unlisted may appear.
+fn load_verified_bundle(bundle_path: &Path) -> Result<(BundleManifest, BTreeMap<String, Vec<u8>>)> {
+    let file =
+        std::fs::File::open(bundle_path).with_context(|| format!("open {:?}", bundle_path))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    let mut manifest: Option<BundleManifest> = None;
+    let mut contents = BTreeMap::new();
+    let mut total: u64 = 0;
+    for entry in archive.entries().context("read bundle archive")? {
+        let mut entry = entry.context("read bundle entry")?;
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            continue;
+        }
+        let name = archive_entry_name(&entry.path()?)?;
+        if !entry_type.is_file() {
+            anyhow::bail!("unsupported entry in bundle: {}", name);
+        }
+        total += entry.size();
+        if total > MAX_BUNDLE_BYTES {
+            anyhow::bail!("bundle is larger than {} bytes", MAX_BUNDLE_BYTES);
+        }
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry
+            .read_to_end(&mut bytes)
+            .with_context(|| format!("read {}", name))?;
+
+        if name == MANIFEST_NAME {
+            manifest = Some(serde_json::from_slice(&bytes).context("parse bundle manifest.json")?);
+        } else if contents.insert(name.clone(), bytes).is_some() {
+            anyhow::bail!("duplicate entry in bundle: {}", name);
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| anyhow::anyhow!("bundle has no manifest.json"))?;
+    if manifest.format != BUNDLE_FORMAT {
+        anyhow::bail!("not a skill bundle (format: {})", manifest.format);
+    }
+    if manifest.version > BUNDLE_VERSION {
+        anyhow::bail!(
+            "bundle format version {} is newer than supported ({})",
+            manifest.version,
+            BUNDLE_VERSION
+        );
+    }
+
+    let mut expected = HashSet::new();
+    let mut names = HashSet::new();
+    for skill in &manifest.skills {
+        validate_skill_name(&skill.name)?;
+        if !names.insert(skill.name.as_str()) {
+            anyhow::bail!("duplicate skill in bundle manifest: {}", skill.name);
+        }
+        for file in &skill.files {
+            let name = format!("{}{}/{}", SKILLS_PREFIX, skill.name, file.path);
+            archive_entry_name(Path::new(&name))?;
+            let bytes = contents
+                .get(&name)
+                .ok_or_else(|| anyhow::anyhow!("integrity check failed: missing {}", name))?;
+            if bytes.len() as u64 != file.size || sha256_hex(bytes) != file.sha256 {
+                anyhow::bail!("integrity check failed: {} does not match manifest", name);
+            }
+            expected.insert(name);
+        }
+    }
+    if let Some(extra) = contents.keys().find(|name| !expected.contains(*name)) {
+        anyhow::bail!("integrity check failed: unexpected entry {}", extra);
+    }
+
+    Ok((manifest, contents))
+}
+
+fn write_skill_files(
+    dest: &Path,
+    skill: &BundleSkill,
+    contents: &mut BTreeMap<String, Vec<u8>>,
+) -> Result<()> {
+    std::fs::create_dir_all(dest).with_context(|| format!("create {:?}", dest))?;
+    for file in &skill.files {
+        let name = format!("{}{}/{}", SKILLS_PREFIX, skill.name, file.path);
+        let bytes = contents.remove(&name).unwrap_or_default();
+        let target = dest.join(&file.path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).with_context(|| format!("create {:?}", parent))?;
+        }
+        std::fs::write(&target, bytes).with_context(|| format!("write {:?}", target))?;
+
+        #[cfg(unix)]
+        if file.executable {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o755))
+                .with_context(|| format!("chmod {:?}", target))?;
+        }
+    }
+    Ok(())
+}
+
+/// Normalize an archive path to `a/b/c`, rejecting absolute paths and `..`.
+pub(crate) fn archive_entry_name(path: &Path) -> Result<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::CurDir => {}
+            _ => anyhow::bail!("unsafe path in bundle: {:?}", path),
+        }
+    }
+    if parts.is_empty() {
+        anyhow::bail!("empty path in bundle");
+    }
+    Ok(parts.join("/"))
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+#[path = "tests/skill_bundle.rs"]
+mod tests;
@@ -0,0 +1,462 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tauri::Manager;
+
+use super::git_credentials::{resolve_git_auth, GitAuth};
+use super::git_fetcher::{git_cli_available, run_git_in, run_git_in_with_auth};
+use super::git_url::github_owner_repo;
+use super::skill_store::{SkillPublicationRecord, SkillRecord, SkillStore};
+use super::sync_engine::copy_dir_recursive;
+use super::util::now_ms;
+
+const PUBLISH_DIR_NAME: &str = "skill-publish";
+const DEFAULT_BRANCH: &str = "main";
+// Used only when the user has no git identity configured.
+const FALLBACK_IDENTITY: [&str; 4] = [
+    "-c",
+    "user.name=Skills Hub",
+    "-c",
+    "user.email=skills-hub@localhost",
+];
+
+#[derive(Clone, Debug)]
+pub struct PublishOptions {
+    pub remote_url: String,
+    pub branch: Option<String>,
+    pub subpath: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PublishStatus {
+    pub skill_id: String,
+    pub remote_url: String,
+    pub branch: String,
+    pub subpath: Option<String>,
+    pub source_ref: Option<String>,
+    pub last_commit: Option<String>,
+    pub pushed_commit: Option<String>,
+    pub last_pushed_at: Option<i64>,
+    pub has_unpushed_changes: bool,
+}
+
+static PUBLISH_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn publish_skill<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    skill_id: &str,
+    opts: &PublishOptions,
+) -> Result<PublishStatus> {
+    let publish_root = app
+        .path()
+        .app_data_dir()
+        .context("failed to resolve app data dir")?
+        .join(PUBLISH_DIR_NAME);
+    publish_skill_in(store, &publish_root, skill_id, opts)
+}
+
+/// Publish a managed skill into `remote_url` (optionally under `subpath` of a monorepo),
+/// push it, and switch the skill record over to `source_type = "git"`.
+pub fn publish_skill_in(
+    store: &SkillStore,
+    publish_root: &Path,
+    skill_id: &str,
+    opts: &PublishOptions,
+) -> Result<PublishStatus> {
+    let remote_url = opts.remote_url.trim();
+    if remote_url.is_empty() {
+        anyhow::bail!("remote url is empty");
+    }
+    let subpath = normalize_subpath(opts.subpath.as_deref())?;
+    check_layout(store, skill_id, remote_url, subpath.as_deref())?;
+    let work_dir = publish_root.join(work_dir_key(remote_url));
+    push_into(
+        store,
+        skill_id,
+        remote_url,
+        opts.branch.as_deref(),
+        subpath.as_deref(),
+        &work_dir,
+        opts.message.as_deref(),
+    )
+}
+
+/// A remote holds either one skill at its root or skills in separate subpaths. They share
+/// one work tree, so publishing to the root would delete the other skills from it.
+fn check_layout(
+    store: &SkillStore,
+    skill_id: &str,
+    remote_url: &str,
+    subpath: Option<&str>,
+) -> Result<()> {
+    for other in store.list_skill_publications()? {
+        if other.skill_id == skill_id || other.remote_url != remote_url {
+            continue;
+        }
+        match (subpath, other.subpath.as_deref()) {
+            (None, _) => anyhow::bail!(
+                "{} already holds other published skills; publish to a subpath or another remote",
+                remote_url
+            ),
+            (_, None) => anyhow::bail!(
+                "another skill is published to the root of {}; publish to another remote",
+                remote_url
+            ),
+            (Some(ours), Some(theirs)) if ours == theirs => anyhow::bail!(
+                "another skill is already published to {} in {}",
+                ours,
+                remote_url
+            ),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Commit and push whatever changed in the central copy since the last push.
+pub fn push_skill_changes(
+    store: &SkillStore,
+    skill_id: &str,
+    message: Option<&str>,
+) -> Result<PublishStatus> {
+    let publication = store
+        .get_skill_publication(skill_id)?
+        .ok_or_else(|| anyhow::anyhow!("skill is not published"))?;
+    push_into(
+        store,
+        skill_id,
+        &publication.remote_url,
+        Some(&publication.branch),
+        publication.subpath.as_deref(),
+        Path::new(&publication.work_dir),
+        message,
+    )
+}
+
+/// Record a local edit of a published skill as a commit in its publish work tree.
+/// Returns `Ok(None)` for skills that were never published. Does not touch the network.
+pub fn commit_skill_change(
+    store: &SkillStore,
+    skill_id: &str,
+    message: &str,
+) -> Result<Option<String>> {
+    let Some(mut publication) = store.get_skill_publication(skill_id)? else {
+        return Ok(None);
+    };
+    let skill = store
+        .get_skill_by_id(skill_id)?
+        .ok_or_else(|| anyhow::anyhow!("skill not found"))?;
+    let work_dir = PathBuf::from(&publication.work_dir);
+    if !work_dir.join(".git").exists() {
+        anyhow::bail!("publish work tree missing: {:?}", work_dir);
+    }
+
+    let lock = PUBLISH_LOCK.get_or_init(|| Mutex::new(()));
+    let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+
+    stage_skill(
+        Path::new(&skill.central_path),
+        &work_dir,
+        publication.subpath.as_deref(),
+    )?;
+    if !commit_if_changed(&work_dir, publication.subpath.as_deref(), message)? {
+        return Ok(publication.last_commit);
+    }
+    let head = run_git_in(&work_dir, &["rev-parse", "HEAD"])?;
+    publication.last_commit = Some(head.clone());
+    publication.updated_at = now_ms();
+    store.upsert_skill_publication(&publication)?;
+    Ok(Some(head))
+}
+
+pub fn get_publish_status(store: &SkillStore, skill_id: &str) -> Result<Option<PublishStatus>> {
+    let Some(publication) = store.get_skill_publication(skill_id)? else {
+        return Ok(None);
+    };
+    let source_ref = store
+        .get_skill_by_id(skill_id)?
+        .and_then(|skill| skill.source_ref);
+    Ok(Some(to_status(&publication, source_ref)))
+}
+
+pub fn has_unpushed_changes(store: &SkillStore, skill_id: &str) -> Result<bool> {
+    Ok(store
+        .get_skill_publication(skill_id)?
+        .is_some_and(|p| p.last_commit != p.pushed_commit))
+}
+
+fn push_into(
+    store: &SkillStore,
+    skill_id: &str,
+    remote_url: &str,
+    branch: Option<&str>,
+    subpath: Option<&str>,
+    work_dir: &Path,
+    message: Option<&str>,
+) -> Result<PublishStatus> {
+    if !git_cli_available() {
+        anyhow::bail!("publishing a skill requires a system git binary");
+    }
+    let skill = store
+        .get_skill_by_id(skill_id)?
+        .ok_or_else(|| anyhow::anyhow!("skill not found"))?;
+    let central_path = PathBuf::from(&skill.central_path);
+    if !central_path.exists() {
+        anyhow::bail!("central path not found: {:?}", central_path);
+    }
+
+    let lock = PUBLISH_LOCK.get_or_init(|| Mutex::new(()));
+    let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+
+    let auth = resolve_git_auth(store, remote_url)?;
+    let branch = prepare_work_dir(work_dir, remote_url, branch, auth.as_ref())?;
+    let source_ref = build_source_ref(remote_url, &branch, subpath);
+
+    stage_skill(&central_path, work_dir, subpath)?;
+    let default_message = format!("Publish {}", skill.name);
+    commit_if_changed(work_dir, subpath, message.unwrap_or(&default_message))?;
+    let head = run_git_in(work_dir, &["rev-parse", "HEAD"])
+        .context("nothing to publish: the skill directory is empty")?;
+    run_git_in_with_auth(
+        work_dir,
+        &["push", "origin", &format!("HEAD:refs/heads/{}", branch)],
+        auth.as_ref(),
+    )
+    .with_context(|| format!("push to {}", remote_url))?;
+
+    let now = now_ms();
+    let existing = store.get_skill_publication(skill_id)?;
+    let publication = SkillPublicationRecord {
+        skill_id: skill_id.to_string(),
+        remote_url: remote_url.to_string(),
+        branch,
+        subpath: subpath.map(|s| s.to_string()),
+        work_dir: work_dir.to_string_lossy().to_string(),
+        last_commit: Some(head.clone()),
+        pushed_commit: Some(head.clone()),
+        last_pushed_at: Some(now),
+        created_at: existing.map(|p| p.created_at).unwrap_or(now),
+        updated_at: now,
+    };
+    store.upsert_skill_publication(&publication)?;
+
+    store.upsert_skill(&SkillRecord {
+        source_type: "git".to_string(),
+        source_ref: Some(source_ref.clone()),
+        source_revision: Some(head),
+        updated_at: now,
+        ..skill
+    })?;
+
+    Ok(to_status(&publication, Some(source_ref)))
+}
+
+/// Clone (or refresh) the publish work tree and check out `branch`, replaying any
+/// local-only commits on top of the remote branch. Returns the branch in use.
+fn prepare_work_dir(
+    work_dir: &Path,
+    remote_url: &str,
+    branch: Option<&str>,
+    auth: Option<&GitAuth>,
+) -> Result<String> {
+    if !work_dir.join(".git").exists() {
+        if work_dir.exists() {
+            std::fs::remove_dir_all(work_dir)
+                .with_context(|| format!("remove stale work tree {:?}", work_dir))?;
+        }
+        let parent = work_dir
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("invalid work dir {:?}", work_dir))?;
+        std::fs::create_dir_all(parent).with_context(|| format!("create {:?}", parent))?;
+        let dir_name = work_dir.to_string_lossy().to_string();
+        run_git_in_with_auth(parent, &["clone", remote_url, &dir_name], auth)
+            .with_context(|| format!("clone {} into {:?}", remote_url, work_dir))?;
+    } else {
+        run_git_in(work_dir, &["remote", "set-url", "origin", remote_url])?;
+        run_git_in_with_auth(work_dir, &["fetch", "--prune", "origin"], auth)
+            .with_context(|| format!("fetch {}", remote_url))?;
+    }
+
+    let has_commits = run_git_in(work_dir, &["rev-parse", "--verify", "--quiet", "HEAD"]).is_ok();
+    if has_commits {
+        // Leftovers from an interrupted publish; the skill is re-staged from the central copy.
+        run_git_in(work_dir, &["reset", "--hard", "HEAD"])?;
+        run_git_in(work_dir, &["clean", "-fd"])?;
+    }
+
+    let current = run_git_in(work_dir, &["symbolic-ref", "--short", "HEAD"]).unwrap_or_default();
+    let branch = match branch.map(str::trim).filter(|b| !b.is_empty()) {
+        Some(b) => b.to_string(),
+        None if has_commits && !current.is_empty() => current.clone(),
+        None => DEFAULT_BRANCH.to_string(),
+    };
+    let remote_ref = format!("origin/{}", branch);
+    let remote_exists =
+        run_git_in(work_dir, &["rev-parse", "--verify", "--quiet", &remote_ref]).is_ok();
+
+    if current != branch {
+        let local_exists = run_git_in(
+            work_dir,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("refs/heads/{}", branch),
+            ],
+        )
+        .is_ok();
+        if local_exists {
+            run_git_in(work_dir, &["checkout", &branch])?;
+        } else if remote_exists {
+            run_git_in(
+                work_dir,
+                &["checkout", "-b", &branch, "--track", &remote_ref],
+            )?;
+        } else if has_commits {
+            run_git_in(work_dir, &["checkout", "-b", &branch])?;
+        } else {
+            run_git_in(
+                work_dir,
+                &["symbolic-ref", "HEAD", &format!("refs/heads/{}", branch)],
+            )?;
+        }
+    }
+
+    if remote_exists {
+        if let Err(err) = run_git_as_user(work_dir, &["rebase", &remote_ref]) {
+            let _ = run_git_in(work_dir, &["rebase", "--abort"]);
+            anyhow::bail!(
+                "remote branch {} has diverged from local changes; resolve it in {:?}\n{:#}",
+                branch,
+                work_dir,
+                err
+            );
+        }
+    }
+
+    Ok(branch)
+}
+
+/// Mirror the central skill directory into the work tree (whole repo or `subpath`).
+fn stage_skill(central_path: &Path, work_dir: &Path, subpath: Option<&str>) -> Result<()> {
+    match subpath {
+        Some(subpath) => {
+            let target = work_dir.join(subpath);
+            if target.exists() {
+                std::fs::remove_dir_all(&target).with_context(|| format!("clear {:?}", target))?;
+            }
+            copy_dir_recursive(central_path, &target)
+                .with_context(|| format!("copy {:?} -> {:?}", central_path, target))
+        }
+        None => {
+            for entry in std::fs::read_dir(work_dir)
+                .with_context(|| format!("read dir {:?}", work_dir))?
+                .flatten()
+            {
+                if entry.file_name() == ".git" {
+                    continue;
+                }
+                let path = entry.path();
+                if path.is_dir() {
+                    std::fs::remove_dir_all(&path)
+                } else {
+                    std::fs::remove_file(&path)
+                }
+                .with_context(|| format!("clear {:?}", path))?;
+            }
+            copy_dir_recursive(central_path, work_dir)
+                .with_context(|| format!("copy {:?} -> {:?}", central_path, work_dir))
+        }
+    }
+}
+
+pub(crate) fn commit_if_changed(
+    work_dir: &Path,
+    subpath: Option<&str>,
+    message: &str,
+) -> Result<bool> {
+    let pathspec = subpath.unwrap_or(".");
+    run_git_in(work_dir, &["add", "-A", "--", pathspec])?;
+    let staged = run_git_in(work_dir, &["status", "--porcelain", "--", pathspec])?;
+    if staged.is_empty() {
+        return Ok(false);
+    }
+    run_git_as_user(work_dir, &["commit", "-m", message])?;
+    Ok(true)
+}
+
+/// Run a git command that creates commits, falling back to a placeholder identity.
+pub(crate) fn run_git_as_user(work_dir: &Path, args: &[&str]) -> Result<String> {
+    let has_identity = run_git_in(work_dir, &["config", "user.email"])
+        .map(|v| !v.is_empty())
+        .unwrap_or(false);
+    let mut full: Vec<&str> = Vec::new();
+    if !has_identity {
+        full.extend(FALLBACK_IDENTITY);
+    }
+    full.extend(args);
+    run_git_in(work_dir, &full)
+}
+
+/// Build a `source_ref` that `install_git_skill`/`update_managed_skill_from_source` can parse.
+pub(crate) fn build_source_ref(remote_url: &str, branch: &str, subpath: Option<&str>) -> String {
+    match (github_owner_repo(remote_url), subpath) {
+        (Some((owner, repo)), Some(subpath)) => format!(
+            "https://github.com/{}/{}/tree/{}/{}",
+            owner, repo, branch, subpath
+        ),
+        (Some((owner, repo)), None) => {
+            format!("https://github.com/{}/{}/tree/{}", owner, repo, branch)
+        }
+        // Other hosts use the `#<ref>:<subpath>` fragment understood by `parse_git_source`.
+        (None, Some(subpath)) => format!("{}#{}:{}", remote_url, branch, subpath),
+        (None, None) => format!("{}#{}", remote_url, branch),
+    }
+}
+
+fn normalize_subpath(subpath: Option<&str>) -> Result<Option<String>> {
+    let Some(raw) = subpath else {
+        return Ok(None);
+    };
+    let trimmed = raw.trim().trim_matches('/');
+    if trimmed.is_empty() || trimmed == "." {
+        return Ok(None);
+    }
+    if trimmed
+        .split('/')
+        .any(|seg| seg.is_empty() || seg == "." || seg == ".." || seg == ".git")
+        || trimmed.contains('\\')
+    {
+        anyhow::bail!("invalid subpath: {}", raw);
+    }
+    Ok(Some(trimmed.to_string()))
+}
+
+fn work_dir_key(remote_url: &str) -> String {
+    use sha2::Digest;
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(remote_url.as_bytes());
+    hex::encode(hasher.finalize())[..16].to_string()
+}
+
+fn to_status(publication: &SkillPublicationRecord, source_ref: Option<String>) -> PublishStatus {
+    PublishStatus {
+        skill_id: publication.skill_id.clone(),
+        remote_url: publication.remote_url.clone(),
+        branch: publication.branch.clone(),
+        subpath: publication.subpath.clone(),
+        source_ref,
+        last_commit: publication.last_commit.clone(),
+        pushed_commit: publication.pushed_commit.clone(),
+        last_pushed_at: publication.last_pushed_at,
+        has_unpushed_changes: publication.last_commit != publication.pushed_commit,
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/skill_publish.rs"]
+mod tests;
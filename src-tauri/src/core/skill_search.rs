@@ -0,0 +1,180 @@
+//! Full-text search over discovered and installed skills.
+//!
+//! Discovered skills are kept in the `skill_search` FTS5 table by triggers on
+//! `discovered_skills`. Installed skills live on disk, so their documents (the
+//! `SKILL.md` body and file names) are re-indexed lazily before each search,
+//! only for skills whose row or `SKILL.md` changed since they were last indexed.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Result;
+use serde::Deserialize;
+use walkdir::WalkDir;
+
+use super::content_hash::IGNORE_NAMES;
+use super::install_state::InstalledSkills;
+use super::skill_store::{
+    InstalledSearchDoc, SkillRecord, SkillSearchHit, SkillSearchParams, SkillStore,
+};
+use super::skill_templates::strip_frontmatter;
+
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+const MAX_SEARCH_LIMIT: usize = 500;
+/// Bounds on how much of an installed skill folder ends up in the `files` column.
+const MAX_INDEXED_FILES: usize = 200;
+const MAX_FILE_DEPTH: usize = 4;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillSearchQuery {
+    pub query: String,
+    pub category: Option<String>,
+    pub source: Option<String>,
+    /// `discovered` or `installed`.
+    pub kind: Option<String>,
+    pub installed: Option<bool>,
+    pub limit: Option<usize>,
+}
+
+/// Turns free text into an FTS5 match expression: every word must match, and
+/// every word also matches as a prefix (`chan` finds `changelog`). Quotes and
+/// FTS operators in the input are treated as plain text. Returns `None` when the
+/// query has no searchable words.
+pub fn match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|token| !token.is_empty())
+        .map(|token| format!("\"{}\"*", token))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" AND "))
+    }
+}
+
+pub fn search_skills(store: &SkillStore, query: &SkillSearchQuery) -> Result<Vec<SkillSearchHit>> {
+    let Some(match_expr) = match_expression(&query.query) else {
+        return Ok(Vec::new());
+    };
+    refresh_installed_index(store)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let mut hits = store.search_skill_index(&SkillSearchParams {
+        match_expr,
+        category: non_empty(&query.category),
+        source: non_empty(&query.source),
+        kind: non_empty(&query.kind),
+        // The `installed` filter applies after the install state is known.
+        limit: query.installed.is_none().then_some(limit),
+    })?;
+
+    // Catalogue entries count as installed when a managed skill came from the same
+    // repository and folder, however either URL is spelled.
+    let installed = InstalledSkills::load(store)?;
+    for hit in &mut hits {
+        hit.installed_skill_id = if hit.kind == "installed" {
+            Some(hit.ref_id.clone())
+        } else {
+            hit.github_url
+                .as_deref()
+                .and_then(|url| installed.lookup(url, None).skill_id)
+        };
+    }
+    if let Some(wanted) = query.installed {
+        hits.retain(|hit| hit.installed_skill_id.is_some() == wanted);
+        hits.truncate(limit);
+    }
+    Ok(hits)
+}
+
+/// Brings the installed-skill documents in line with the `skills` table and
+/// the files on disk. Returns how many documents were (re)written.
+pub fn refresh_installed_index(store: &SkillStore) -> Result<usize> {
+    let indexed = store.installed_search_stamps()?;
+    let skills = store.list_skills()?;
+
+    let mut written = 0;
+    for skill in &skills {
+        let stamp = document_stamp(skill);
+        if indexed.get(&skill.id) == Some(&stamp) {
+            continue;
+        }
+        store.upsert_installed_search_doc(&build_document(skill, stamp))?;
+        written += 1;
+    }
+
+    let live: HashSet<&str> = skills.iter().map(|skill| skill.id.as_str()).collect();
+    for id in indexed.keys() {
+        if !live.contains(id.as_str()) {
+            store.delete_installed_search_doc(id)?;
+        }
+    }
+    Ok(written)
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn document_stamp(skill: &SkillRecord) -> String {
+    let modified = std::fs::metadata(Path::new(&skill.central_path).join("SKILL.md"))
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0);
+    format!("{}:{}", skill.updated_at, modified)
+}
+
+fn build_document(skill: &SkillRecord, stamp: String) -> InstalledSearchDoc {
+    let root = Path::new(&skill.central_path);
+    let body = std::fs::read_to_string(root.join("SKILL.md"))
+        .map(|text| strip_frontmatter(&text).to_string())
+        .unwrap_or_default();
+    InstalledSearchDoc {
+        skill_id: skill.id.clone(),
+        name: skill.name.clone(),
+        description: skill.description.clone().unwrap_or_default(),
+        category: skill.category.clone().unwrap_or_default(),
+        source_type: skill.source_type.clone(),
+        body,
+        files: list_files(root),
+        stamp,
+    }
+}
+
+fn list_files(root: &Path) -> String {
+    WalkDir::new(root)
+        .max_depth(MAX_FILE_DEPTH)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            !IGNORE_NAMES.iter().any(|ignored| *ignored == name)
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(root)
+                .ok()
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+        })
+        .take(MAX_INDEXED_FILES)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+#[path = "tests/skill_search.rs"]
+mod tests;
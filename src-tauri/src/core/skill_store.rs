@@ -1,4 +1,5 @@
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result};
 use rusqlite::{params, Connection};
@@ -8,7 +9,9 @@ const DB_FILE_NAME: &str = "skills_hub.db";
 const LEGACY_APP_IDENTIFIERS: &[&str] = &["com.tauri.dev", "com.tauri.dev.skillshub"];
 
 // Schema versioning: bump when making changes and add a migration step.
-const SCHEMA_VERSION: i32 = 6;
+const SCHEMA_VERSION: i32 = 18;
+// Version a fresh database is labelled with after SCHEMA_V1 (never change either).
+const FRESH_SCHEMA_VERSION: i32 = 6;
 
 // Minimal schema for MVP: skills, skill_targets, settings, discovered_skills(optional).
 const SCHEMA_V1: &str = r#"
@@ -79,9 +82,44 @@ CREATE INDEX IF NOT EXISTS idx_scan_paths_path ON scan_paths(path);
 CREATE INDEX IF NOT EXISTS idx_categories_id ON categories(id);
 "#;
 
+// Full-text index over listed discovered skills (kept current by triggers) and installed
+// skills (refreshed from disk by `skill_search`). Columns after `files` are stored only.
+const SKILL_SEARCH_SCHEMA: &str = r#"
+CREATE VIRTUAL TABLE IF NOT EXISTS skill_search USING fts5(
+  name, description, tags, body, files,
+  kind UNINDEXED, ref_id UNINDEXED, category UNINDEXED, source UNINDEXED, stamp UNINDEXED,
+  tokenize = 'unicode61 remove_diacritics 2',
+  prefix = '2 3'
+);
+
+CREATE TRIGGER IF NOT EXISTS discovered_skills_search_insert
+AFTER INSERT ON discovered_skills WHEN new.delisted_at IS NULL
+BEGIN
+  INSERT INTO skill_search (name, description, tags, body, files, kind, ref_id, category, source, stamp)
+  VALUES (new.name, new.description, new.tags, '', '', 'discovered', new.id, new.category, new.source, '');
+END;
+
+CREATE TRIGGER IF NOT EXISTS discovered_skills_search_update
+AFTER UPDATE OF name, description, tags, category, source, delisted_at ON discovered_skills
+BEGIN
+  DELETE FROM skill_search WHERE kind = 'discovered' AND ref_id = old.id;
+  INSERT INTO skill_search (name, description, tags, body, files, kind, ref_id, category, source, stamp)
+  SELECT new.name, new.description, new.tags, '', '', 'discovered', new.id, new.category, new.source, ''
+  WHERE new.delisted_at IS NULL;
+END;
+
+CREATE TRIGGER IF NOT EXISTS discovered_skills_search_delete
+AFTER DELETE ON discovered_skills
+BEGIN
+  DELETE FROM skill_search WHERE kind = 'discovered' AND ref_id = old.id;
+END;
+"#;
+
 #[derive(Clone, Debug)]
 pub struct SkillStore {
     db_path: PathBuf,
+    /// Set on the store handed out by [`SkillStore::transaction`]; every call shares it.
+    tx_conn: Option<Arc<Mutex<Connection>>>,
 }
 
 #[derive(Clone, Debug)]
@@ -121,10 +159,181 @@ pub struct DiscoveredSkillRecord {
     pub description: String,
     pub github_url: String,
     pub category: String,
+    /// The registry source the row's details come from. Other sources may list the same
+    /// skill too (see `discovered_skill_sources`).
     pub source: String,
     pub tags: String,
     pub created_at: i64,
     pub updated_at: i64,
+    /// When a sync first listed the skill.
+    pub first_seen_at: i64,
+    /// When a sync last listed the skill. Rows the source stopped listing are kept with
+    /// `delisted_at` set (cleared if it lists them again) and left out of listings.
+    pub last_seen_at: i64,
+    /// The category as the source listed it, which mapping rules turn into `category`;
+    /// `None` for rows not synced since rules were introduced.
+    pub raw_category: Option<String>,
+}
+
+/// Outcome of merging a source's listing into `discovered_skills`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiscoveredSkillsMerge {
+    pub listed: usize,
+    pub added: usize,
+    pub delisted: usize,
+}
+
+/// What the metadata enricher learned about a discovered skill's repository.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiscoveredSkillMetadata {
+    pub skill_id: String,
+    /// The skill's URL when it was enriched; a changed URL is enriched again.
+    pub github_url: String,
+    /// `owner/repo` on GitHub.
+    pub repo: Option<String>,
+    /// `ok`, `not_found` (broken link), `unsupported` (not a GitHub URL) or `error`.
+    pub status: String,
+    pub stars: Option<i64>,
+    /// Last push to the repository (ms).
+    pub last_commit_at: Option<i64>,
+    /// SPDX id, e.g. `MIT`.
+    pub license: Option<String>,
+    pub archived: bool,
+    /// `SKILL.md` frontmatter at the skill's path, when there is one.
+    pub skill_name: Option<String>,
+    pub skill_description: Option<String>,
+    /// What installing from the URL would offer: `installable` (one skill),
+    /// `multiple`, `no_skill` or `not_found` (missing branch or repository). Unset
+    /// when it could not be checked.
+    pub install_status: Option<String>,
+    /// Number of skills installing from the URL would offer.
+    pub skill_count: Option<i64>,
+    /// Commit the URL's branch (or the default branch) pointed at.
+    pub head_revision: Option<String>,
+    pub error: Option<String>,
+    pub etag: Option<String>,
+    pub fetched_at: i64,
+}
+
+/// An installed skill as the search index stores it.
+#[derive(Clone, Debug)]
+pub struct InstalledSearchDoc {
+    pub skill_id: String,
+    pub name: String,
+    pub description: String,
+    pub category: String,
+    pub source_type: String,
+    /// `SKILL.md` text without its frontmatter.
+    pub body: String,
+    /// Relative file paths, space separated.
+    pub files: String,
+    /// Changes whenever the document needs re-indexing.
+    pub stamp: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SkillSearchParams {
+    /// FTS5 match expression.
+    pub match_expr: String,
+    pub category: Option<String>,
+    pub source: Option<String>,
+    /// `discovered` or `installed`.
+    pub kind: Option<String>,
+    /// Every match when unset.
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SkillSearchHit {
+    pub kind: String,
+    /// Discovered skill id or installed skill id, depending on `kind`.
+    pub ref_id: String,
+    pub name: String,
+    pub description: String,
+    pub category: String,
+    /// Registry source id, or the installed skill's source type.
+    pub source: String,
+    pub github_url: Option<String>,
+    /// Set by [`super::skill_search::search_skills`], which matches URLs the way the
+    /// other discovery views do.
+    pub installed_skill_id: Option<String>,
+    /// Best matching fragment, matches wrapped in `<mark>`.
+    pub snippet: String,
+    /// BM25 score; lower is better.
+    pub score: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct RegistrySourceRecord {
+    pub id: String,
+    pub kind: String,
+    pub name: String,
+    pub location: String,
+    pub enabled: bool,
+    pub last_synced_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Cache validators from the last full fetch, for conditional requests.
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CategoryRuleRecord {
+    pub id: String,
+    /// `keyword` or `regex`.
+    pub kind: String,
+    pub pattern: String,
+    pub category_id: String,
+    /// Rules are tried in ascending position; the first match wins.
+    pub position: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct SkillPublicationRecord {
+    pub skill_id: String,
+    pub remote_url: String,
+    pub branch: String,
+    pub subpath: Option<String>,
+    pub work_dir: String,
+    pub last_commit: Option<String>,
+    pub pushed_commit: Option<String>,
+    pub last_pushed_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct GitCredentialRecord {
+    pub host: String,
+    pub kind: String,
+    pub username: Option<String>,
+    /// Plaintext token written before tokens moved to the OS keychain.
+    pub secret: Option<String>,
+    /// Keychain account holding the token.
+    pub secret_ref: Option<String>,
+    pub ssh_key_path: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct GitCacheEntryRecord {
+    pub key: String,
+    /// `mirror`, `worktree`, `clone` (libgit2 fallback) or `tarball`.
+    pub kind: String,
+    pub clone_url: String,
+    pub ref_name: Option<String>,
+    /// Bare mirror a worktree belongs to.
+    pub mirror_key: Option<String>,
+    pub path: String,
+    pub head: Option<String>,
+    pub size_bytes: i64,
+    pub last_fetched_at: i64,
+    pub last_used_at: i64,
 }
 
 #[derive(Clone, Debug)]
@@ -139,7 +348,10 @@ pub struct AiAgentRecord {
 
 impl SkillStore {
     pub fn new(db_path: PathBuf) -> Self {
-        Self { db_path }
+        Self {
+            db_path,
+            tx_conn: None,
+        }
     }
 
     #[allow(dead_code)]
@@ -148,13 +360,22 @@ impl SkillStore {
     }
 
     pub fn ensure_schema(&self) -> Result<()> {
+        // Each pass applies one migration step; repeat until the schema is current.
+        while self.ensure_schema_step()? {}
+        Ok(())
+    }
+
+    /// Returns whether another migration step is pending.
+    fn ensure_schema_step(&self) -> Result<bool> {
         self.with_conn(|conn| {
             conn.execute_batch("PRAGMA foreign_keys = ON;")?;
 
             let user_version: i32 = conn.query_row("PRAGMA user_version;", [], |row| row.get(0))?;
             if user_version == 0 {
+                // Fresh databases start from the frozen V1 layout, which has always been
+                // labelled v6, and reach the current schema through the migrations below.
                 conn.execute_batch(SCHEMA_V1)?;
-                conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+                conn.pragma_update(None, "user_version", FRESH_SCHEMA_VERSION)?;
             } else if user_version == 1 {
                 // Migration from v1 to v2: add description column
                 conn.execute("ALTER TABLE skills ADD COLUMN description TEXT NULL", [])?;
@@ -177,8 +398,6 @@ impl SkillStore {
                     [],
                 )?;
                 conn.execute("CREATE INDEX IF NOT EXISTS idx_categories_id ON categories(id)", [])?;
-                // Initialize default categories
-                Self::initialize_default_categories(conn)?;
                 conn.pragma_update(None, "user_version", 4)?;
             } else if user_version == 4 {
                 // Migration from v4 to v5: recreate discovered_skills table with new schema
@@ -217,6 +436,220 @@ impl SkillStore {
                 )?;
                 conn.execute("CREATE INDEX IF NOT EXISTS idx_ai_agents_name ON ai_agents(name)", [])?;
                 conn.pragma_update(None, "user_version", 6)?;
+            } else if user_version == 6 {
+                // Migration from v6 to v7: add skill_publications table
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS skill_publications (
+                        skill_id TEXT PRIMARY KEY,
+                        remote_url TEXT NOT NULL,
+                        branch TEXT NOT NULL,
+                        subpath TEXT NULL,
+                        work_dir TEXT NOT NULL,
+                        last_commit TEXT NULL,
+                        pushed_commit TEXT NULL,
+                        last_pushed_at INTEGER NULL,
+                        created_at INTEGER NOT NULL,
+                        updated_at INTEGER NOT NULL,
+                        FOREIGN KEY(skill_id) REFERENCES skills(id) ON DELETE CASCADE
+                    )",
+                    [],
+                )?;
+                conn.pragma_update(None, "user_version", 7)?;
+            } else if user_version == 7 {
+                // Migration from v7 to v8: add git_credentials table
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS git_credentials (
+                        host TEXT PRIMARY KEY,
+                        kind TEXT NOT NULL,
+                        username TEXT NULL,
+                        secret TEXT NULL,
+                        ssh_key_path TEXT NULL,
+                        created_at INTEGER NOT NULL,
+                        updated_at INTEGER NOT NULL
+                    )",
+                    [],
+                )?;
+                conn.pragma_update(None, "user_version", 8)?;
+            } else if user_version == 8 {
+                // Migration from v8 to v9: add the shared git cache index
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS git_cache_entries (
+                        key TEXT PRIMARY KEY,
+                        kind TEXT NOT NULL,
+                        clone_url TEXT NOT NULL,
+                        ref_name TEXT NULL,
+                        mirror_key TEXT NULL,
+                        path TEXT NOT NULL,
+                        head TEXT NULL,
+                        size_bytes INTEGER NOT NULL,
+                        last_fetched_at INTEGER NOT NULL,
+                        last_used_at INTEGER NOT NULL
+                    )",
+                    [],
+                )?;
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS git_cache_skill_refs (
+                        skill_id TEXT PRIMARY KEY,
+                        cache_key TEXT NOT NULL,
+                        FOREIGN KEY(skill_id) REFERENCES skills(id) ON DELETE CASCADE
+                    )",
+                    [],
+                )?;
+                conn.pragma_update(None, "user_version", 9)?;
+            } else if user_version == 9 {
+                // Migration from v9 to v10: add registry_sources. Fresh databases got the
+                // pre-v5 discovered_skills layout from SCHEMA_V1; it never held rows the
+                // app could write, so recreate it.
+                let has_source: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM pragma_table_info('discovered_skills') WHERE name = 'source'",
+                    [],
+                    |row| row.get(0),
+                )?;
+                if has_source == 0 {
+                    conn.execute("DROP TABLE IF EXISTS discovered_skills", [])?;
+                }
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS discovered_skills (
+                        id TEXT PRIMARY KEY,
+                        name TEXT NOT NULL,
+                        description TEXT NOT NULL,
+                        github_url TEXT NOT NULL,
+                        category TEXT NOT NULL,
+                        source TEXT NOT NULL,
+                        tags TEXT NOT NULL,
+                        created_at INTEGER NOT NULL,
+                        updated_at INTEGER NOT NULL
+                    )",
+                    [],
+                )?;
+                conn.execute("CREATE INDEX IF NOT EXISTS idx_discovered_skills_category ON discovered_skills(category)", [])?;
+                conn.execute("CREATE INDEX IF NOT EXISTS idx_discovered_skills_source ON discovered_skills(source)", [])?;
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS registry_sources (
+                        id TEXT PRIMARY KEY,
+                        kind TEXT NOT NULL,
+                        name TEXT NOT NULL,
+                        location TEXT NOT NULL,
+                        enabled INTEGER NOT NULL,
+                        last_synced_at INTEGER NULL,
+                        last_error TEXT NULL,
+                        created_at INTEGER NOT NULL,
+                        updated_at INTEGER NOT NULL
+                    )",
+                    [],
+                )?;
+                conn.pragma_update(None, "user_version", 10)?;
+            } else if user_version == 10 {
+                // Migration from v10 to v11: track when discovered skills were first/last
+                // listed and keep delisted ones, plus HTTP validators per registry source.
+                conn.execute("ALTER TABLE discovered_skills ADD COLUMN first_seen_at INTEGER NULL", [])?;
+                conn.execute("ALTER TABLE discovered_skills ADD COLUMN last_seen_at INTEGER NULL", [])?;
+                conn.execute("ALTER TABLE discovered_skills ADD COLUMN delisted_at INTEGER NULL", [])?;
+                conn.execute(
+                    "UPDATE discovered_skills SET first_seen_at = created_at, last_seen_at = updated_at",
+                    [],
+                )?;
+                conn.execute("CREATE INDEX IF NOT EXISTS idx_discovered_skills_first_seen_at ON discovered_skills(first_seen_at)", [])?;
+                conn.execute("ALTER TABLE registry_sources ADD COLUMN etag TEXT NULL", [])?;
+                conn.execute("ALTER TABLE registry_sources ADD COLUMN last_modified TEXT NULL", [])?;
+                conn.pragma_update(None, "user_version", 11)?;
+            } else if user_version == 11 {
+                // Migration from v11 to v12: full-text search index, seeded with the
+                // listed discovered skills; installed skills are indexed on first search.
+                conn.execute_batch(SKILL_SEARCH_SCHEMA)?;
+                conn.execute("DELETE FROM skill_search WHERE kind = 'discovered'", [])?;
+                conn.execute(
+                    "INSERT INTO skill_search (name, description, tags, body, files, kind, ref_id, category, source, stamp)
+                     SELECT name, description, tags, '', '', 'discovered', id, category, source, ''
+                     FROM discovered_skills WHERE delisted_at IS NULL",
+                    [],
+                )?;
+                conn.pragma_update(None, "user_version", 12)?;
+            } else if user_version == 12 {
+                // Migration from v12 to v13: repository metadata of discovered skills
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS discovered_skill_metadata (
+                        skill_id TEXT PRIMARY KEY,
+                        github_url TEXT NOT NULL,
+                        repo TEXT NULL,
+                        status TEXT NOT NULL,
+                        stars INTEGER NULL,
+                        last_commit_at INTEGER NULL,
+                        license TEXT NULL,
+                        archived INTEGER NOT NULL DEFAULT 0,
+                        skill_name TEXT NULL,
+                        skill_description TEXT NULL,
+                        error TEXT NULL,
+                        etag TEXT NULL,
+                        fetched_at INTEGER NOT NULL,
+                        FOREIGN KEY(skill_id) REFERENCES discovered_skills(id) ON DELETE CASCADE
+                    )",
+                    [],
+                )?;
+                conn.pragma_update(None, "user_version", 13)?;
+            } else if user_version == 13 {
+                // Migration from v13 to v14: installability of discovered skills; existing
+                // metadata is marked stale so the next enrichment run checks it.
+                conn.execute("ALTER TABLE discovered_skill_metadata ADD COLUMN install_status TEXT NULL", [])?;
+                conn.execute("ALTER TABLE discovered_skill_metadata ADD COLUMN skill_count INTEGER NULL", [])?;
+                conn.execute("UPDATE discovered_skill_metadata SET fetched_at = 0", [])?;
+                conn.pragma_update(None, "user_version", 14)?;
+            } else if user_version == 14 {
+                // Migration from v14 to v15: commit each discovered skill's ref points at,
+                // to compare installed revisions with; refetched on the next enrichment run.
+                conn.execute("ALTER TABLE discovered_skill_metadata ADD COLUMN head_revision TEXT NULL", [])?;
+                conn.execute("UPDATE discovered_skill_metadata SET fetched_at = 0", [])?;
+                conn.pragma_update(None, "user_version", 15)?;
+            } else if user_version == 15 {
+                // Migration from v15 to v16: category mapping rules, and the category each
+                // source listed a skill under so rule changes can re-categorize it. Sources
+                // are refetched in full to record those; built-in categories are no longer
+                // rows, so the ones seeded in v4 are dropped.
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS category_rules (
+                        id TEXT PRIMARY KEY,
+                        kind TEXT NOT NULL,
+                        pattern TEXT NOT NULL,
+                        category_id TEXT NOT NULL,
+                        position INTEGER NOT NULL,
+                        created_at INTEGER NOT NULL,
+                        updated_at INTEGER NOT NULL
+                    )",
+                    [],
+                )?;
+                conn.execute("ALTER TABLE discovered_skills ADD COLUMN raw_category TEXT NULL", [])?;
+                conn.execute("UPDATE registry_sources SET etag = NULL, last_modified = NULL", [])?;
+                conn.execute(
+                    "DELETE FROM categories WHERE id IN ('development', 'productivity', 'ai', 'documentation')",
+                    [],
+                )?;
+                conn.pragma_update(None, "user_version", 16)?;
+            } else if user_version == 16 {
+                // Migration from v16 to v17: tokens move to the OS keychain; rows keep the
+                // keychain account name. Plaintext secrets are moved on the next startup.
+                conn.execute("ALTER TABLE git_credentials ADD COLUMN secret_ref TEXT NULL", [])?;
+                conn.pragma_update(None, "user_version", 17)?;
+            } else if user_version == 17 {
+                // Migration from v17 to v18: which sources list each discovered skill, so
+                // one source going away keeps the skills others still list. Rows so far
+                // only knew their last source; the others are refetched in full.
+                conn.execute(
+                    "CREATE TABLE IF NOT EXISTS discovered_skill_sources (
+                        skill_id TEXT NOT NULL,
+                        source TEXT NOT NULL,
+                        PRIMARY KEY(skill_id, source),
+                        FOREIGN KEY(skill_id) REFERENCES discovered_skills(id) ON DELETE CASCADE
+                    )",
+                    [],
+                )?;
+                conn.execute("CREATE INDEX IF NOT EXISTS idx_discovered_skill_sources_source ON discovered_skill_sources(source)", [])?;
+                conn.execute(
+                    "INSERT OR IGNORE INTO discovered_skill_sources (skill_id, source)
+                     SELECT id, source FROM discovered_skills WHERE delisted_at IS NULL",
+                    [],
+                )?;
+                conn.execute("UPDATE registry_sources SET etag = NULL, last_modified = NULL", [])?;
+                conn.pragma_update(None, "user_version", 18)?;
             } else if user_version > SCHEMA_VERSION {
                 anyhow::bail!(
                     "database schema version {} is newer than app supports {}",
@@ -240,7 +673,24 @@ impl SkillStore {
                 [],
             )?;
 
-            Ok(())
+            // Fresh databases skip the v5 -> v6 step, so make sure ai_agents exists too.
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS ai_agents (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    api_key TEXT NOT NULL,
+                    base_url TEXT NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    updated_at INTEGER NOT NULL
+                )",
+                [],
+            )?;
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_ai_agents_name ON ai_agents(name)",
+                [],
+            )?;
+
+            Ok(user_version < SCHEMA_VERSION)
         })
     }
 
@@ -266,6 +716,18 @@ impl SkillStore {
         })
     }
 
+    pub fn list_settings(&self) -> Result<Vec<(String, String)>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare("SELECT key, value FROM settings ORDER BY key ASC")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
     #[allow(dead_code)]
     pub fn set_onboarding_completed(&self, completed: bool) -> Result<()> {
         self.set_setting(
@@ -622,33 +1084,6 @@ impl SkillStore {
     }
 
     // Categories management
-    fn initialize_default_categories(conn: &Connection) -> Result<()> {
-        use super::discovery_config::DiscoveryConfig;
-        let config = DiscoveryConfig::get_default();
-        
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as i64;
-        
-        for (category_id, category) in config.categories {
-            // Check if category already exists
-            let exists: i64 = conn.query_row(
-                "SELECT COUNT(*) FROM categories WHERE id = ?1",
-                params![category_id],
-                |row| row.get(0)
-            ).unwrap_or(0);
-            
-            if exists == 0 {
-                conn.execute(
-                    "INSERT INTO categories (id, name, description, icon, color, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                    params![category.id, category.name, category.description, category.icon, category.color, now],
-                )?;
-            }
-        }
-        Ok(())
-    }
-
     pub fn add_category(&self, id: &str, name: &str, description: &str, icon: &str, color: &str) -> Result<String> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -693,57 +1128,69 @@ impl SkillStore {
         })
     }
 
-    // Discovered skills management
-    pub fn upsert_discovered_skill(&self, record: &DiscoveredSkillRecord) -> Result<()> {
+    pub fn list_category_rules(&self) -> Result<Vec<CategoryRuleRecord>> {
         self.with_conn(|conn| {
-            conn.execute(
-                "INSERT INTO discovered_skills (
-                    id, name, description, github_url, category, source, tags, created_at, updated_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-                ON CONFLICT(id) DO UPDATE SET
-                    name = excluded.name,
-                    description = excluded.description,
-                    github_url = excluded.github_url,
-                    category = excluded.category,
-                    source = excluded.source,
-                    tags = excluded.tags,
-                    updated_at = excluded.updated_at",
-                params![
-                    record.id,
-                    record.name,
-                    record.description,
-                    record.github_url,
-                    record.category,
-                    record.source,
-                    record.tags,
-                    record.created_at,
-                    record.updated_at
-                ],
+            let mut stmt = conn.prepare(
+                "SELECT id, kind, pattern, category_id, position, created_at, updated_at
+                 FROM category_rules
+                 ORDER BY position ASC, created_at ASC, id ASC",
             )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(CategoryRuleRecord {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    pattern: row.get(2)?,
+                    category_id: row.get(3)?,
+                    position: row.get(4)?,
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            })?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    pub fn upsert_category_rule(&self, record: &CategoryRuleRecord) -> Result<()> {
+        self.with_conn(|conn| {
+            upsert_category_rule_row(conn, record)?;
+            Ok(())
+        })
+    }
+
+    /// Returns whether the rule existed.
+    pub fn delete_category_rule(&self, id: &str) -> Result<bool> {
+        self.with_conn(|conn| {
+            Ok(conn.execute("DELETE FROM category_rules WHERE id = ?1", params![id])? > 0)
+        })
+    }
+
+    /// Replace every rule with `records`.
+    pub fn replace_category_rules(&self, records: &[CategoryRuleRecord]) -> Result<()> {
+        self.with_tx(|tx| {
+            tx.execute("DELETE FROM category_rules", [])?;
+            for record in records {
+                upsert_category_rule_row(tx, record)?;
+            }
             Ok(())
         })
     }
 
+    // Discovered skills management
     pub fn list_discovered_skills(&self) -> Result<Vec<DiscoveredSkillRecord>> {
         self.with_conn(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, name, description, github_url, category, source, tags, created_at, updated_at
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {}
                  FROM discovered_skills
+                 WHERE delisted_at IS NULL
                  ORDER BY category ASC, name ASC",
-            )?;
-            let rows = stmt.query_map([], |row| {
-                Ok(DiscoveredSkillRecord {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    github_url: row.get(3)?,
-                    category: row.get(4)?,
-                    source: row.get(5)?,
-                    tags: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            })?;
+                DISCOVERED_SKILL_COLUMNS
+            ))?;
+            let rows = stmt.query_map([], discovered_skill_from_row)?;
 
             let mut items = Vec::new();
             for row in rows {
@@ -755,25 +1202,14 @@ impl SkillStore {
 
     pub fn list_discovered_skills_by_category(&self, category: &str) -> Result<Vec<DiscoveredSkillRecord>> {
         self.with_conn(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, name, description, github_url, category, source, tags, created_at, updated_at
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {}
                  FROM discovered_skills
-                 WHERE category = ?1
+                 WHERE category = ?1 AND delisted_at IS NULL
                  ORDER BY name ASC",
-            )?;
-            let rows = stmt.query_map(params![category], |row| {
-                Ok(DiscoveredSkillRecord {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    github_url: row.get(3)?,
-                    category: row.get(4)?,
-                    source: row.get(5)?,
-                    tags: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            })?;
+                DISCOVERED_SKILL_COLUMNS
+            ))?;
+            let rows = stmt.query_map(params![category], discovered_skill_from_row)?;
 
             let mut items = Vec::new();
             for row in rows {
@@ -783,28 +1219,26 @@ impl SkillStore {
         })
     }
 
+    /// Listed discovered skills matching `query`, best match first (see
+    /// [`super::skill_search::match_expression`] for the query syntax).
     pub fn search_discovered_skills(&self, query: &str) -> Result<Vec<DiscoveredSkillRecord>> {
+        let Some(match_expr) = super::skill_search::match_expression(query) else {
+            return Ok(Vec::new());
+        };
         self.with_conn(|conn| {
-            let search_pattern = format!("%{}%", query.to_lowercase());
-            let mut stmt = conn.prepare(
-                "SELECT id, name, description, github_url, category, source, tags, created_at, updated_at
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {}
                  FROM discovered_skills
-                 WHERE LOWER(name) LIKE ?1 OR LOWER(description) LIKE ?1 OR LOWER(tags) LIKE ?1
-                 ORDER BY name ASC",
-            )?;
-            let rows = stmt.query_map(params![search_pattern], |row| {
-                Ok(DiscoveredSkillRecord {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    github_url: row.get(3)?,
-                    category: row.get(4)?,
-                    source: row.get(5)?,
-                    tags: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            })?;
+                 JOIN (
+                   SELECT ref_id, bm25(skill_search, {}) AS score
+                   FROM skill_search
+                   WHERE skill_search MATCH ?1 AND kind = 'discovered'
+                 ) AS hits ON hits.ref_id = discovered_skills.id
+                 WHERE delisted_at IS NULL
+                 ORDER BY hits.score ASC, name ASC",
+                DISCOVERED_SKILL_COLUMNS, SKILL_SEARCH_WEIGHTS
+            ))?;
+            let rows = stmt.query_map(params![match_expr], discovered_skill_from_row)?;
 
             let mut items = Vec::new();
             for row in rows {
@@ -814,24 +1248,823 @@ impl SkillStore {
         })
     }
 
-    pub fn clear_discovered_skills(&self) -> Result<()> {
+    /// Skills first listed at or after `since` (ms), newest first.
+    pub fn list_new_discovered_skills(&self, since: i64) -> Result<Vec<DiscoveredSkillRecord>> {
         self.with_conn(|conn| {
-            conn.execute("DELETE FROM discovered_skills", [])?;
-            Ok(())
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {}
+                 FROM discovered_skills
+                 WHERE first_seen_at >= ?1 AND delisted_at IS NULL
+                 ORDER BY first_seen_at DESC, name ASC",
+                DISCOVERED_SKILL_COLUMNS
+            ))?;
+            let rows = stmt.query_map(params![since], discovered_skill_from_row)?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
         })
     }
 
-    // AI Agents management
-    pub fn add_ai_agent(&self, name: &str, api_key: &str, base_url: &str) -> Result<String> {
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as i64;
-        self.with_conn(|conn| {
-            conn.execute(
-                "INSERT INTO ai_agents (id, name, api_key, base_url, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                params![id, name, api_key, base_url, now, now],
+    /// Merge the current listing of `source` into the rows by id, in one transaction.
+    /// Listed rows are inserted or updated (keeping when they were first seen, and
+    /// relisting them if they had been delisted) and recorded as listed by `source`; the
+    /// details of a row another source also lists stay that source's. Rows `source` no
+    /// longer lists are dropped from it, and marked delisted at `now` rather than deleted
+    /// once no source lists them.
+    pub fn merge_discovered_skills_for_source(
+        &self,
+        source: &str,
+        records: &[DiscoveredSkillRecord],
+        now: i64,
+    ) -> Result<DiscoveredSkillsMerge> {
+        self.with_tx(|tx| {
+            let mut existing = std::collections::HashSet::new();
+            {
+                let mut stmt =
+                    tx.prepare("SELECT skill_id FROM discovered_skill_sources WHERE source = ?1")?;
+                let rows = stmt.query_map(params![source], |row| row.get::<_, String>(0))?;
+                for row in rows {
+                    existing.insert(row?);
+                }
+            }
+
+            let mut merge = DiscoveredSkillsMerge::default();
+            let mut listed = std::collections::HashSet::new();
+            for record in records {
+                if !listed.insert(record.id.as_str()) {
+                    continue;
+                }
+                let known: bool = tx.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM discovered_skills WHERE id = ?1)",
+                    params![record.id],
+                    |row| row.get(0),
+                )?;
+                if !known {
+                    merge.added += 1;
+                }
+                let updated = tx.execute(
+                    "INSERT INTO discovered_skills (
+                        id, name, description, github_url, category, source, tags, created_at, updated_at,
+                        first_seen_at, last_seen_at, delisted_at, raw_category
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10, NULL, ?11)
+                    ON CONFLICT(id) DO UPDATE SET
+                        name = excluded.name,
+                        description = excluded.description,
+                        github_url = excluded.github_url,
+                        category = excluded.category,
+                        source = excluded.source,
+                        tags = excluded.tags,
+                        updated_at = excluded.updated_at,
+                        last_seen_at = excluded.last_seen_at,
+                        delisted_at = NULL,
+                        raw_category = excluded.raw_category
+                    WHERE discovered_skills.source = excluded.source
+                       OR NOT EXISTS (
+                         SELECT 1 FROM discovered_skill_sources m
+                         WHERE m.skill_id = discovered_skills.id AND m.source = discovered_skills.source
+                       )",
+                    params![
+                        record.id,
+                        record.name,
+                        record.description,
+                        record.github_url,
+                        record.category,
+                        source,
+                        record.tags,
+                        record.created_at,
+                        record.updated_at,
+                        now,
+                        record.raw_category
+                    ],
+                )?;
+                if updated == 0 {
+                    tx.execute(
+                        "UPDATE discovered_skills SET last_seen_at = ?2 WHERE id = ?1",
+                        params![record.id, now],
+                    )?;
+                }
+                tx.execute(
+                    "INSERT OR IGNORE INTO discovered_skill_sources (skill_id, source) VALUES (?1, ?2)",
+                    params![record.id, source],
+                )?;
+            }
+            merge.listed = listed.len();
+
+            for id in existing.iter().filter(|id| !listed.contains(id.as_str())) {
+                tx.execute(
+                    "DELETE FROM discovered_skill_sources WHERE skill_id = ?1 AND source = ?2",
+                    params![id, source],
+                )?;
+                merge.delisted += tx.execute(
+                    "UPDATE discovered_skills SET delisted_at = ?2
+                     WHERE id = ?1 AND delisted_at IS NULL
+                       AND NOT EXISTS (SELECT 1 FROM discovered_skill_sources m WHERE m.skill_id = ?1)",
+                    params![id, now],
+                )?;
+            }
+            hand_over_discovered_skills(tx, source)?;
+            Ok(merge)
+        })
+    }
+
+    /// Mark every row `source` lists as seen at `now`, for a source that reported no
+    /// changes. Returns how many rows it lists.
+    pub fn touch_discovered_skills_for_source(&self, source: &str, now: i64) -> Result<usize> {
+        self.with_conn(|conn| {
+            Ok(conn.execute(
+                "UPDATE discovered_skills SET last_seen_at = ?2
+                 WHERE id IN (SELECT skill_id FROM discovered_skill_sources WHERE source = ?1)",
+                params![source, now],
+            )?)
+        })
+    }
+
+    /// How many listed discovered skills each source lists.
+    pub fn count_discovered_skills_by_source(
+        &self,
+    ) -> Result<std::collections::HashMap<String, usize>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT source, COUNT(*) FROM discovered_skill_sources GROUP BY source")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })?;
+
+            let mut counts = std::collections::HashMap::new();
+            for row in rows {
+                let (source, count) = row?;
+                counts.insert(source, count);
+            }
+            Ok(counts)
+        })
+    }
+
+    /// `(id, raw_category, category)` of every discovered skill, delisted ones included.
+    pub fn list_discovered_skill_categories(&self) -> Result<Vec<(String, Option<String>, String)>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare("SELECT id, raw_category, category FROM discovered_skills")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    /// Set the category of each `(id, category)` pair. Returns how many rows changed.
+    pub fn set_discovered_skill_categories(&self, categories: &[(String, String)]) -> Result<usize> {
+        self.with_tx(|tx| {
+            let mut changed = 0;
+            for (id, category) in categories {
+                changed += tx.execute(
+                    "UPDATE discovered_skills SET category = ?2 WHERE id = ?1 AND category <> ?2",
+                    params![id, category],
+                )?;
+            }
+            Ok(changed)
+        })
+    }
+
+    /// Drop `source` from the skills it lists, deleting the ones no other source lists
+    /// (and its delisted rows).
+    pub fn delete_discovered_skills_for_source(&self, source: &str) -> Result<()> {
+        self.with_tx(|tx| {
+            forget_discovered_skills_source(tx, source)?;
+            Ok(())
+        })
+    }
+
+    // Discovered skill metadata
+    /// Listed discovered skills without metadata, or whose metadata is older than
+    /// `stale_before` or was fetched for a different URL; never enriched ones first.
+    /// Incomplete metadata (a failed lookup, or a repository whose install check could
+    /// not run) is already stale once older than `retry_before`.
+    pub fn list_discovered_skills_needing_metadata(
+        &self,
+        stale_before: i64,
+        retry_before: i64,
+        limit: usize,
+    ) -> Result<Vec<DiscoveredSkillRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {}
+                 FROM discovered_skills
+                 WHERE delisted_at IS NULL
+                   AND NOT EXISTS (
+                     SELECT 1 FROM discovered_skill_metadata m
+                     WHERE m.skill_id = discovered_skills.id
+                       AND m.fetched_at >= CASE
+                         WHEN m.status = 'error' OR (m.status = 'ok' AND m.install_status IS NULL)
+                         THEN ?2 ELSE ?1
+                       END
+                       AND m.github_url = discovered_skills.github_url
+                   )
+                 ORDER BY COALESCE(
+                   (SELECT m.fetched_at FROM discovered_skill_metadata m
+                    WHERE m.skill_id = discovered_skills.id),
+                   0
+                 ) ASC, name ASC
+                 LIMIT ?3",
+                DISCOVERED_SKILL_COLUMNS
+            ))?;
+            let rows = stmt.query_map(
+                params![stale_before, retry_before, limit as i64],
+                discovered_skill_from_row,
+            )?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    pub fn upsert_discovered_skill_metadata(&self, record: &DiscoveredSkillMetadata) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO discovered_skill_metadata (
+                    skill_id, github_url, repo, status, stars, last_commit_at, license, archived,
+                    skill_name, skill_description, install_status, skill_count, head_revision,
+                    error, etag, fetched_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+                ON CONFLICT(skill_id) DO UPDATE SET
+                    github_url = excluded.github_url,
+                    repo = excluded.repo,
+                    status = excluded.status,
+                    stars = excluded.stars,
+                    last_commit_at = excluded.last_commit_at,
+                    license = excluded.license,
+                    archived = excluded.archived,
+                    skill_name = excluded.skill_name,
+                    skill_description = excluded.skill_description,
+                    install_status = excluded.install_status,
+                    skill_count = excluded.skill_count,
+                    head_revision = excluded.head_revision,
+                    error = excluded.error,
+                    etag = excluded.etag,
+                    fetched_at = excluded.fetched_at",
+                params![
+                    record.skill_id,
+                    record.github_url,
+                    record.repo,
+                    record.status,
+                    record.stars,
+                    record.last_commit_at,
+                    record.license,
+                    record.archived as i64,
+                    record.skill_name,
+                    record.skill_description,
+                    record.install_status,
+                    record.skill_count,
+                    record.head_revision,
+                    record.error,
+                    record.etag,
+                    record.fetched_at
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn list_discovered_skill_metadata(&self) -> Result<Vec<DiscoveredSkillMetadata>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT skill_id, github_url, repo, status, stars, last_commit_at, license, archived,
+                        skill_name, skill_description, install_status, skill_count, head_revision,
+                        error, etag, fetched_at
+                 FROM discovered_skill_metadata",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(DiscoveredSkillMetadata {
+                    skill_id: row.get(0)?,
+                    github_url: row.get(1)?,
+                    repo: row.get(2)?,
+                    status: row.get(3)?,
+                    stars: row.get(4)?,
+                    last_commit_at: row.get(5)?,
+                    license: row.get(6)?,
+                    archived: row.get::<_, i64>(7)? != 0,
+                    skill_name: row.get(8)?,
+                    skill_description: row.get(9)?,
+                    install_status: row.get(10)?,
+                    skill_count: row.get(11)?,
+                    head_revision: row.get(12)?,
+                    error: row.get(13)?,
+                    etag: row.get(14)?,
+                    fetched_at: row.get(15)?,
+                })
+            })?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    // Full-text search index
+    /// `stamp` of every indexed installed skill, by skill id.
+    pub fn installed_search_stamps(&self) -> Result<std::collections::HashMap<String, String>> {
+        self.with_conn(|conn| {
+            let mut stmt =
+                conn.prepare("SELECT ref_id, stamp FROM skill_search WHERE kind = 'installed'")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            let mut items = std::collections::HashMap::new();
+            for row in rows {
+                let (id, stamp) = row?;
+                items.insert(id, stamp);
+            }
+            Ok(items)
+        })
+    }
+
+    pub fn upsert_installed_search_doc(&self, doc: &InstalledSearchDoc) -> Result<()> {
+        self.with_tx(|tx| {
+            tx.execute(
+                "DELETE FROM skill_search WHERE kind = 'installed' AND ref_id = ?1",
+                params![doc.skill_id],
+            )?;
+            tx.execute(
+                "INSERT INTO skill_search (
+                    name, description, tags, body, files, kind, ref_id, category, source, stamp
+                ) VALUES (?1, ?2, '', ?3, ?4, 'installed', ?5, ?6, ?7, ?8)",
+                params![
+                    doc.name,
+                    doc.description,
+                    doc.body,
+                    doc.files,
+                    doc.skill_id,
+                    doc.category,
+                    doc.source_type,
+                    doc.stamp
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn delete_installed_search_doc(&self, skill_id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM skill_search WHERE kind = 'installed' AND ref_id = ?1",
+                params![skill_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn search_skill_index(&self, search: &SkillSearchParams) -> Result<Vec<SkillSearchHit>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT skill_search.kind, skill_search.ref_id, skill_search.name,
+                        skill_search.description, skill_search.category, skill_search.source,
+                        d.github_url,
+                        snippet(skill_search, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+                        bm25(skill_search, {}) AS score
+                 FROM skill_search
+                 LEFT JOIN discovered_skills d
+                   ON skill_search.kind = 'discovered' AND d.id = skill_search.ref_id
+                 WHERE skill_search MATCH ?1
+                   AND (?2 IS NULL OR skill_search.category = ?2)
+                   AND (?3 IS NULL OR skill_search.source = ?3 OR EXISTS (
+                     SELECT 1 FROM discovered_skill_sources m
+                     WHERE skill_search.kind = 'discovered' AND m.skill_id = skill_search.ref_id
+                       AND m.source = ?3
+                   ))
+                   AND (?4 IS NULL OR skill_search.kind = ?4)
+                 ORDER BY score ASC, skill_search.name ASC
+                 LIMIT ?5",
+                SKILL_SEARCH_WEIGHTS
+            ))?;
+            let rows = stmt.query_map(
+                params![
+                    search.match_expr,
+                    search.category,
+                    search.source,
+                    search.kind,
+                    search.limit.map_or(-1, |limit| limit as i64)
+                ],
+                |row| {
+                    Ok(SkillSearchHit {
+                        kind: row.get(0)?,
+                        ref_id: row.get(1)?,
+                        name: row.get(2)?,
+                        description: row.get(3)?,
+                        category: row.get(4)?,
+                        source: row.get(5)?,
+                        github_url: row.get(6)?,
+                        installed_skill_id: None,
+                        snippet: row.get(7)?,
+                        score: row.get(8)?,
+                    })
+                },
+            )?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    // Registry sources management
+    pub fn upsert_registry_source(&self, record: &RegistrySourceRecord) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO registry_sources (
+                    id, kind, name, location, enabled, last_synced_at, last_error, created_at, updated_at,
+                    etag, last_modified
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                ON CONFLICT(id) DO UPDATE SET
+                    kind = excluded.kind,
+                    name = excluded.name,
+                    location = excluded.location,
+                    enabled = excluded.enabled,
+                    last_synced_at = excluded.last_synced_at,
+                    last_error = excluded.last_error,
+                    updated_at = excluded.updated_at,
+                    etag = excluded.etag,
+                    last_modified = excluded.last_modified",
+                params![
+                    record.id,
+                    record.kind,
+                    record.name,
+                    record.location,
+                    record.enabled,
+                    record.last_synced_at,
+                    record.last_error,
+                    record.created_at,
+                    record.updated_at,
+                    record.etag,
+                    record.last_modified
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_registry_source(&self, id: &str) -> Result<Option<RegistrySourceRecord>> {
+        Ok(self
+            .list_registry_sources()?
+            .into_iter()
+            .find(|source| source.id == id))
+    }
+
+    pub fn list_registry_sources(&self) -> Result<Vec<RegistrySourceRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, kind, name, location, enabled, last_synced_at, last_error, created_at, updated_at,
+                        etag, last_modified
+                 FROM registry_sources
+                 ORDER BY created_at ASC, id ASC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(RegistrySourceRecord {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    name: row.get(2)?,
+                    location: row.get(3)?,
+                    enabled: row.get(4)?,
+                    last_synced_at: row.get(5)?,
+                    last_error: row.get(6)?,
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                    etag: row.get(9)?,
+                    last_modified: row.get(10)?,
+                })
+            })?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    /// Remove a registry source together with the skills only it lists.
+    pub fn delete_registry_source(&self, id: &str) -> Result<()> {
+        self.with_tx(|tx| {
+            forget_discovered_skills_source(tx, id)?;
+            tx.execute("DELETE FROM registry_sources WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+    }
+
+    // Skill publications management
+    pub fn upsert_skill_publication(&self, record: &SkillPublicationRecord) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO skill_publications (
+                    skill_id, remote_url, branch, subpath, work_dir, last_commit, pushed_commit,
+                    last_pushed_at, created_at, updated_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                ON CONFLICT(skill_id) DO UPDATE SET
+                    remote_url = excluded.remote_url,
+                    branch = excluded.branch,
+                    subpath = excluded.subpath,
+                    work_dir = excluded.work_dir,
+                    last_commit = excluded.last_commit,
+                    pushed_commit = excluded.pushed_commit,
+                    last_pushed_at = excluded.last_pushed_at,
+                    updated_at = excluded.updated_at",
+                params![
+                    record.skill_id,
+                    record.remote_url,
+                    record.branch,
+                    record.subpath,
+                    record.work_dir,
+                    record.last_commit,
+                    record.pushed_commit,
+                    record.last_pushed_at,
+                    record.created_at,
+                    record.updated_at
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_skill_publication(&self, skill_id: &str) -> Result<Option<SkillPublicationRecord>> {
+        Ok(self
+            .list_skill_publications()?
+            .into_iter()
+            .find(|publication| publication.skill_id == skill_id))
+    }
+
+    pub fn list_skill_publications(&self) -> Result<Vec<SkillPublicationRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT skill_id, remote_url, branch, subpath, work_dir, last_commit, pushed_commit,
+                        last_pushed_at, created_at, updated_at
+                 FROM skill_publications
+                 ORDER BY created_at ASC, skill_id ASC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(SkillPublicationRecord {
+                    skill_id: row.get(0)?,
+                    remote_url: row.get(1)?,
+                    branch: row.get(2)?,
+                    subpath: row.get(3)?,
+                    work_dir: row.get(4)?,
+                    last_commit: row.get(5)?,
+                    pushed_commit: row.get(6)?,
+                    last_pushed_at: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                })
+            })?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    // Git credentials management
+    pub fn upsert_git_credential(&self, record: &GitCredentialRecord) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO git_credentials (
+                    host, kind, username, secret, secret_ref, ssh_key_path, created_at, updated_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                ON CONFLICT(host) DO UPDATE SET
+                    kind = excluded.kind,
+                    username = excluded.username,
+                    secret = excluded.secret,
+                    secret_ref = excluded.secret_ref,
+                    ssh_key_path = excluded.ssh_key_path,
+                    updated_at = excluded.updated_at",
+                params![
+                    record.host,
+                    record.kind,
+                    record.username,
+                    record.secret,
+                    record.secret_ref,
+                    record.ssh_key_path,
+                    record.created_at,
+                    record.updated_at
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_git_credential(&self, host: &str) -> Result<Option<GitCredentialRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT host, kind, username, secret, secret_ref, ssh_key_path, created_at,
+                        updated_at
+                 FROM git_credentials
+                 WHERE host = ?1",
+            )?;
+            let mut rows = stmt.query(params![host])?;
+            if let Some(row) = rows.next()? {
+                Ok(Some(GitCredentialRecord {
+                    host: row.get(0)?,
+                    kind: row.get(1)?,
+                    username: row.get(2)?,
+                    secret: row.get(3)?,
+                    secret_ref: row.get(4)?,
+                    ssh_key_path: row.get(5)?,
+                    created_at: row.get(6)?,
+                    updated_at: row.get(7)?,
+                }))
+            } else {
+                Ok(None)
+            }
+        })
+    }
+
+    pub fn list_git_credentials(&self) -> Result<Vec<GitCredentialRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT host, kind, username, secret, secret_ref, ssh_key_path, created_at,
+                        updated_at
+                 FROM git_credentials
+                 ORDER BY host ASC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(GitCredentialRecord {
+                    host: row.get(0)?,
+                    kind: row.get(1)?,
+                    username: row.get(2)?,
+                    secret: row.get(3)?,
+                    secret_ref: row.get(4)?,
+                    ssh_key_path: row.get(5)?,
+                    created_at: row.get(6)?,
+                    updated_at: row.get(7)?,
+                })
+            })?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    pub fn delete_git_credential(&self, host: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute("DELETE FROM git_credentials WHERE host = ?1", params![host])?;
+            Ok(())
+        })
+    }
+
+    // Git cache index
+    pub fn upsert_git_cache_entry(&self, record: &GitCacheEntryRecord) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO git_cache_entries (
+                    key, kind, clone_url, ref_name, mirror_key, path, head, size_bytes,
+                    last_fetched_at, last_used_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                ON CONFLICT(key) DO UPDATE SET
+                    kind = excluded.kind,
+                    clone_url = excluded.clone_url,
+                    ref_name = excluded.ref_name,
+                    mirror_key = excluded.mirror_key,
+                    path = excluded.path,
+                    head = excluded.head,
+                    size_bytes = excluded.size_bytes,
+                    last_fetched_at = excluded.last_fetched_at,
+                    last_used_at = excluded.last_used_at",
+                params![
+                    record.key,
+                    record.kind,
+                    record.clone_url,
+                    record.ref_name,
+                    record.mirror_key,
+                    record.path,
+                    record.head,
+                    record.size_bytes,
+                    record.last_fetched_at,
+                    record.last_used_at
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get_git_cache_entry(&self, key: &str) -> Result<Option<GitCacheEntryRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT key, kind, clone_url, ref_name, mirror_key, path, head, size_bytes,
+                        last_fetched_at, last_used_at
+                 FROM git_cache_entries
+                 WHERE key = ?1",
+            )?;
+            let mut rows = stmt.query(params![key])?;
+            if let Some(row) = rows.next()? {
+                Ok(Some(GitCacheEntryRecord {
+                    key: row.get(0)?,
+                    kind: row.get(1)?,
+                    clone_url: row.get(2)?,
+                    ref_name: row.get(3)?,
+                    mirror_key: row.get(4)?,
+                    path: row.get(5)?,
+                    head: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    last_fetched_at: row.get(8)?,
+                    last_used_at: row.get(9)?,
+                }))
+            } else {
+                Ok(None)
+            }
+        })
+    }
+
+    /// All cache entries, least recently used first.
+    pub fn list_git_cache_entries(&self) -> Result<Vec<GitCacheEntryRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT key, kind, clone_url, ref_name, mirror_key, path, head, size_bytes,
+                        last_fetched_at, last_used_at
+                 FROM git_cache_entries
+                 ORDER BY last_used_at ASC, key ASC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(GitCacheEntryRecord {
+                    key: row.get(0)?,
+                    kind: row.get(1)?,
+                    clone_url: row.get(2)?,
+                    ref_name: row.get(3)?,
+                    mirror_key: row.get(4)?,
+                    path: row.get(5)?,
+                    head: row.get(6)?,
+                    size_bytes: row.get(7)?,
+                    last_fetched_at: row.get(8)?,
+                    last_used_at: row.get(9)?,
+                })
+            })?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    pub fn delete_git_cache_entry(&self, key: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute("DELETE FROM git_cache_entries WHERE key = ?1", params![key])?;
+            Ok(())
+        })
+    }
+
+    /// Remember which cache entry a managed skill was last installed or updated from.
+    pub fn set_git_cache_skill_ref(&self, skill_id: &str, cache_key: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO git_cache_skill_refs (skill_id, cache_key) VALUES (?1, ?2)
+                 ON CONFLICT(skill_id) DO UPDATE SET cache_key = excluded.cache_key",
+                params![skill_id, cache_key],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// `(cache_key, skill_id)` pairs; rows of deleted skills cascade away.
+    pub fn list_git_cache_skill_refs(&self) -> Result<Vec<(String, String)>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT cache_key, skill_id
+                 FROM git_cache_skill_refs
+                 ORDER BY cache_key ASC, skill_id ASC",
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    // AI Agents management
+    pub fn add_ai_agent(&self, name: &str, api_key: &str, base_url: &str) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO ai_agents (id, name, api_key, base_url, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![id, name, api_key, base_url, now, now],
             )?;
             Ok(id)
         })
@@ -909,15 +2142,143 @@ impl SkillStore {
         })
     }
 
+    /// Run `f` against a store whose calls all share one transaction, committed only
+    /// when `f` returns `Ok`.
+    pub fn transaction<T>(&self, f: impl FnOnce(&SkillStore) -> Result<T>) -> Result<T> {
+        if self.tx_conn.is_some() {
+            return f(self);
+        }
+        let conn = self.open_conn()?;
+        conn.execute_batch("BEGIN IMMEDIATE;")?;
+        let store = SkillStore {
+            db_path: self.db_path.clone(),
+            tx_conn: Some(Arc::new(Mutex::new(conn))),
+        };
+        match f(&store) {
+            Ok(value) => {
+                store.with_conn(|conn| Ok(conn.execute_batch("COMMIT;")?))?;
+                Ok(value)
+            }
+            Err(err) => {
+                let _ = store.with_conn(|conn| Ok(conn.execute_batch("ROLLBACK;")?));
+                Err(err)
+            }
+        }
+    }
+
+    /// [`Self::with_conn`] in a transaction of its own, or in the one
+    /// [`Self::transaction`] opened.
+    fn with_tx<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        self.with_conn(|conn| {
+            if !conn.is_autocommit() {
+                return f(conn);
+            }
+            let tx = conn.unchecked_transaction()?;
+            let value = f(&tx)?;
+            tx.commit()?;
+            Ok(value)
+        })
+    }
+
     fn with_conn<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        if let Some(conn) = &self.tx_conn {
+            let conn = conn.lock().map_err(|e| anyhow::anyhow!("{}", e))?;
+            return f(&conn);
+        }
+        f(&self.open_conn()?)
+    }
+
+    fn open_conn(&self) -> Result<Connection> {
         let conn = Connection::open(&self.db_path)
             .with_context(|| format!("failed to open db at {:?}", self.db_path))?;
         // Enforce foreign key constraints on every connection (rusqlite PRAGMA is per-connection).
         conn.execute_batch("PRAGMA foreign_keys = ON;")?;
-        f(&conn)
+        Ok(conn)
     }
 }
 
+/// BM25 weights of the `skill_search` columns: name, description, tags, body, files.
+const SKILL_SEARCH_WEIGHTS: &str = "10.0, 4.0, 3.0, 1.0, 2.0";
+
+/// Remove `source` from the sources listing each discovered skill, then delete its rows
+/// no source lists any more and hand the rest over to a source that still lists them.
+fn forget_discovered_skills_source(conn: &Connection, source: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM discovered_skill_sources WHERE source = ?1",
+        params![source],
+    )?;
+    conn.execute(
+        "DELETE FROM discovered_skills
+         WHERE source = ?1
+           AND NOT EXISTS (SELECT 1 FROM discovered_skill_sources m WHERE m.skill_id = discovered_skills.id)",
+        params![source],
+    )?;
+    hand_over_discovered_skills(conn, source)
+}
+
+/// Rows whose details came from `source` but that it no longer lists take their details'
+/// source from another source that lists them; rows no source lists keep `source`.
+fn hand_over_discovered_skills(conn: &Connection, source: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE discovered_skills
+         SET source = (
+           SELECT MIN(m.source) FROM discovered_skill_sources m WHERE m.skill_id = discovered_skills.id
+         )
+         WHERE source = ?1
+           AND EXISTS (SELECT 1 FROM discovered_skill_sources m WHERE m.skill_id = discovered_skills.id)
+           AND NOT EXISTS (
+             SELECT 1 FROM discovered_skill_sources m
+             WHERE m.skill_id = discovered_skills.id AND m.source = ?1
+           )",
+        params![source],
+    )?;
+    Ok(())
+}
+
+fn upsert_category_rule_row(conn: &Connection, record: &CategoryRuleRecord) -> Result<()> {
+    conn.execute(
+        "INSERT INTO category_rules (id, kind, pattern, category_id, position, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET
+            kind = excluded.kind,
+            pattern = excluded.pattern,
+            category_id = excluded.category_id,
+            position = excluded.position,
+            updated_at = excluded.updated_at",
+        params![
+            record.id,
+            record.kind,
+            record.pattern,
+            record.category_id,
+            record.position,
+            record.created_at,
+            record.updated_at
+        ],
+    )?;
+    Ok(())
+}
+
+const DISCOVERED_SKILL_COLUMNS: &str =
+    "id, name, description, github_url, category, source, tags, created_at, updated_at,
+    COALESCE(first_seen_at, created_at), COALESCE(last_seen_at, updated_at), raw_category";
+
+fn discovered_skill_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DiscoveredSkillRecord> {
+    Ok(DiscoveredSkillRecord {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        github_url: row.get(3)?,
+        category: row.get(4)?,
+        source: row.get(5)?,
+        tags: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+        first_seen_at: row.get(9)?,
+        last_seen_at: row.get(10)?,
+        raw_category: row.get(11)?,
+    })
+}
+
 pub fn default_db_path<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<PathBuf> {
     let app_dir = app
         .path()
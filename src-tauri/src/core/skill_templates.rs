@@ -0,0 +1,471 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::central_repo::{ensure_central_repo, resolve_central_repo_path};
+use super::installer::{compute_content_hash, InstallResult};
+use super::skill_store::{SkillRecord, SkillStore};
+use super::util::{is_executable, now_ms};
+
+const USER_TEMPLATES_DIR_NAME: &str = "skill-templates";
+const TEMPLATE_META_FILE: &str = "template.json";
+const IGNORE_NAMES: [&str; 4] = [".git", ".DS_Store", "Thumbs.db", TEMPLATE_META_FILE];
+
+pub const SOURCE_TYPE_AUTHORED: &str = "authored";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SkillTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub builtin: bool,
+    pub files: Vec<String>,
+    #[serde(skip)]
+    contents: Vec<TemplateFile>,
+}
+
+#[derive(Clone, Debug)]
+struct TemplateFile {
+    path: String,
+    content: Vec<u8>,
+    executable: bool,
+}
+
+/// Optional `template.json` placed at the root of a user template directory.
+#[derive(Debug, Default, Deserialize)]
+struct UserTemplateMeta {
+    name: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct NewSkillSpec {
+    pub template_id: String,
+    pub name: String,
+    pub description: String,
+    pub category: Option<String>,
+}
+
+const BASIC_SKILL_MD: &str = r#"# {{name}}
+
+{{description}}
+
+## When to use
+
+- Describe the situations where this skill should be applied.
+
+## Instructions
+
+1. Step one.
+2. Step two.
+"#;
+
+const SCRIPT_SKILL_MD: &str = r#"# {{name}}
+
+{{description}}
+
+## When to use
+
+- Describe the situations where this skill should be applied.
+
+## Instructions
+
+Run `scripts/run.sh` from the skill directory and use its output:
+
+```bash
+bash scripts/run.sh <args>
+```
+"#;
+
+const SCRIPT_RUN_SH: &str = r#"#!/usr/bin/env bash
+# Entry point for the {{name}} skill.
+set -euo pipefail
+
+echo "{{name}}: $*"
+"#;
+
+const REFERENCE_SKILL_MD: &str = r#"# {{name}}
+
+{{description}}
+
+## When to use
+
+- Describe the situations where this skill should be applied.
+
+## References
+
+Read the relevant file under `references/` before answering:
+
+- `references/overview.md` — background and key concepts.
+"#;
+
+const REFERENCE_OVERVIEW_MD: &str = r#"# {{name}} overview
+
+Add the reference material the agent should consult here.
+"#;
+
+const MULTI_SKILL_MD: &str = r#"# {{name}}
+
+{{description}}
+
+## When to use
+
+- Describe the situations where this skill should be applied.
+
+## Layout
+
+- `scripts/` — helper scripts the agent can execute.
+- `references/` — documentation loaded on demand.
+- `assets/` — templates and other files used in the output.
+
+## Instructions
+
+1. Read `references/overview.md` for context.
+2. Run `scripts/run.sh` when the task needs automation.
+3. Start from `assets/template.md` when producing documents.
+"#;
+
+const MULTI_TEMPLATE_MD: &str = r#"# Title
+
+Fill in the document produced by {{name}}.
+"#;
+
+fn builtin_templates() -> Vec<SkillTemplate> {
+    let file = |path: &str, content: &str| TemplateFile {
+        path: path.to_string(),
+        content: content.as_bytes().to_vec(),
+        executable: false,
+    };
+    let script = |path: &str, content: &str| TemplateFile {
+        path: path.to_string(),
+        content: content.as_bytes().to_vec(),
+        executable: true,
+    };
+
+    vec![
+        make_template(
+            "basic",
+            "Basic",
+            "A single SKILL.md with usage notes and instructions.",
+            true,
+            vec![file("SKILL.md", BASIC_SKILL_MD)],
+        ),
+        make_template(
+            "script",
+            "Script-backed",
+            "SKILL.md plus an executable helper script under scripts/.",
+            true,
+            vec![
+                file("SKILL.md", SCRIPT_SKILL_MD),
+                script("scripts/run.sh", SCRIPT_RUN_SH),
+            ],
+        ),
+        make_template(
+            "reference",
+            "Reference docs",
+            "SKILL.md that points the agent at documents under references/.",
+            true,
+            vec![
+                file("SKILL.md", REFERENCE_SKILL_MD),
+                file("references/overview.md", REFERENCE_OVERVIEW_MD),
+            ],
+        ),
+        make_template(
+            "multi-file",
+            "Multi-file",
+            "SKILL.md with scripts/, references/ and assets/ folders.",
+            true,
+            vec![
+                file("SKILL.md", MULTI_SKILL_MD),
+                script("scripts/run.sh", SCRIPT_RUN_SH),
+                file("references/overview.md", REFERENCE_OVERVIEW_MD),
+                file("assets/template.md", MULTI_TEMPLATE_MD),
+            ],
+        ),
+    ]
+}
+
+fn make_template(
+    id: &str,
+    name: &str,
+    description: &str,
+    builtin: bool,
+    contents: Vec<TemplateFile>,
+) -> SkillTemplate {
+    SkillTemplate {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        builtin,
+        files: contents.iter().map(|f| f.path.clone()).collect(),
+        contents,
+    }
+}
+
+pub fn resolve_user_templates_dir<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<PathBuf> {
+    let base = app
+        .path()
+        .app_data_dir()
+        .context("failed to resolve app data dir")?;
+    Ok(base.join(USER_TEMPLATES_DIR_NAME))
+}
+
+pub fn list_skill_templates<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> Result<Vec<SkillTemplate>> {
+    let user_dir = resolve_user_templates_dir(app)?;
+    list_skill_templates_in(&user_dir)
+}
+
+/// Built-in templates first, then one template per sub-directory of `user_dir`.
+/// A user template with the same id as a built-in one replaces it.
+pub fn list_skill_templates_in(user_dir: &Path) -> Result<Vec<SkillTemplate>> {
+    let mut templates = builtin_templates();
+    if !user_dir.exists() {
+        return Ok(templates);
+    }
+
+    let mut entries: Vec<_> = std::fs::read_dir(user_dir)
+        .with_context(|| format!("read templates dir {:?}", user_dir))?
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let id = entry.file_name().to_string_lossy().to_string();
+        if id.starts_with('.') {
+            continue;
+        }
+        match load_user_template(&id, &entry.path()) {
+            Ok(template) => {
+                templates.retain(|t| t.id != template.id);
+                templates.push(template);
+            }
+            Err(err) => {
+                log::warn!(
+                    "[skill_templates] skip template {:?}: {:#}",
+                    entry.path(),
+                    err
+                );
+            }
+        }
+    }
+
+    Ok(templates)
+}
+
+fn load_user_template(id: &str, dir: &Path) -> Result<SkillTemplate> {
+    let meta: UserTemplateMeta = match std::fs::read_to_string(dir.join(TEMPLATE_META_FILE)) {
+        Ok(raw) => serde_json::from_str(&raw)
+            .with_context(|| format!("parse {:?}", dir.join(TEMPLATE_META_FILE)))?,
+        Err(_) => UserTemplateMeta::default(),
+    };
+
+    let mut contents = Vec::new();
+    for entry in WalkDir::new(dir)
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !IGNORE_NAMES.iter().any(|n| *n == name.as_ref())
+        })
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry
+            .path()
+            .strip_prefix(dir)
+            .with_context(|| format!("strip prefix {:?}", entry.path()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+        let content =
+            std::fs::read(entry.path()).with_context(|| format!("read {:?}", entry.path()))?;
+        contents.push(TemplateFile {
+            path: rel,
+            content,
+            executable: is_executable(entry.path()),
+        });
+    }
+
+    if !contents.iter().any(|f| f.path == "SKILL.md") {
+        anyhow::bail!("template is missing SKILL.md");
+    }
+
+    Ok(make_template(
+        id,
+        meta.name.as_deref().unwrap_or(id),
+        meta.description.as_deref().unwrap_or(""),
+        false,
+        contents,
+    ))
+}
+
+pub fn create_skill_from_template<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    spec: &NewSkillSpec,
+) -> Result<InstallResult> {
+    let templates = list_skill_templates(app)?;
+    let template = templates
+        .iter()
+        .find(|t| t.id == spec.template_id)
+        .ok_or_else(|| anyhow::anyhow!("unknown skill template: {}", spec.template_id))?;
+
+    let central_dir = resolve_central_repo_path(app, store)?;
+    ensure_central_repo(&central_dir)?;
+    create_skill_in(store, &central_dir, template, spec)
+}
+
+pub fn create_skill_in(
+    store: &SkillStore,
+    central_dir: &Path,
+    template: &SkillTemplate,
+    spec: &NewSkillSpec,
+) -> Result<InstallResult> {
+    let name = validate_skill_name(&spec.name)?;
+    let central_path = central_dir.join(&name);
+    if central_path.exists() {
+        anyhow::bail!("skill already exists in central repo: {:?}", central_path);
+    }
+
+    if let Err(err) = scaffold_skill_dir(template, &central_path, &name, spec) {
+        let _ = std::fs::remove_dir_all(&central_path);
+        return Err(err);
+    }
+
+    let now = now_ms();
+    let content_hash = compute_content_hash(&central_path);
+    let description = single_line(&spec.description);
+    let record = SkillRecord {
+        id: Uuid::new_v4().to_string(),
+        name,
+        description: (!description.is_empty()).then_some(description),
+        category: spec.category.clone().filter(|c| !c.trim().is_empty()),
+        source_type: SOURCE_TYPE_AUTHORED.to_string(),
+        source_ref: None,
+        source_revision: None,
+        central_path: central_path.to_string_lossy().to_string(),
+        content_hash: content_hash.clone(),
+        created_at: now,
+        updated_at: now,
+        last_sync_at: None,
+        last_seen_at: now,
+        status: "ok".to_string(),
+    };
+    store.upsert_skill(&record)?;
+
+    Ok(InstallResult {
+        skill_id: record.id,
+        name: record.name,
+        central_path,
+        content_hash,
+    })
+}
+
+fn scaffold_skill_dir(
+    template: &SkillTemplate,
+    dest: &Path,
+    name: &str,
+    spec: &NewSkillSpec,
+) -> Result<()> {
+    std::fs::create_dir_all(dest).with_context(|| format!("create {:?}", dest))?;
+    let description = single_line(&spec.description);
+    let category = spec.category.as_deref().unwrap_or("").trim();
+
+    for file in &template.contents {
+        let target = dest.join(&file.path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).with_context(|| format!("create {:?}", parent))?;
+        }
+
+        let bytes = match std::str::from_utf8(&file.content) {
+            Ok(text) => {
+                let rendered = render_placeholders(text, name, &description, category);
+                if file.path == "SKILL.md" {
+                    build_skill_md(name, &description, category, &rendered).into_bytes()
+                } else {
+                    rendered.into_bytes()
+                }
+            }
+            Err(_) => file.content.clone(),
+        };
+        std::fs::write(&target, bytes).with_context(|| format!("write {:?}", target))?;
+
+        #[cfg(unix)]
+        if file.executable {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o755))
+                .with_context(|| format!("chmod {:?}", target))?;
+        }
+    }
+    Ok(())
+}
+
+fn render_placeholders(text: &str, name: &str, description: &str, category: &str) -> String {
+    text.replace("{{name}}", name)
+        .replace("{{description}}", description)
+        .replace("{{category}}", category)
+}
+
+/// Generate the frontmatter ourselves so the result always parses with `parse_skill_md`,
+/// dropping any frontmatter the template body may already carry.
+fn build_skill_md(name: &str, description: &str, category: &str, body: &str) -> String {
+    let mut out = String::from("---\n");
+    out.push_str(&format!("name: {}\n", yaml_string(name)));
+    out.push_str(&format!("description: {}\n", yaml_string(description)));
+    if !category.is_empty() {
+        out.push_str(&format!("category: {}\n", yaml_string(category)));
+    }
+    out.push_str("---\n\n");
+    out.push_str(strip_frontmatter(body).trim_start());
+    out
+}
+
+/// Double-quoted YAML scalar, so a `:`, `#` or leading `-` stays part of the value.
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(crate) fn strip_frontmatter(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("---\n") else {
+        return text;
+    };
+    match rest.find("\n---") {
+        Some(pos) => {
+            let after = &rest[pos + 4..];
+            after.strip_prefix('\n').unwrap_or(after)
+        }
+        None => text,
+    }
+}
+
+fn single_line(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn validate_skill_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("skill name is empty");
+    }
+    if name.starts_with('.')
+        || name.contains('/')
+        || name.contains('\\')
+        || name.chars().any(|c| c.is_control())
+    {
+        anyhow::bail!("invalid skill name: {}", name);
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+#[path = "tests/skill_templates.rs"]
+mod tests;
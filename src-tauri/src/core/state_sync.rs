@@ -0,0 +1,583 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::backup::PendingTarget;
+use super::git_credentials::{resolve_git_auth, GitAuth};
+use super::git_fetcher::{git_cli_available, run_git_in, run_git_in_with_auth};
+use super::installer::{compute_content_hash, uninstall_skill};
+use super::skill_bundle::BundleCategory;
+use super::skill_publish::{commit_if_changed, run_git_as_user};
+use super::skill_store::{SkillRecord, SkillStore};
+use super::skill_templates::validate_skill_name;
+use super::tool_adapters::{adapter_by_key, is_tool_installed};
+use super::util::now_ms;
+
+pub const STATE_SYNC_ENABLED_KEY: &str = "state_sync_enabled";
+pub const STATE_SYNC_REMOTE_KEY: &str = "state_sync_remote";
+const STATE_SYNC_CONFLICTS_KEY: &str = "state_sync_conflicts";
+const STATE_SYNC_LAST_REPORT_KEY: &str = "state_sync_last_report";
+pub const STATE_FILE_NAME: &str = "skills-hub-state.json";
+const STATE_VERSION: u32 = 1;
+const BRANCH: &str = "main";
+const REMOTE_BRANCH: &str = "origin/main";
+const GITIGNORE: &str = ".DS_Store\nThumbs.db\n.restore-*/\n.*.import-*/\n";
+
+/// Metadata shared between machines, keyed by the skill's directory in the central repo.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SharedState {
+    pub version: u32,
+    pub skills: Vec<SharedSkill>,
+    pub categories: Vec<BundleCategory>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SharedSkill {
+    pub dir: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub source_type: String,
+    pub source_ref: Option<String>,
+    pub source_revision: Option<String>,
+    /// Tools this skill should be synced to on every machine.
+    pub tools: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateSyncConfig {
+    pub enabled: bool,
+    pub remote_url: Option<String>,
+    pub conflicts: Vec<String>,
+    pub last_report: Option<StateSyncReport>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StateSyncReport {
+    /// `ok`, `conflict` or `disabled`.
+    pub status: String,
+    pub head: Option<String>,
+    pub committed: bool,
+    pub pulled: bool,
+    pub pushed: bool,
+    pub conflicts: Vec<String>,
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    /// Tool targets that other machines intend but this one no longer has.
+    pub stale_targets: Vec<String>,
+    pub synced_targets: Vec<String>,
+    pub target_errors: Vec<String>,
+    pub finished_at: i64,
+    #[serde(skip)]
+    pub pending_targets: Vec<PendingTarget>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    KeepLocal,
+    KeepRemote,
+}
+
+static STATE_SYNC_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn is_state_sync_enabled(store: &SkillStore) -> bool {
+    store
+        .get_setting(STATE_SYNC_ENABLED_KEY)
+        .ok()
+        .flatten()
+        .map(|v| v == "true")
+        .unwrap_or(false)
+}
+
+pub fn get_state_sync_config(store: &SkillStore) -> Result<StateSyncConfig> {
+    Ok(StateSyncConfig {
+        enabled: is_state_sync_enabled(store),
+        remote_url: store.get_setting(STATE_SYNC_REMOTE_KEY)?,
+        conflicts: load_conflicts(store),
+        last_report: store
+            .get_setting(STATE_SYNC_LAST_REPORT_KEY)?
+            .and_then(|raw| serde_json::from_str(&raw).ok()),
+    })
+}
+
+/// Turn the central repo into a git work tree tracking `remote_url` and run a first sync.
+pub fn enable_state_sync(
+    store: &SkillStore,
+    central_dir: &Path,
+    remote_url: &str,
+) -> Result<StateSyncReport> {
+    if !git_cli_available() {
+        anyhow::bail!("state sync requires a system git binary");
+    }
+    let remote_url = remote_url.trim();
+    if remote_url.is_empty() {
+        anyhow::bail!("remote url is empty");
+    }
+    std::fs::create_dir_all(central_dir).with_context(|| format!("create {:?}", central_dir))?;
+
+    if !central_dir.join(".git").exists() {
+        run_git_in(central_dir, &["init"])?;
+        run_git_in(
+            central_dir,
+            &["symbolic-ref", "HEAD", &format!("refs/heads/{}", BRANCH)],
+        )?;
+    }
+    if run_git_in(central_dir, &["remote", "get-url", "origin"]).is_ok() {
+        run_git_in(central_dir, &["remote", "set-url", "origin", remote_url])?;
+    } else {
+        run_git_in(central_dir, &["remote", "add", "origin", remote_url])?;
+    }
+    let gitignore = central_dir.join(".gitignore");
+    if !gitignore.exists() {
+        std::fs::write(&gitignore, GITIGNORE).with_context(|| format!("write {:?}", gitignore))?;
+    }
+
+    store.set_setting(STATE_SYNC_REMOTE_KEY, remote_url)?;
+    store.set_setting(STATE_SYNC_ENABLED_KEY, "true")?;
+    sync_state(store, central_dir)
+}
+
+/// Stop syncing. The central repo keeps its `.git` so sync can be re-enabled later.
+pub fn disable_state_sync(store: &SkillStore) -> Result<()> {
+    store.set_setting(STATE_SYNC_ENABLED_KEY, "false")?;
+    Ok(())
+}
+
+/// Commit local state, replay it on top of the remote, reconcile `SkillStore` with the
+/// merged state file and push. Any conflict stops the sync and is persisted until it is
+/// resolved with [`resolve_state_sync_conflicts`].
+pub fn sync_state(store: &SkillStore, central_dir: &Path) -> Result<StateSyncReport> {
+    let lock = STATE_SYNC_LOCK.get_or_init(|| Mutex::new(()));
+    let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+
+    let report = if !is_state_sync_enabled(store) {
+        StateSyncReport {
+            status: "disabled".to_string(),
+            ..Default::default()
+        }
+    } else {
+        let conflicts = load_conflicts(store);
+        if conflicts.is_empty() {
+            let mut report = StateSyncReport {
+                status: "ok".to_string(),
+                ..Default::default()
+            };
+            sync_state_locked(store, central_dir, &mut report)?;
+            report
+        } else {
+            StateSyncReport {
+                status: "conflict".to_string(),
+                conflicts,
+                ..Default::default()
+            }
+        }
+    };
+    Ok(finish(store, report))
+}
+
+/// Settle a reported conflict: `KeepRemote` discards unpushed local changes,
+/// `KeepLocal` records the local state as the winner and pushes it.
+pub fn resolve_state_sync_conflicts(
+    store: &SkillStore,
+    central_dir: &Path,
+    resolution: ConflictResolution,
+) -> Result<StateSyncReport> {
+    let lock = STATE_SYNC_LOCK.get_or_init(|| Mutex::new(()));
+    let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
+
+    let mut report = StateSyncReport {
+        status: "ok".to_string(),
+        ..Default::default()
+    };
+    run_git_in_with_auth(
+        central_dir,
+        &["fetch", "origin"],
+        remote_auth(store)?.as_ref(),
+    )?;
+    let remote_exists = rev_exists(central_dir, REMOTE_BRANCH);
+    match resolution {
+        ConflictResolution::KeepRemote => {
+            if remote_exists {
+                run_git_in(central_dir, &["reset", "--hard", REMOTE_BRANCH])?;
+                reconcile(store, central_dir, &mut report)?;
+                report.pulled = true;
+            }
+        }
+        ConflictResolution::KeepLocal => {
+            write_state_file(central_dir, &export_state(store, central_dir)?)?;
+            commit_if_changed(central_dir, None, &commit_message())?;
+            if remote_exists && !is_ancestor(central_dir, REMOTE_BRANCH, "HEAD") {
+                run_git_as_user(
+                    central_dir,
+                    &[
+                        "merge",
+                        "-s",
+                        "ours",
+                        "--allow-unrelated-histories",
+                        "-m",
+                        "Keep local skills hub state",
+                        REMOTE_BRANCH,
+                    ],
+                )?;
+            }
+        }
+    }
+    store.set_setting(STATE_SYNC_CONFLICTS_KEY, "[]")?;
+    sync_state_locked(store, central_dir, &mut report)?;
+    Ok(finish(store, report))
+}
+
+fn sync_state_locked(
+    store: &SkillStore,
+    central_dir: &Path,
+    report: &mut StateSyncReport,
+) -> Result<()> {
+    if !central_dir.join(".git").exists() {
+        anyhow::bail!("central repo is not a git repository: {:?}", central_dir);
+    }
+
+    let auth = remote_auth(store)?;
+    run_git_in_with_auth(central_dir, &["fetch", "--prune", "origin"], auth.as_ref())
+        .context("fetch sync remote")?;
+    let remote_exists = rev_exists(central_dir, REMOTE_BRANCH);
+    let has_commits = rev_exists(central_dir, "HEAD");
+
+    if remote_exists && !has_commits {
+        // First sync on this machine: adopt the remote history without touching local files,
+        // then treat local files that differ from the remote as conflicts.
+        run_git_in(central_dir, &["reset", REMOTE_BRANCH])?;
+        let modified: Vec<String> =
+            run_git_in(central_dir, &["diff", "--name-only", "--diff-filter=M"])?
+                .lines()
+                .filter(|path| *path != STATE_FILE_NAME)
+                .map(|path| path.to_string())
+                .collect();
+        if !modified.is_empty() {
+            mark_conflict(store, report, modified);
+            return Ok(());
+        }
+        run_git_in(central_dir, &["checkout", "--", "."])?;
+        reconcile(store, central_dir, report)?;
+        report.pulled = true;
+    }
+
+    write_state_file(central_dir, &export_state(store, central_dir)?)?;
+    report.committed |= commit_if_changed(central_dir, None, &commit_message())?;
+
+    if remote_exists && !is_ancestor(central_dir, REMOTE_BRANCH, "HEAD") {
+        if let Err(err) = run_git_as_user(central_dir, &["rebase", REMOTE_BRANCH]) {
+            let conflicted: Vec<String> =
+                run_git_in(central_dir, &["diff", "--name-only", "--diff-filter=U"])
+                    .unwrap_or_default()
+                    .lines()
+                    .map(|path| path.to_string())
+                    .collect();
+            let _ = run_git_in(central_dir, &["rebase", "--abort"]);
+            log::warn!("[state_sync] rebase onto remote failed: {:#}", err);
+            let conflicted = if conflicted.is_empty() {
+                vec![STATE_FILE_NAME.to_string()]
+            } else {
+                conflicted
+            };
+            mark_conflict(store, report, conflicted);
+            return Ok(());
+        }
+        reconcile(store, central_dir, report)?;
+        report.pulled = true;
+        // Pick up anything the merge changed locally, such as newly reconciled targets.
+        write_state_file(central_dir, &export_state(store, central_dir)?)?;
+        report.committed |= commit_if_changed(central_dir, None, &commit_message())?;
+    }
+
+    let head = run_git_in(central_dir, &["rev-parse", "HEAD"]).ok();
+    let remote_head = run_git_in(central_dir, &["rev-parse", REMOTE_BRANCH]).ok();
+    if head.is_some() && head != remote_head {
+        run_git_in_with_auth(
+            central_dir,
+            &["push", "origin", &format!("HEAD:refs/heads/{}", BRANCH)],
+            auth.as_ref(),
+        )
+        .context("push sync remote")?;
+        report.pushed = true;
+    }
+    report.head = head;
+    Ok(())
+}
+
+/// Whether the central repo or the exported metadata changed since the last sync commit.
+pub fn has_local_changes(store: &SkillStore, central_dir: &Path) -> Result<bool> {
+    if !central_dir.join(".git").exists() {
+        return Ok(false);
+    }
+    let json = state_json(&export_state(store, central_dir)?)?;
+    let current = std::fs::read_to_string(central_dir.join(STATE_FILE_NAME)).unwrap_or_default();
+    if current != json {
+        return Ok(true);
+    }
+    Ok(!run_git_in(central_dir, &["status", "--porcelain"])?.is_empty())
+}
+
+/// Snapshot of the skills that live in `central_dir`, in a stable order.
+pub fn export_state(store: &SkillStore, central_dir: &Path) -> Result<SharedState> {
+    let mut skills = Vec::new();
+    for record in store.list_skills()? {
+        let path = PathBuf::from(&record.central_path);
+        if path.parent() != Some(central_dir) || !path.is_dir() {
+            continue;
+        }
+        let Some(dir) = path.file_name().map(|v| v.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let tools: BTreeSet<String> = store
+            .list_skill_targets(&record.id)?
+            .into_iter()
+            .map(|t| t.tool)
+            .collect();
+        skills.push(SharedSkill {
+            dir,
+            name: record.name,
+            description: record.description,
+            category: record.category,
+            source_type: record.source_type,
+            source_ref: record.source_ref,
+            source_revision: record.source_revision,
+            tools: tools.into_iter().collect(),
+        });
+    }
+    skills.sort_by(|a, b| a.dir.cmp(&b.dir));
+
+    let mut categories: Vec<BundleCategory> = store
+        .list_categories()?
+        .into_iter()
+        .map(|c| BundleCategory {
+            id: c.id,
+            name: c.name,
+            description: c.description,
+            icon: c.icon,
+            color: c.color,
+        })
+        .collect();
+    categories.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(SharedState {
+        version: STATE_VERSION,
+        skills,
+        categories,
+    })
+}
+
+/// Make `SkillStore` match the state file currently checked out in `central_dir`.
+pub(crate) fn reconcile(
+    store: &SkillStore,
+    central_dir: &Path,
+    report: &mut StateSyncReport,
+) -> Result<()> {
+    let state_path = central_dir.join(STATE_FILE_NAME);
+    if !state_path.exists() {
+        return Ok(());
+    }
+    let raw =
+        std::fs::read_to_string(&state_path).with_context(|| format!("read {:?}", state_path))?;
+    let state: SharedState =
+        serde_json::from_str(&raw).with_context(|| format!("parse {:?}", state_path))?;
+    if state.version > STATE_VERSION {
+        anyhow::bail!(
+            "state file version {} is newer than supported ({})",
+            state.version,
+            STATE_VERSION
+        );
+    }
+
+    let categories = store.list_categories()?;
+    for category in &state.categories {
+        if !categories.iter().any(|c| c.id == category.id) {
+            store.add_category(
+                &category.id,
+                &category.name,
+                &category.description,
+                &category.icon,
+                &category.color,
+            )?;
+        }
+    }
+
+    let local = store.list_skills()?;
+    let now = now_ms();
+    for shared in &state.skills {
+        validate_skill_name(&shared.dir)?;
+        let central_path = central_dir.join(&shared.dir);
+        if !central_path.is_dir() {
+            continue;
+        }
+        let existing = local
+            .iter()
+            .find(|r| Path::new(&r.central_path) == central_path);
+        let (record, dirty) = match existing {
+            Some(record) => {
+                let unchanged = record.name == shared.name
+                    && record.description == shared.description
+                    && record.category == shared.category
+                    && record.source_type == shared.source_type
+                    && record.source_ref == shared.source_ref
+                    && record.source_revision == shared.source_revision;
+                if unchanged {
+                    (record.clone(), false)
+                } else {
+                    report.updated.push(shared.name.clone());
+                    let updated = SkillRecord {
+                        name: shared.name.clone(),
+                        description: shared.description.clone(),
+                        category: shared.category.clone(),
+                        source_type: shared.source_type.clone(),
+                        source_ref: shared.source_ref.clone(),
+                        source_revision: shared.source_revision.clone(),
+                        content_hash: compute_content_hash(&central_path),
+                        updated_at: now,
+                        ..record.clone()
+                    };
+                    (updated, true)
+                }
+            }
+            None => {
+                report.added.push(shared.name.clone());
+                let created = SkillRecord {
+                    id: Uuid::new_v4().to_string(),
+                    name: shared.name.clone(),
+                    description: shared.description.clone(),
+                    category: shared.category.clone(),
+                    source_type: shared.source_type.clone(),
+                    source_ref: shared.source_ref.clone(),
+                    source_revision: shared.source_revision.clone(),
+                    central_path: central_path.to_string_lossy().to_string(),
+                    content_hash: compute_content_hash(&central_path),
+                    created_at: now,
+                    updated_at: now,
+                    last_sync_at: None,
+                    last_seen_at: now,
+                    status: "ok".to_string(),
+                };
+                (created, true)
+            }
+        };
+        if dirty {
+            store.upsert_skill(&record)?;
+        }
+
+        let targets = store.list_skill_targets(&record.id)?;
+        for tool in &shared.tools {
+            if targets.iter().any(|t| &t.tool == tool) {
+                continue;
+            }
+            let installed = adapter_by_key(tool)
+                .map(|adapter| is_tool_installed(&adapter).unwrap_or(false))
+                .unwrap_or(false);
+            if installed {
+                report.pending_targets.push(PendingTarget {
+                    skill_id: record.id.clone(),
+                    name: shared.dir.clone(),
+                    central_path: central_path.clone(),
+                    tool: tool.clone(),
+                });
+            }
+        }
+        for target in &targets {
+            if !shared.tools.contains(&target.tool) {
+                report
+                    .stale_targets
+                    .push(format!("{}: {}", shared.name, target.tool));
+            }
+        }
+    }
+
+    // Skills whose directory was deleted upstream.
+    for record in &local {
+        let path = PathBuf::from(&record.central_path);
+        if path.parent() != Some(central_dir) || path.exists() {
+            continue;
+        }
+        let dir = path.file_name().map(|v| v.to_string_lossy().to_string());
+        if state.skills.iter().any(|s| Some(&s.dir) == dir.as_ref()) {
+            continue;
+        }
+        // Same path as a manual uninstall, so tool links to the vanished copy go too.
+        if let Err(err) = uninstall_skill(store, &record.id) {
+            log::warn!("[state_sync] failed to clean up {}: {:#}", record.name, err);
+        }
+        report.removed.push(record.name.clone());
+    }
+
+    Ok(())
+}
+
+fn write_state_file(central_dir: &Path, state: &SharedState) -> Result<()> {
+    let path = central_dir.join(STATE_FILE_NAME);
+    let json = state_json(state)?;
+    let current = std::fs::read_to_string(&path).unwrap_or_default();
+    if current != json {
+        std::fs::write(&path, json).with_context(|| format!("write {:?}", path))?;
+    }
+    Ok(())
+}
+
+fn state_json(state: &SharedState) -> Result<String> {
+    let mut json = serde_json::to_string_pretty(state)?;
+    json.push('\n');
+    Ok(json)
+}
+
+fn mark_conflict(store: &SkillStore, report: &mut StateSyncReport, conflicts: Vec<String>) {
+    if let Ok(raw) = serde_json::to_string(&conflicts) {
+        let _ = store.set_setting(STATE_SYNC_CONFLICTS_KEY, &raw);
+    }
+    report.status = "conflict".to_string();
+    report.conflicts = conflicts;
+}
+
+fn finish(store: &SkillStore, mut report: StateSyncReport) -> StateSyncReport {
+    report.finished_at = now_ms();
+    if let Ok(raw) = serde_json::to_string(&report) {
+        let _ = store.set_setting(STATE_SYNC_LAST_REPORT_KEY, &raw);
+    }
+    report
+}
+
+fn load_conflicts(store: &SkillStore) -> Vec<String> {
+    store
+        .get_setting(STATE_SYNC_CONFLICTS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn remote_auth(store: &SkillStore) -> Result<Option<GitAuth>> {
+    match store.get_setting(STATE_SYNC_REMOTE_KEY)? {
+        Some(url) => resolve_git_auth(store, &url),
+        None => Ok(None),
+    }
+}
+
+fn rev_exists(dir: &Path, rev: &str) -> bool {
+    run_git_in(dir, &["rev-parse", "--verify", "--quiet", rev]).is_ok()
+}
+
+fn is_ancestor(dir: &Path, ancestor: &str, rev: &str) -> bool {
+    run_git_in(dir, &["merge-base", "--is-ancestor", ancestor, rev]).is_ok()
+}
+
+fn commit_message() -> String {
+    let host = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or_else(|_| "unknown host".to_string());
+    format!("Update skills hub state from {}", host)
+}
+
+#[cfg(test)]
+#[path = "tests/state_sync.rs"]
+mod tests;
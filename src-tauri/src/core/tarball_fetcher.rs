@@ -0,0 +1,260 @@
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+use super::jobs::{self, JobReader};
+use super::network;
+use super::skill_store::SkillStore;
+
+pub const GIT_FETCH_STRATEGY_KEY: &str = "git_fetch_strategy";
+
+const GITHUB_API_BASE: &str = "https://api.github.com";
+const CODELOAD_BASE: &str = "https://codeload.github.com";
+
+/// How remote repositories are fetched into the cache.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchStrategy {
+    /// System git when available, otherwise GitHub tarballs.
+    #[default]
+    Auto,
+    /// Always git (git-cli, or libgit2 when explicitly allowed).
+    Git,
+    /// GitHub tarballs over HTTPS; other hosts still use git.
+    Tarball,
+}
+
+impl FetchStrategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Git => "git",
+            Self::Tarball => "tarball",
+        }
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim() {
+            "auto" => Some(Self::Auto),
+            "git" => Some(Self::Git),
+            "tarball" => Some(Self::Tarball),
+            _ => None,
+        }
+    }
+
+    /// Whether a GitHub repository should be fetched as a tarball.
+    pub fn prefers_tarball(self, git_available: bool) -> bool {
+        match self {
+            Self::Auto => !git_available,
+            Self::Git => false,
+            Self::Tarball => true,
+        }
+    }
+}
+
+pub fn get_fetch_strategy(store: &SkillStore) -> FetchStrategy {
+    store
+        .get_setting(GIT_FETCH_STRATEGY_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| FetchStrategy::parse(&raw))
+        .unwrap_or_default()
+}
+
+pub fn set_fetch_strategy(store: &SkillStore, strategy: FetchStrategy) -> Result<()> {
+    store.set_setting(GIT_FETCH_STRATEGY_KEY, strategy.as_str())
+}
+
+/// Download `owner/repo` at `git_ref` (default branch when `None`) and extract it into
+/// `dest`, replacing its contents. With `paths`, only root-level files and entries under
+/// those directories are extracted. Returns the resolved commit SHA.
+pub fn fetch_github_tarball(
+    owner: &str,
+    repo: &str,
+    git_ref: Option<&str>,
+    dest: &Path,
+    paths: Option<&[String]>,
+    token: Option<&str>,
+) -> Result<String> {
+    fetch_github_tarball_inner(
+        GITHUB_API_BASE,
+        CODELOAD_BASE,
+        owner,
+        repo,
+        git_ref,
+        dest,
+        paths,
+        token,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fetch_github_tarball_inner(
+    api_base: &str,
+    codeload_base: &str,
+    owner: &str,
+    repo: &str,
+    git_ref: Option<&str>,
+    dest: &Path,
+    paths: Option<&[String]>,
+    token: Option<&str>,
+) -> Result<String> {
+    network::ensure_online_for(api_base)?;
+    let client = network::http_client()?;
+    let sha = resolve_commit(&client, api_base, owner, repo, git_ref, token)?;
+
+    // Private repositories need the authenticated API endpoint, which redirects to codeload.
+    let url = match token {
+        Some(_) => format!("{}/repos/{}/{}/tarball/{}", api_base, owner, repo, sha),
+        None => format!("{}/{}/{}/tar.gz/{}", codeload_base, owner, repo, sha),
+    };
+    let mut request = client.get(&url).header("User-Agent", "skills-hub");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .with_context(|| format!("download {}/{} tarball", owner, repo))?
+        .error_for_status()
+        .with_context(|| format!("download {}/{} tarball", owner, repo))?;
+
+    let parent = dest
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("invalid cache dir {:?}", dest))?;
+    std::fs::create_dir_all(parent).with_context(|| format!("create {:?}", parent))?;
+    let staging = parent.join(format!(
+        ".{}.tarball-{}",
+        dest.file_name().unwrap_or_default().to_string_lossy(),
+        uuid::Uuid::new_v4()
+    ));
+    let total = response.content_length();
+    let download = JobReader::new(response, "Downloading", total);
+    let result = extract_tarball(download, &staging, paths).and_then(|()| {
+        if dest.exists() {
+            std::fs::remove_dir_all(dest).with_context(|| format!("remove {:?}", dest))?;
+        }
+        std::fs::rename(&staging, dest).with_context(|| format!("move {:?} -> {:?}", staging, dest))
+    });
+    if result.is_err() && staging.exists() {
+        let _ = std::fs::remove_dir_all(&staging);
+    }
+    result.map_err(jobs::cancellation_or)?;
+    Ok(sha)
+}
+
+fn resolve_commit(
+    client: &Client,
+    api_base: &str,
+    owner: &str,
+    repo: &str,
+    git_ref: Option<&str>,
+    token: Option<&str>,
+) -> Result<String> {
+    let git_ref = git_ref.unwrap_or("HEAD");
+    let url = format!(
+        "{}/repos/{}/{}/commits/{}",
+        api_base,
+        owner,
+        repo,
+        git_ref
+            .split('/')
+            .map(|part| urlencoding::encode(part).into_owned())
+            .collect::<Vec<_>>()
+            .join("/")
+    );
+    let mut request = client
+        .get(&url)
+        .header("User-Agent", "skills-hub")
+        .header("Accept", "application/vnd.github.sha");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let sha = request
+        .send()
+        .with_context(|| format!("resolve {}/{}@{}", owner, repo, git_ref))?
+        .error_for_status()
+        .with_context(|| format!("resolve {}/{}@{}", owner, repo, git_ref))?
+        .text()?
+        .trim()
+        .to_string();
+    if sha.len() != 40 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!(
+            "unexpected commit sha for {}/{}@{}: {}",
+            owner,
+            repo,
+            git_ref,
+            sha
+        );
+    }
+    Ok(sha)
+}
+
+fn extract_tarball(
+    reader: impl std::io::Read,
+    dest: &Path,
+    paths: Option<&[String]>,
+) -> Result<()> {
+    std::fs::create_dir_all(dest).with_context(|| format!("create {:?}", dest))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+    for entry in archive.entries().context("read tarball")? {
+        let mut entry = entry.context("read tarball entry")?;
+        let kind = entry.header().entry_type();
+        if !kind.is_file() && !kind.is_dir() {
+            // Symlinks and other special entries could point outside the cache.
+            continue;
+        }
+        let path = entry
+            .path()
+            .context("read tarball entry path")?
+            .into_owned();
+        // GitHub wraps everything in a single `<repo>-<sha>/` directory.
+        let Some(rel) = relative_entry_path(&path) else {
+            continue;
+        };
+        if !is_wanted(&rel, kind.is_dir(), paths) {
+            continue;
+        }
+        let target = dest.join(&rel);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).with_context(|| format!("create {:?}", parent))?;
+        }
+        entry
+            .unpack(&target)
+            .with_context(|| format!("extract {:?}", rel))?;
+    }
+    Ok(())
+}
+
+fn relative_entry_path(path: &Path) -> Option<PathBuf> {
+    let mut components = path.components();
+    components.next()?;
+    let mut rel = PathBuf::new();
+    for component in components {
+        match component {
+            Component::Normal(part) => rel.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!rel.as_os_str().is_empty()).then_some(rel)
+}
+
+/// Mirrors cone-mode sparse checkout: root files always, plus everything under `paths`.
+fn is_wanted(rel: &Path, is_dir: bool, paths: Option<&[String]>) -> bool {
+    let Some(paths) = paths else {
+        return true;
+    };
+    if !is_dir && rel.components().count() == 1 {
+        return true;
+    }
+    paths.iter().any(|p| {
+        let wanted = Path::new(p.trim_matches('/'));
+        rel.starts_with(wanted) || (is_dir && wanted.starts_with(rel))
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/tarball_fetcher.rs"]
+mod tests;
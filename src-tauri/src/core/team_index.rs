@@ -0,0 +1,403 @@
+//! Team registry index: a versioned JSON or YAML catalogue of approved skills that an
+//! organization hosts itself (any static URL or a shared file).
+//!
+//! ```yaml
+//! version: 1
+//! name: Acme approved skills
+//! skills:
+//!   - name: release-notes
+//!     description: Drafts release notes from merged PRs
+//!     gitUrl: https://git.acme.dev/tools/skills.git
+//!     subpath: skills/release-notes
+//!     revision: 4f1c2e9a7b3d5c6e8f9a0b1c2d3e4f5a6b7c8d9e
+//!     category: writing
+//!     tags: [release, docs]
+//!     maintainer: platform-team@acme.dev
+//!     checksum: sha256:<hex digest of the skill folder>
+//! ```
+//!
+//! `revision` is a tag, a branch or a full 40-character commit id; git cannot fetch
+//! abbreviated ids, so the index is rejected when it pins one.
+//! `checksum` is the content hash Skills Hub computes for an installed skill folder
+//! ([`compute_skill_checksum`]); installs whose files hash differently are rolled back.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::content_hash::hash_dir;
+use super::discovery_parser::ParsedSkill;
+use super::installer::{install_git_skill, InstallResult};
+use super::network;
+use super::registry_sources::RegistrySource;
+use super::skill_store::{RegistrySourceRecord, SkillStore};
+
+/// Index format version this build understands.
+pub const TEAM_INDEX_VERSION: u32 = 1;
+
+/// Directory (next to the database) holding the last fetched copy of each URL index.
+const CACHE_DIR_NAME: &str = "registry-index";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamIndex {
+    pub version: u32,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub skills: Vec<TeamIndexSkill>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamIndexSkill {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(alias = "git_url", alias = "url")]
+    pub git_url: String,
+    /// Skill folder inside the repository; the repository root when absent.
+    #[serde(default)]
+    pub subpath: Option<String>,
+    /// Commit, tag or branch to install; the default branch when absent.
+    #[serde(default, alias = "rev")]
+    pub revision: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub maintainer: Option<String>,
+    /// `sha256:<hex>`; normalized to that form when the index is parsed.
+    #[serde(default)]
+    pub checksum: Option<String>,
+}
+
+impl TeamIndexSkill {
+    /// Git source the installer understands: the URL plus a `#<revision>:<subpath>`
+    /// fragment when either is set.
+    pub fn source_ref(&self) -> String {
+        let revision = self.revision.as_deref().unwrap_or("");
+        match self.subpath.as_deref() {
+            Some(subpath) => format!("{}#{}:{}", self.git_url, revision, subpath),
+            None if !revision.is_empty() => format!("{}#{}", self.git_url, revision),
+            None => self.git_url.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: Option<u32>,
+}
+
+/// Parse and validate an index. JSON is detected by a leading `{`; anything else is
+/// read as YAML.
+pub fn parse_team_index(content: &str) -> Result<TeamIndex> {
+    let is_json = content.trim_start().starts_with('{');
+    // Check the version first so a newer index fails with a clear message rather than
+    // on whichever field changed.
+    let probe: VersionProbe = decode(content, is_json)?;
+    match probe.version {
+        None => anyhow::bail!("registry index is missing `version`"),
+        Some(version) if version != TEAM_INDEX_VERSION => anyhow::bail!(
+            "unsupported registry index version {} (supported: {})",
+            version,
+            TEAM_INDEX_VERSION
+        ),
+        Some(_) => {}
+    }
+
+    let mut index: TeamIndex = decode(content, is_json)?;
+    let mut seen = std::collections::HashSet::new();
+    for skill in &mut index.skills {
+        skill.name = skill.name.trim().to_string();
+        skill.git_url = skill.git_url.trim().to_string();
+        if skill.name.is_empty() || skill.git_url.is_empty() {
+            anyhow::bail!("registry index entries need a `name` and a `gitUrl`");
+        }
+        if !seen.insert(skill.name.clone()) {
+            anyhow::bail!("duplicate skill in registry index: {}", skill.name);
+        }
+        skill.subpath = trimmed(skill.subpath.take()).map(|p| p.trim_matches('/').to_string());
+        skill.revision = trimmed(skill.revision.take());
+        if let Some(revision) = skill.revision.as_deref() {
+            if looks_like_commit(revision) && revision.len() < 40 {
+                anyhow::bail!(
+                    "{} pins the abbreviated commit {}; use the full 40-character id",
+                    skill.name,
+                    revision
+                );
+            }
+        }
+        if let Some(raw) = trimmed(skill.checksum.take()) {
+            let checksum = normalize_checksum(&raw)
+                .with_context(|| format!("invalid checksum for {}", skill.name))?;
+            skill.checksum = Some(checksum);
+        }
+    }
+    Ok(index)
+}
+
+fn decode<T: DeserializeOwned>(content: &str, is_json: bool) -> Result<T> {
+    if is_json {
+        serde_json::from_str(content).context("invalid JSON registry index")
+    } else {
+        serde_yaml::from_str(content).context("invalid YAML registry index")
+    }
+}
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty() && v != ".")
+}
+
+/// `sha256:<64 lowercase hex>` from that form or a bare hex digest.
+pub fn normalize_checksum(raw: &str) -> Result<String> {
+    let raw = raw.trim();
+    let hex = match raw.split_once(':') {
+        Some((algo, hex)) if algo.eq_ignore_ascii_case("sha256") => hex,
+        Some((algo, _)) => anyhow::bail!("unsupported checksum algorithm: {}", algo),
+        None => raw,
+    };
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("expected a sha256 hex digest, got {:?}", raw);
+    }
+    Ok(format!("sha256:{}", hex.to_ascii_lowercase()))
+}
+
+/// The checksum a registry index should declare for the skill folder at `path`.
+pub fn compute_skill_checksum(path: &Path) -> Result<String> {
+    if !path.is_dir() {
+        anyhow::bail!("skill folder not found: {:?}", path);
+    }
+    Ok(format!("sha256:{}", hash_dir(path)?))
+}
+
+/// Fail unless the folder at `path` hashes to `expected` (any form
+/// [`normalize_checksum`] accepts).
+pub fn verify_skill_checksum(path: &Path, expected: &str) -> Result<()> {
+    let expected = normalize_checksum(expected)?;
+    let actual = compute_skill_checksum(path)?;
+    if actual != expected {
+        anyhow::bail!(
+            "checksum mismatch: registry index declares {}, installed files hash to {}",
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedIndex {
+    etag: Option<String>,
+    content: String,
+}
+
+/// Where the last fetched copy of a URL index is kept for conditional requests.
+pub fn team_index_cache_file(store: &SkillStore, source_id: &str) -> Option<PathBuf> {
+    store
+        .db_path()
+        .parent()
+        .map(|dir| dir.join(CACHE_DIR_NAME).join(format!("{}.json", source_id)))
+}
+
+/// A team index at an `http(s)://` URL or a local file (`file://` or a plain path).
+pub struct TeamIndexSource {
+    pub location: String,
+    /// Cache for URL indexes; without one every sync downloads the full index.
+    pub cache_file: Option<PathBuf>,
+}
+
+impl TeamIndexSource {
+    /// Read the index. URL indexes are requested with the cached `ETag`, so an unchanged
+    /// index costs a `304 Not Modified`; offline, the cached copy is used as is.
+    pub fn load(&self) -> Result<TeamIndex> {
+        let lower = self.location.to_ascii_lowercase();
+        if !(lower.starts_with("http://") || lower.starts_with("https://")) {
+            let path = self
+                .location
+                .strip_prefix("file://")
+                .unwrap_or(&self.location);
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path))?;
+            return parse_team_index(&content);
+        }
+
+        let cached = self.read_cache();
+        if network::is_offline() && network::needs_network(&self.location) {
+            if let Some(cached) = cached {
+                return parse_team_index(&cached.content);
+            }
+        }
+        network::ensure_online_for(&self.location)?;
+
+        let mut request = network::http_client()?
+            .get(&self.location)
+            .header("User-Agent", "skills-hub");
+        if let Some(etag) = cached.as_ref().and_then(|c| c.etag.as_deref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request
+            .send()
+            .with_context(|| format!("Failed to fetch {}", self.location))?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                return parse_team_index(&cached.content);
+            }
+            anyhow::bail!("{} answered 304 without a cached copy", self.location);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to fetch {}: HTTP {}",
+                self.location,
+                response.status()
+            );
+        }
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let content = response.text().context("Failed to read response body")?;
+        let index = parse_team_index(&content)?;
+        self.write_cache(&CachedIndex { etag, content });
+        Ok(index)
+    }
+
+    /// The cached copy of a URL index, without touching the network.
+    pub fn load_cached(&self) -> Option<TeamIndex> {
+        self.read_cache()
+            .and_then(|cached| parse_team_index(&cached.content).ok())
+    }
+
+    fn read_cache(&self) -> Option<CachedIndex> {
+        let raw = std::fs::read_to_string(self.cache_file.as_ref()?).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    fn write_cache(&self, cached: &CachedIndex) {
+        let Some(path) = &self.cache_file else {
+            return;
+        };
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(path, serde_json::to_string(cached).unwrap_or_default()));
+        if let Err(err) = written {
+            log::warn!("[team_index] failed to cache {:?}: {}", path, err);
+        }
+    }
+}
+
+impl RegistrySource for TeamIndexSource {
+    fn fetch(&self) -> Result<Vec<ParsedSkill>> {
+        Ok(self
+            .load()?
+            .skills
+            .into_iter()
+            .map(|skill| ParsedSkill {
+                github_url: skill.source_ref(),
+                name: skill.name,
+                description: skill.description.unwrap_or_default(),
+                category: skill.category.unwrap_or_default(),
+                tags: skill.tags,
+            })
+            .collect())
+    }
+}
+
+fn team_index_source(store: &SkillStore, source_id: &str) -> Result<TeamIndexSource> {
+    let record: RegistrySourceRecord = store
+        .get_registry_source(source_id)?
+        .with_context(|| format!("registry source not found: {}", source_id))?;
+    if record.kind != "team_index" {
+        anyhow::bail!("registry source {} is not a team index", record.name);
+    }
+    Ok(TeamIndexSource {
+        location: record.location,
+        cache_file: team_index_cache_file(store, source_id),
+    })
+}
+
+/// The full index behind a team index source, including revisions, maintainers and
+/// checksums. URL indexes are served from the copy cached by the last sync when there
+/// is one.
+pub fn get_team_index(store: &SkillStore, source_id: &str) -> Result<TeamIndex> {
+    let source = team_index_source(store, source_id)?;
+    match source.load_cached() {
+        Some(index) => Ok(index),
+        None => source.load(),
+    }
+}
+
+/// Install `skill_name` from a team index at its pinned revision. The installed folder
+/// must match the declared checksum, and a pinned commit must match the fetched one;
+/// otherwise the install is rolled back.
+pub fn install_team_index_skill<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    source_id: &str,
+    skill_name: &str,
+) -> Result<InstallResult> {
+    let index = get_team_index(store, source_id)?;
+    let skill = index
+        .skills
+        .into_iter()
+        .find(|s| s.name == skill_name)
+        .with_context(|| format!("skill not found in registry index: {}", skill_name))?;
+
+    let result = install_git_skill(app, store, &skill.source_ref(), Some(skill.name.clone()))?;
+    let verified = verify_installed(store, &skill, &result);
+    if let Err(err) = verified {
+        if let Err(cleanup) = std::fs::remove_dir_all(&result.central_path) {
+            log::warn!(
+                "[team_index] failed to remove {:?}: {}",
+                result.central_path,
+                cleanup
+            );
+        }
+        store.delete_skill(&result.skill_id)?;
+        return Err(err.context(format!("integrity check failed for {}", skill.name)));
+    }
+    Ok(result)
+}
+
+fn verify_installed(
+    store: &SkillStore,
+    skill: &TeamIndexSkill,
+    result: &InstallResult,
+) -> Result<()> {
+    if let Some(revision) = skill.revision.as_deref().filter(|r| looks_like_commit(r)) {
+        let installed = store
+            .get_skill_by_id(&result.skill_id)?
+            .and_then(|record| record.source_revision)
+            .unwrap_or_default();
+        if !installed.starts_with(&revision.to_ascii_lowercase()) {
+            anyhow::bail!(
+                "revision mismatch: registry index pins {}, fetched {}",
+                revision,
+                installed
+            );
+        }
+    }
+    if let Some(checksum) = &skill.checksum {
+        verify_skill_checksum(&result.central_path, checksum)?;
+    }
+    Ok(())
+}
+
+/// Abbreviated or full commit ids; tags and branches are not compared.
+pub(crate) fn looks_like_commit(revision: &str) -> bool {
+    (7..=40).contains(&revision.len()) && revision.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+#[path = "tests/team_index.rs"]
+mod tests;
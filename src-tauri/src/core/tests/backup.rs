@@ -0,0 +1,382 @@
+use std::fs;
+use std::path::Path;
+
+use crate::core::analytics_store::{AnalyticsStore, SkillEventRow};
+use crate::core::category_taxonomy::{
+    list_category_rules, save_category_rule, CategoryRuleInput, CategoryRuleKind,
+};
+use crate::core::skill_store::{
+    RegistrySourceRecord, SkillPublicationRecord, SkillRecord, SkillStore, SkillTargetRecord,
+};
+
+use super::{create_backup, rebase_home, restore_backup_in, ConflictPolicy};
+
+fn make_store(dir: &Path) -> SkillStore {
+    let store = SkillStore::new(dir.join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    store
+}
+
+fn add_skill(store: &SkillStore, id: &str, central: &Path, body: &str) {
+    fs::create_dir_all(central).unwrap();
+    fs::write(central.join("SKILL.md"), body).unwrap();
+    store
+        .upsert_skill(&SkillRecord {
+            id: id.to_string(),
+            name: central.file_name().unwrap().to_string_lossy().to_string(),
+            description: Some("demo".to_string()),
+            category: Some("team".to_string()),
+            source_type: "git".to_string(),
+            source_ref: Some("https://github.com/me/demo".to_string()),
+            source_revision: Some("abc".to_string()),
+            central_path: central.to_string_lossy().to_string(),
+            content_hash: None,
+            created_at: 42,
+            updated_at: 42,
+            last_sync_at: None,
+            last_seen_at: 42,
+            status: "ok".to_string(),
+        })
+        .unwrap();
+}
+
+fn event(id: &str) -> SkillEventRow {
+    SkillEventRow {
+        id: id.to_string(),
+        event_type: "skill_invoke".to_string(),
+        skill_id: "s1".to_string(),
+        timestamp: 1_700_000_000,
+        user_id: "u".to_string(),
+        session_id: "sess".to_string(),
+        input_hash: None,
+        success: true,
+        duration_ms: Some(10),
+        error: None,
+        feedback_score: None,
+        token_input: None,
+        token_output: None,
+        api_cost_usd: None,
+        caller_agent: None,
+        caller_workflow: None,
+        caller_tool: None,
+        metadata_json: None,
+    }
+}
+
+/// A populated "old laptop" plus its backup archive.
+fn make_backup(root: &Path) -> std::path::PathBuf {
+    let src = root.join("src");
+    fs::create_dir_all(&src).unwrap();
+    let store = make_store(&src);
+    add_skill(
+        &store,
+        "s1",
+        &src.join("central/demo"),
+        "# demo from backup\n",
+    );
+    store
+        .upsert_skill_target(&SkillTargetRecord {
+            id: "t1".to_string(),
+            skill_id: "s1".to_string(),
+            tool: "claude_code".to_string(),
+            target_path: "/old/home/.claude/skills/demo".to_string(),
+            mode: "symlink".to_string(),
+            status: "ok".to_string(),
+            last_error: None,
+            synced_at: Some(1),
+        })
+        .unwrap();
+    store.set_setting("auto_update_enabled", "true").unwrap();
+    store
+        .set_setting("central_repo_path", "/old/home/.skillshub")
+        .unwrap();
+    store
+        .set_setting("state_sync_remote", "git@example.com:me/state.git")
+        .unwrap();
+    store
+        .set_setting("network_proxy_url", "http://proxy.office:3128")
+        .unwrap();
+    store.set_setting("offline_mode", "true").unwrap();
+    store
+        .upsert_registry_source(&RegistrySourceRecord {
+            id: "team".to_string(),
+            kind: "json_index".to_string(),
+            name: "Team".to_string(),
+            location: "https://skills.example.com/index.json".to_string(),
+            enabled: false,
+            last_synced_at: Some(1),
+            last_error: None,
+            created_at: 1,
+            updated_at: 1,
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        })
+        .unwrap();
+    save_category_rule(
+        &store,
+        CategoryRuleInput {
+            id: None,
+            kind: CategoryRuleKind::Keyword,
+            pattern: "video".to_string(),
+            category_id: "media".to_string(),
+            position: Some(-1),
+        },
+    )
+    .unwrap();
+    store
+        .upsert_skill_publication(&SkillPublicationRecord {
+            skill_id: "s1".to_string(),
+            remote_url: "https://git.example.com/me/demo.git".to_string(),
+            branch: "main".to_string(),
+            subpath: None,
+            work_dir: "/old/home/.skillshub-publish/abc".to_string(),
+            last_commit: Some("c2".to_string()),
+            pushed_commit: Some("c1".to_string()),
+            last_pushed_at: Some(5),
+            created_at: 1,
+            updated_at: 1,
+        })
+        .unwrap();
+    store.add_scan_path("~/.cursor/skills").unwrap();
+    store
+        .add_category("team", "Team", "Team skills", "users", "#123456")
+        .unwrap();
+    store
+        .add_ai_agent("helper", "sk-test", "https://api.example.com")
+        .unwrap();
+
+    let analytics = AnalyticsStore::new(src.join("analytics.db")).unwrap();
+    analytics
+        .insert_events(&[event("e1"), event("e2")])
+        .unwrap();
+
+    let archive = root.join("out/backup.tar.gz");
+    let summary = create_backup(&store, Some(&analytics), &archive).unwrap();
+    assert_eq!(summary.skill_count, 1);
+    assert!(summary.has_analytics);
+    archive
+}
+
+#[test]
+fn restore_recreates_state_on_a_fresh_machine() {
+    let root = tempfile::tempdir().unwrap();
+    let archive = make_backup(root.path());
+
+    let dst = root.path().join("dst");
+    fs::create_dir_all(dst.join("central")).unwrap();
+    let store = make_store(&dst);
+    let analytics = AnalyticsStore::new(dst.join("analytics.db")).unwrap();
+    analytics.insert_events(&[event("e1")]).unwrap();
+
+    let report = restore_backup_in(
+        &store,
+        Some(&analytics),
+        &dst.join("central"),
+        &archive,
+        ConflictPolicy::Skip,
+    )
+    .unwrap();
+
+    assert_eq!(report.restored.len(), 1);
+    assert_eq!(report.restored[0].skill_id, "s1");
+    assert_eq!(report.restored[0].action, "created");
+    assert_eq!(
+        fs::read_to_string(dst.join("central/demo/SKILL.md")).unwrap(),
+        "# demo from backup\n"
+    );
+    let skill = store.get_skill_by_id("s1").unwrap().unwrap();
+    assert_eq!(skill.source_type, "git");
+    assert_eq!(skill.created_at, 42);
+    assert_eq!(
+        Path::new(&skill.central_path),
+        dst.join("central/demo").as_path()
+    );
+
+    assert_eq!(report.pending_targets.len(), 1);
+    assert_eq!(report.pending_targets[0].tool, "claude_code");
+    assert_eq!(report.pending_targets[0].name, "demo");
+
+    assert_eq!(
+        store.get_setting("auto_update_enabled").unwrap().as_deref(),
+        Some("true")
+    );
+    for machine_setting in [
+        "central_repo_path",
+        "state_sync_remote",
+        "network_proxy_url",
+        "offline_mode",
+    ] {
+        assert_eq!(store.get_setting(machine_setting).unwrap(), None);
+    }
+
+    let source = store.get_registry_source("team").unwrap().unwrap();
+    assert!(!source.enabled);
+    assert_eq!(source.etag, None);
+    assert_eq!(report.registry_sources_added, 1);
+    let rules = store.list_category_rules().unwrap();
+    assert_eq!(report.category_rules_restored, rules.len());
+    assert_eq!(rules[0].pattern, "video");
+    let publication = store.get_skill_publication("s1").unwrap().unwrap();
+    assert_eq!(
+        publication.remote_url,
+        "https://git.example.com/me/demo.git"
+    );
+    // Unpushed commits stayed in the old work tree.
+    assert_eq!(publication.last_commit.as_deref(), Some("c1"));
+    assert_eq!(report.publications_restored, 1);
+    assert!(store
+        .list_scan_paths()
+        .unwrap()
+        .contains(&"~/.cursor/skills".to_string()));
+    assert!(store
+        .list_categories()
+        .unwrap()
+        .iter()
+        .any(|c| c.id == "team"));
+    let agents = store.list_ai_agents().unwrap();
+    assert_eq!(agents.len(), 1);
+    assert_eq!(agents[0].api_key, "sk-test");
+    assert_eq!(report.analytics_events_merged, 1);
+
+    // The staging directory is cleaned up.
+    let leftovers: Vec<_> = fs::read_dir(dst.join("central"))
+        .unwrap()
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with(".restore-"))
+        .collect();
+    assert!(leftovers.is_empty());
+}
+
+#[test]
+fn conflict_policies_for_existing_skills() {
+    let root = tempfile::tempdir().unwrap();
+    let archive = make_backup(root.path());
+
+    let dst = root.path().join("dst");
+    let central = dst.join("central");
+    fs::create_dir_all(&central).unwrap();
+    let store = make_store(&dst);
+    add_skill(&store, "local-id", &central.join("demo"), "# local\n");
+    save_category_rule(
+        &store,
+        CategoryRuleInput {
+            id: None,
+            kind: CategoryRuleKind::Keyword,
+            pattern: "audio".to_string(),
+            category_id: "media".to_string(),
+            position: Some(-1),
+        },
+    )
+    .unwrap();
+    let has_rule = |pattern: &str| {
+        list_category_rules(&store)
+            .unwrap()
+            .iter()
+            .any(|r| r.pattern == pattern)
+    };
+
+    let report = restore_backup_in(&store, None, &central, &archive, ConflictPolicy::Skip).unwrap();
+    assert!(report.restored.is_empty());
+    assert_eq!(report.skipped[0].name, "demo");
+    assert!(report.pending_targets.is_empty());
+    assert_eq!(
+        fs::read_to_string(central.join("demo/SKILL.md")).unwrap(),
+        "# local\n"
+    );
+    // Local mapping rules stay; the backup only adds the ones missing here.
+    assert_eq!(report.category_rules_restored, 1);
+    assert!(has_rule("audio") && has_rule("video"));
+
+    let report =
+        restore_backup_in(&store, None, &central, &archive, ConflictPolicy::KeepBoth).unwrap();
+    assert_eq!(report.restored[0].name, "demo-restored");
+    assert_eq!(report.restored[0].action, "renamed");
+    assert_eq!(
+        fs::read_to_string(central.join("demo-restored/SKILL.md")).unwrap(),
+        "# demo from backup\n"
+    );
+    assert_eq!(report.pending_targets[0].name, "demo-restored");
+
+    let report =
+        restore_backup_in(&store, None, &central, &archive, ConflictPolicy::Replace).unwrap();
+    assert_eq!(report.restored[0].skill_id, "local-id");
+    assert_eq!(report.restored[0].action, "replaced");
+    assert_eq!(
+        fs::read_to_string(central.join("demo/SKILL.md")).unwrap(),
+        "# demo from backup\n"
+    );
+    assert_eq!(store.list_skills().unwrap().len(), 2);
+    assert!(!has_rule("audio") && has_rule("video"));
+    // The replaced copy is swapped out through sibling temp directories that are gone now.
+    let leftovers: Vec<_> = fs::read_dir(&central)
+        .unwrap()
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with('.'))
+        .collect();
+    assert!(leftovers.is_empty());
+    // Publications follow the skill to whichever id it was restored under.
+    assert!(store.get_skill_publication("local-id").unwrap().is_some());
+    // Agents are matched by name and not duplicated on repeated restores.
+    assert_eq!(store.list_ai_agents().unwrap().len(), 1);
+}
+
+#[test]
+fn failed_restore_leaves_files_and_records_untouched() {
+    let root = tempfile::tempdir().unwrap();
+    let archive = make_backup(root.path());
+
+    let dst = root.path().join("dst");
+    let central = dst.join("central");
+    fs::create_dir_all(&central).unwrap();
+    let store = make_store(&dst);
+    add_skill(&store, "local-id", &central.join("demo"), "# local\n");
+    // Publications are written last, after every other record.
+    rusqlite::Connection::open(dst.join("test.db"))
+        .unwrap()
+        .execute_batch(
+            "CREATE TRIGGER fail_publications BEFORE INSERT ON skill_publications
+             BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+        )
+        .unwrap();
+
+    let err =
+        restore_backup_in(&store, None, &central, &archive, ConflictPolicy::Replace).unwrap_err();
+    assert!(format!("{:#}", err).contains("disk full"));
+    assert_eq!(store.get_setting("auto_update_enabled").unwrap(), None);
+    assert!(store.list_registry_sources().unwrap().is_empty());
+    assert!(store.list_ai_agents().unwrap().is_empty());
+    let skills = store.list_skills().unwrap();
+    assert_eq!(skills.len(), 1);
+    assert_eq!(skills[0].content_hash, None);
+    assert_eq!(
+        fs::read_to_string(central.join("demo/SKILL.md")).unwrap(),
+        "# local\n"
+    );
+    let entries: Vec<_> = fs::read_dir(&central)
+        .unwrap()
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(entries, vec!["demo".to_string()]);
+}
+
+#[test]
+fn rebases_paths_under_old_home() {
+    let new_home = Path::new("/home/new");
+    assert_eq!(
+        rebase_home("/Users/old/skills/x", Some("/Users/old"), Some(new_home)),
+        new_home.join("skills/x").to_string_lossy()
+    );
+    assert_eq!(
+        rebase_home("/Users/old", Some("/Users/old"), Some(new_home)),
+        "/home/new"
+    );
+    assert_eq!(
+        rebase_home("/Users/older/x", Some("/Users/old"), Some(new_home)),
+        "/Users/older/x"
+    );
+    assert_eq!(
+        rebase_home("~/.claude/skills", Some("/Users/old"), Some(new_home)),
+        "~/.claude/skills"
+    );
+}
@@ -0,0 +1,270 @@
+use std::fs;
+use std::path::Path;
+
+use crate::core::installer::git_cache_key_for_source;
+use crate::core::skill_store::{GitCacheEntryRecord, SkillRecord, SkillStore};
+
+use super::{
+    dir_size, enforce_git_cache_budget, evict_git_cache_entries, get_git_cache_max_size_mb,
+    get_git_cache_usage, set_git_cache_max_size_mb,
+};
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn add_entry(
+    store: &SkillStore,
+    key: &str,
+    kind: &str,
+    mirror_key: Option<&str>,
+    path: &Path,
+    last_used_at: i64,
+) {
+    fs::create_dir_all(path).unwrap();
+    fs::write(path.join("f"), "12345").unwrap();
+    store
+        .upsert_git_cache_entry(&GitCacheEntryRecord {
+            key: key.to_string(),
+            kind: kind.to_string(),
+            clone_url: "https://example.com/o/r.git".to_string(),
+            ref_name: None,
+            mirror_key: mirror_key.map(|k| k.to_string()),
+            path: path.to_string_lossy().to_string(),
+            head: None,
+            size_bytes: 5,
+            last_fetched_at: last_used_at,
+            last_used_at,
+        })
+        .unwrap();
+}
+
+fn keys(store: &SkillStore) -> Vec<String> {
+    store
+        .list_git_cache_entries()
+        .unwrap()
+        .into_iter()
+        .map(|e| e.key)
+        .collect()
+}
+
+#[test]
+fn evicts_old_worktrees_and_orphaned_mirrors_only() {
+    let (dir, store) = make_store();
+    let root = dir.path().join("cache");
+    add_entry(
+        &store,
+        "m1",
+        "mirror",
+        None,
+        &root.join("mirrors/m1.git"),
+        300,
+    );
+    add_entry(
+        &store,
+        "w-old",
+        "worktree",
+        Some("m1"),
+        &root.join("worktrees/w-old"),
+        100,
+    );
+    add_entry(
+        &store,
+        "w-new",
+        "worktree",
+        Some("m1"),
+        &root.join("worktrees/w-new"),
+        500,
+    );
+    add_entry(
+        &store,
+        "m2",
+        "mirror",
+        None,
+        &root.join("mirrors/m2.git"),
+        300,
+    );
+    add_entry(
+        &store,
+        "w2",
+        "worktree",
+        Some("m2"),
+        &root.join("worktrees/w2"),
+        50,
+    );
+    add_entry(
+        &store,
+        "t1",
+        "tarball",
+        None,
+        &root.join("tarballs/t1"),
+        600,
+    );
+
+    let removed = evict_git_cache_entries(&store, |e| e.last_used_at < 200).unwrap();
+    // w-old, w2 and the now unused m2.
+    assert_eq!(removed, 3);
+    assert_eq!(keys(&store), vec!["m1", "w-new", "t1"]);
+    assert!(root.join("mirrors/m1.git").exists());
+    assert!(!root.join("mirrors/m2.git").exists());
+    assert!(!root.join("worktrees/w-old").exists());
+    assert!(root.join("worktrees/w-new").exists());
+}
+
+#[test]
+fn drops_index_rows_for_missing_directories() {
+    let (dir, store) = make_store();
+    let path = dir.path().join("cache/tarballs/t1");
+    add_entry(&store, "t1", "tarball", None, &path, 100);
+    fs::remove_dir_all(&path).unwrap();
+
+    assert_eq!(evict_git_cache_entries(&store, |_| false).unwrap(), 0);
+    assert!(keys(&store).is_empty());
+}
+
+#[test]
+fn dir_size_sums_nested_files() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("a/b")).unwrap();
+    fs::write(dir.path().join("a/one"), "1").unwrap();
+    fs::write(dir.path().join("a/b/three"), "333").unwrap();
+    assert_eq!(dir_size(dir.path()), 4);
+    assert_eq!(dir_size(&dir.path().join("missing")), 0);
+}
+
+fn add_git_skill(store: &SkillStore, id: &str, source_ref: &str) {
+    store
+        .upsert_skill(&SkillRecord {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            category: None,
+            source_type: "git".to_string(),
+            source_ref: Some(source_ref.to_string()),
+            source_revision: None,
+            central_path: format!("/central/{}", id),
+            content_hash: None,
+            created_at: 1,
+            updated_at: 1,
+            last_sync_at: None,
+            last_seen_at: 1,
+            status: "ok".to_string(),
+        })
+        .unwrap();
+}
+
+fn set_size(store: &SkillStore, key: &str, size_bytes: i64) {
+    let mut entry = store.get_git_cache_entry(key).unwrap().unwrap();
+    entry.size_bytes = size_bytes;
+    store.upsert_git_cache_entry(&entry).unwrap();
+}
+
+#[test]
+fn budget_evicts_lru_but_keeps_referenced_and_in_use_entries() {
+    let (dir, store) = make_store();
+    let root = dir.path().join("cache");
+    let referenced = git_cache_key_for_source("https://example.com/o/r.git");
+    add_git_skill(&store, "s1", "https://example.com/o/r.git");
+
+    add_entry(
+        &store,
+        "m1",
+        "mirror",
+        None,
+        &root.join("mirrors/m1.git"),
+        10,
+    );
+    add_entry(
+        &store,
+        &referenced,
+        "worktree",
+        Some("m1"),
+        &root.join("wt/ref"),
+        10,
+    );
+    add_entry(
+        &store,
+        "old",
+        "worktree",
+        Some("m1"),
+        &root.join("wt/old"),
+        20,
+    );
+    add_entry(
+        &store,
+        "t-old",
+        "tarball",
+        None,
+        &root.join("tarballs/t-old"),
+        30,
+    );
+    add_entry(
+        &store,
+        "t-new",
+        "tarball",
+        None,
+        &root.join("tarballs/t-new"),
+        40,
+    );
+    add_entry(
+        &store,
+        "current",
+        "tarball",
+        None,
+        &root.join("tarballs/cur"),
+        5,
+    );
+    let mb = 1024 * 1024;
+    for (key, size) in [
+        ("m1", mb),
+        (referenced.as_str(), mb),
+        ("old", mb),
+        ("t-old", mb),
+        ("t-new", mb),
+        ("current", 2 * mb),
+    ] {
+        set_size(&store, key, size);
+    }
+
+    // 7 MB cached, 5 MB allowed: `current` is older but in use, the referenced
+    // worktree (and so its mirror) is protected, so `old` and `t-old` go.
+    set_git_cache_max_size_mb(&store, 5).unwrap();
+    let removed = enforce_git_cache_budget(&store, Some("current")).unwrap();
+    assert_eq!(removed, 2);
+    let mut left = keys(&store);
+    left.sort();
+    let mut expected = vec![
+        "current".to_string(),
+        "m1".to_string(),
+        referenced.clone(),
+        "t-new".to_string(),
+    ];
+    expected.sort();
+    assert_eq!(left, expected);
+
+    let usage = get_git_cache_usage(&store).unwrap();
+    assert_eq!(usage.total_bytes, 5 * mb);
+    assert_eq!(usage.max_bytes, 5 * mb);
+    assert_eq!(usage.repos.len(), 1);
+    assert_eq!(usage.repos[0].skill_ids, vec!["s1".to_string()]);
+    assert_eq!(usage.repos[0].last_used_at, 40);
+
+    // 0 disables the budget.
+    set_git_cache_max_size_mb(&store, 0).unwrap();
+    set_size(&store, "t-new", 100 * mb);
+    assert_eq!(enforce_git_cache_budget(&store, None).unwrap(), 0);
+}
+
+#[test]
+fn max_size_setting_is_validated() {
+    let (_dir, store) = make_store();
+    assert_eq!(get_git_cache_max_size_mb(&store), 2048);
+    assert!(set_git_cache_max_size_mb(&store, -1).is_err());
+    set_git_cache_max_size_mb(&store, 300).unwrap();
+    assert_eq!(get_git_cache_max_size_mb(&store), 300);
+    store.set_setting("git_cache_max_size_mb", "junk").unwrap();
+    assert_eq!(get_git_cache_max_size_mb(&store), 2048);
+}
@@ -0,0 +1,152 @@
+use crate::core::skill_store::{DiscoveredSkillRecord, SkillStore};
+
+use super::{
+    builtin_categories, delete_category_rule, list_categories, list_category_rules,
+    recategorize_discovered_skills, reset_category_rules, save_category_rule, CategoryRuleInput,
+    CategoryRuleKind, CategoryRules,
+};
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn discovered(id: &str, raw_category: Option<&str>) -> DiscoveredSkillRecord {
+    DiscoveredSkillRecord {
+        id: id.to_string(),
+        name: id.to_string(),
+        description: String::new(),
+        github_url: format!("https://github.com/acme/{}", id),
+        category: "other".to_string(),
+        source: "s".to_string(),
+        tags: String::new(),
+        created_at: 0,
+        updated_at: 0,
+        first_seen_at: 0,
+        last_seen_at: 0,
+        raw_category: raw_category.map(str::to_string),
+    }
+}
+
+fn rule(kind: CategoryRuleKind, pattern: &str, category_id: &str) -> CategoryRuleInput {
+    CategoryRuleInput {
+        id: None,
+        kind,
+        pattern: pattern.to_string(),
+        category_id: category_id.to_string(),
+        position: None,
+    }
+}
+
+fn category_of(store: &SkillStore, id: &str) -> String {
+    store
+        .list_discovered_skills()
+        .unwrap()
+        .into_iter()
+        .find(|skill| skill.id == id)
+        .unwrap()
+        .category
+}
+
+#[test]
+fn default_rules_map_awesome_list_headings() {
+    let (_dir, store) = make_store();
+    let rules = CategoryRules::load(&store).unwrap();
+    assert_eq!(rules.categorize("Document Skills"), "document");
+    assert_eq!(rules.categorize("Development & Code Tools"), "development");
+    assert_eq!(rules.categorize("Data & Analysis"), "data-analysis");
+    assert_eq!(rules.categorize("Unknown Category"), "other");
+
+    // The defaults are seeded once; a removed default stays removed.
+    let defaults = list_category_rules(&store).unwrap();
+    assert_eq!(defaults.len(), 12);
+    delete_category_rule(&store, &defaults[0].id).unwrap();
+    let rules = CategoryRules::load(&store).unwrap();
+    assert_eq!(rules.categorize("Document Skills"), "other");
+    assert_eq!(list_category_rules(&store).unwrap().len(), 11);
+
+    assert_eq!(reset_category_rules(&store).unwrap().len(), 12);
+    let rules = CategoryRules::load(&store).unwrap();
+    assert_eq!(rules.categorize("Document Skills"), "document");
+}
+
+#[test]
+fn rule_changes_recategorize_synced_skills() {
+    let (_dir, store) = make_store();
+    store
+        .merge_discovered_skills_for_source(
+            "s",
+            &[
+                discovered("clip", Some("Video Tools")),
+                discovered("old", None),
+            ],
+            1,
+        )
+        .unwrap();
+
+    // New rules go after the defaults.
+    let saved =
+        save_category_rule(&store, rule(CategoryRuleKind::Keyword, " VIDEO ", "media")).unwrap();
+    assert_eq!(saved.pattern, "VIDEO");
+    assert_eq!(saved.position, 12);
+    assert_eq!(category_of(&store, "clip"), "media");
+    // Rows synced before rules existed keep their category until the next sync.
+    assert_eq!(category_of(&store, "old"), "other");
+
+    // Earlier rules win; an edit can move a rule ahead of the defaults.
+    let mut moved = rule(CategoryRuleKind::Regex, r"^video\b", "media");
+    moved.id = Some(saved.id.clone());
+    moved.position = Some(-1);
+    assert_eq!(save_category_rule(&store, moved).unwrap().position, -1);
+    assert_eq!(list_category_rules(&store).unwrap()[0].id, saved.id);
+
+    // Rules may point at categories the user added; removing one drops its rules.
+    store
+        .add_category("3d", "3D", "Modeling", "🧊", "#000000")
+        .unwrap();
+    save_category_rule(&store, rule(CategoryRuleKind::Keyword, "tools", "3d")).unwrap();
+    assert_eq!(category_of(&store, "clip"), "media");
+    delete_category_rule(&store, &saved.id).unwrap();
+    assert_eq!(category_of(&store, "clip"), "3d");
+    store.remove_category("3d").unwrap();
+    assert_eq!(recategorize_discovered_skills(&store).unwrap(), 1);
+    assert_eq!(category_of(&store, "clip"), "other");
+
+    for invalid in [
+        rule(CategoryRuleKind::Regex, "(", "media"),
+        rule(CategoryRuleKind::Keyword, "  ", "media"),
+        rule(CategoryRuleKind::Keyword, "video", "3d"),
+    ] {
+        assert!(save_category_rule(&store, invalid).is_err());
+    }
+    assert!(delete_category_rule(&store, "missing").is_err());
+}
+
+#[test]
+fn builtin_categories_are_localized() {
+    let (_dir, store) = make_store();
+    let en = builtin_categories("en");
+    let zh = builtin_categories("zh-CN");
+    assert_eq!(en.len(), zh.len());
+    assert_eq!(en[0].id, "development");
+    assert_eq!(en[0].name, "Development");
+    assert_eq!(zh[0].name, "开发工具");
+    assert_eq!(builtin_categories("fr")[0].name, "Development");
+
+    // Rows the user added follow the built-ins; leftover rows with built-in ids do not.
+    store
+        .add_category("3d", "3D", "Modeling", "🧊", "#000000")
+        .unwrap();
+    store
+        .add_category("ai", "AI 辅助", "", "🤖", "#8b5cf6")
+        .unwrap();
+    let all = list_categories(&store, "en").unwrap();
+    assert_eq!(all.len(), en.len() + 1);
+    assert_eq!(all.last().unwrap().id, "3d");
+    assert_eq!(
+        all.iter().find(|c| c.id == "ai").unwrap().name,
+        "AI Assistance"
+    );
+}
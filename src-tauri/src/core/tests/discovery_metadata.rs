@@ -0,0 +1,411 @@
+use mockito::Matcher;
+
+use crate::core::skill_store::{DiscoveredSkillRecord, SkillStore};
+
+use super::{
+    arrange_discovered_skills, candidate_dirs, enrich_with, metadata_by_skill, DiscoverySort,
+    Endpoints, InstallCheck, RefListing,
+};
+
+const DAY: i64 = 24 * 60 * 60 * 1000;
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn discovered(id: &str, github_url: &str) -> DiscoveredSkillRecord {
+    DiscoveredSkillRecord {
+        id: id.to_string(),
+        name: id.to_string(),
+        description: String::new(),
+        github_url: github_url.to_string(),
+        category: "development".to_string(),
+        source: "awesome".to_string(),
+        tags: String::new(),
+        created_at: 0,
+        updated_at: 0,
+        first_seen_at: 0,
+        last_seen_at: 0,
+        raw_category: None,
+    }
+}
+
+fn endpoints(server: &mockito::Server) -> Endpoints {
+    Endpoints {
+        api: server.url(),
+        raw: server.url(),
+    }
+}
+
+fn list(store: &SkillStore) -> Vec<DiscoveredSkillRecord> {
+    store.list_discovered_skills().unwrap()
+}
+
+fn ids(skills: &[DiscoveredSkillRecord]) -> Vec<&str> {
+    skills.iter().map(|skill| skill.id.as_str()).collect()
+}
+
+#[test]
+fn enriches_repositories_and_skill_frontmatter() {
+    let (_dir, store) = make_store();
+    let mut server = mockito::Server::new();
+    store
+        .merge_discovered_skills_for_source(
+            "awesome",
+            &[
+                discovered("docx", "https://github.com/acme/skills/tree/main/docx"),
+                discovered("gone", "https://github.com/acme/gone"),
+                discovered("lab", "https://gitlab.com/acme/lab"),
+                discovered("pdf", "https://github.com/acme/skills/tree/main/pdf"),
+                discovered("tiny", "https://github.com/solo/tiny"),
+            ],
+            1,
+        )
+        .unwrap();
+
+    let skills_repo = server
+        .mock("GET", "/repos/acme/skills")
+        .with_status(200)
+        .with_header("etag", "\"e1\"")
+        .with_header("x-ratelimit-remaining", "40")
+        .with_body(
+            r#"{"stargazers_count": 120, "pushed_at": "2024-05-01T00:00:00Z",
+                "archived": false, "license": {"spdx_id": "MIT", "name": "MIT License"}}"#,
+        )
+        .expect(1)
+        .create();
+    server
+        .mock("GET", "/repos/solo/tiny")
+        .with_status(200)
+        .with_body(
+            r#"{"stargazers_count": 3, "pushed_at": "2025-01-01T00:00:00Z",
+                "archived": true, "license": {"spdx_id": "NOASSERTION", "name": "Other"}}"#,
+        )
+        .create();
+    server
+        .mock("GET", "/repos/acme/gone")
+        .with_status(404)
+        .create();
+    server
+        .mock("GET", "/repos/acme/skills/commits/main")
+        .match_header("accept", "application/vnd.github.sha")
+        .with_status(200)
+        .with_body("abc123")
+        .create();
+    let skills_tree = server
+        .mock("GET", "/repos/acme/skills/git/trees/abc123?recursive=1")
+        .with_status(200)
+        .with_body(
+            r#"{"tree": [{"path": "docx", "type": "tree"},
+                         {"path": "pdf/SKILL.md", "type": "blob"},
+                         {"path": "pdf/forms.py", "type": "blob"}]}"#,
+        )
+        .expect(1)
+        .create();
+    server
+        .mock("GET", "/repos/solo/tiny/commits/HEAD")
+        .with_status(409)
+        .create();
+    server
+        .mock("GET", "/acme/skills/main/pdf/SKILL.md")
+        .with_status(200)
+        .with_body("---\nname: pdf-tools\ndescription: Fill PDF forms\n---\nbody\n")
+        .create();
+    server
+        .mock("GET", Matcher::Regex(r"SKILL\.md$".to_string()))
+        .with_status(404)
+        .create();
+
+    let report = enrich_with(&store, &endpoints(&server), None, 10, 5 * DAY).unwrap();
+    assert_eq!(report.enriched, 5);
+    assert_eq!(report.rate_limited_until, None);
+    // Both skills of acme/skills share one repository lookup and tree listing.
+    skills_repo.assert();
+    skills_tree.assert();
+
+    let metadata = metadata_by_skill(&store).unwrap();
+    let pdf = &metadata["pdf"];
+    assert_eq!(pdf.repo.as_deref(), Some("acme/skills"));
+    assert_eq!(pdf.status, "ok");
+    assert_eq!(pdf.stars, Some(120));
+    assert_eq!(pdf.last_commit_at, Some(1_714_521_600_000));
+    assert_eq!(pdf.license.as_deref(), Some("MIT"));
+    assert_eq!(pdf.skill_name.as_deref(), Some("pdf-tools"));
+    assert_eq!(pdf.skill_description.as_deref(), Some("Fill PDF forms"));
+    assert_eq!(pdf.install_status.as_deref(), Some("installable"));
+    assert_eq!(pdf.skill_count, Some(1));
+    assert_eq!(pdf.head_revision.as_deref(), Some("abc123"));
+    let docx = &metadata["docx"];
+    assert_eq!(docx.skill_name, None);
+    assert_eq!(docx.install_status.as_deref(), Some("no_skill"));
+    assert_eq!(docx.skill_count, Some(0));
+    // An empty repository has nothing to install.
+    assert_eq!(metadata["tiny"].license.as_deref(), Some("Other"));
+    assert_eq!(metadata["tiny"].install_status.as_deref(), Some("no_skill"));
+    assert_eq!(metadata["gone"].status, "not_found");
+    assert_eq!(
+        metadata["gone"].install_status.as_deref(),
+        Some("not_found")
+    );
+    assert_eq!(metadata["lab"].status, "unsupported");
+    assert_eq!(metadata["lab"].install_status, None);
+
+    // Fresh metadata is not fetched again.
+    let report = enrich_with(&store, &endpoints(&server), None, 10, 6 * DAY).unwrap();
+    assert_eq!(report.enriched, 0);
+
+    // Archived and missing repositories are hidden unless asked for.
+    let shown = arrange_discovered_skills(list(&store), &metadata, DiscoverySort::Name, false);
+    assert_eq!(ids(&shown), vec!["docx", "lab", "pdf"]);
+    let all = arrange_discovered_skills(list(&store), &metadata, DiscoverySort::Popularity, true);
+    assert_eq!(ids(&all), vec!["docx", "pdf", "tiny", "gone", "lab"]);
+    let fresh = arrange_discovered_skills(list(&store), &metadata, DiscoverySort::Freshness, true);
+    assert_eq!(ids(&fresh), vec!["tiny", "docx", "pdf", "gone", "lab"]);
+}
+
+#[test]
+fn stale_entries_are_revalidated_and_rate_limits_pause_enrichment() {
+    let (_dir, store) = make_store();
+    let mut server = mockito::Server::new();
+    store
+        .merge_discovered_skills_for_source(
+            "awesome",
+            &[
+                discovered("a", "https://github.com/acme/a"),
+                discovered("b", "https://github.com/acme/b"),
+            ],
+            1,
+        )
+        .unwrap();
+
+    let first = server
+        .mock("GET", Matcher::Regex(r"^/repos/acme/[ab]$".to_string()))
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_body(r#"{"stargazers_count": 7, "archived": false}"#)
+        .create();
+    let head = server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/repos/acme/[ab]/commits/HEAD$".to_string()),
+        )
+        .with_status(200)
+        .with_body("c0ffee\n")
+        .create();
+    let tree = server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/repos/acme/[ab]/git/trees/c0ffee".to_string()),
+        )
+        .with_status(200)
+        .with_body(r#"{"tree": [{"path": "SKILL.md", "type": "blob"}]}"#)
+        .create();
+    let raw = server
+        .mock("GET", Matcher::Regex(r"SKILL\.md$".to_string()))
+        .with_status(404)
+        .create();
+    enrich_with(&store, &endpoints(&server), None, 10, DAY).unwrap();
+    first.remove();
+    head.remove();
+    tree.remove();
+
+    // A day later the entries are revalidated; the quota runs out on the first one.
+    let reset_secs = 4_000_000_000_i64;
+    let unchanged = server
+        .mock("GET", Matcher::Regex(r"^/repos/acme/[ab]$".to_string()))
+        .match_header("if-none-match", "\"v1\"")
+        .with_status(304)
+        .with_header("x-ratelimit-remaining", "0")
+        .with_header("x-ratelimit-reset", &reset_secs.to_string())
+        .expect(1)
+        .create();
+    let now = 3 * DAY;
+    let report = enrich_with(&store, &endpoints(&server), None, 10, now).unwrap();
+    unchanged.assert();
+    assert_eq!(report.enriched, 1);
+    assert_eq!(report.rate_limited_until, Some(reset_secs * 1000));
+    let metadata = metadata_by_skill(&store).unwrap();
+    let revalidated = metadata
+        .values()
+        .find(|meta| meta.fetched_at == now)
+        .unwrap();
+    assert_eq!(revalidated.stars, Some(7));
+    assert_eq!(revalidated.etag.as_deref(), Some("\"v1\""));
+    // The repository is unchanged, so its install check is kept without a tree listing.
+    assert_eq!(revalidated.install_status.as_deref(), Some("installable"));
+    assert_eq!(revalidated.head_revision.as_deref(), Some("c0ffee"));
+
+    // Until the reset, runs do not touch the network.
+    let report = enrich_with(&store, &endpoints(&server), None, 10, now + 1).unwrap();
+    assert_eq!(report.enriched, 0);
+    assert_eq!(report.rate_limited_until, Some(reset_secs * 1000));
+    raw.remove();
+}
+
+#[test]
+fn rate_limited_responses_are_not_recorded() {
+    let (_dir, store) = make_store();
+    let mut server = mockito::Server::new();
+    store
+        .merge_discovered_skills_for_source(
+            "awesome",
+            &[discovered("a", "https://github.com/acme/a")],
+            1,
+        )
+        .unwrap();
+    server
+        .mock("GET", "/repos/acme/a")
+        .with_status(403)
+        .with_header("x-ratelimit-remaining", "0")
+        .create();
+
+    let now = DAY;
+    let report = enrich_with(&store, &endpoints(&server), None, 10, now).unwrap();
+    assert_eq!(report.enriched, 0);
+    // Without a reset time, the run waits an hour.
+    assert_eq!(report.rate_limited_until, Some(now + 60 * 60 * 1000));
+    assert!(metadata_by_skill(&store).unwrap().is_empty());
+}
+
+#[test]
+fn install_checks_mirror_git_skill_listing() {
+    let files: Vec<String> = [
+        "SKILL.md",
+        "skills/a/SKILL.md",
+        "skills/a/nested/SKILL.md",
+        "skills/.curated/b/SKILL.md",
+        "docs/x/SKILL.md",
+        "docs/NOTSKILL.md",
+    ]
+    .iter()
+    .map(|path| path.to_string())
+    .collect();
+
+    // A repository URL offers the root and the standard discovery locations.
+    assert_eq!(
+        candidate_dirs(&files, None),
+        vec!["", "skills/.curated/b", "skills/a"]
+    );
+    // A folder URL offers only that folder.
+    assert_eq!(candidate_dirs(&files, Some("/docs/x/")), vec!["docs/x"]);
+    assert!(candidate_dirs(&files, Some("docs")).is_empty());
+
+    let listing = RefListing {
+        revision: Some("abc123".to_string()),
+        files: files.clone(),
+        truncated: false,
+    };
+    let check = InstallCheck::new(Some(&listing), None);
+    assert_eq!(check.status, "multiple");
+    assert_eq!(check.skill_count, Some(3));
+    assert_eq!(check.skill_dir, None);
+    let check = InstallCheck::new(Some(&listing), Some("skills/a"));
+    assert_eq!(check.status, "installable");
+    assert_eq!(check.skill_dir.as_deref(), Some("skills/a"));
+    assert_eq!(check.revision.as_deref(), Some("abc123"));
+    // A link to a branch that does not exist.
+    assert_eq!(
+        InstallCheck::new(None, Some("skills/a")),
+        InstallCheck::missing()
+    );
+}
+
+#[test]
+fn failed_lookups_are_retried_after_an_hour() {
+    let (_dir, store) = make_store();
+    let mut server = mockito::Server::new();
+    store
+        .merge_discovered_skills_for_source(
+            "awesome",
+            &[discovered("a", "https://github.com/acme/a")],
+            1,
+        )
+        .unwrap();
+    let failing = server
+        .mock("GET", "/repos/acme/a")
+        .with_status(502)
+        .create();
+
+    let now = DAY;
+    let report = enrich_with(&store, &endpoints(&server), None, 10, now).unwrap();
+    assert_eq!(report.enriched, 1);
+    assert_eq!(metadata_by_skill(&store).unwrap()["a"].status, "error");
+    failing.remove();
+
+    let report = enrich_with(&store, &endpoints(&server), None, 10, now + 30 * 60 * 1000).unwrap();
+    assert_eq!(report.enriched, 0);
+
+    server
+        .mock("GET", "/repos/acme/a")
+        .with_status(200)
+        .with_body(r#"{"stargazers_count": 7, "archived": false}"#)
+        .create();
+    server
+        .mock("GET", "/repos/acme/a/commits/HEAD")
+        .with_status(200)
+        .with_body("c0ffee")
+        .create();
+    server
+        .mock("GET", "/repos/acme/a/git/trees/c0ffee?recursive=1")
+        .with_status(200)
+        .with_body(r#"{"tree": []}"#)
+        .create();
+    let report = enrich_with(&store, &endpoints(&server), None, 10, now + 61 * 60 * 1000).unwrap();
+    assert_eq!(report.enriched, 1);
+    let a = &metadata_by_skill(&store).unwrap()["a"];
+    assert_eq!((a.status.as_str(), a.stars), ("ok", Some(7)));
+}
+
+#[test]
+fn truncated_trees_are_not_reported_as_skill_less() {
+    let (_dir, store) = make_store();
+    let mut server = mockito::Server::new();
+    store
+        .merge_discovered_skills_for_source(
+            "awesome",
+            &[
+                discovered("big", "https://github.com/acme/big"),
+                discovered("deep", "https://github.com/acme/big/tree/main/zz/deep"),
+            ],
+            1,
+        )
+        .unwrap();
+    server
+        .mock("GET", "/repos/acme/big")
+        .with_status(200)
+        .with_body(r#"{"stargazers_count": 7, "archived": false}"#)
+        .create();
+    server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/repos/acme/big/commits/(HEAD|main)$".to_string()),
+        )
+        .with_status(200)
+        .with_body("c0ffee")
+        .create();
+    server
+        .mock("GET", "/repos/acme/big/git/trees/c0ffee?recursive=1")
+        .with_status(200)
+        .with_body(r#"{"tree": [{"path": "README.md", "type": "blob"}], "truncated": true}"#)
+        .create();
+    // The folder past the cut is probed directly.
+    server
+        .mock("GET", "/acme/big/main/zz/deep/SKILL.md")
+        .with_status(200)
+        .with_body("---\nname: deep\ndescription: Deep skill\n---\n")
+        .create();
+
+    let report = enrich_with(&store, &endpoints(&server), None, 10, DAY).unwrap();
+    assert_eq!(report.enriched, 2);
+    let metadata = metadata_by_skill(&store).unwrap();
+    // The repository URL may offer skills past the cut: left unchecked.
+    assert_eq!(metadata["big"].status, "ok");
+    assert_eq!(metadata["big"].install_status, None);
+    let deep = &metadata["deep"];
+    assert_eq!(deep.install_status.as_deref(), Some("installable"));
+    assert_eq!(deep.skill_count, Some(1));
+    assert_eq!(deep.skill_name.as_deref(), Some("deep"));
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, Once};
+
+use crate::core::git_fetcher::git_cli_available;
+use crate::core::skill_store::{GitCredentialRecord, SkillStore};
+
+use super::{
+    delete_git_credential, github_api_token, list_git_credentials, move_git_secrets_to_keychain,
+    resolve_git_auth, save_git_credential, url_host, GitCredentialInput, GitCredentialKind,
+    KEYCHAIN_SERVICE,
+};
+
+type Items = Arc<Mutex<HashMap<(String, String), Vec<u8>>>>;
+
+/// Process-wide in-memory stand-in for the OS keychain.
+#[derive(Debug, Default)]
+struct MemoryKeychain {
+    items: Items,
+}
+
+#[derive(Debug)]
+struct MemoryItem {
+    items: Items,
+    key: (String, String),
+}
+
+impl keyring::credential::CredentialBuilderApi for MemoryKeychain {
+    fn build(
+        &self,
+        _target: Option<&str>,
+        service: &str,
+        user: &str,
+    ) -> keyring::Result<Box<keyring::credential::Credential>> {
+        Ok(Box::new(MemoryItem {
+            items: self.items.clone(),
+            key: (service.to_string(), user.to_string()),
+        }))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl keyring::credential::CredentialApi for MemoryItem {
+    fn set_secret(&self, secret: &[u8]) -> keyring::Result<()> {
+        self.items
+            .lock()
+            .unwrap()
+            .insert(self.key.clone(), secret.to_vec());
+        Ok(())
+    }
+
+    fn get_secret(&self) -> keyring::Result<Vec<u8>> {
+        self.items
+            .lock()
+            .unwrap()
+            .get(&self.key)
+            .cloned()
+            .ok_or(keyring::Error::NoEntry)
+    }
+
+    fn delete_credential(&self) -> keyring::Result<()> {
+        self.items
+            .lock()
+            .unwrap()
+            .remove(&self.key)
+            .map(|_| ())
+            .ok_or(keyring::Error::NoEntry)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+fn keychain_item(secret_ref: &str) -> Option<String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, secret_ref)
+        .unwrap()
+        .get_password()
+        .ok()
+}
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    static KEYCHAIN: Once = Once::new();
+    KEYCHAIN
+        .call_once(|| keyring::set_default_credential_builder(Box::new(MemoryKeychain::default())));
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn token_input(host: &str, token: Option<&str>) -> GitCredentialInput {
+    GitCredentialInput {
+        host: host.to_string(),
+        kind: GitCredentialKind::Token,
+        username: None,
+        token: token.map(|t| t.to_string()),
+        ssh_key_path: None,
+    }
+}
+
+#[test]
+fn extracts_hosts_from_remote_urls() {
+    assert_eq!(
+        url_host("https://GitLab.acme.dev/team/skills.git").as_deref(),
+        Some("gitlab.acme.dev")
+    );
+    assert_eq!(
+        url_host("ssh://git@git.acme.dev:2222/team/skills.git").as_deref(),
+        Some("git.acme.dev")
+    );
+    assert_eq!(
+        url_host("git@bitbucket.org:acme/skills.git").as_deref(),
+        Some("bitbucket.org")
+    );
+    assert_eq!(url_host("/srv/repos/skills"), None);
+    assert_eq!(url_host(r"C:\repos\skills"), None);
+}
+
+#[test]
+fn stores_tokens_without_exposing_them() {
+    let (_dir, store) = make_store();
+    let info = save_git_credential(
+        &store,
+        token_input("https://gitlab.acme.dev/", Some("glpat-123")),
+    )
+    .unwrap();
+    assert_eq!(info.host, "gitlab.acme.dev");
+    assert!(info.has_token);
+
+    // Updating without a token keeps the stored one.
+    let mut input = token_input("gitlab.acme.dev", None);
+    input.username = Some("ci".to_string());
+    save_git_credential(&store, input).unwrap();
+
+    let listed = list_git_credentials(&store).unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].username.as_deref(), Some("ci"));
+    assert!(serde_json::to_string(&listed)
+        .unwrap()
+        .find("glpat")
+        .is_none());
+
+    // Only the keychain holds the token.
+    let record = store
+        .get_git_credential("gitlab.acme.dev")
+        .unwrap()
+        .unwrap();
+    assert_eq!(record.secret, None);
+    let secret_ref = record.secret_ref.expect("keychain reference");
+    assert_eq!(keychain_item(&secret_ref).as_deref(), Some("glpat-123"));
+
+    let auth = resolve_git_auth(&store, "https://gitlab.acme.dev/team/skills.git")
+        .unwrap()
+        .expect("credential for host");
+    assert_eq!(auth.token.as_deref(), Some("glpat-123"));
+    assert!(resolve_git_auth(&store, "https://github.com/a/b")
+        .unwrap()
+        .is_none());
+
+    delete_git_credential(&store, "gitlab.acme.dev").unwrap();
+    assert!(list_git_credentials(&store).unwrap().is_empty());
+    assert_eq!(keychain_item(&secret_ref), None);
+}
+
+#[test]
+fn plaintext_tokens_move_to_keychain() {
+    let (_dir, store) = make_store();
+    store
+        .upsert_git_credential(&GitCredentialRecord {
+            host: "git.legacy.dev".to_string(),
+            kind: "token".to_string(),
+            username: None,
+            secret: Some("old-token".to_string()),
+            secret_ref: None,
+            ssh_key_path: None,
+            created_at: 1,
+            updated_at: 1,
+        })
+        .unwrap();
+    let auth = resolve_git_auth(&store, "https://git.legacy.dev/a/b")
+        .unwrap()
+        .unwrap();
+    assert_eq!(auth.token.as_deref(), Some("old-token"));
+
+    assert_eq!(move_git_secrets_to_keychain(&store).unwrap(), 1);
+    assert_eq!(move_git_secrets_to_keychain(&store).unwrap(), 0);
+    let record = store.get_git_credential("git.legacy.dev").unwrap().unwrap();
+    assert_eq!(record.secret, None);
+    assert_eq!(
+        keychain_item(record.secret_ref.as_deref().unwrap()).as_deref(),
+        Some("old-token")
+    );
+    let auth = resolve_git_auth(&store, "https://git.legacy.dev/a/b")
+        .unwrap()
+        .unwrap();
+    assert_eq!(auth.token.as_deref(), Some("old-token"));
+
+    // Switching to another kind drops the keychain item.
+    let secret_ref = record.secret_ref.unwrap();
+    save_git_credential(
+        &store,
+        GitCredentialInput {
+            host: "git.legacy.dev".to_string(),
+            kind: GitCredentialKind::System,
+            username: None,
+            token: None,
+            ssh_key_path: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(keychain_item(&secret_ref), None);
+}
+
+#[test]
+fn validates_credential_input() {
+    let (dir, store) = make_store();
+    assert!(save_git_credential(&store, token_input("github.com", None)).is_err());
+    assert!(save_git_credential(&store, token_input("not a host", Some("x"))).is_err());
+
+    let missing_key = GitCredentialInput {
+        host: "git.acme.dev".to_string(),
+        kind: GitCredentialKind::SshKey,
+        username: None,
+        token: None,
+        ssh_key_path: Some(dir.path().join("id_missing").to_string_lossy().to_string()),
+    };
+    assert!(save_git_credential(&store, missing_key).is_err());
+
+    let key = dir.path().join("id_ed25519");
+    std::fs::write(&key, "key").unwrap();
+    let info = save_git_credential(
+        &store,
+        GitCredentialInput {
+            host: "git.acme.dev".to_string(),
+            kind: GitCredentialKind::SshKey,
+            username: None,
+            token: None,
+            ssh_key_path: Some(key.to_string_lossy().to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(info.kind, GitCredentialKind::SshKey);
+    assert!(!info.has_token);
+}
+
+#[test]
+fn github_token_is_used_for_api_calls() {
+    let (_dir, store) = make_store();
+    assert_eq!(github_api_token(&store), None);
+    save_git_credential(&store, token_input("github.com", Some("ghp_abc"))).unwrap();
+    assert_eq!(github_api_token(&store).as_deref(), Some("ghp_abc"));
+}
+
+#[test]
+fn token_is_handed_to_git_through_credential_helper() {
+    if !git_cli_available() {
+        return;
+    }
+    let (_dir, store) = make_store();
+    save_git_credential(&store, token_input("git.acme.dev", Some("s3cret"))).unwrap();
+    let auth = resolve_git_auth(&store, "https://git.acme.dev/team/skills.git")
+        .unwrap()
+        .unwrap();
+
+    let mut cmd = Command::new("git");
+    auth.apply_to_command(&mut cmd);
+    let mut child = cmd
+        .args(["credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"protocol=https\nhost=git.acme.dev\n\n")
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("username=x-access-token"), "{stdout}");
+    assert!(stdout.contains("password=s3cret"), "{stdout}");
+}
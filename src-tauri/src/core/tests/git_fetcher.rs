@@ -1,6 +1,12 @@
 use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
-use crate::core::git_fetcher::clone_or_pull;
+use crate::core::git_fetcher::{
+    clone_or_pull, ensure_sparse_checkout, fetch_into_worktree, git_cli_available,
+    is_linked_worktree, remove_worktree, run_bounded,
+};
+use crate::core::jobs::{is_cancelled, JobRegistry};
 
 fn commit_file(repo: &git2::Repository, path: &str, content: &[u8], msg: &str) -> git2::Oid {
     let workdir = repo.workdir().expect("workdir");
@@ -35,10 +41,164 @@ fn clone_then_pull_updates_head() {
     let dest_dir = tempfile::tempdir().unwrap();
     let dest = dest_dir.path().join("clone");
 
-    let h1 = clone_or_pull(origin_dir.path().to_string_lossy().as_ref(), &dest, None).unwrap();
+    let h1 = clone_or_pull(
+        origin_dir.path().to_string_lossy().as_ref(),
+        &dest,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
     assert_eq!(h1, c2.to_string(), "首次 clone 应指向最新提交");
 
     let c3 = commit_file(&origin, "b.txt", b"v3", "c3");
-    let h2 = clone_or_pull(origin_dir.path().to_string_lossy().as_ref(), &dest, None).unwrap();
+    let h2 = clone_or_pull(
+        origin_dir.path().to_string_lossy().as_ref(),
+        &dest,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
     assert_eq!(h2, c3.to_string(), "再次调用应更新到最新提交");
 }
+
+#[test]
+fn sparse_clone_checks_out_requested_paths_and_widens() {
+    if !git_cli_available() {
+        return;
+    }
+    let origin_dir = tempfile::tempdir().unwrap();
+    let origin = git2::Repository::init(origin_dir.path()).unwrap();
+    commit_file(&origin, "README.md", b"readme", "c1");
+    commit_file(&origin, "skills/a/SKILL.md", b"a", "c2");
+    commit_file(&origin, "skills/b/SKILL.md", b"b", "c3");
+    commit_file(&origin, "tools/x.txt", b"x", "c4");
+
+    let dest_dir = tempfile::tempdir().unwrap();
+    let dest = dest_dir.path().join("clone");
+    let url = format!("file://{}", origin_dir.path().to_string_lossy());
+
+    clone_or_pull(&url, &dest, None, None, Some(&["skills/a"])).unwrap();
+    assert!(dest.join("README.md").exists());
+    assert!(dest.join("skills/a/SKILL.md").exists());
+    assert!(!dest.join("skills/b").exists());
+    assert!(!dest.join("tools").exists());
+
+    // A cached checkout grows to cover newly requested paths.
+    ensure_sparse_checkout(&dest, Some(&["skills"]), None).unwrap();
+    assert!(dest.join("skills/b/SKILL.md").exists());
+    assert!(!dest.join("tools").exists());
+
+    // Pulling keeps the sparse set; `None` widens to the full tree.
+    clone_or_pull(&url, &dest, None, None, Some(&["skills/a"])).unwrap();
+    assert!(dest.join("skills/b/SKILL.md").exists());
+    ensure_sparse_checkout(&dest, None, None).unwrap();
+    assert!(dest.join("tools/x.txt").exists());
+}
+
+#[test]
+fn worktrees_share_one_mirror_across_refs() {
+    if !git_cli_available() {
+        return;
+    }
+    let origin_dir = tempfile::tempdir().unwrap();
+    let origin = git2::Repository::init(origin_dir.path()).unwrap();
+    commit_file(&origin, "README.md", b"readme", "c1");
+    commit_file(&origin, "skills/a/SKILL.md", b"a", "c2");
+    let main_head = commit_file(&origin, "skills/b/SKILL.md", b"b", "c3");
+    let main = origin.head().unwrap().shorthand().unwrap().to_string();
+    let main_commit = origin.find_commit(main_head).unwrap();
+    origin.branch("dev", &main_commit, false).unwrap();
+    origin.set_head("refs/heads/dev").unwrap();
+    let dev_head = commit_file(&origin, "skills/c/SKILL.md", b"c", "c4");
+    origin.set_head(&format!("refs/heads/{}", main)).unwrap();
+
+    let cache = tempfile::tempdir().unwrap();
+    let mirror = cache.path().join("mirrors/r.git");
+    let wt_main = cache.path().join("worktrees/main");
+    let wt_dev = cache.path().join("worktrees/dev");
+    let url = format!("file://{}", origin_dir.path().to_string_lossy());
+
+    let h1 = fetch_into_worktree(&url, &mirror, &wt_main, None, None, Some(&["skills/a"])).unwrap();
+    assert_eq!(h1, main_head.to_string());
+    assert!(is_linked_worktree(&wt_main));
+    assert!(wt_main.join("skills/a/SKILL.md").exists());
+    assert!(!wt_main.join("skills/b").exists());
+
+    let h2 = fetch_into_worktree(&url, &mirror, &wt_dev, Some("dev"), None, None).unwrap();
+    assert_eq!(h2, dev_head.to_string());
+    assert!(wt_dev.join("skills/c/SKILL.md").exists());
+    // The first worktree keeps its own sparse set and revision.
+    assert!(!wt_main.join("skills/c").exists());
+    assert!(!wt_main.join("skills/b").exists());
+
+    // Refetching moves the worktree to the new head and widens it on request.
+    origin.set_head("refs/heads/dev").unwrap();
+    let dev_next = commit_file(&origin, "skills/c/SKILL.md", b"c2", "c5");
+    origin.set_head(&format!("refs/heads/{}", main)).unwrap();
+    let h3 = fetch_into_worktree(&url, &mirror, &wt_dev, Some("dev"), None, None).unwrap();
+    assert_eq!(h3, dev_next.to_string());
+    assert_eq!(fs::read(wt_dev.join("skills/c/SKILL.md")).unwrap(), b"c2");
+    fetch_into_worktree(&url, &mirror, &wt_main, None, None, None).unwrap();
+    assert!(wt_main.join("skills/b/SKILL.md").exists());
+
+    // A worktree whose mirror is gone is recreated instead of reused.
+    remove_worktree(&mirror, &wt_dev).unwrap();
+    fs::remove_dir_all(&mirror).unwrap();
+    assert!(!is_linked_worktree(&wt_main));
+    fetch_into_worktree(&url, &mirror, &wt_main, None, None, None).unwrap();
+    assert!(is_linked_worktree(&wt_main));
+    assert!(wt_main.join("skills/b/SKILL.md").exists());
+}
+
+#[test]
+fn clone_in_a_job_reports_progress_and_honours_cancellation() {
+    if !git_cli_available() {
+        return;
+    }
+    let origin_dir = tempfile::tempdir().unwrap();
+    let origin = git2::Repository::init(origin_dir.path()).unwrap();
+    commit_file(&origin, "skills/a/SKILL.md", b"a", "c1");
+    let url = format!("file://{}", origin_dir.path().to_string_lossy());
+    let cache = tempfile::tempdir().unwrap();
+
+    let registry = JobRegistry::default();
+    let phases = Arc::new(Mutex::new(Vec::new()));
+    let sink = phases.clone();
+    let job = registry.start("install_git", move |info| {
+        if let Some(phase) = &info.progress.phase {
+            sink.lock().unwrap().push(phase.clone());
+        }
+    });
+    job.run(|| clone_or_pull(&url, &cache.path().join("a"), None, None, None))
+        .unwrap();
+    assert!(phases
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|phase| phase.contains("objects")));
+
+    registry.cancel(job.id()).unwrap();
+    let err = job
+        .run(|| clone_or_pull(&url, &cache.path().join("b"), None, None, None))
+        .unwrap_err();
+    assert!(is_cancelled(&err));
+}
+
+#[test]
+fn run_bounded_keeps_order_and_caps_concurrency() {
+    let running = AtomicUsize::new(0);
+    let peak = AtomicUsize::new(0);
+    let results = run_bounded((0..12).collect(), 3, |n: u32| {
+        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+        peak.fetch_max(now, Ordering::SeqCst);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        running.fetch_sub(1, Ordering::SeqCst);
+        n * 2
+    });
+    assert_eq!(results, (0..12).map(|n| n * 2).collect::<Vec<_>>());
+    assert!(peak.load(Ordering::SeqCst) <= 3);
+    assert!(peak.load(Ordering::SeqCst) > 1);
+    assert!(run_bounded(Vec::<u32>::new(), 4, |n| n).is_empty());
+}
@@ -0,0 +1,139 @@
+use super::{parse_git_source, recognize_git_host_url};
+
+fn parts(input: &str) -> (String, Option<String>, Option<String>) {
+    let p = parse_git_source(input);
+    (p.clone_url, p.branch, p.subpath)
+}
+
+fn some(s: &str) -> Option<String> {
+    Some(s.to_string())
+}
+
+#[test]
+fn parses_gitlab_folder_urls_with_nested_groups() {
+    assert_eq!(
+        parts("https://gitlab.com/acme/platform/skills/-/tree/main/skills/lint"),
+        (
+            "https://gitlab.com/acme/platform/skills.git".to_string(),
+            some("main"),
+            some("skills/lint")
+        )
+    );
+    assert_eq!(
+        parts("https://git.acme.internal/team/skills/-/blob/dev/SKILL.md"),
+        (
+            "https://git.acme.internal/team/skills.git".to_string(),
+            some("dev"),
+            some("SKILL.md")
+        )
+    );
+    assert_eq!(
+        parts("https://gitlab.com/acme/skills"),
+        ("https://gitlab.com/acme/skills.git".to_string(), None, None)
+    );
+}
+
+#[test]
+fn parses_gitea_and_bitbucket_folder_urls() {
+    assert_eq!(
+        parts("https://codeberg.org/acme/skills/src/branch/main/skills/fmt"),
+        (
+            "https://codeberg.org/acme/skills.git".to_string(),
+            some("main"),
+            some("skills/fmt")
+        )
+    );
+    assert_eq!(
+        parts("https://gitea.acme.dev/acme/skills/src/tag/v1.2"),
+        (
+            "https://gitea.acme.dev/acme/skills.git".to_string(),
+            some("v1.2"),
+            None
+        )
+    );
+    assert_eq!(
+        parts("https://bitbucket.org/acme/skills/src/main/skills/x/"),
+        (
+            "https://bitbucket.org/acme/skills.git".to_string(),
+            some("main"),
+            some("skills/x")
+        )
+    );
+    assert_eq!(
+        parts("https://bitbucket.org/acme/skills.git"),
+        (
+            "https://bitbucket.org/acme/skills.git".to_string(),
+            None,
+            None
+        )
+    );
+}
+
+#[test]
+fn passes_through_ssh_and_unknown_urls() {
+    assert_eq!(
+        parts("git@git.acme.internal:team/skills.git"),
+        (
+            "git@git.acme.internal:team/skills.git".to_string(),
+            None,
+            None
+        )
+    );
+    assert_eq!(
+        parts("ssh://git@git.acme.internal:2222/team/skills.git"),
+        (
+            "ssh://git@git.acme.internal:2222/team/skills.git".to_string(),
+            None,
+            None
+        )
+    );
+    assert_eq!(
+        parts("https://git.acme.internal/team/skills.git"),
+        (
+            "https://git.acme.internal/team/skills.git".to_string(),
+            None,
+            None
+        )
+    );
+}
+
+#[test]
+fn fragment_sets_ref_and_subpath() {
+    assert_eq!(
+        parts("git@git.acme.internal:team/skills.git#release/2.0:skills/lint"),
+        (
+            "git@git.acme.internal:team/skills.git".to_string(),
+            some("release/2.0"),
+            some("skills/lint")
+        )
+    );
+    assert_eq!(
+        parts("acme/skills#dev"),
+        (
+            "https://github.com/acme/skills.git".to_string(),
+            some("dev"),
+            None
+        )
+    );
+    assert_eq!(
+        parts("/srv/repos/skills#:skills/lint/"),
+        ("/srv/repos/skills".to_string(), None, some("skills/lint"))
+    );
+    // Fragment wins over what the web URL encoded.
+    assert_eq!(
+        parts("https://github.com/acme/skills/tree/main/a#v2:b"),
+        (
+            "https://github.com/acme/skills.git".to_string(),
+            some("v2"),
+            some("b")
+        )
+    );
+}
+
+#[test]
+fn recognizes_only_known_host_urls() {
+    assert!(recognize_git_host_url("https://github.com/acme/skills").is_some());
+    assert!(recognize_git_host_url("https://gitlab.com/acme/skills/-/tree/main/x").is_some());
+    assert!(recognize_git_host_url("https://example.com/acme/skills").is_none());
+    assert!(recognize_git_host_url("git@git.acme.internal:team/skills.git").is_none());
+}
@@ -1,6 +1,6 @@
 use mockito::Matcher;
 
-use super::search_github_repos_inner;
+use super::{search_github_repos_inner, search_github_skills_inner};
 
 fn json_one_repo() -> String {
     r#"{
@@ -33,7 +33,7 @@ fn limit_is_clamped() {
         .with_body(json_one_repo())
         .create();
 
-    let out = search_github_repos_inner(&server.url(), "hello", 0).unwrap();
+    let out = search_github_repos_inner(&server.url(), "hello", 0, None).unwrap();
     assert_eq!(out.len(), 1);
 
     let _m2 = server
@@ -47,7 +47,7 @@ fn limit_is_clamped() {
         .with_body(json_one_repo())
         .create();
 
-    let _ = search_github_repos_inner(&server.url(), "hello", 999).unwrap();
+    let _ = search_github_repos_inner(&server.url(), "hello", 999, None).unwrap();
 }
 
 #[test]
@@ -64,7 +64,7 @@ fn maps_fields() {
         .with_body(json_one_repo())
         .create();
 
-    let out = search_github_repos_inner(&server.url(), "x", 2).unwrap();
+    let out = search_github_repos_inner(&server.url(), "x", 2, None).unwrap();
     assert_eq!(out[0].full_name, "o/r");
     assert_eq!(out[0].stars, 123);
 }
@@ -78,7 +78,111 @@ fn http_error_has_context() {
         .with_body("oops")
         .create();
 
-    let err = search_github_repos_inner(&server.url(), "x", 2).unwrap_err();
+    let err = search_github_repos_inner(&server.url(), "x", 2, None).unwrap_err();
     let msg = format!("{:#}", err);
     assert!(msg.contains("GitHub search returned error"), "{msg}");
 }
+
+#[test]
+fn sends_token_when_configured() {
+    let mut server = mockito::Server::new();
+    let _m = server
+        .mock("GET", "/search/repositories")
+        .match_query(Matcher::Any)
+        .match_header("authorization", "Bearer secret-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json_one_repo())
+        .create();
+
+    let out = search_github_repos_inner(&server.url(), "x", 2, Some("secret-token")).unwrap();
+    assert_eq!(out.len(), 1);
+}
+
+fn code_hit(path: &str, repo: &str) -> String {
+    format!(
+        r#"{{"name": "{name}", "path": "{path}",
+            "repository": {{"full_name": "{repo}", "html_url": "https://github.com/{repo}",
+                            "description": "repo {repo}"}}}}"#,
+        name = path.rsplit('/').next().unwrap(),
+        path = path,
+        repo = repo
+    )
+}
+
+#[test]
+fn code_search_groups_skill_files_by_repository() {
+    let mut server = mockito::Server::new();
+    let hits = [
+        code_hit("skills/pdf/SKILL.md", "big/monorepo"),
+        code_hit("SKILL.md", "solo/tool"),
+        code_hit("skills/docx/SKILL.md", "big/monorepo"),
+        code_hit("skills/pdf/SKILL.md", "big/monorepo"),
+        code_hit("notes/skill.md", "solo/tool"),
+    ];
+    let search = server
+        .mock("GET", "/search/code")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("q".into(), "pdf filename:SKILL.md".into()),
+            Matcher::UrlEncoded("per_page".into(), "5".into()),
+        ]))
+        .match_header("authorization", "Bearer secret-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(r#"{{"items": [{}]}}"#, hits.join(",")))
+        .create();
+    server
+        .mock("GET", "/big/monorepo/HEAD/skills/pdf/SKILL.md")
+        .with_status(200)
+        .with_body("---\nname: pdf-forms\ndescription: Fill PDF forms\n---\n")
+        .expect(1)
+        .create();
+    server
+        .mock("GET", Matcher::Regex(r"SKILL\.md$".to_string()))
+        .with_status(404)
+        .create();
+
+    let out = search_github_skills_inner(
+        &server.url(),
+        &server.url(),
+        " pdf ",
+        5,
+        Some("secret-token"),
+    )
+    .unwrap();
+    search.assert();
+    let repos: Vec<_> = out.iter().map(|repo| repo.full_name.as_str()).collect();
+    assert_eq!(repos, vec!["big/monorepo", "solo/tool"]);
+
+    let monorepo = &out[0];
+    assert_eq!(monorepo.html_url, "https://github.com/big/monorepo");
+    let skills: Vec<_> = monorepo
+        .skills
+        .iter()
+        .map(|skill| (skill.name.as_str(), skill.subpath.as_str()))
+        .collect();
+    // Without parsable frontmatter the folder name is used.
+    assert_eq!(
+        skills,
+        vec![("docx", "skills/docx"), ("pdf-forms", "skills/pdf")]
+    );
+    assert_eq!(
+        monorepo.skills[1].description.as_deref(),
+        Some("Fill PDF forms")
+    );
+
+    // A root skill installs with subpath "."; other letter cases are not skills.
+    assert_eq!(out[1].skills.len(), 1);
+    assert_eq!(out[1].skills[0].subpath, ".");
+    assert_eq!(out[1].skills[0].name, "tool");
+}
+
+#[test]
+fn code_search_needs_a_token_and_a_query() {
+    let err =
+        search_github_skills_inner("http://unused", "http://unused", "pdf", 5, None).unwrap_err();
+    assert!(format!("{:#}", err).contains("requires a GitHub token"));
+    let err = search_github_skills_inner("http://unused", "http://unused", "  ", 5, Some("t"))
+        .unwrap_err();
+    assert!(format!("{:#}", err).contains("query is empty"));
+}
@@ -0,0 +1,109 @@
+use crate::core::skill_store::SkillRecord;
+
+use super::{InstalledMatch, InstalledSkills, InstalledState};
+
+fn skill(id: &str, source_type: &str, source_ref: &str, revision: Option<&str>) -> SkillRecord {
+    SkillRecord {
+        id: id.to_string(),
+        name: id.to_string(),
+        description: None,
+        category: None,
+        source_type: source_type.to_string(),
+        source_ref: Some(source_ref.to_string()),
+        source_revision: revision.map(str::to_string),
+        central_path: format!("/central/{}", id),
+        content_hash: None,
+        created_at: 0,
+        updated_at: 0,
+        last_sync_at: None,
+        last_seen_at: 0,
+        status: "ok".to_string(),
+    }
+}
+
+fn state(installed: &InstalledSkills, url: &str, head: Option<&str>) -> InstalledState {
+    installed.lookup(url, head).state
+}
+
+#[test]
+fn matches_installs_by_repository_and_folder() {
+    let installed = InstalledSkills::new(vec![
+        skill(
+            "pdf",
+            "git",
+            "https://github.com/Acme/Skills.git#:skills/pdf",
+            Some("aaaa1111"),
+        ),
+        skill(
+            "root",
+            "git",
+            "https://github.com/solo/tool",
+            Some("bbbb2222"),
+        ),
+        skill("local", "local", "/home/me/skills/docx", None),
+    ]);
+
+    assert_eq!(
+        installed.lookup("https://github.com/acme/skills/tree/main/skills/pdf", None),
+        InstalledMatch {
+            state: InstalledState::Installed,
+            skill_id: Some("pdf".to_string()),
+        }
+    );
+    assert_eq!(
+        state(&installed, "https://github.com/solo/tool/", None),
+        InstalledState::Installed
+    );
+    // Other folders of the same repository, and local installs, do not count.
+    assert_eq!(
+        installed.lookup("https://github.com/acme/skills/tree/main/skills/docx", None),
+        InstalledMatch::default()
+    );
+    assert_eq!(
+        state(&installed, "/home/me/skills/docx", None),
+        InstalledState::NotInstalled
+    );
+}
+
+#[test]
+fn compares_revisions_on_the_same_branch_or_pin() {
+    let installed = InstalledSkills::new(vec![
+        skill(
+            "branch",
+            "git",
+            "https://github.com/acme/a/tree/main/x",
+            Some("AAAA1111ffff"),
+        ),
+        skill(
+            "pinned",
+            "git",
+            "https://github.com/acme/b.git#1234567:y",
+            Some("1234567deadbeef"),
+        ),
+    ]);
+
+    let url = "https://github.com/acme/a/tree/main/x";
+    assert_eq!(
+        state(&installed, url, Some("aaaa1111")),
+        InstalledState::Installed
+    );
+    assert_eq!(
+        state(&installed, url, Some("cccc3333")),
+        InstalledState::Outdated
+    );
+    // Results on the default branch are not compared with an install from `main`.
+    assert_eq!(
+        state(&installed, "https://github.com/acme/a#:x", Some("cccc3333")),
+        InstalledState::Installed
+    );
+
+    // Team index pins are compared directly.
+    assert_eq!(
+        state(&installed, "https://github.com/acme/b.git#1234567:y", None),
+        InstalledState::Installed
+    );
+    assert_eq!(
+        state(&installed, "https://github.com/acme/b.git#89abcde:y", None),
+        InstalledState::Outdated
+    );
+}
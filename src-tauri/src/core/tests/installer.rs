@@ -1,7 +1,9 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::core::skill_store::{SkillStore, SkillTargetRecord};
+use crate::core::skill_store::{
+    GitCacheEntryRecord, SkillPublicationRecord, SkillStore, SkillTargetRecord,
+};
 
 fn make_store() -> (tempfile::TempDir, SkillStore) {
     let dir = tempfile::tempdir().expect("tempdir");
@@ -59,32 +61,32 @@ fn commit_all(repo: &git2::Repository, msg: &str) -> git2::Oid {
 
 #[test]
 fn parses_github_urls() {
-    let p = super::parse_github_url("https://github.com/owner/repo");
+    let p = super::parse_git_source("https://github.com/owner/repo");
     assert_eq!(p.clone_url, "https://github.com/owner/repo.git");
     assert!(p.branch.is_none());
     assert!(p.subpath.is_none());
 
-    let p = super::parse_github_url("anthropics/skills");
+    let p = super::parse_git_source("anthropics/skills");
     assert_eq!(p.clone_url, "https://github.com/anthropics/skills.git");
     assert!(p.branch.is_none());
     assert!(p.subpath.is_none());
 
-    let p = super::parse_github_url("github.com/owner/repo");
+    let p = super::parse_git_source("github.com/owner/repo");
     assert_eq!(p.clone_url, "https://github.com/owner/repo.git");
     assert!(p.branch.is_none());
     assert!(p.subpath.is_none());
 
-    let p = super::parse_github_url("https://github.com/owner/repo/tree/main/skills/x");
+    let p = super::parse_git_source("https://github.com/owner/repo/tree/main/skills/x");
     assert_eq!(p.clone_url, "https://github.com/owner/repo.git");
     assert_eq!(p.branch.as_deref(), Some("main"));
     assert_eq!(p.subpath.as_deref(), Some("skills/x"));
 
-    let p = super::parse_github_url("owner/repo/tree/main/skills/x");
+    let p = super::parse_git_source("owner/repo/tree/main/skills/x");
     assert_eq!(p.clone_url, "https://github.com/owner/repo.git");
     assert_eq!(p.branch.as_deref(), Some("main"));
     assert_eq!(p.subpath.as_deref(), Some("skills/x"));
 
-    let p = super::parse_github_url("/local/path/to/repo");
+    let p = super::parse_git_source("/local/path/to/repo");
     assert_eq!(p.clone_url, "/local/path/to/repo");
 }
 
@@ -217,6 +219,48 @@ fn lists_and_installs_git_skills_without_network() {
     assert!(res.central_path.exists());
 }
 
+#[test]
+fn update_refuses_to_overwrite_unpushed_publish_commits() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+
+    let source = tempfile::tempdir().unwrap();
+    fs::write(source.path().join("SKILL.md"), b"---\nname: x\n---\n").unwrap();
+    fs::write(source.path().join("a.txt"), b"v1").unwrap();
+    let res = super::install_local_skill(
+        app.handle(),
+        &store,
+        source.path(),
+        Some("published".to_string()),
+    )
+    .unwrap();
+    store
+        .upsert_skill_publication(&SkillPublicationRecord {
+            skill_id: res.skill_id.clone(),
+            remote_url: "https://example.com/acme/skills.git".to_string(),
+            branch: "main".to_string(),
+            subpath: None,
+            work_dir: res.central_path.to_string_lossy().to_string(),
+            last_commit: Some("b".repeat(40)),
+            pushed_commit: Some("a".repeat(40)),
+            last_pushed_at: None,
+            created_at: 0,
+            updated_at: 0,
+        })
+        .unwrap();
+
+    fs::write(source.path().join("a.txt"), b"v2").unwrap();
+    let err = match super::update_managed_skill_from_source(app.handle(), &store, &res.skill_id) {
+        Ok(_) => panic!("expected error"),
+        Err(err) => err,
+    };
+    assert!(format!("{:#}", err).contains("unpushed local changes"));
+    assert_eq!(fs::read(res.central_path.join("a.txt")).unwrap(), b"v1");
+}
+
 #[test]
 fn install_git_skill_errors_on_multi_skills_repo_root() {
     let app = tauri::test::mock_app();
@@ -327,3 +371,93 @@ fn install_local_selection_validates_skill_md() {
     };
     assert!(format!("{:#}", err).contains("SKILL_INVALID|missing_skill_md"));
 }
+
+#[test]
+fn bulk_selection_install_shares_one_checkout() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+
+    let repo_dir = tempfile::tempdir().unwrap();
+    for name in ["a", "b"] {
+        fs::create_dir_all(repo_dir.path().join("skills").join(name)).unwrap();
+        fs::write(
+            repo_dir.path().join("skills").join(name).join("SKILL.md"),
+            format!("---\nname: {}\n---\n", name),
+        )
+        .unwrap();
+    }
+    init_git_repo(repo_dir.path());
+
+    let subpaths = vec![
+        "skills/a".to_string(),
+        "skills/b".to_string(),
+        "skills/missing".to_string(),
+    ];
+    let results = super::install_git_selections(
+        app.handle(),
+        &store,
+        repo_dir.path().to_string_lossy().as_ref(),
+        &subpaths,
+    )
+    .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].1.as_ref().unwrap().name, "a");
+    assert_eq!(results[1].1.as_ref().unwrap().name, "b");
+    let err = results[2].1.as_ref().err().unwrap();
+    assert!(format!("{:#}", err).contains("path not found"));
+
+    // Both skills came from a single cached checkout of the repository.
+    let checkouts: Vec<_> = store
+        .list_git_cache_entries()
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.kind != "mirror")
+        .collect();
+    assert_eq!(checkouts.len(), 1);
+    assert!(Path::new(&checkouts[0].path).join("skills/b").exists());
+}
+
+#[test]
+fn dropping_a_mirror_drops_its_worktrees() {
+    let (_dir, store) = make_store();
+    let cache = tempfile::tempdir().unwrap();
+    let index = |key: &str, kind: &str, mirror_key: Option<&str>| {
+        let path = cache.path().join(key);
+        fs::create_dir_all(&path).unwrap();
+        store
+            .upsert_git_cache_entry(&GitCacheEntryRecord {
+                key: key.to_string(),
+                kind: kind.to_string(),
+                clone_url: "https://example.com/o/r.git".to_string(),
+                ref_name: None,
+                mirror_key: mirror_key.map(|k| k.to_string()),
+                path: path.to_string_lossy().to_string(),
+                head: None,
+                size_bytes: 5,
+                last_fetched_at: 1,
+                last_used_at: 1,
+            })
+            .unwrap();
+        path
+    };
+    let mirror = index("m", "mirror", None);
+    let main = index("wt-main", "worktree", Some("m"));
+    let dev = index("wt-dev", "worktree", Some("m"));
+    let other = index("wt-other", "worktree", Some("n"));
+
+    super::drop_mirror(&store, "m", &mirror);
+
+    for path in [&mirror, &main, &dev] {
+        assert!(!path.exists());
+    }
+    assert!(other.exists());
+    let keys: Vec<_> = store
+        .list_git_cache_entries()
+        .unwrap()
+        .into_iter()
+        .map(|e| e.key)
+        .collect();
+    assert_eq!(keys, vec!["wt-other".to_string()]);
+}
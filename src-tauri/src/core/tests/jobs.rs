@@ -0,0 +1,135 @@
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+
+use super::{
+    current, is_cancelled, parse_git_progress, JobInfo, JobProgress, JobReader, JobRegistry,
+    JobStatus, MAX_FINISHED_JOBS,
+};
+
+fn recorder() -> (
+    Arc<Mutex<Vec<JobInfo>>>,
+    impl Fn(&JobInfo) + Send + Sync + 'static,
+) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    (events, move |info: &JobInfo| {
+        sink.lock().unwrap().push(info.clone())
+    })
+}
+
+#[test]
+fn job_lifecycle_emits_changes_and_records_outcome() {
+    let registry = JobRegistry::default();
+    let (events, notify) = recorder();
+    let job = registry.start("install_git", notify);
+
+    job.set_phase("Receiving objects");
+    job.report(|p| p.current = Some(3));
+    // Unchanged progress is not re-emitted.
+    job.report(|p| p.current = Some(3));
+    job.finish(Ok(vec!["a"]), |err| err.to_string());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[2].progress.current, Some(3));
+    let info = registry.get(job.id()).unwrap();
+    assert_eq!(info.status, JobStatus::Succeeded);
+    assert_eq!(info.result, Some(serde_json::json!(["a"])));
+    assert!(info.finished_at.is_some());
+    // Finished jobs cannot be cancelled any more.
+    assert!(!registry.cancel(job.id()).unwrap());
+    assert!(registry.cancel("job-missing").is_err());
+}
+
+#[test]
+fn cancelled_and_failed_jobs_are_told_apart() {
+    let registry = JobRegistry::default();
+    let cancelled = registry.start("update_skills", |_| {});
+    let failed = registry.start("update_skills", |_| {});
+
+    assert!(registry.cancel(cancelled.id()).unwrap());
+    let err = cancelled.check_cancelled().unwrap_err();
+    assert!(is_cancelled(&err.context("while updating")));
+    cancelled.finish(cancelled.check_cancelled().map(|()| 1), |err| {
+        err.to_string()
+    });
+    failed.finish(Err::<(), _>(anyhow::anyhow!("boom")), |err| {
+        format!("nice: {}", err)
+    });
+
+    let cancelled = registry.get(cancelled.id()).unwrap();
+    assert_eq!(cancelled.status, JobStatus::Cancelled);
+    let failed = registry.get(failed.id()).unwrap();
+    assert_eq!(failed.status, JobStatus::Failed);
+    assert_eq!(failed.error.as_deref(), Some("nice: boom"));
+}
+
+#[test]
+fn only_recent_finished_jobs_are_kept() {
+    let registry = JobRegistry::default();
+    let running = registry.start("long", |_| {});
+    for _ in 0..MAX_FINISHED_JOBS + 5 {
+        registry
+            .start("short", |_| {})
+            .finish(Ok(()), |err| err.to_string());
+    }
+    let jobs = registry.list();
+    assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 1);
+    assert_eq!(jobs[0].id, running.id());
+    assert_eq!(jobs[1].id, "job-7");
+}
+
+#[test]
+fn run_sets_the_current_job_for_nested_code() {
+    let registry = JobRegistry::default();
+    let job = registry.start("install_git", |_| {});
+    assert!(current().is_none());
+    let seen = job.run(|| current().map(|j| j.id().to_string()));
+    assert_eq!(seen.as_deref(), Some(job.id()));
+    assert!(current().is_none());
+}
+
+#[test]
+fn job_reader_reports_bytes_and_stops_when_cancelled() {
+    let registry = JobRegistry::default();
+    let job = registry.start("install_git", |_| {});
+    let data = vec![7u8; 200 * 1024];
+
+    job.run(|| {
+        let mut reader = JobReader::new(data.as_slice(), "Downloading", Some(data.len() as u64));
+        let mut first = vec![0u8; 100 * 1024];
+        reader.read_exact(&mut first).unwrap();
+        let info = registry.get(job.id()).unwrap();
+        assert_eq!(info.progress.phase.as_deref(), Some("Downloading"));
+        assert_eq!(info.progress.total, Some(200 * 1024));
+        assert_eq!(info.progress.bytes, Some(100 * 1024));
+
+        registry.cancel(job.id()).unwrap();
+        let err = reader.read(&mut first).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Other);
+    });
+}
+
+#[test]
+fn parses_git_progress_lines() {
+    assert_eq!(
+        parse_git_progress("Receiving objects:  45% (450/1000), 1.50 MiB | 2.00 MiB/s"),
+        Some(JobProgress {
+            phase: Some("Receiving objects".to_string()),
+            current: Some(450),
+            total: Some(1000),
+            bytes: Some(1572864),
+            current_skill: None,
+        })
+    );
+    let counting = parse_git_progress("remote: Counting objects: 100% (12/12), done.").unwrap();
+    assert_eq!(counting.phase.as_deref(), Some("Counting objects"));
+    assert_eq!((counting.current, counting.total), (Some(12), Some(12)));
+    assert_eq!(counting.bytes, None);
+    let enumerating = parse_git_progress("remote: Enumerating objects: 42, done.").unwrap();
+    assert_eq!(enumerating.current, Some(42));
+
+    assert!(parse_git_progress("Cloning into bare repository 'x.git'...").is_none());
+    assert!(parse_git_progress("fatal: repository 'x' not found").is_none());
+    assert!(parse_git_progress("error: 403 forbidden").is_none());
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::process::Command;
+
+use crate::core::skill_store::SkillStore;
+
+use super::{
+    get_network_config, get_offline_mode, is_offline_error, needs_network, probe,
+    set_network_config, set_offline_mode, NetworkConfig, NetworkSettings, Offline,
+};
+
+const TEST_CA_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBkTCCATegAwIBAgIUNPEL/FLK198BfySVIgJRbp7xsCkwCgYIKoZIzj0EAwIw
+HTEbMBkGA1UEAwwSU2tpbGxzIEh1YiBUZXN0IENBMCAXDTI2MTAxODEzNTk0NloY
+DzIxMjYwOTI0MTM1OTQ2WjAdMRswGQYDVQQDDBJTa2lsbHMgSHViIFRlc3QgQ0Ew
+WTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAQVojAXLAYi4kxM74IOOUL7hzSYEMee
+bIsHESooP+mozm1We98+CUDxcLy3smkTEa8Z5NbMh5p1OmJIHoKf0j7yo1MwUTAd
+BgNVHQ4EFgQU/R6T5/BQiCq3qOjczII5VXYYVaQwHwYDVR0jBBgwFoAU/R6T5/BQ
+iCq3qOjczII5VXYYVaQwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNIADBF
+AiEAna54lGlktl9BOCaoD9Xvch0I2s0zFtMrxL7173ZqIswCIFcJejl5n/gN0Eq9
+VJm3VuHZzilew9BirNhQeSHSxA7c
+-----END CERTIFICATE-----
+";
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn config(proxy: Option<&str>, no_proxy: Option<&str>, ca: Option<&str>) -> NetworkConfig {
+    NetworkConfig {
+        proxy_url: proxy.map(str::to_string),
+        no_proxy: no_proxy.map(str::to_string),
+        ca_cert_path: ca.map(str::to_string),
+    }
+}
+
+#[test]
+fn settings_are_normalized_and_can_be_cleared() {
+    let (_dir, store) = make_store();
+    assert_eq!(get_network_config(&store), NetworkConfig::default());
+
+    let saved = set_network_config(
+        &store,
+        config(
+            Some(" http://proxy.corp:3128 "),
+            Some(" localhost, ,.corp.example ,10.0.0.0/8"),
+            Some("  "),
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        saved,
+        config(
+            Some("http://proxy.corp:3128"),
+            Some("localhost,.corp.example,10.0.0.0/8"),
+            None
+        )
+    );
+    assert_eq!(get_network_config(&store), saved);
+
+    set_network_config(&store, NetworkConfig::default()).unwrap();
+    assert_eq!(get_network_config(&store), NetworkConfig::default());
+}
+
+#[test]
+fn invalid_settings_are_rejected_and_not_saved() {
+    let (dir, store) = make_store();
+    set_network_config(&store, config(Some("http://proxy:8080"), None, None)).unwrap();
+
+    for proxy in ["socks5://proxy:1080", "proxy:8080", "http://"] {
+        assert!(
+            set_network_config(&store, config(Some(proxy), None, None)).is_err(),
+            "{}",
+            proxy
+        );
+    }
+    let not_pem = dir.path().join("not.pem");
+    std::fs::write(&not_pem, "hello").unwrap();
+    for ca in [not_pem, dir.path().join("missing.pem")] {
+        let ca = ca.to_string_lossy().to_string();
+        assert!(set_network_config(&store, config(None, None, Some(&ca))).is_err());
+    }
+    assert_eq!(
+        get_network_config(&store).proxy_url.as_deref(),
+        Some("http://proxy:8080")
+    );
+}
+
+#[test]
+fn settings_apply_to_git_commands_and_http_clients() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = dir.path().join("corp.pem");
+    std::fs::write(&ca, TEST_CA_PEM).unwrap();
+    let bundle_dir = dir.path().join("data");
+    let settings = NetworkSettings::from_config(
+        &config(
+            Some("http://proxy.corp:3128"),
+            Some("localhost"),
+            Some(&ca.to_string_lossy()),
+        ),
+        Some(&bundle_dir),
+    )
+    .unwrap();
+
+    let mut cmd = Command::new("git");
+    settings.apply_to_command(&mut cmd);
+    let envs: HashMap<&OsStr, Option<&OsStr>> = cmd.get_envs().collect();
+    let env = |key: &str| envs.get(OsStr::new(key)).copied().flatten();
+    assert_eq!(
+        env("https_proxy"),
+        Some(OsStr::new("http://proxy.corp:3128"))
+    );
+    assert_eq!(env("NO_PROXY"), Some(OsStr::new("localhost")));
+    let bundle = env("GIT_SSL_CAINFO").expect("CA bundle");
+    let bundle = std::fs::read_to_string(bundle).unwrap();
+    assert!(bundle.ends_with(TEST_CA_PEM));
+
+    settings.client_builder().unwrap().build().unwrap();
+
+    // Without settings, git keeps using the environment and its own trust store.
+    let mut plain = Command::new("git");
+    NetworkSettings::default().apply_to_command(&mut plain);
+    assert_eq!(plain.get_envs().count(), 0);
+}
+
+#[test]
+fn offline_mode_is_persisted() {
+    let (_dir, store) = make_store();
+    assert!(!get_offline_mode(&store));
+    set_offline_mode(&store, true).unwrap();
+    assert!(get_offline_mode(&store));
+    set_offline_mode(&store, false).unwrap();
+    assert!(!get_offline_mode(&store));
+}
+
+#[test]
+fn local_sources_do_not_need_the_network() {
+    for url in [
+        "https://github.com/owner/repo",
+        "git@github.com:owner/repo.git",
+        "ssh://git@gitlab.example.com/team/skills.git",
+    ] {
+        assert!(needs_network(url), "{}", url);
+    }
+    for url in [
+        "/tmp/skills-repo",
+        "file:///tmp/skills-repo",
+        r"C:\skills\repo",
+        "http://127.0.0.1:8080/api",
+        "http://localhost:3000",
+    ] {
+        assert!(!needs_network(url), "{}", url);
+    }
+}
+
+#[test]
+fn offline_errors_are_recognised_through_context() {
+    let err = anyhow::Error::new(Offline).context("cannot reach https://github.com");
+    assert!(is_offline_error(&err));
+    assert!(!is_offline_error(&anyhow::anyhow!("timed out")));
+}
+
+#[test]
+fn probe_detects_listening_and_closed_ports() {
+    let timeout = std::time::Duration::from_secs(1);
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    assert!(probe("127.0.0.1", port, timeout));
+    drop(listener);
+    assert!(!probe("127.0.0.1", port, timeout));
+}
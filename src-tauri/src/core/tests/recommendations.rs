@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::Path;
+
+use crate::core::discovery::DiscoveredSkill;
+
+use super::{detect_project, rank_skills, relevance, SignalKind};
+
+fn write(root: &Path, relative: &str, content: &str) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn skill(name: &str, description: &str, category: &str, tags: &[&str]) -> DiscoveredSkill {
+    DiscoveredSkill {
+        name: name.to_string(),
+        description: description.to_string(),
+        github_url: format!("https://github.com/acme/{}", name),
+        category: category.to_string(),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+    }
+}
+
+fn signal_ids(root: &Path) -> Vec<String> {
+    detect_project(root)
+        .unwrap()
+        .signals
+        .into_iter()
+        .map(|signal| signal.id)
+        .collect()
+}
+
+#[test]
+fn detects_languages_frameworks_and_tools() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "web/package.json",
+        r#"{"dependencies": {"next": "14.0.0", "react": "18.0.0"},
+            "devDependencies": {"vitest": "1.0.0"}}"#,
+    );
+    write(root, "web/app/page.tsx", "");
+    write(root, "Dockerfile", "FROM node:20");
+    write(root, ".github/workflows/ci.yml", "on: push");
+    write(
+        root,
+        "ml/requirements.txt",
+        "torch==2.1\nPandas>=2\n# comment\n",
+    );
+    // Dependencies of vendored code are not the project's.
+    write(root, "node_modules/vue/package.json", "{}");
+
+    let profile = detect_project(root).unwrap();
+    let ids: Vec<_> = profile.signals.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "typescript",
+            "javascript",
+            "python",
+            "nextjs",
+            "react",
+            "machine-learning",
+            "data",
+            "testing",
+            "docker",
+            "github-actions",
+        ]
+    );
+    let next = &profile.signals[3];
+    assert_eq!(next.kind, SignalKind::Framework);
+    assert_eq!(next.evidence, "web/package.json");
+    assert_eq!(profile.signals[0].evidence, "web/app/page.tsx");
+}
+
+#[test]
+fn reads_text_manifests() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"app\"\n\n[dependencies]\ntauri = { version = \"2\" }\n",
+    );
+    write(
+        dir.path(),
+        "pyproject.toml",
+        "[project]\ndependencies = [\n  \"fastapi>=0.100\",\n]\n",
+    );
+    write(dir.path(), "Gemfile", "gem 'rails', '~> 7.1'\n");
+    assert_eq!(
+        signal_ids(dir.path()),
+        vec!["rust", "python", "ruby", "tauri", "fastapi", "rails"]
+    );
+    assert!(detect_project(&dir.path().join("missing")).is_err());
+}
+
+#[test]
+fn skips_unreadable_manifests() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "Gemfile", "gem 'rails', '~> 7.1'\n");
+    fs::create_dir_all(dir.path().join("legacy")).unwrap();
+    fs::write(dir.path().join("legacy/package.json"), [0xff, 0xfe, 0x00]).unwrap();
+    // The file still counts as evidence; only its dependencies are lost.
+    assert_eq!(signal_ids(dir.path()), vec!["javascript", "ruby", "rails"]);
+}
+
+#[test]
+fn ranks_matching_skills_with_reasons() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        dir.path(),
+        "package.json",
+        r#"{"dependencies": {"next": "14"}}"#,
+    );
+    write(dir.path(), "Dockerfile", "FROM node:20");
+    write(dir.path(), "go.mod", "module example.com/app\n");
+    let profile = detect_project(dir.path()).unwrap();
+
+    let ranked = rank_skills(
+        &profile,
+        vec![
+            (
+                skill("pdf", "Fill PDF forms", "document", &[]),
+                "awesome".to_string(),
+            ),
+            (
+                skill(
+                    "deploy",
+                    "Ship Next.js apps in a container",
+                    "development",
+                    &[],
+                ),
+                "awesome".to_string(),
+            ),
+            (
+                skill(
+                    "nextjs-routes",
+                    "Routing helpers",
+                    "development",
+                    &["react", "javascript"],
+                ),
+                "team".to_string(),
+            ),
+        ],
+    );
+    let names: Vec<_> = ranked.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["nextjs-routes", "deploy"]);
+    assert_eq!(ranked[0].source, "team");
+    assert_eq!(
+        ranked[1].reasons,
+        vec![
+            "the project uses Next.js (package.json)",
+            "the project uses Docker (Dockerfile)",
+        ]
+    );
+
+    // Short keywords only count as tags or in names, and words must match whole.
+    let (score, _) = relevance(
+        &profile,
+        &skill("tools", "Lets you go nodeless", "other", &[]),
+    );
+    assert_eq!(score, 0);
+}
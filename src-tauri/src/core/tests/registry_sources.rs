@@ -0,0 +1,353 @@
+use std::fs;
+use std::path::Path;
+
+use crate::core::discovery::{get_skills_by_category, search_skills};
+use crate::core::skill_store::SkillStore;
+
+use super::{
+    add_registry_source, ensure_default_registry_sources, list_registry_sources,
+    remove_registry_source, set_registry_refresh_interval_hours, set_registry_source_enabled,
+    sync_due_registry_sources, sync_registry_source, sync_registry_sources, RegistrySourceInput,
+    RegistrySourceKind, RECOMMENDED_ID, SKILLS_SH_POPULAR_ID,
+};
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn write_skill(dir: &Path, name: &str, category: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+        dir.join("SKILL.md"),
+        format!(
+            "---\nname: {}\ndescription: {} skill\ncategory: {}\n---\nbody\n",
+            name, name, category
+        ),
+    )
+    .unwrap();
+}
+
+fn input(kind: RegistrySourceKind, location: &Path) -> RegistrySourceInput {
+    RegistrySourceInput {
+        kind,
+        name: None,
+        location: location.to_string_lossy().to_string(),
+    }
+}
+
+fn source_names(store: &SkillStore, source: &str) -> Vec<String> {
+    let mut names: Vec<String> = store
+        .list_discovered_skills()
+        .unwrap()
+        .into_iter()
+        .filter(|s| s.source == source)
+        .map(|s| s.name)
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn default_sources_are_seeded_once() {
+    let (_dir, store) = make_store();
+    ensure_default_registry_sources(&store).unwrap();
+    let sources = list_registry_sources(&store).unwrap();
+    let kinds: Vec<_> = sources.iter().map(|s| s.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            RegistrySourceKind::AwesomeList,
+            RegistrySourceKind::GithubTopic,
+            RegistrySourceKind::Builtin,
+            RegistrySourceKind::Builtin
+        ]
+    );
+    assert_eq!(sources[0].id, "awesome-claude-skills");
+
+    remove_registry_source(&store, "awesome-claude-skills").unwrap();
+    ensure_default_registry_sources(&store).unwrap();
+    assert_eq!(list_registry_sources(&store).unwrap().len(), 3);
+}
+
+#[test]
+fn built_in_lists_are_added_to_earlier_seeds_and_can_be_disabled() {
+    let (_dir, store) = make_store();
+    // Seeded before the built-in lists became sources.
+    store
+        .set_setting("registry_sources_seeded", "true")
+        .unwrap();
+    ensure_default_registry_sources(&store).unwrap();
+    let ids: Vec<_> = list_registry_sources(&store)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    assert_eq!(ids, vec![SKILLS_SH_POPULAR_ID, RECOMMENDED_ID]);
+
+    let stats = sync_registry_source(&store, SKILLS_SH_POPULAR_ID).unwrap();
+    assert!(stats.synced > 0);
+    assert!(!get_skills_by_category(&store, "development").is_empty());
+    let hits = search_skills(&store, "supabase postgres").unwrap();
+    assert_eq!(hits[0].name, "supabase-postgres-best-practices");
+
+    set_registry_source_enabled(&store, RECOMMENDED_ID, false).unwrap();
+    set_registry_source_enabled(&store, SKILLS_SH_POPULAR_ID, false).unwrap();
+    assert!(get_skills_by_category(&store, "development").is_empty());
+    assert!(search_skills(&store, "supabase").unwrap().is_empty());
+    assert!(add_registry_source(
+        &store,
+        RegistrySourceInput {
+            kind: RegistrySourceKind::Builtin,
+            name: None,
+            location: "recommended".to_string(),
+        }
+    )
+    .is_err());
+}
+
+#[test]
+fn local_dir_source_feeds_discovered_skills() {
+    let (dir, store) = make_store();
+    let root = dir.path().join("skills");
+    write_skill(&root.join("pdf"), "pdf", "Document Skills");
+    write_skill(&root.join("team/lint"), "lint", "Development");
+    fs::create_dir_all(root.join("empty")).unwrap();
+
+    let id = add_registry_source(&store, input(RegistrySourceKind::LocalDir, &root)).unwrap();
+    assert_eq!(sync_registry_source(&store, &id).unwrap().synced, 2);
+    assert_eq!(source_names(&store, &id), vec!["lint", "pdf"]);
+    let pdf = store
+        .list_discovered_skills()
+        .unwrap()
+        .into_iter()
+        .find(|s| s.name == "pdf")
+        .unwrap();
+    assert_eq!(pdf.category, "document");
+    assert_eq!(pdf.github_url, root.join("pdf").to_string_lossy());
+
+    let info = &list_registry_sources(&store).unwrap()[0];
+    assert_eq!(info.skill_count, 2);
+    assert!(info.last_synced_at.is_some());
+
+    // A removed skill is delisted on the next sync, and comes back with its history.
+    let lint_first_seen = store.list_discovered_skills().unwrap()[0].first_seen_at;
+    fs::remove_dir_all(root.join("team")).unwrap();
+    let stats = sync_registry_source(&store, &id).unwrap();
+    assert_eq!((stats.synced, stats.added, stats.delisted), (1, 0, 1));
+    assert_eq!(source_names(&store, &id), vec!["pdf"]);
+    write_skill(&root.join("team/lint"), "lint", "Development");
+    let stats = sync_registry_source(&store, &id).unwrap();
+    assert_eq!((stats.synced, stats.added, stats.delisted), (2, 0, 0));
+    let lint = store
+        .list_discovered_skills()
+        .unwrap()
+        .into_iter()
+        .find(|s| s.name == "lint")
+        .unwrap();
+    assert_eq!(lint.first_seen_at, lint_first_seen);
+
+    set_registry_source_enabled(&store, &id, false).unwrap();
+    assert!(source_names(&store, &id).is_empty());
+    assert!(sync_registry_sources(&store).unwrap().is_empty());
+
+    remove_registry_source(&store, &id).unwrap();
+    assert!(list_registry_sources(&store).unwrap().is_empty());
+}
+
+#[test]
+fn json_index_and_awesome_list_files_are_parsed() {
+    let (dir, store) = make_store();
+    let index = dir.path().join("index.json");
+    fs::write(
+        &index,
+        r#"{"skills": [
+            {"name": "docx", "description": "Word files", "githubUrl": "https://github.com/acme/skills/tree/main/docx", "category": "Document", "tags": ["office", "word"]},
+            {"name": "", "url": "https://github.com/acme/ignored"}
+        ]}"#,
+    )
+    .unwrap();
+    let readme = dir.path().join("README.md");
+    fs::write(
+        &readme,
+        "## 🔧 Development & Code Tools\n- [lint](https://github.com/acme/lint) - Lint helper.\n",
+    )
+    .unwrap();
+
+    let json_id =
+        add_registry_source(&store, input(RegistrySourceKind::JsonIndex, &index)).unwrap();
+    let list_id =
+        add_registry_source(&store, input(RegistrySourceKind::AwesomeList, &readme)).unwrap();
+    let results = sync_registry_sources(&store).unwrap();
+    assert_eq!(
+        results.iter().map(|r| r.stats.synced).collect::<Vec<_>>(),
+        vec![1, 1]
+    );
+
+    let skills = store.list_discovered_skills().unwrap();
+    let docx = skills.iter().find(|s| s.name == "docx").unwrap();
+    assert_eq!(docx.source, json_id);
+    assert_eq!(docx.tags, "office,word");
+    assert_eq!(docx.category, "document");
+    let lint = skills.iter().find(|s| s.name == "lint").unwrap();
+    assert_eq!(lint.source, list_id);
+    assert_eq!(lint.category, "development");
+
+    // A top-level array works too.
+    fs::write(
+        &index,
+        r#"[{"name": "xlsx", "repo": "https://github.com/acme/xlsx"}]"#,
+    )
+    .unwrap();
+    sync_registry_source(&store, &json_id).unwrap();
+    assert_eq!(source_names(&store, &json_id), vec!["xlsx"]);
+}
+
+#[test]
+fn failed_sync_is_recorded_and_keeps_previous_skills() {
+    let (dir, store) = make_store();
+    let index = dir.path().join("index.json");
+    fs::write(
+        &index,
+        r#"[{"name": "docx", "url": "https://github.com/acme/docx"}]"#,
+    )
+    .unwrap();
+    let id = add_registry_source(&store, input(RegistrySourceKind::JsonIndex, &index)).unwrap();
+    sync_registry_source(&store, &id).unwrap();
+
+    fs::write(&index, "not json").unwrap();
+    assert!(sync_registry_source(&store, &id).is_err());
+    let info = &list_registry_sources(&store).unwrap()[0];
+    assert!(info
+        .last_error
+        .as_deref()
+        .unwrap()
+        .contains("invalid skill index"));
+    assert_eq!(info.skill_count, 1);
+
+    let results = sync_registry_sources(&store).unwrap();
+    assert_eq!(results[0].stats.synced, 0);
+    assert!(results[0].error.is_some());
+}
+
+#[test]
+fn invalid_locations_are_rejected() {
+    let (dir, store) = make_store();
+    let missing = dir.path().join("missing");
+    assert!(add_registry_source(&store, input(RegistrySourceKind::LocalDir, &missing)).is_err());
+    assert!(add_registry_source(&store, input(RegistrySourceKind::JsonIndex, &missing)).is_err());
+    assert!(add_registry_source(
+        &store,
+        RegistrySourceInput {
+            kind: RegistrySourceKind::GithubTopic,
+            name: None,
+            location: "  ".to_string(),
+        }
+    )
+    .is_err());
+    add_registry_source(
+        &store,
+        RegistrySourceInput {
+            kind: RegistrySourceKind::JsonIndex,
+            name: Some("Team".to_string()),
+            location: "https://skills.example.com/index.json".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(list_registry_sources(&store).unwrap()[0].name, "Team");
+}
+
+#[test]
+fn team_index_files_feed_discovered_skills() {
+    let (dir, store) = make_store();
+    let index = dir.path().join("team.yaml");
+    fs::write(
+        &index,
+        "version: 1\nskills:\n  - name: lint\n    gitUrl: https://git.acme.dev/skills.git\n    subpath: lint\n    revision: v2\n    tags: [ci]\n",
+    )
+    .unwrap();
+    let id = add_registry_source(&store, input(RegistrySourceKind::TeamIndex, &index)).unwrap();
+    assert_eq!(sync_registry_source(&store, &id).unwrap().synced, 1);
+    let lint = &store.list_discovered_skills().unwrap()[0];
+    assert_eq!(lint.github_url, "https://git.acme.dev/skills.git#v2:lint");
+    assert_eq!(lint.tags, "ci");
+
+    fs::write(&index, "version: 9\nskills: []\n").unwrap();
+    assert!(sync_registry_source(&store, &id).is_err());
+    assert_eq!(source_names(&store, &id), vec!["lint"]);
+}
+
+#[test]
+fn url_sources_are_refreshed_conditionally() {
+    let (_dir, store) = make_store();
+    let mut server = mockito::Server::new();
+    let readme = "## 🔧 Development & Code Tools\n- [lint](https://github.com/acme/lint) - Lint.\n";
+    let full = server
+        .mock("GET", "/README.md")
+        .match_header("if-none-match", mockito::Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"abc\"")
+        .with_header("last-modified", "Wed, 01 Jul 2026 10:00:00 GMT")
+        .with_body(readme)
+        .expect(1)
+        .create();
+    let id = add_registry_source(
+        &store,
+        RegistrySourceInput {
+            kind: RegistrySourceKind::AwesomeList,
+            name: None,
+            location: format!("{}/README.md", server.url()),
+        },
+    )
+    .unwrap();
+    let stats = sync_registry_source(&store, &id).unwrap();
+    assert_eq!(
+        (stats.synced, stats.added, stats.not_modified),
+        (1, 1, false)
+    );
+    full.assert();
+    let seen = store.list_discovered_skills().unwrap()[0].last_seen_at;
+
+    let unchanged = server
+        .mock("GET", "/README.md")
+        .match_header("if-none-match", "\"abc\"")
+        .match_header("if-modified-since", "Wed, 01 Jul 2026 10:00:00 GMT")
+        .with_status(304)
+        .expect(1)
+        .create();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let stats = sync_registry_source(&store, &id).unwrap();
+    assert_eq!(
+        (stats.synced, stats.added, stats.not_modified),
+        (1, 0, true)
+    );
+    unchanged.assert();
+    assert!(store.list_discovered_skills().unwrap()[0].last_seen_at > seen);
+
+    // Disabling forgets the validators, so re-enabling fetches the full listing.
+    set_registry_source_enabled(&store, &id, false).unwrap();
+    let record = store.get_registry_source(&id).unwrap().unwrap();
+    assert_eq!((record.etag, record.last_modified), (None, None));
+}
+
+#[test]
+fn only_due_sources_are_synced() {
+    let (dir, store) = make_store();
+    let index = dir.path().join("index.json");
+    fs::write(
+        &index,
+        r#"[{"name": "docx", "url": "https://github.com/acme/docx"}]"#,
+    )
+    .unwrap();
+    add_registry_source(&store, input(RegistrySourceKind::JsonIndex, &index)).unwrap();
+
+    // Never synced: due regardless of the interval.
+    assert_eq!(sync_due_registry_sources(&store).unwrap().len(), 1);
+    assert!(sync_due_registry_sources(&store).unwrap().is_empty());
+
+    set_registry_refresh_interval_hours(&store, 0).unwrap();
+    assert!(sync_due_registry_sources(&store).unwrap().is_empty());
+    assert!(set_registry_refresh_interval_hours(&store, 10_000).is_err());
+}
@@ -0,0 +1,299 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::core::skill_store::{SkillRecord, SkillStore};
+
+use super::{
+    export_skills_bundle, import_skills_bundle_in, read_skill_bundle, BUNDLE_FORMAT, BUNDLE_VERSION,
+};
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn add_skill(store: &SkillStore, id: &str, central: &Path, category: Option<&str>) {
+    fs::create_dir_all(central.join("scripts")).unwrap();
+    fs::create_dir_all(central.join(".git")).unwrap();
+    fs::write(central.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+    fs::write(central.join("SKILL.md"), "---\nname: demo\n---\n# demo\n").unwrap();
+    fs::write(central.join("scripts/run.sh"), "echo hi\n").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(
+            central.join("scripts/run.sh"),
+            fs::Permissions::from_mode(0o755),
+        )
+        .unwrap();
+    }
+    store
+        .upsert_skill(&SkillRecord {
+            id: id.to_string(),
+            name: central.file_name().unwrap().to_string_lossy().to_string(),
+            description: Some("demo skill".to_string()),
+            category: category.map(|c| c.to_string()),
+            source_type: "git".to_string(),
+            source_ref: Some("https://github.com/me/demo".to_string()),
+            source_revision: Some("abc123".to_string()),
+            central_path: central.to_string_lossy().to_string(),
+            content_hash: None,
+            created_at: 1,
+            updated_at: 1,
+            last_sync_at: None,
+            last_seen_at: 1,
+            status: "ok".to_string(),
+        })
+        .unwrap();
+}
+
+fn write_raw_bundle(path: &Path, entries: &[(&str, &[u8])]) {
+    let file = fs::File::create(path).unwrap();
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        file,
+        flate2::Compression::default(),
+    ));
+    for (name, bytes) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(bytes.len() as u64);
+        header.set_mode(0o644);
+        header.set_entry_type(tar::EntryType::Regular);
+        builder.append_data(&mut header, name, *bytes).unwrap();
+    }
+    builder
+        .into_inner()
+        .unwrap()
+        .finish()
+        .unwrap()
+        .flush()
+        .unwrap();
+}
+
+#[test]
+fn export_then_import_round_trips_files_and_metadata() {
+    let (src_dir, src_store) = make_store();
+    src_store
+        .add_category("team", "Team", "Team skills", "users", "#123456")
+        .unwrap();
+    add_skill(
+        &src_store,
+        "s1",
+        &src_dir.path().join("central/demo"),
+        Some("team"),
+    );
+    add_skill(
+        &src_store,
+        "s2",
+        &src_dir.path().join("central/other"),
+        None,
+    );
+
+    let bundle = src_dir.path().join("out/skills.skillbundle.tar.gz");
+    let exported =
+        export_skills_bundle(&src_store, &["s1".to_string(), "s2".to_string()], &bundle).unwrap();
+    assert_eq!(exported.skill_count, 2);
+    assert_eq!(exported.file_count, 4);
+
+    let manifest = read_skill_bundle(&bundle).unwrap();
+    assert_eq!(manifest.format, BUNDLE_FORMAT);
+    assert_eq!(manifest.version, BUNDLE_VERSION);
+    assert_eq!(manifest.categories.len(), 1);
+    let paths: Vec<&str> = manifest.skills[0]
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["SKILL.md", "scripts/run.sh"]);
+
+    let (dst_dir, dst_store) = make_store();
+    let central = dst_dir.path().join("central");
+    fs::create_dir_all(&central).unwrap();
+    let result = import_skills_bundle_in(&dst_store, &central, &bundle, false).unwrap();
+    assert_eq!(result.imported.len(), 2);
+    assert!(result.skipped.is_empty());
+
+    assert_eq!(
+        fs::read_to_string(central.join("demo/scripts/run.sh")).unwrap(),
+        "echo hi\n"
+    );
+    assert!(!central.join("demo/.git").exists());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(central.join("demo/scripts/run.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_ne!(mode & 0o111, 0);
+    }
+
+    let skills = dst_store.list_skills().unwrap();
+    let demo = skills.iter().find(|s| s.name == "demo").unwrap();
+    assert_eq!(demo.source_type, "git");
+    assert_eq!(
+        demo.source_ref.as_deref(),
+        Some("https://github.com/me/demo")
+    );
+    assert_eq!(demo.source_revision.as_deref(), Some("abc123"));
+    assert_eq!(demo.category.as_deref(), Some("team"));
+    assert!(demo.content_hash.is_some());
+    assert!(dst_store
+        .list_categories()
+        .unwrap()
+        .iter()
+        .any(|c| c.id == "team" && c.color == "#123456"));
+}
+
+#[test]
+fn import_skips_existing_skills_unless_overwrite() {
+    let (dir, store) = make_store();
+    let central = dir.path().join("central");
+    add_skill(&store, "s1", &central.join("demo"), None);
+    let bundle = dir.path().join("demo.skillbundle.tar.gz");
+    export_skills_bundle(&store, &["s1".to_string()], &bundle).unwrap();
+
+    fs::write(central.join("demo/SKILL.md"), "# local edit\n").unwrap();
+    let result = import_skills_bundle_in(&store, &central, &bundle, false).unwrap();
+    assert!(result.imported.is_empty());
+    assert_eq!(result.skipped[0].name, "demo");
+    assert_eq!(
+        fs::read_to_string(central.join("demo/SKILL.md")).unwrap(),
+        "# local edit\n"
+    );
+
+    let result = import_skills_bundle_in(&store, &central, &bundle, true).unwrap();
+    assert_eq!(result.imported[0].skill_id, "s1");
+    assert!(result.imported[0].replaced);
+    assert!(fs::read_to_string(central.join("demo/SKILL.md"))
+        .unwrap()
+        .contains("# demo"));
+    assert_eq!(store.list_skills().unwrap().len(), 1);
+}
+
+#[test]
+fn tampered_bundle_fails_integrity_check() {
+    let (dir, store) = make_store();
+    let manifest = format!(
+        r#"{{"format":"{}","version":1,"created_at":0,"app_version":"0","skills":[
+            {{"name":"demo","description":null,"category":null,"source_type":"local",
+              "source_ref":null,"source_revision":null,"content_hash":null,
+              "files":[{{"path":"SKILL.md","size":7,"sha256":"{}"}}]}}]}}"#,
+        BUNDLE_FORMAT,
+        hex::encode(<sha2::Sha256 as sha2::Digest>::digest(b"# demo\n"))
+    );
+
+    let tampered = dir.path().join("tampered.tar.gz");
+    write_raw_bundle(
+        &tampered,
+        &[
+            ("manifest.json", manifest.as_bytes()),
+            ("skills/demo/SKILL.md", b"# evil\n"),
+        ],
+    );
+    let err = read_skill_bundle(&tampered).unwrap_err();
+    assert!(format!("{:#}", err).contains("integrity check failed"));
+
+    let extra = dir.path().join("extra.tar.gz");
+    write_raw_bundle(
+        &extra,
+        &[
+            ("manifest.json", manifest.as_bytes()),
+            ("skills/demo/SKILL.md", b"# demo\n"),
+            ("skills/demo/payload.sh", b"rm -rf ~\n"),
+        ],
+    );
+    let central = dir.path().join("central");
+    fs::create_dir_all(&central).unwrap();
+    assert!(import_skills_bundle_in(&store, &central, &extra, false).is_err());
+    assert!(!central.join("demo").exists());
+    assert!(store.list_skills().unwrap().is_empty());
+
+    let ok = dir.path().join("ok.tar.gz");
+    write_raw_bundle(
+        &ok,
+        &[
+            ("manifest.json", manifest.as_bytes()),
+            ("skills/demo/SKILL.md", b"# demo\n"),
+        ],
+    );
+    assert_eq!(read_skill_bundle(&ok).unwrap().skills.len(), 1);
+}
+
+#[test]
+fn import_checks_manifest_names_and_content_hash() {
+    let (dir, store) = make_store();
+    let skill_md = b"# demo\n";
+    let manifest = |names: &[&str], content_hash: &str| {
+        let skills: Vec<String> = names
+            .iter()
+            .map(|name| {
+                format!(
+                    r#"{{"name":"{}","description":null,"category":null,"source_type":"local",
+                      "source_ref":null,"source_revision":null,"content_hash":"{}",
+                      "files":[{{"path":"SKILL.md","size":7,"sha256":"{}"}}]}}"#,
+                    name,
+                    content_hash,
+                    hex::encode(<sha2::Sha256 as sha2::Digest>::digest(skill_md))
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"format":"{}","version":1,"created_at":0,"app_version":"0","skills":[{}]}}"#,
+            BUNDLE_FORMAT,
+            skills.join(",")
+        )
+    };
+
+    let duplicate = dir.path().join("duplicate.tar.gz");
+    write_raw_bundle(
+        &duplicate,
+        &[
+            ("manifest.json", manifest(&["demo", "demo"], "x").as_bytes()),
+            ("skills/demo/SKILL.md", skill_md),
+        ],
+    );
+    let err = read_skill_bundle(&duplicate).unwrap_err();
+    assert!(format!("{:#}", err).contains("duplicate skill"));
+
+    let mismatched = dir.path().join("mismatched.tar.gz");
+    write_raw_bundle(
+        &mismatched,
+        &[
+            ("manifest.json", manifest(&["demo"], "0000").as_bytes()),
+            ("skills/demo/SKILL.md", skill_md),
+        ],
+    );
+    let central = dir.path().join("central");
+    fs::create_dir_all(&central).unwrap();
+    let result = import_skills_bundle_in(&store, &central, &mismatched, false).unwrap();
+    assert!(result.imported.is_empty());
+    assert!(result.skipped[0].reason.contains("content hash"));
+    assert!(!central.join("demo").exists());
+    assert_eq!(fs::read_dir(&central).unwrap().count(), 0);
+}
+
+#[test]
+fn overwrite_removes_copy_at_old_central_path() {
+    let (dir, store) = make_store();
+    let old_path = dir.path().join("old-central/demo");
+    add_skill(&store, "s1", &old_path, None);
+    let bundle = dir.path().join("demo.skillbundle.tar.gz");
+    export_skills_bundle(&store, &["s1".to_string()], &bundle).unwrap();
+    assert!(read_skill_bundle(&bundle).unwrap().skills[0]
+        .content_hash
+        .is_some());
+
+    let central = dir.path().join("central");
+    fs::create_dir_all(&central).unwrap();
+    let result = import_skills_bundle_in(&store, &central, &bundle, true).unwrap();
+    assert_eq!(result.imported[0].skill_id, "s1");
+    assert!(!old_path.exists());
+    assert!(central.join("demo/SKILL.md").exists());
+    assert_eq!(
+        store.get_skill_by_id("s1").unwrap().unwrap().central_path,
+        central.join("demo").to_string_lossy()
+    );
+}
@@ -0,0 +1,260 @@
+use std::fs;
+use std::path::Path;
+
+use crate::core::git_fetcher::git_cli_available;
+use crate::core::git_url::parse_git_source;
+use crate::core::skill_store::{SkillPublicationRecord, SkillRecord, SkillStore};
+
+use super::{
+    build_source_ref, check_layout, commit_skill_change, get_publish_status, has_unpushed_changes,
+    normalize_subpath, publish_skill_in, push_skill_changes, stage_skill, PublishOptions,
+};
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn add_skill(store: &SkillStore, central: &Path) {
+    fs::create_dir_all(central.join("scripts")).unwrap();
+    fs::write(central.join("SKILL.md"), "---\nname: demo\n---\n# demo\n").unwrap();
+    fs::write(central.join("scripts/run.sh"), "echo hi\n").unwrap();
+    store
+        .upsert_skill(&SkillRecord {
+            id: "s1".to_string(),
+            name: "demo".to_string(),
+            description: None,
+            category: None,
+            source_type: "authored".to_string(),
+            source_ref: None,
+            source_revision: None,
+            central_path: central.to_string_lossy().to_string(),
+            content_hash: None,
+            created_at: 1,
+            updated_at: 1,
+            last_sync_at: None,
+            last_seen_at: 1,
+            status: "ok".to_string(),
+        })
+        .unwrap();
+}
+
+fn remote_file(remote: &Path, branch: &str, path: &str) -> Option<String> {
+    let repo = git2::Repository::open_bare(remote).unwrap();
+    let commit = repo
+        .find_reference(&format!("refs/heads/{}", branch))
+        .ok()?
+        .peel_to_commit()
+        .unwrap();
+    let entry = commit.tree().unwrap().get_path(Path::new(path)).ok()?;
+    let blob = repo.find_blob(entry.id()).unwrap();
+    Some(String::from_utf8_lossy(blob.content()).to_string())
+}
+
+#[test]
+fn normalizes_subpaths() {
+    assert_eq!(normalize_subpath(None).unwrap(), None);
+    assert_eq!(normalize_subpath(Some(" / ")).unwrap(), None);
+    assert_eq!(normalize_subpath(Some(".")).unwrap(), None);
+    assert_eq!(
+        normalize_subpath(Some("/skills/demo/")).unwrap(),
+        Some("skills/demo".to_string())
+    );
+    assert!(normalize_subpath(Some("../escape")).is_err());
+    assert!(normalize_subpath(Some("a//b")).is_err());
+    assert!(normalize_subpath(Some(".git/hooks")).is_err());
+}
+
+#[test]
+fn builds_source_refs_the_installer_understands() {
+    assert_eq!(
+        build_source_ref("git@github.com:me/skills.git", "main", Some("tools/demo")),
+        "https://github.com/me/skills/tree/main/tools/demo"
+    );
+    assert_eq!(
+        build_source_ref("https://github.com/me/demo", "dev", None),
+        "https://github.com/me/demo/tree/dev"
+    );
+    assert_eq!(
+        build_source_ref("https://git.example.com/me/demo.git", "main", None),
+        "https://git.example.com/me/demo.git#main"
+    );
+    assert_eq!(
+        build_source_ref("git@git.example.com:me/demo.git", "main", Some("tools/x")),
+        "git@git.example.com:me/demo.git#main:tools/x"
+    );
+}
+
+#[test]
+fn publishes_commits_and_pushes_to_bare_remote() {
+    if !git_cli_available() {
+        return;
+    }
+    let (dir, store) = make_store();
+    let remote = dir.path().join("remote.git");
+    git2::Repository::init_bare(&remote).unwrap();
+    let central = dir.path().join("central/demo");
+    add_skill(&store, &central);
+
+    let remote_url = remote.to_string_lossy().to_string();
+    let status = publish_skill_in(
+        &store,
+        &dir.path().join("publish"),
+        "s1",
+        &PublishOptions {
+            remote_url: remote_url.clone(),
+            branch: None,
+            subpath: None,
+            message: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(status.branch, "main");
+    assert!(!status.has_unpushed_changes);
+    assert_eq!(
+        remote_file(&remote, "main", "scripts/run.sh").as_deref(),
+        Some("echo hi\n")
+    );
+
+    let skill = store.get_skill_by_id("s1").unwrap().unwrap();
+    assert_eq!(skill.source_type, "git");
+    assert_eq!(
+        skill.source_ref.as_deref(),
+        Some(format!("{}#main", remote_url).as_str())
+    );
+    assert_eq!(skill.source_revision, status.pushed_commit);
+
+    // Local edits become commits but stay unpushed until asked.
+    fs::write(central.join("SKILL.md"), "---\nname: demo\n---\n# v2\n").unwrap();
+    let commit = commit_skill_change(&store, "s1", "Update SKILL.md")
+        .unwrap()
+        .expect("published skill");
+    assert_ne!(Some(commit.clone()), status.pushed_commit);
+    assert!(has_unpushed_changes(&store, "s1").unwrap());
+    assert!(remote_file(&remote, "main", "SKILL.md")
+        .unwrap()
+        .contains("# demo"));
+
+    let pushed = push_skill_changes(&store, "s1", None).unwrap();
+    assert_eq!(pushed.pushed_commit.as_deref(), Some(commit.as_str()));
+    assert!(!has_unpushed_changes(&store, "s1").unwrap());
+    assert!(remote_file(&remote, "main", "SKILL.md")
+        .unwrap()
+        .contains("# v2"));
+}
+
+#[test]
+fn publishes_into_subpath_on_non_github_remote() {
+    if !git_cli_available() {
+        return;
+    }
+    let (dir, store) = make_store();
+    let remote = dir.path().join("remote.git");
+    git2::Repository::init_bare(&remote).unwrap();
+
+    // Seed the remote with an unrelated skill on `main`.
+    let seed = dir.path().join("seed");
+    let repo = git2::Repository::init(&seed).unwrap();
+    fs::create_dir_all(seed.join("skills/other")).unwrap();
+    fs::write(seed.join("skills/other/SKILL.md"), "# other\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("t", "t@example.com").unwrap();
+    repo.commit(Some("refs/heads/main"), &sig, &sig, "seed", &tree, &[])
+        .unwrap();
+    repo.remote("origin", &remote.to_string_lossy())
+        .unwrap()
+        .push(&["refs/heads/main:refs/heads/main"], None)
+        .unwrap();
+
+    let central = dir.path().join("central/demo");
+    add_skill(&store, &central);
+    let remote_url = remote.to_string_lossy().to_string();
+    let opts = PublishOptions {
+        remote_url: remote_url.clone(),
+        branch: Some("main".to_string()),
+        subpath: Some("skills/demo".to_string()),
+        message: Some("Add demo".to_string()),
+    };
+    publish_skill_in(&store, &dir.path().join("publish"), "s1", &opts).unwrap();
+    assert!(get_publish_status(&store, "s1").unwrap().is_some());
+    assert_eq!(
+        remote_file(&remote, "main", "skills/demo/SKILL.md").as_deref(),
+        Some("---\nname: demo\n---\n# demo\n")
+    );
+
+    // The recorded source_ref round-trips through the installer's URL parser.
+    let skill = store.get_skill_by_id("s1").unwrap().unwrap();
+    let parsed = parse_git_source(skill.source_ref.as_deref().unwrap());
+    assert_eq!(parsed.clone_url, remote_url);
+    assert_eq!(parsed.branch.as_deref(), Some("main"));
+    assert_eq!(parsed.subpath.as_deref(), Some("skills/demo"));
+    assert_eq!(
+        remote_file(&remote, "main", "skills/other/SKILL.md").as_deref(),
+        Some("# other\n")
+    );
+}
+
+#[test]
+fn staging_into_subpath_keeps_sibling_skills() {
+    let dir = tempfile::tempdir().unwrap();
+    let work = dir.path().join("work");
+    fs::create_dir_all(work.join(".git")).unwrap();
+    fs::create_dir_all(work.join("skills/other")).unwrap();
+    fs::write(work.join("skills/other/SKILL.md"), "# other\n").unwrap();
+    fs::create_dir_all(work.join("skills/demo")).unwrap();
+    fs::write(work.join("skills/demo/stale.md"), "old").unwrap();
+    let central = dir.path().join("central");
+    fs::create_dir_all(&central).unwrap();
+    fs::write(central.join("SKILL.md"), "# demo\n").unwrap();
+
+    stage_skill(&central, &work, Some("skills/demo")).unwrap();
+    assert!(work.join("skills/other/SKILL.md").exists());
+    assert!(work.join("skills/demo/SKILL.md").exists());
+    assert!(!work.join("skills/demo/stale.md").exists());
+
+    stage_skill(&central, &work, None).unwrap();
+    assert!(work.join(".git").exists());
+    assert!(!work.join("skills").exists());
+    assert!(work.join("SKILL.md").exists());
+}
+
+#[test]
+fn refuses_to_mix_root_and_subpath_publications() {
+    let (dir, store) = make_store();
+    add_skill(&store, &dir.path().join("central/demo"));
+    let remote = "https://git.example.com/acme/skills.git";
+    let publish_s1 = |subpath: Option<&str>| {
+        store
+            .upsert_skill_publication(&SkillPublicationRecord {
+                skill_id: "s1".to_string(),
+                remote_url: remote.to_string(),
+                branch: "main".to_string(),
+                subpath: subpath.map(str::to_string),
+                work_dir: "/tmp/work".to_string(),
+                last_commit: None,
+                pushed_commit: None,
+                last_pushed_at: None,
+                created_at: 1,
+                updated_at: 1,
+            })
+            .unwrap();
+    };
+
+    publish_s1(Some("skills/a"));
+    assert!(check_layout(&store, "s2", remote, None).is_err());
+    assert!(check_layout(&store, "s2", remote, Some("skills/a")).is_err());
+    assert!(check_layout(&store, "s2", remote, Some("skills/b")).is_ok());
+    assert!(check_layout(&store, "s2", "https://git.example.com/other.git", None).is_ok());
+    // A skill may move between layouts when it is alone in the remote.
+    assert!(check_layout(&store, "s1", remote, None).is_ok());
+
+    publish_s1(None);
+    assert!(check_layout(&store, "s2", remote, Some("skills/b")).is_err());
+}
@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::Path;
+
+use crate::core::skill_store::{DiscoveredSkillRecord, SkillRecord, SkillStore};
+
+use super::{match_expression, refresh_installed_index, search_skills, SkillSearchQuery};
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn discovered(id: &str, name: &str, description: &str, category: &str) -> DiscoveredSkillRecord {
+    DiscoveredSkillRecord {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        github_url: format!("https://github.com/acme/{}", id),
+        category: category.to_string(),
+        source: "awesome".to_string(),
+        tags: String::new(),
+        created_at: 0,
+        updated_at: 0,
+        first_seen_at: 0,
+        last_seen_at: 0,
+        raw_category: None,
+    }
+}
+
+fn install(store: &SkillStore, root: &Path, id: &str, body: &str) -> SkillRecord {
+    let dir = root.join(id);
+    fs::create_dir_all(dir.join("scripts")).unwrap();
+    fs::write(
+        dir.join("SKILL.md"),
+        format!(
+            "---\nname: {}\ndescription: secret frontmatter\n---\n{}\n",
+            id, body
+        ),
+    )
+    .unwrap();
+    fs::write(dir.join("scripts").join("render_chart.py"), "").unwrap();
+    let record = SkillRecord {
+        id: id.to_string(),
+        name: id.to_string(),
+        description: Some(format!("{} skill", id)),
+        category: Some("data".to_string()),
+        source_type: "git".to_string(),
+        source_ref: Some(format!("https://github.com/acme/{}", id)),
+        source_revision: None,
+        central_path: dir.to_string_lossy().to_string(),
+        content_hash: None,
+        created_at: 0,
+        updated_at: 1,
+        last_sync_at: None,
+        last_seen_at: 0,
+        status: "ok".to_string(),
+    };
+    store.upsert_skill(&record).unwrap();
+    record
+}
+
+fn query(text: &str) -> SkillSearchQuery {
+    SkillSearchQuery {
+        query: text.to_string(),
+        ..Default::default()
+    }
+}
+
+fn names(store: &SkillStore, search: &SkillSearchQuery) -> Vec<String> {
+    search_skills(store, search)
+        .unwrap()
+        .into_iter()
+        .map(|hit| hit.name)
+        .collect()
+}
+
+#[test]
+fn match_expression_quotes_words_as_prefixes() {
+    assert_eq!(
+        match_expression("pdf  form-fill").as_deref(),
+        Some("\"pdf\"* AND \"form\"* AND \"fill\"*")
+    );
+    assert_eq!(
+        match_expression("\"x\" OR NEAR(").as_deref(),
+        Some("\"x\"* AND \"OR\"* AND \"NEAR\"*")
+    );
+    assert_eq!(match_expression(" -* "), None);
+}
+
+#[test]
+fn discovered_skills_are_ranked_and_follow_the_catalogue() {
+    let (_dir, store) = make_store();
+    store
+        .merge_discovered_skills_for_source(
+            "awesome",
+            &[
+                discovered("a", "changelog", "Writes release notes", "writing"),
+                discovered("b", "release-helper", "Changelog automation", "devops"),
+                discovered("c", "pdf", "PDF tools", "documents"),
+            ],
+            1,
+        )
+        .unwrap();
+
+    // Name matches outrank description matches; prefixes match.
+    assert_eq!(
+        names(&store, &query("chang")),
+        vec!["changelog", "release-helper"]
+    );
+    let ids: Vec<_> = store
+        .search_discovered_skills("changelog")
+        .unwrap()
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    assert_eq!(ids, vec!["a", "b"]);
+
+    let search = SkillSearchQuery {
+        category: Some("devops".to_string()),
+        ..query("changelog")
+    };
+    assert_eq!(names(&store, &search), vec!["release-helper"]);
+
+    let hit = &search_skills(&store, &query("release notes")).unwrap()[0];
+    assert_eq!(
+        hit.snippet,
+        "Writes <mark>release</mark> <mark>notes</mark>"
+    );
+    assert_eq!(hit.github_url.as_deref(), Some("https://github.com/acme/a"));
+
+    // Delisted rows drop out of the index, relisted ones come back.
+    store
+        .merge_discovered_skills_for_source(
+            "awesome",
+            &[discovered("c", "pdf", "PDF tools", "documents")],
+            2,
+        )
+        .unwrap();
+    assert!(names(&store, &query("changelog")).is_empty());
+    store
+        .delete_discovered_skills_for_source("awesome")
+        .unwrap();
+    assert!(names(&store, &query("pdf")).is_empty());
+}
+
+#[test]
+fn installed_skills_index_body_and_file_names() {
+    let (dir, store) = make_store();
+    let skill = install(&store, dir.path(), "charts", "Plot quarterly revenue");
+    store
+        .merge_discovered_skills_for_source(
+            "awesome",
+            &[
+                discovered("charts", "charts", "Charting", "data"),
+                DiscoveredSkillRecord {
+                    github_url: "https://github.com/Acme/charts.git".to_string(),
+                    ..discovered("charts-git", "charts-git", "Charting", "data")
+                },
+                discovered("maps", "maps", "Charting on maps", "data"),
+            ],
+            1,
+        )
+        .unwrap();
+
+    let hits = search_skills(&store, &query("quarterly")).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].kind, "installed");
+    assert_eq!(hits[0].installed_skill_id.as_deref(), Some("charts"));
+    assert!(names(&store, &query("render_chart")).contains(&"charts".to_string()));
+    // Frontmatter is not part of the body.
+    assert!(names(&store, &query("secret")).is_empty());
+
+    // Catalogue entries installed from the same URL count as installed.
+    let search = SkillSearchQuery {
+        kind: Some("discovered".to_string()),
+        installed: Some(false),
+        ..query("charting")
+    };
+    assert_eq!(names(&store, &search), vec!["maps"]);
+    let search = SkillSearchQuery {
+        kind: Some("discovered".to_string()),
+        installed: Some(true),
+        ..query("charting")
+    };
+    // Matched on repository and folder, like the other discovery views.
+    let hits = search_skills(&store, &search).unwrap();
+    let installed: Vec<_> = hits
+        .iter()
+        .map(|hit| (hit.name.as_str(), hit.installed_skill_id.as_deref()))
+        .collect();
+    assert_eq!(
+        installed,
+        vec![("charts", Some("charts")), ("charts-git", Some("charts"))]
+    );
+
+    // Unchanged skills are not re-indexed; edits and removals are picked up.
+    assert_eq!(refresh_installed_index(&store).unwrap(), 0);
+    store
+        .upsert_skill(&SkillRecord {
+            updated_at: 2,
+            ..skill
+        })
+        .unwrap();
+    fs::write(
+        dir.path().join("charts").join("SKILL.md"),
+        "---\nname: charts\n---\nPlot weekly revenue\n",
+    )
+    .unwrap();
+    assert!(names(&store, &query("quarterly")).is_empty());
+    assert_eq!(names(&store, &query("weekly")), vec!["charts"]);
+
+    store.delete_skill("charts").unwrap();
+    assert!(names(&store, &query("weekly")).is_empty());
+}
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::core::skill_store::{SkillRecord, SkillStore, SkillTargetRecord};
+use crate::core::skill_store::{DiscoveredSkillRecord, SkillRecord, SkillStore, SkillTargetRecord};
 
 fn make_store() -> (tempfile::TempDir, SkillStore) {
     let dir = tempfile::tempdir().expect("tempdir");
@@ -179,6 +179,33 @@ fn deleting_skill_cascades_targets() {
     assert_eq!(store.list_skill_targets("s1").unwrap().len(), 0);
 }
 
+#[test]
+fn git_cache_skill_refs_follow_skill_lifecycle() {
+    let (_dir, store) = make_store();
+    store
+        .upsert_skill(&make_skill("s1", "S1", "/central/s1", 1))
+        .unwrap();
+    store
+        .upsert_skill(&make_skill("s2", "S2", "/central/s2", 1))
+        .unwrap();
+    store.set_git_cache_skill_ref("s1", "k1").unwrap();
+    store.set_git_cache_skill_ref("s2", "k1").unwrap();
+    store.set_git_cache_skill_ref("s2", "k2").unwrap();
+    assert_eq!(
+        store.list_git_cache_skill_refs().unwrap(),
+        vec![
+            ("k1".to_string(), "s1".to_string()),
+            ("k2".to_string(), "s2".to_string())
+        ]
+    );
+
+    store.delete_skill("s1").unwrap();
+    assert_eq!(
+        store.list_git_cache_skill_refs().unwrap(),
+        vec![("k2".to_string(), "s2".to_string())]
+    );
+}
+
 #[test]
 fn error_context_includes_db_path() {
     let store = SkillStore::new(PathBuf::from("/this/path/should/not/exist/test.db"));
@@ -186,3 +213,169 @@ fn error_context_includes_db_path() {
     let msg = format!("{:#}", err);
     assert!(msg.contains("failed to open db at"), "{msg}");
 }
+
+#[test]
+fn v1_layout_databases_get_registry_sources_and_a_usable_discovered_skills_table() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db = dir.path().join("test.db");
+    {
+        // Fresh databases are created from SCHEMA_V1, with the pre-v5 discovered_skills
+        // layout, and labelled v6.
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute_batch(super::SCHEMA_V1).unwrap();
+        conn.execute_batch("PRAGMA user_version = 6;").unwrap();
+    }
+    let store = SkillStore::new(db);
+    store.ensure_schema().expect("ensure_schema");
+
+    let record = DiscoveredSkillRecord {
+        id: "d1".to_string(),
+        name: "pdf".to_string(),
+        description: "PDF tools".to_string(),
+        github_url: "https://github.com/acme/pdf".to_string(),
+        category: "document".to_string(),
+        source: "team".to_string(),
+        tags: String::new(),
+        created_at: 1,
+        updated_at: 1,
+        first_seen_at: 1,
+        last_seen_at: 1,
+        raw_category: None,
+    };
+    store
+        .merge_discovered_skills_for_source("team", &[record], 1)
+        .unwrap();
+    assert_eq!(store.list_discovered_skills().unwrap().len(), 1);
+    assert!(store.list_registry_sources().unwrap().is_empty());
+}
+
+fn discovered(id: &str, source: &str) -> DiscoveredSkillRecord {
+    DiscoveredSkillRecord {
+        id: id.to_string(),
+        name: id.to_string(),
+        description: String::new(),
+        github_url: format!("https://github.com/acme/{}", id),
+        category: "development".to_string(),
+        source: source.to_string(),
+        tags: String::new(),
+        created_at: 0,
+        updated_at: 0,
+        first_seen_at: 0,
+        last_seen_at: 0,
+        raw_category: None,
+    }
+}
+
+#[test]
+fn merging_discovered_skills_tracks_first_and_last_seen() {
+    let (_dir, store) = make_store();
+    let merge = store
+        .merge_discovered_skills_for_source("s", &[discovered("a", "s"), discovered("b", "s")], 100)
+        .unwrap();
+    assert_eq!((merge.listed, merge.added, merge.delisted), (2, 2, 0));
+
+    let merge = store
+        .merge_discovered_skills_for_source("s", &[discovered("a", "s"), discovered("c", "s")], 200)
+        .unwrap();
+    assert_eq!((merge.listed, merge.added, merge.delisted), (2, 1, 1));
+    let skills = store.list_discovered_skills().unwrap();
+    let seen: Vec<_> = skills
+        .iter()
+        .map(|s| (s.id.as_str(), s.first_seen_at, s.last_seen_at))
+        .collect();
+    assert_eq!(seen, vec![("a", 100, 200), ("c", 200, 200)]);
+    assert_eq!(store.list_new_discovered_skills(150).unwrap()[0].id, "c");
+
+    // A relisted skill keeps when it was first seen.
+    let merge = store
+        .merge_discovered_skills_for_source("s", &[discovered("b", "s")], 300)
+        .unwrap();
+    assert_eq!((merge.added, merge.delisted), (0, 2));
+    let b = &store.list_discovered_skills().unwrap()[0];
+    assert_eq!(
+        (b.id.as_str(), b.first_seen_at, b.last_seen_at),
+        ("b", 100, 300)
+    );
+
+    assert_eq!(
+        store.touch_discovered_skills_for_source("s", 400).unwrap(),
+        1
+    );
+    assert_eq!(store.list_discovered_skills().unwrap()[0].last_seen_at, 400);
+}
+
+#[test]
+fn skills_listed_by_several_sources_stay_until_no_source_lists_them() {
+    let (_dir, store) = make_store();
+    let mut from_t = discovered("a", "t");
+    from_t.description = "from t".to_string();
+    store
+        .merge_discovered_skills_for_source("s", &[discovered("a", "s")], 100)
+        .unwrap();
+    let merge = store
+        .merge_discovered_skills_for_source("t", &[from_t], 100)
+        .unwrap();
+    assert_eq!((merge.listed, merge.added), (1, 0));
+    let counts = store.count_discovered_skills_by_source().unwrap();
+    assert_eq!((counts["s"], counts["t"]), (1, 1));
+
+    // The row keeps the details of the source it came from instead of flipping.
+    store
+        .merge_discovered_skills_for_source("s", &[discovered("a", "s")], 200)
+        .unwrap();
+    let a = &store.list_discovered_skills().unwrap()[0];
+    assert_eq!((a.source.as_str(), a.description.as_str()), ("s", ""));
+
+    // A source dropping the skill hands it to the other one.
+    let merge = store
+        .merge_discovered_skills_for_source("s", &[], 300)
+        .unwrap();
+    assert_eq!(merge.delisted, 0);
+    let a = &store.list_discovered_skills().unwrap()[0];
+    assert_eq!(a.source, "t");
+
+    store
+        .merge_discovered_skills_for_source("s", &[discovered("a", "s")], 400)
+        .unwrap();
+    store.delete_discovered_skills_for_source("t").unwrap();
+    let a = &store.list_discovered_skills().unwrap()[0];
+    assert_eq!(a.source, "s");
+    assert_eq!(store.search_discovered_skills("a").unwrap().len(), 1);
+
+    store.delete_discovered_skills_for_source("s").unwrap();
+    assert!(store.list_discovered_skills().unwrap().is_empty());
+}
+
+#[test]
+fn v10_databases_backfill_discovered_skill_history() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db = dir.path().join("test.db");
+    let store = SkillStore::new(db.clone());
+    store.ensure_schema().expect("ensure_schema");
+    {
+        // Roll back to the v10 layout with one synced row.
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "DROP TABLE discovered_skill_sources;
+             DROP TABLE discovered_skills;
+             CREATE TABLE discovered_skills (
+               id TEXT PRIMARY KEY, name TEXT NOT NULL, description TEXT NOT NULL,
+               github_url TEXT NOT NULL, category TEXT NOT NULL, source TEXT NOT NULL,
+               tags TEXT NOT NULL, created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL);
+             INSERT INTO discovered_skills VALUES ('a', 'a', '', 'u', 'c', 's', '', 5, 7);
+             ALTER TABLE registry_sources DROP COLUMN etag;
+             ALTER TABLE registry_sources DROP COLUMN last_modified;
+             DROP TABLE discovered_skill_metadata;
+             ALTER TABLE git_credentials DROP COLUMN secret_ref;
+             PRAGMA user_version = 10;",
+        )
+        .unwrap();
+    }
+    store.ensure_schema().expect("ensure_schema");
+
+    let a = &store.list_discovered_skills().unwrap()[0];
+    assert_eq!((a.first_seen_at, a.last_seen_at), (5, 7));
+    assert!(store.list_registry_sources().unwrap().is_empty());
+    // The search index added in v12 is seeded from the existing rows.
+    assert_eq!(store.search_discovered_skills("a").unwrap().len(), 1);
+}
@@ -0,0 +1,134 @@
+use std::fs;
+
+use crate::core::installer::parse_skill_md;
+use crate::core::skill_store::SkillStore;
+
+use super::{create_skill_in, list_skill_templates_in, validate_skill_name, NewSkillSpec};
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn spec(template_id: &str, name: &str) -> NewSkillSpec {
+    NewSkillSpec {
+        template_id: template_id.to_string(),
+        name: name.to_string(),
+        description: "Does\nthings".to_string(),
+        category: Some("development".to_string()),
+    }
+}
+
+#[test]
+fn lists_builtin_templates_without_user_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let templates = list_skill_templates_in(&dir.path().join("missing")).unwrap();
+    let ids: Vec<&str> = templates.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, vec!["basic", "script", "reference", "multi-file"]);
+    assert!(templates.iter().all(|t| t.builtin));
+}
+
+#[test]
+fn user_templates_are_loaded_and_override_builtins() {
+    let dir = tempfile::tempdir().unwrap();
+    let custom = dir.path().join("team");
+    fs::create_dir_all(custom.join("docs")).unwrap();
+    fs::write(custom.join("SKILL.md"), "# {{name}}\n").unwrap();
+    fs::write(custom.join("docs/guide.md"), "guide for {{name}}").unwrap();
+    fs::write(
+        custom.join("template.json"),
+        r#"{"name": "Team", "description": "Team layout"}"#,
+    )
+    .unwrap();
+
+    let basic = dir.path().join("basic");
+    fs::create_dir_all(&basic).unwrap();
+    fs::write(basic.join("SKILL.md"), "custom basic").unwrap();
+
+    // Missing SKILL.md: skipped instead of failing the whole listing.
+    fs::create_dir_all(dir.path().join("broken")).unwrap();
+
+    let templates = list_skill_templates_in(dir.path()).unwrap();
+    let team = templates.iter().find(|t| t.id == "team").expect("team");
+    assert_eq!(team.name, "Team");
+    assert!(!team.builtin);
+    assert_eq!(team.files, vec!["SKILL.md", "docs/guide.md"]);
+
+    let basic = templates.iter().find(|t| t.id == "basic").expect("basic");
+    assert!(
+        !basic.builtin,
+        "user template should replace the built-in one"
+    );
+    assert_eq!(templates.iter().filter(|t| t.id == "basic").count(), 1);
+    assert!(!templates.iter().any(|t| t.id == "broken"));
+}
+
+#[test]
+fn creates_authored_skill_from_template() {
+    let (_dir, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    let templates = list_skill_templates_in(&central.path().join("none")).unwrap();
+    let template = templates.iter().find(|t| t.id == "multi-file").unwrap();
+
+    let res = create_skill_in(
+        &store,
+        central.path(),
+        template,
+        &spec("multi-file", "my-skill"),
+    )
+    .unwrap();
+    assert!(res.central_path.join("scripts/run.sh").exists());
+    assert!(res.central_path.join("assets/template.md").exists());
+
+    let (name, desc, category) = parse_skill_md(&res.central_path.join("SKILL.md")).unwrap();
+    assert_eq!(name, "my-skill");
+    assert_eq!(desc.as_deref(), Some("Does things"));
+    assert_eq!(category.as_deref(), Some("development"));
+
+    let record = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    assert_eq!(record.source_type, "authored");
+    assert!(record.source_ref.is_none());
+
+    let err = match create_skill_in(
+        &store,
+        central.path(),
+        template,
+        &spec("multi-file", "my-skill"),
+    ) {
+        Ok(_) => panic!("expected error"),
+        Err(e) => e,
+    };
+    assert!(format!("{:#}", err).contains("skill already exists"));
+}
+
+#[test]
+fn frontmatter_values_are_quoted() {
+    let (_dir, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    let templates = list_skill_templates_in(&central.path().join("none")).unwrap();
+    let template = templates.iter().find(|t| t.id == "basic").unwrap();
+    let mut spec = spec("basic", "pdf: tools");
+    spec.description = r#"- Fill forms: fast # "really" \ safe"#.to_string();
+
+    let res = create_skill_in(&store, central.path(), template, &spec).unwrap();
+    let text = fs::read_to_string(res.central_path.join("SKILL.md")).unwrap();
+    let frontmatter = text.split("---\n").nth(1).unwrap();
+    let yaml: serde_yaml::Value = serde_yaml::from_str(frontmatter).unwrap();
+    assert_eq!(yaml["name"].as_str(), Some("pdf: tools"));
+    assert_eq!(yaml["description"].as_str(), Some(spec.description.as_str()));
+
+    let (name, desc, _) = parse_skill_md(&res.central_path.join("SKILL.md")).unwrap();
+    assert_eq!(name, "pdf: tools");
+    assert_eq!(desc, Some(spec.description));
+}
+
+#[test]
+fn rejects_unsafe_skill_names() {
+    assert!(validate_skill_name("  ").is_err());
+    assert!(validate_skill_name("../x").is_err());
+    assert!(validate_skill_name(".hidden").is_err());
+    assert!(validate_skill_name("a\\b").is_err());
+    assert_eq!(validate_skill_name(" ok-name ").unwrap(), "ok-name");
+}
@@ -0,0 +1,194 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::git_fetcher::git_cli_available;
+use crate::core::skill_store::{SkillRecord, SkillStore, SkillTargetRecord};
+
+use super::{
+    enable_state_sync, get_state_sync_config, has_local_changes, resolve_state_sync_conflicts,
+    sync_state, ConflictResolution, STATE_FILE_NAME,
+};
+
+struct Machine {
+    _dir: tempfile::TempDir,
+    store: SkillStore,
+    central: PathBuf,
+}
+
+fn machine() -> Machine {
+    let dir = tempfile::tempdir().unwrap();
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().unwrap();
+    let central = dir.path().join("central");
+    fs::create_dir_all(&central).unwrap();
+    Machine {
+        _dir: dir,
+        store,
+        central,
+    }
+}
+
+fn add_skill(m: &Machine, name: &str, description: &str) {
+    let path = m.central.join(name);
+    fs::create_dir_all(&path).unwrap();
+    fs::write(path.join("SKILL.md"), format!("# {}\n", name)).unwrap();
+    m.store
+        .upsert_skill(&SkillRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            category: None,
+            source_type: "local".to_string(),
+            source_ref: None,
+            source_revision: None,
+            central_path: path.to_string_lossy().to_string(),
+            content_hash: None,
+            created_at: 1,
+            updated_at: 1,
+            last_sync_at: None,
+            last_seen_at: 1,
+            status: "ok".to_string(),
+        })
+        .unwrap();
+}
+
+fn set_description(m: &Machine, name: &str, description: &str) {
+    let mut record = skill(m, name).unwrap();
+    record.description = Some(description.to_string());
+    m.store.upsert_skill(&record).unwrap();
+}
+
+fn skill(m: &Machine, name: &str) -> Option<SkillRecord> {
+    m.store
+        .list_skills()
+        .unwrap()
+        .into_iter()
+        .find(|s| s.name == name)
+}
+
+fn bare_remote(root: &Path) -> String {
+    let remote = root.join("remote.git");
+    git2::Repository::init_bare(&remote).unwrap();
+    remote.to_string_lossy().to_string()
+}
+
+#[test]
+fn skills_and_metadata_flow_between_machines() {
+    if !git_cli_available() {
+        return;
+    }
+    let root = tempfile::tempdir().unwrap();
+    let remote = bare_remote(root.path());
+
+    let a = machine();
+    add_skill(&a, "alpha", "from a");
+    let report = enable_state_sync(&a.store, &a.central, &remote).unwrap();
+    assert_eq!(report.status, "ok");
+    assert!(report.pushed);
+    assert!(a.central.join(STATE_FILE_NAME).exists());
+    assert!(!has_local_changes(&a.store, &a.central).unwrap());
+
+    let b = machine();
+    add_skill(&b, "beta", "from b");
+    let report = enable_state_sync(&b.store, &b.central, &remote).unwrap();
+    assert_eq!(report.status, "ok");
+    assert_eq!(report.added, vec!["alpha".to_string()]);
+    assert!(b.central.join("alpha/SKILL.md").exists());
+    assert_eq!(
+        skill(&b, "alpha").unwrap().description.as_deref(),
+        Some("from a")
+    );
+
+    // B's local skill (synced to a tool) and an edit on B reach A.
+    let link = b.central.parent().unwrap().join("tool/beta");
+    fs::create_dir_all(link.parent().unwrap()).unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(b.central.join("beta"), &link).unwrap();
+    #[cfg(not(unix))]
+    fs::create_dir_all(&link).unwrap();
+    b.store
+        .upsert_skill_target(&SkillTargetRecord {
+            id: "t-beta".to_string(),
+            skill_id: skill(&b, "beta").unwrap().id,
+            tool: "claude_code".to_string(),
+            target_path: link.to_string_lossy().to_string(),
+            mode: "symlink".to_string(),
+            status: "ok".to_string(),
+            last_error: None,
+            synced_at: Some(1),
+        })
+        .unwrap();
+    set_description(&b, "alpha", "edited on b");
+    assert!(has_local_changes(&b.store, &b.central).unwrap());
+    sync_state(&b.store, &b.central).unwrap();
+    let report = sync_state(&a.store, &a.central).unwrap();
+    assert!(report.pulled);
+    assert!(report.added.contains(&"beta".to_string()));
+    assert!(report.updated.contains(&"alpha".to_string()));
+    assert_eq!(
+        skill(&a, "alpha").unwrap().description.as_deref(),
+        Some("edited on b")
+    );
+
+    // Deleting a skill upstream removes it downstream, along with its tool links.
+    let beta = skill(&a, "beta").unwrap();
+    fs::remove_dir_all(&beta.central_path).unwrap();
+    a.store.delete_skill(&beta.id).unwrap();
+    sync_state(&a.store, &a.central).unwrap();
+    let report = sync_state(&b.store, &b.central).unwrap();
+    assert_eq!(report.removed, vec!["beta".to_string()]);
+    assert!(skill(&b, "beta").is_none());
+    assert!(fs::symlink_metadata(&link).is_err());
+}
+
+#[test]
+fn concurrent_edits_are_reported_not_merged() {
+    if !git_cli_available() {
+        return;
+    }
+    let root = tempfile::tempdir().unwrap();
+    let remote = bare_remote(root.path());
+
+    let a = machine();
+    add_skill(&a, "alpha", "v1");
+    enable_state_sync(&a.store, &a.central, &remote).unwrap();
+    let b = machine();
+    enable_state_sync(&b.store, &b.central, &remote).unwrap();
+
+    fs::write(a.central.join("alpha/SKILL.md"), "# from a\n").unwrap();
+    set_description(&a, "alpha", "a wins?");
+    fs::write(b.central.join("alpha/SKILL.md"), "# from b\n").unwrap();
+    set_description(&b, "alpha", "b wins?");
+
+    assert_eq!(sync_state(&a.store, &a.central).unwrap().status, "ok");
+    let report = sync_state(&b.store, &b.central).unwrap();
+    assert_eq!(report.status, "conflict");
+    assert!(report.conflicts.contains(&"alpha/SKILL.md".to_string()));
+    assert!(!report.pushed);
+    // Local files are left alone and the conflict sticks until resolved.
+    assert_eq!(
+        fs::read_to_string(b.central.join("alpha/SKILL.md")).unwrap(),
+        "# from b\n"
+    );
+    assert_eq!(sync_state(&b.store, &b.central).unwrap().status, "conflict");
+    assert!(!get_state_sync_config(&b.store)
+        .unwrap()
+        .conflicts
+        .is_empty());
+
+    let report =
+        resolve_state_sync_conflicts(&b.store, &b.central, ConflictResolution::KeepRemote).unwrap();
+    assert_eq!(report.status, "ok");
+    assert_eq!(
+        fs::read_to_string(b.central.join("alpha/SKILL.md")).unwrap(),
+        "# from a\n"
+    );
+    assert_eq!(
+        skill(&b, "alpha").unwrap().description.as_deref(),
+        Some("a wins?")
+    );
+    assert!(get_state_sync_config(&b.store)
+        .unwrap()
+        .conflicts
+        .is_empty());
+}
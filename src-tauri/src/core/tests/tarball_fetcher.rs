@@ -0,0 +1,173 @@
+use std::fs;
+
+use mockito::Matcher;
+
+use crate::core::skill_store::SkillStore;
+
+use super::{fetch_github_tarball_inner, get_fetch_strategy, set_fetch_strategy, FetchStrategy};
+
+const SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+fn repo_tarball() -> Vec<u8> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    let prefix = format!("r-{}", SHA);
+    for (path, body) in [
+        ("README.md", "# repo\n"),
+        ("skills/a/SKILL.md", "---\nname: a\n---\n"),
+        ("skills/b/SKILL.md", "---\nname: b\n---\n"),
+        ("docs/guide.md", "guide\n"),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(body.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, format!("{}/{}", prefix, path), body.as_bytes())
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+fn mock_repo(server: &mut mockito::ServerGuard) -> (mockito::Mock, mockito::Mock) {
+    let commit = server
+        .mock("GET", "/repos/o/r/commits/main")
+        .match_header("accept", "application/vnd.github.sha")
+        .with_status(200)
+        .with_body(SHA)
+        .create();
+    let tarball = server
+        .mock("GET", format!("/o/r/tar.gz/{}", SHA).as_str())
+        .with_status(200)
+        .with_body(repo_tarball())
+        .create();
+    (commit, tarball)
+}
+
+#[test]
+fn extracts_only_requested_paths_and_returns_sha() {
+    let mut server = mockito::Server::new();
+    let _mocks = mock_repo(&mut server);
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("cache");
+    fs::create_dir_all(&dest).unwrap();
+    fs::write(dest.join("stale.txt"), "old").unwrap();
+
+    let paths = vec!["skills/a".to_string()];
+    let sha = fetch_github_tarball_inner(
+        &server.url(),
+        &server.url(),
+        "o",
+        "r",
+        Some("main"),
+        &dest,
+        Some(&paths),
+        None,
+    )
+    .unwrap();
+    assert_eq!(sha, SHA);
+    assert!(dest.join("README.md").exists());
+    assert!(dest.join("skills/a/SKILL.md").exists());
+    assert!(!dest.join("skills/b").exists());
+    assert!(!dest.join("docs").exists());
+    assert!(!dest.join("stale.txt").exists());
+
+    let sha = fetch_github_tarball_inner(
+        &server.url(),
+        &server.url(),
+        "o",
+        "r",
+        Some("main"),
+        &dest,
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(sha, SHA);
+    assert!(dest.join("skills/b/SKILL.md").exists());
+    assert!(dest.join("docs/guide.md").exists());
+}
+
+#[test]
+fn private_repos_download_through_the_api_with_token() {
+    let mut server = mockito::Server::new();
+    let _commit = server
+        .mock("GET", "/repos/o/r/commits/HEAD")
+        .match_header("authorization", "Bearer t0ken")
+        .with_status(200)
+        .with_body(SHA)
+        .create();
+    let _tarball = server
+        .mock("GET", format!("/repos/o/r/tarball/{}", SHA).as_str())
+        .match_header("authorization", "Bearer t0ken")
+        .with_status(200)
+        .with_body(repo_tarball())
+        .create();
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("cache");
+
+    let sha = fetch_github_tarball_inner(
+        &server.url(),
+        "http://127.0.0.1:9",
+        "o",
+        "r",
+        None,
+        &dest,
+        None,
+        Some("t0ken"),
+    )
+    .unwrap();
+    assert_eq!(sha, SHA);
+    assert!(dest.join("skills/a/SKILL.md").exists());
+}
+
+#[test]
+fn rejects_unexpected_commit_response_and_keeps_cache() {
+    let mut server = mockito::Server::new();
+    let _commit = server
+        .mock("GET", Matcher::Regex("^/repos/o/r/commits/".into()))
+        .with_status(200)
+        .with_body("<html>rate limited</html>")
+        .create();
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("cache");
+    fs::create_dir_all(&dest).unwrap();
+    fs::write(dest.join("keep.txt"), "x").unwrap();
+
+    let err = fetch_github_tarball_inner(
+        &server.url(),
+        &server.url(),
+        "o",
+        "r",
+        Some("main"),
+        &dest,
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert!(format!("{:#}", err).contains("unexpected commit sha"));
+    assert!(dest.join("keep.txt").exists());
+}
+
+#[test]
+fn fetch_strategy_setting_round_trips() {
+    let (_dir, store) = make_store();
+    assert_eq!(get_fetch_strategy(&store), FetchStrategy::Auto);
+    assert!(FetchStrategy::Auto.prefers_tarball(false));
+    assert!(!FetchStrategy::Auto.prefers_tarball(true));
+    assert!(!FetchStrategy::Git.prefers_tarball(false));
+    assert!(FetchStrategy::Tarball.prefers_tarball(true));
+
+    set_fetch_strategy(&store, FetchStrategy::Tarball).unwrap();
+    assert_eq!(get_fetch_strategy(&store), FetchStrategy::Tarball);
+
+    store.set_setting("git_fetch_strategy", "bogus").unwrap();
+    assert_eq!(get_fetch_strategy(&store), FetchStrategy::Auto);
+}
@@ -0,0 +1,265 @@
+use std::fs;
+
+use mockito::Matcher;
+
+use crate::core::registry_sources::RegistrySource;
+use crate::core::skill_store::{RegistrySourceRecord, SkillStore};
+
+use super::{
+    compute_skill_checksum, install_team_index_skill, normalize_checksum, parse_team_index,
+    verify_skill_checksum, TeamIndexSource,
+};
+
+const DIGEST: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+fn yaml_index() -> String {
+    format!(
+        r#"version: 1
+name: Acme skills
+skills:
+  - name: release-notes
+    description: Drafts release notes
+    git_url: https://git.acme.dev/tools/skills.git
+    subpath: /skills/release-notes/
+    revision: 4f1c2e9a7b3d5c6e8f9a0b1c2d3e4f5a6b7c8d9e
+    category: writing
+    tags: [release, docs]
+    maintainer: platform-team@acme.dev
+    checksum: SHA256:{}
+  - name: lint
+    gitUrl: https://git.acme.dev/tools/lint.git
+"#,
+        DIGEST.to_ascii_uppercase()
+    )
+}
+
+#[test]
+fn parses_yaml_and_normalizes_entries() {
+    let index = parse_team_index(&yaml_index()).unwrap();
+    assert_eq!(index.name.as_deref(), Some("Acme skills"));
+    assert_eq!(index.skills.len(), 2);
+
+    let notes = &index.skills[0];
+    assert_eq!(notes.subpath.as_deref(), Some("skills/release-notes"));
+    assert_eq!(notes.maintainer.as_deref(), Some("platform-team@acme.dev"));
+    assert_eq!(notes.checksum, Some(format!("sha256:{}", DIGEST)));
+    assert_eq!(
+        notes.source_ref(),
+        "https://git.acme.dev/tools/skills.git#4f1c2e9a7b3d5c6e8f9a0b1c2d3e4f5a6b7c8d9e:skills/release-notes"
+    );
+    assert_eq!(
+        index.skills[1].source_ref(),
+        "https://git.acme.dev/tools/lint.git"
+    );
+}
+
+#[test]
+fn parses_json_with_camel_case_fields() {
+    let index = parse_team_index(
+        r#"{"version": 1, "skills": [
+            {"name": "a", "gitUrl": "https://example.com/a.git", "subpath": "skills/a"},
+            {"name": "b", "gitUrl": "https://example.com/b.git", "revision": "v1.2.0"}
+        ]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        index.skills[0].source_ref(),
+        "https://example.com/a.git#:skills/a"
+    );
+    assert_eq!(
+        index.skills[1].source_ref(),
+        "https://example.com/b.git#v1.2.0"
+    );
+}
+
+#[test]
+fn rejects_unknown_versions_and_bad_entries() {
+    let err = parse_team_index("version: 2\nskills: []\n").unwrap_err();
+    assert!(format!("{:#}", err).contains("unsupported registry index version 2"));
+
+    let err = parse_team_index("skills: []\n").unwrap_err();
+    assert!(format!("{:#}", err).contains("missing `version`"));
+
+    let err = parse_team_index(
+        "version: 1\nskills:\n  - name: a\n    gitUrl: x\n  - name: a\n    gitUrl: y\n",
+    )
+    .unwrap_err();
+    assert!(format!("{:#}", err).contains("duplicate skill"));
+
+    let err = parse_team_index(
+        "version: 1\nskills:\n  - name: a\n    gitUrl: x\n    checksum: md5:abc\n",
+    )
+    .unwrap_err();
+    assert!(format!("{:#}", err).contains("unsupported checksum algorithm"));
+
+    let err = parse_team_index(
+        "version: 1\nskills:\n  - name: a\n    gitUrl: x\n    revision: 4f1c2e9a7b3d\n",
+    )
+    .unwrap_err();
+    assert!(format!("{:#}", err).contains("full 40-character id"));
+}
+
+#[test]
+fn checksum_forms() {
+    let expected = format!("sha256:{}", DIGEST);
+    assert_eq!(normalize_checksum(DIGEST).unwrap(), expected);
+    assert_eq!(normalize_checksum(&expected).unwrap(), expected);
+    assert!(normalize_checksum("sha256:1234").is_err());
+}
+
+#[test]
+fn verifies_skill_folders_against_their_checksum() {
+    let dir = tempfile::tempdir().unwrap();
+    let skill = dir.path().join("skill");
+    fs::create_dir_all(&skill).unwrap();
+    fs::write(skill.join("SKILL.md"), "---\nname: s\n---\n").unwrap();
+
+    let checksum = compute_skill_checksum(&skill).unwrap();
+    assert!(checksum.starts_with("sha256:"));
+    verify_skill_checksum(&skill, &checksum).unwrap();
+    // Ignored files (e.g. `.git`) do not affect the checksum.
+    fs::create_dir_all(skill.join(".git")).unwrap();
+    fs::write(skill.join(".git").join("HEAD"), "ref").unwrap();
+    verify_skill_checksum(&skill, &checksum).unwrap();
+
+    fs::write(skill.join("extra.md"), "changed").unwrap();
+    let err = verify_skill_checksum(&skill, &checksum).unwrap_err();
+    assert!(err.to_string().contains("checksum mismatch"));
+}
+
+#[test]
+fn url_indexes_are_refreshed_with_etag() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut server = mockito::Server::new();
+    let source = TeamIndexSource {
+        location: format!("{}/index.yaml", server.url()),
+        cache_file: Some(dir.path().join("registry-index").join("team.json")),
+    };
+
+    let first = server
+        .mock("GET", "/index.yaml")
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_body(yaml_index())
+        .expect(1)
+        .create();
+    let skills = source.fetch().unwrap();
+    assert_eq!(skills.len(), 2);
+    assert_eq!(skills[0].category, "writing");
+    first.assert();
+
+    let unchanged = server
+        .mock("GET", "/index.yaml")
+        .match_header("if-none-match", "\"v1\"")
+        .with_status(304)
+        .expect(1)
+        .create();
+    let skills = source.fetch().unwrap();
+    assert_eq!(skills.len(), 2);
+    assert_eq!(
+        skills[0].github_url,
+        "https://git.acme.dev/tools/skills.git#4f1c2e9a7b3d5c6e8f9a0b1c2d3e4f5a6b7c8d9e:skills/release-notes"
+    );
+    unchanged.assert();
+    assert_eq!(source.load_cached().unwrap().skills.len(), 2);
+}
+
+#[test]
+fn invalid_downloads_keep_the_cached_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut server = mockito::Server::new();
+    let source = TeamIndexSource {
+        location: format!("{}/index.json", server.url()),
+        cache_file: Some(dir.path().join("team.json")),
+    };
+
+    let ok = server
+        .mock("GET", "/index.json")
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_body(yaml_index())
+        .create();
+    source.load().unwrap();
+    ok.remove();
+
+    let _broken = server
+        .mock("GET", "/index.json")
+        .with_status(200)
+        .with_header("etag", "\"v2\"")
+        .with_body("version: 3\n")
+        .create();
+    assert!(source.load().is_err());
+    assert_eq!(source.load_cached().unwrap().skills.len(), 2);
+}
+
+fn commit_all(repo: &git2::Repository, message: &str) -> String {
+    let sig = git2::Signature::now("t", "t@example.com").unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn installs_skills_at_their_pinned_commit() {
+    let app = tauri::test::mock_app();
+    let dir = tempfile::tempdir().unwrap();
+    let store = SkillStore::new(dir.path().join("test.db"));
+    store.ensure_schema().unwrap();
+    let central = dir.path().join("central");
+    store
+        .set_setting("central_repo_path", central.to_string_lossy().as_ref())
+        .unwrap();
+
+    let repo_dir = dir.path().join("repo");
+    let skill_dir = repo_dir.join("skills/notes");
+    fs::create_dir_all(&skill_dir).unwrap();
+    fs::write(skill_dir.join("SKILL.md"), "---\nname: notes\n---\n# v1\n").unwrap();
+    let repo = git2::Repository::init(&repo_dir).unwrap();
+    let pinned = commit_all(&repo, "v1");
+    let checksum = compute_skill_checksum(&skill_dir).unwrap();
+    fs::write(skill_dir.join("SKILL.md"), "---\nname: notes\n---\n# v2\n").unwrap();
+    commit_all(&repo, "v2");
+
+    let index_file = dir.path().join("index.yaml");
+    fs::write(
+        &index_file,
+        format!(
+            "version: 1\nskills:\n  - name: notes\n    gitUrl: {}\n    subpath: skills/notes\n    revision: {}\n    checksum: {}\n",
+            repo_dir.display(),
+            pinned,
+            checksum
+        ),
+    )
+    .unwrap();
+    store
+        .upsert_registry_source(&RegistrySourceRecord {
+            id: "team".to_string(),
+            kind: "team_index".to_string(),
+            name: "Team".to_string(),
+            location: index_file.to_string_lossy().to_string(),
+            enabled: true,
+            last_synced_at: None,
+            last_error: None,
+            created_at: 0,
+            updated_at: 0,
+            etag: None,
+            last_modified: None,
+        })
+        .unwrap();
+
+    let result = install_team_index_skill(app.handle(), &store, "team", "notes").unwrap();
+    assert!(fs::read_to_string(result.central_path.join("SKILL.md"))
+        .unwrap()
+        .contains("# v1"));
+    let record = store.get_skill_by_id(&result.skill_id).unwrap().unwrap();
+    assert_eq!(record.source_revision.as_deref(), Some(pinned.as_str()));
+}
@@ -28,7 +28,25 @@ pub fn run() {
             let store = SkillStore::new(db_path.clone());
             store.ensure_schema().map_err(tauri::Error::from)?;
             store.initialize_default_scan_paths().map_err(tauri::Error::from)?;
+            core::registry_sources::ensure_default_registry_sources(&store)
+                .map_err(tauri::Error::from)?;
+            if let Err(err) = core::network::activate_network_config(&store) {
+                log::warn!("[network] Failed to apply proxy/CA settings: {:#}", err);
+            }
             app.manage(store.clone());
+            // Tokens saved by older versions leave the database for the OS keychain.
+            let store_for_secrets = store.clone();
+            std::thread::spawn(move || {
+                match core::git_credentials::move_git_secrets_to_keychain(&store_for_secrets) {
+                    Ok(moved) if moved > 0 => {
+                        log::info!("[git_credentials] moved {} tokens to the OS keychain", moved)
+                    }
+                    Ok(_) => {}
+                    Err(err) => log::warn!("[git_credentials] keychain migration failed: {:#}", err),
+                }
+            });
+            let jobs = core::jobs::JobRegistry::default();
+            app.manage(jobs.clone());
 
             // Initialize Analytics
             let analytics_db_path = db_path.with_file_name("skills_hub_analytics.db");
@@ -61,6 +79,7 @@ pub fn run() {
             // - And are older than the max age.
             let handle = app.handle().clone();
             let store_for_cleanup = store.clone();
+            let jobs_for_update = jobs.clone();
             tauri::async_runtime::spawn(async move {
                 let removed = core::temp_cleanup::cleanup_old_git_temp_dirs(
                     &handle,
@@ -76,31 +95,109 @@ pub fn run() {
                 if cleanup_days > 0 {
                     let max_age =
                         std::time::Duration::from_secs(cleanup_days as u64 * 24 * 60 * 60);
-                    let removed =
-                        core::cache_cleanup::cleanup_git_cache_dirs(&handle, max_age).unwrap_or(0);
+                    let removed = core::cache_cleanup::cleanup_git_cache_dirs(
+                        &handle,
+                        &store_for_cleanup,
+                        max_age,
+                    )
+                    .unwrap_or(0);
                     if removed > 0 {
                         log::info!("cleaned up {} git cache dirs", removed);
                     }
                 }
+                let removed =
+                    core::cache_cleanup::enforce_git_cache_budget(&store_for_cleanup, None)
+                        .unwrap_or(0);
+                if removed > 0 {
+                    log::info!("evicted {} git cache entries over the size budget", removed);
+                }
+
+                // Probe connectivity once, so an offline session skips network calls
+                // (including the auto-update below) instead of waiting for timeouts.
+                let probe =
+                    tauri::async_runtime::spawn_blocking(core::network::check_connectivity).await;
+                if let Ok(status) = probe {
+                    let _ =
+                        tauri::Emitter::emit(&handle, core::network::NETWORK_STATUS_EVENT, &status);
+                }
+
+                // Pull shared state before auto-update touches the central repo, then keep
+                // pushing local changes while the app runs.
+                let store_for_sync = store_for_cleanup.clone();
+                let handle_for_sync = handle.clone();
+                let initial_sync = tauri::async_runtime::spawn_blocking(move || {
+                    commands::run_state_sync(&handle_for_sync, &store_for_sync, false)
+                })
+                .await;
+                if let Ok(result) = initial_sync {
+                    log_state_sync(result);
+                }
+                let store_for_sync = store_for_cleanup.clone();
+                let handle_for_sync = handle.clone();
+                std::thread::spawn(move || loop {
+                    std::thread::sleep(std::time::Duration::from_secs(60));
+                    log_state_sync(commands::run_state_sync(&handle_for_sync, &store_for_sync, true));
+                });
 
-                // Check for auto-updates on startup
+                // Check for auto-updates on startup, as a job the UI can follow and cancel.
                 let store_for_update = store_for_cleanup.clone();
                 let handle_for_update = handle.clone();
-                tauri::async_runtime::spawn_blocking(move || {
-                    match core::auto_update::check_auto_updates(&handle_for_update, &store_for_update) {
-                        Ok(updated) => {
-                            if !updated.is_empty() {
+                if store_for_update.get_auto_update_enabled().unwrap_or(false) {
+                    commands::spawn_job(&handle, &jobs_for_update, "auto_update", move |_| {
+                        let updated =
+                            core::auto_update::check_auto_updates(&handle_for_update, &store_for_update);
+                        match &updated {
+                            Ok(updated) if !updated.is_empty() => {
                                 log::info!(
                                     "[auto_update] Startup check: {} skills were auto-updated: {:?}",
                                     updated.len(),
                                     updated
                                 );
                             }
+                            Ok(_) => {}
+                            Err(err) => {
+                                log::warn!("[auto_update] Startup check failed: {}", err);
+                            }
+                        }
+                        updated
+                    });
+                }
+
+                // Refresh the registry sources that are due, then check again every hour.
+                // Offline sessions keep the stored listings.
+                let store_for_registry = store_for_cleanup.clone();
+                let handle_for_registry = handle.clone();
+                std::thread::spawn(move || loop {
+                    if !core::network::is_offline() {
+                        match core::registry_sources::sync_due_registry_sources(&store_for_registry) {
+                            Ok(results) if !results.is_empty() => {
+                                let _ = tauri::Emitter::emit(
+                                    &handle_for_registry,
+                                    core::registry_sources::REGISTRY_SYNC_EVENT,
+                                    &results,
+                                );
+                            }
+                            Ok(_) => {}
+                            Err(err) => log::warn!("[registry] scheduled refresh failed: {:#}", err),
                         }
-                        Err(err) => {
-                            log::warn!("[auto_update] Startup check failed: {}", err);
+                        // Then fill in repository metadata for new or stale entries.
+                        match core::discovery_metadata::enrich_discovered_skills(
+                            &store_for_registry,
+                            core::git_credentials::github_api_token(&store_for_registry).as_deref(),
+                            core::discovery_metadata::ENRICH_BATCH_SIZE,
+                        ) {
+                            Ok(report) if report.enriched > 0 => {
+                                let _ = tauri::Emitter::emit(
+                                    &handle_for_registry,
+                                    core::discovery_metadata::METADATA_EVENT,
+                                    &report,
+                                );
+                            }
+                            Ok(_) => {}
+                            Err(err) => log::warn!("[registry] metadata enrichment failed: {:#}", err),
                         }
                     }
+                    std::thread::sleep(core::registry_sources::REFRESH_CHECK_INTERVAL);
                 });
             });
 
@@ -114,6 +211,25 @@ pub fn run() {
             commands::get_git_cache_ttl_secs,
             commands::set_git_cache_cleanup_days,
             commands::set_git_cache_ttl_secs,
+            commands::get_git_fetch_strategy,
+            commands::set_git_fetch_strategy,
+            commands::get_git_cache_max_size_mb,
+            commands::set_git_cache_max_size_mb,
+            commands::get_git_cache_usage,
+            commands::get_network_config,
+            commands::set_network_config,
+            commands::get_network_status,
+            commands::set_offline_mode,
+            commands::list_registry_sources,
+            commands::add_registry_source,
+            commands::set_registry_source_enabled,
+            commands::remove_registry_source,
+            commands::sync_registry_sources,
+            commands::get_registry_refresh_interval,
+            commands::set_registry_refresh_interval,
+            commands::get_team_index,
+            commands::install_team_index_skill,
+            commands::compute_skill_checksum,
             commands::clear_git_cache_now,
             commands::get_auto_update_enabled,
             commands::set_auto_update_enabled,
@@ -129,11 +245,13 @@ pub fn run() {
             commands::unsync_skill_from_tool,
             commands::update_managed_skill,
             commands::search_github,
+            commands::search_github_skills_cmd,
             commands::import_existing_skill,
             commands::get_managed_skills,
             commands::delete_managed_skill,
             commands::update_skill_category,
             commands::fetch_discovered_skills,
+            commands::get_project_recommendations,
             commands::get_categories,
             commands::get_skills_by_category,
             commands::search_skills,
@@ -149,6 +267,10 @@ pub fn run() {
             commands::add_category,
             commands::remove_category,
             commands::list_categories_db,
+            commands::list_category_rules,
+            commands::save_category_rule,
+            commands::delete_category_rule,
+            commands::reset_category_rules,
             commands::get_analytics_overview,
             commands::get_analytics_daily_trend,
             commands::get_analytics_top_skills,
@@ -162,12 +284,50 @@ pub fn run() {
             commands::fetch_discovered_skills_from_db,
             commands::fetch_discovered_skills_by_category_from_db,
             commands::search_discovered_skills_from_db,
+            commands::fetch_new_discovered_skills_from_db,
+            commands::enrich_discovered_skills,
+            commands::search_skill_index,
             commands::add_ai_agent,
             commands::update_ai_agent,
             commands::remove_ai_agent,
             commands::list_ai_agents,
             commands::scan_for_new_skills,
+            commands::list_skill_templates,
+            commands::create_skill,
+            commands::publish_skill,
+            commands::push_skill_changes,
+            commands::get_skill_publish_status,
+            commands::export_skills_bundle,
+            commands::preview_skills_bundle,
+            commands::import_skills_bundle,
+            commands::create_backup,
+            commands::restore_backup,
+            commands::get_state_sync_config,
+            commands::enable_state_sync,
+            commands::disable_state_sync,
+            commands::sync_state_now,
+            commands::resolve_state_sync_conflicts,
+            commands::list_git_credentials,
+            commands::save_git_credential,
+            commands::delete_git_credential,
+            commands::test_git_credential,
+            commands::list_jobs,
+            commands::get_job,
+            commands::cancel_job,
+            commands::start_install_git_job,
+            commands::start_install_git_selection_job,
+            commands::start_update_skills_job,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+fn log_state_sync(result: anyhow::Result<Option<core::state_sync::StateSyncReport>>) {
+    match result {
+        Ok(Some(report)) if report.status == "conflict" => {
+            log::warn!("[state_sync] conflicts: {:?}", report.conflicts);
+        }
+        Ok(_) => {}
+        Err(err) => log::warn!("[state_sync] sync failed: {:#}", err),
+    }
+}